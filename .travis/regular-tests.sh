@@ -41,6 +41,7 @@ then
 fi
 
 cargo -q test -q -p tract-core -p tract-hir -p tract-onnx -p tract-linalg
+cargo -q test -q -p tract-core -p tract-linalg --features multithread-mm
 # doc test are not finding libtensorflow.so
 cargo -q test -q -p tract-tensorflow --lib $ALL_FEATURES
 
@@ -90,7 +90,7 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg model: +takes_value "Sets the model to use")
 
     (@arg format: -f --format +takes_value
-     "Hint the model format ('kaldi', 'onnx', 'nnef' or 'tf') instead of guess from extension.")
+     "Hint the model format ('kaldi', 'onnx', 'nnef', 'tflite' or 'tf') instead of guess from extension.")
 
     (@arg input: -i --input +takes_value +multiple number_of_values(1)
      "Set input shape and type (@file.pb or @file.npz:thing.npy or 3x4xi32).")
@@ -122,6 +122,12 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg tf_initializer_output_node: --("tf-initializer-output-node") +takes_value +multiple number_of_values(1)
      "Set an initializer node")
 
+    (@arg tf_saved_model_tag: --("tf-saved-model-tag") +takes_value +multiple number_of_values(1)
+     "Tag of the meta graph to load from a SavedModel directory (default: serve)")
+
+    (@arg tf_signature: --("tf-signature") +takes_value
+     "Signature to load from a SavedModel directory (default: serving_default)")
+
     (@arg output_node: --("output-node") +takes_value +multiple number_of_values(1)
      "Override output nodes name (auto-detects otherwise).")
 
@@ -453,7 +459,27 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
         #[cfg(feature = "onnx")]
         {
             let onnx = tract_onnx::onnx();
-            let names = onnx.op_register.0.keys().sorted().into_iter().join(", ");
+            let names = onnx
+                .op_register
+                .0
+                .iter()
+                .flat_map(|((domain, op), builders)| {
+                    let name =
+                        if domain == "" { op.to_string() } else { format!("{}:{}", domain, op) };
+                    builders
+                        .iter()
+                        .map(move |(opsets, _)| {
+                            if *opsets == tract_onnx::model::OpsetRange::all() {
+                                name.clone()
+                            } else {
+                                format!("{}[{}]", name, opsets)
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .sorted()
+                .into_iter()
+                .join(", ");
             println!("Onnx:\n");
             println!("{}", names);
             println!("\n");
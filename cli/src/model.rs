@@ -66,6 +66,10 @@ pub trait Model:
             self.node_op(id).downcast_ref::<tract_hir::ops::scan::InferenceScan>()
         {
             vec![("loop".into(), &hir.body)]
+        } else if let Some(ite) =
+            self.node_op(id).downcast_ref::<tract_core::ops::logic::IfThenElse>()
+        {
+            vec![("then".into(), &ite.then_body), ("else".into(), &ite.else_body)]
         } else {
             vec![]
         }
@@ -82,6 +86,10 @@ pub trait Model:
         {
             // if we have typefact, we hopefully have type ops
             unreachable!();
+        } else if let Some(_) =
+            self.node_op(id).downcast_ref::<tract_core::ops::logic::IfThenElse>()
+        {
+            vec![Some(1.to_dim()), Some(1.to_dim())]
         } else {
             vec![]
         }
@@ -91,6 +99,9 @@ pub trait Model:
 
     fn properties(&self) -> &HashMap<String, Arc<Tensor>>;
 
+    /// Symbols of the model, by name
+    fn symbol_table(&self) -> &SymbolTable;
+
     fn rename_node(&mut self, id: usize, name: &str) -> TractResult<()>;
 }
 
@@ -195,6 +206,10 @@ where
         &self.properties
     }
 
+    fn symbol_table(&self) -> &SymbolTable {
+        &self.symbol_table
+    }
+
     fn rename_node(&mut self, id: usize, name: &str) -> TractResult<()> {
         self.rename_node(id, name)
     }
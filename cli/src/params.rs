@@ -110,6 +110,10 @@ impl Parameters {
                 "onnx"
             } else if filename.extension().map(|s| s == "raw" || s == "txt").unwrap_or(false) {
                 "kaldi"
+            } else if filename.extension().map(|s| s == "tflite").unwrap_or(false) {
+                "tflite"
+            } else if filename.is_dir() && filename.join("saved_model.pb").exists() {
+                "tf"
             } else if filename.is_dir()
                 || filename.to_string_lossy().ends_with(".tar")
                 || filename.to_string_lossy().ends_with(".tar.gz")
@@ -186,16 +190,45 @@ impl Parameters {
                     (SomeGraphDef::NoGraphDef, Box::new(parsed.model), Option::<TfExt>::None)
                 }
             }
+            #[cfg(feature = "tflite")]
+            "tflite" => {
+                let tflite = tract_tflite::tflite();
+                info_usage("loaded framework (tflite)", probe);
+                let graph = tflite.proto_model_for_path(&filename)?;
+                info_usage("proto model loaded", probe);
+                let model = tflite.model_for_proto_model(&graph)?;
+                (SomeGraphDef::NoGraphDef, Box::new(model), Option::<TfExt>::None)
+            }
             #[cfg(feature = "tf")]
             "tf" => {
                 let tf = tract_tensorflow::tensorflow();
                 info_usage("loaded framework (tf)", probe);
-                let mut graph = tf.proto_model_for_path(&filename)?;
+                let (mut graph, signature) = if filename.is_dir() {
+                    let tags = matches
+                        .values_of("tf_saved_model_tag")
+                        .map(|tags| tags.collect())
+                        .unwrap_or(vec![tract_tensorflow::model::SERVING_TAG]);
+                    let mut meta = tf.read_saved_model_dir(&filename, &tags)?;
+                    let signature = matches
+                        .value_of("tf_signature")
+                        .unwrap_or(tract_tensorflow::model::DEFAULT_SIGNATURE);
+                    let signature = meta.signature_def.remove(signature);
+                    if signature.is_none() && matches.is_present("tf_signature") {
+                        bail!("Signature not found in saved model")
+                    }
+                    (meta.graph_def.context("Meta graph has no graph")?, signature)
+                } else {
+                    (tf.proto_model_for_path(&filename)?, None)
+                };
                 info_usage("proto model loaded", probe);
                 if matches.is_present("determinize") {
                     tract_tensorflow::Tensorflow::determinize(&mut graph)?;
                 }
-                let mut model_and_ext = tf.parse_graph(&graph)?;
+                let mut model_and_ext = if let Some(signature) = signature {
+                    tf.parse_graph_with_signature(&graph, &signature)?
+                } else {
+                    tf.parse_graph(&graph)?
+                };
                 model_and_ext.1.initializing_nodes = matches
                     .values_of("tf_initializer_output_node")
                     .map(|values| {
@@ -296,7 +329,8 @@ impl Parameters {
                         .nth(0)
                         .unwrap()
                         .parse::<usize>()?;
-                    let (name, tensor) = tensor::for_data(file.path().to_str().unwrap())?;
+                    let (name, tensor) =
+                        tensor::for_data(&raw_model.symbol_table, file.path().to_str().unwrap())?;
                     Ok(Some((
                         ix,
                         is_input,
@@ -373,7 +407,7 @@ impl Parameters {
 
         if let Some(inputs) = matches.values_of("input") {
             for (ix, v) in inputs.enumerate() {
-                let (name, t) = tensor::for_string(v)?;
+                let (name, t) = tensor::for_string(&raw_model.symbol_table, v)?;
                 let fact = t.clone().without_value();
                 let fact: F = (&fact).try_into().unwrap();
                 let outlet = if let Some(name) = name.as_ref().filter(|s| s.len() > 0) {
@@ -606,7 +640,7 @@ impl Parameters {
         #[cfg(feature = "pulse")]
         {
             if let Some(dim) = concretize_stream_dim {
-                stage!("concretize-stream-dim", typed_model -> typed_model, |m:TypedModel| Ok(m.concretize_dims(&SymbolValues::default().with(stream_symbol(), dim as _))?));
+                stage!("concretize-stream-dim", typed_model -> typed_model, |m:TypedModel| Ok(m.concretize_dims(&SymbolValues::default().with(&stream_symbol(), dim as _))?));
                 stage!("concretize-stream-dim-declutter", typed_model -> typed_model, |m:TypedModel| Ok(m.declutter()?));
             } else if let Some(pulse) = pulse {
                 stage!("pulse", typed_model -> pulsed_model, |m:TypedModel| Ok(PulsedModel::new(&m, pulse)?));
@@ -691,7 +725,7 @@ impl Parameters {
 
         if let Some(inputs) = matches.values_of("input") {
             let names = inputs
-                .map(|t| Ok(tensor::for_string(t)?.0))
+                .map(|t| Ok(tensor::for_string(raw_model.symbol_table(), t)?.0))
                 .collect::<CliResult<Vec<Option<String>>>>()?;
             if names.iter().all(|s| s.is_some() && s.as_ref().unwrap().len() > 0) {
                 let names: Vec<&str> = names.iter().map(|s| &**s.as_ref().unwrap()).collect();
@@ -711,7 +745,7 @@ impl Parameters {
 
         if let Some(override_facts) = matches.values_of("override_fact") {
             for fact in override_facts {
-                let (name, fact) = tensor::for_string(fact)?;
+                let (name, fact) = tensor::for_string(raw_model.symbol_table(), fact)?;
                 let node = raw_model.node_id_by_name(&name.unwrap())?;
                 if let Some(inf) = raw_model.downcast_mut::<InferenceModel>() {
                     inf.set_outlet_fact(OutletId::new(node, 0), fact)?;
@@ -736,7 +770,8 @@ impl Parameters {
             })
             .collect();
 
-        let mut assertions = Assertions::from_clap(matches, &*output_names_and_labels)?;
+        let mut assertions =
+            Assertions::from_clap(matches, raw_model.symbol_table(), &*output_names_and_labels)?;
 
         if let Some(sub) = matches.value_of("kaldi_downsample") {
             dispatch_model_mut_no_pulse!(raw_model, |m| Self::kaldi_downsample(m, sub.parse()?))?;
@@ -854,14 +889,20 @@ pub struct Assertions {
 impl Assertions {
     fn from_clap(
         matches: &clap::ArgMatches,
+        symbol_table: &SymbolTable,
         output_names: &[Vec<String>],
     ) -> CliResult<Assertions> {
         if let Some(sub) = matches.subcommand.as_ref().map(|sub| &sub.matches) {
             let mut assert_outputs: Vec<Option<Arc<Tensor>>> = vec![None; output_names.len()];
             if let Some(values) = sub.values_of("assert-output") {
                 for (ix, o) in values.enumerate() {
-                    let (name, fact) = tensor::for_string(o)?;
-                    info!("Output assertion #{}: (named: {}) {:?}", ix, name.as_deref().unwrap_or(""), fact);
+                    let (name, fact) = tensor::for_string(symbol_table, o)?;
+                    info!(
+                        "Output assertion #{}: (named: {}) {:?}",
+                        ix,
+                        name.as_deref().unwrap_or(""),
+                        fact
+                    );
                     let oix = if let Some(name) = name {
                         output_names.iter().position(|names| names.contains(&name)).unwrap_or(ix)
                     } else {
@@ -898,7 +939,7 @@ impl Assertions {
 
             let assert_output_facts: Option<Vec<InferenceFact>> = matches
                 .values_of("assert-output-fact")
-                .map(|vs| vs.map(|v| tensor::for_string(v).unwrap().1).collect());
+                .map(|vs| vs.map(|v| tensor::for_string(symbol_table, v).unwrap().1).collect());
             let assert_op_count: Option<Vec<(String, usize)>> = sub
                 .values_of("assert-op-count")
                 .map(|vs| {
@@ -153,7 +153,7 @@ fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<T
     //    println!("output_fact: {:?}", output_fact);
     let output_dim = output_fact
         .dim
-        .eval(&SymbolValues::default().with(stream_symbol(), input_dim as i64))
+        .eval(&SymbolValues::default().with(&stream_symbol(), input_dim as i64))
         .to_usize()?;
     let mut output_shape = output_fact.shape.to_vec();
     output_shape[output_fact.axis] =
@@ -9,6 +9,7 @@ use tract_hir::internal::*;
 fn parse_dt(dt: &str) -> CliResult<DatumType> {
     Ok(match dt.to_lowercase().as_ref() {
         "f16" => DatumType::F16,
+        "bf16" => DatumType::BF16,
         "f32" => DatumType::F32,
         "f64" => DatumType::F64,
         "i8" => DatumType::I8,
@@ -25,18 +26,18 @@ fn parse_dt(dt: &str) -> CliResult<DatumType> {
     })
 }
 
-pub fn parse_spec(size: &str) -> CliResult<InferenceFact> {
+pub fn parse_spec(symbol_table: &SymbolTable, size: &str) -> CliResult<InferenceFact> {
     if size.len() == 0 {
         return Ok(InferenceFact::default());
     }
     if size.contains("x") && !size.contains(",") {
         parse_x_spec(size)
     } else {
-        parse_coma_spec(size)
+        parse_coma_spec(symbol_table, size)
     }
 }
 
-pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
+pub fn parse_coma_spec(symbol_table: &SymbolTable, size: &str) -> CliResult<InferenceFact> {
     let splits = size.split(",").collect::<Vec<_>>();
 
     if splits.len() < 1 {
@@ -54,7 +55,11 @@ pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
         shape
             .iter()
             .map(|&s| {
-                Ok(if s == "_" { GenericFactoid::Any } else { GenericFactoid::Only(parse_dim(s)?) })
+                Ok(if s == "_" {
+                    GenericFactoid::Any
+                } else {
+                    GenericFactoid::Only(parse_dim(symbol_table, s)?)
+                })
             })
             .collect::<CliResult<TVec<DimFact>>>()?,
     );
@@ -66,26 +71,27 @@ pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
     }
 }
 
-pub fn parse_dim(i: &str) -> CliResult<TDim> {
-    // ensure the magic S is pre-registered
-    #[cfg(feature = "pulse")]
-    let _ = tract_pulse::internal::stream_symbol();
-
+pub fn parse_dim(symbol_table: &SymbolTable, i: &str) -> CliResult<TDim> {
     if i.len() == 0 {
         bail!("Can not parse empty string as Dim")
     }
     let number_len = i.chars().take_while(|c| c.is_digit(10)).count();
-    let symbol_len = i.len() - number_len;
-    if symbol_len > 1 {
-        bail!("Can not parse {} as Dim", i)
-    }
     let number: i64 = if number_len > 0 { i[..number_len].parse()? } else { 1 };
-    if symbol_len == 0 {
+    let name = &i[number_len..];
+    if name.len() == 0 {
         return Ok(number.to_dim());
     }
-    let symbol = i.chars().last().unwrap();
-    let symbol = Symbol::from(symbol);
-    Ok(symbol.to_dim() * number)
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        bail!("Can not parse {} as Dim", i)
+    }
+    // the magic S is the pulse stream symbol
+    #[cfg(feature = "pulse")]
+    {
+        if name == "S" {
+            return Ok(tract_pulse::internal::stream_dim() * number);
+        }
+    }
+    Ok(symbol_table.sym(name).to_dim() * number)
 }
 
 pub fn parse_x_spec(size: &str) -> CliResult<InferenceFact> {
@@ -134,14 +140,14 @@ fn parse_values<'a, T: Datum + FromStr>(shape: &[usize], it: Vec<&'a str>) -> Cl
     Ok(tract_ndarray::Array::from_shape_vec(shape, values)?.into())
 }
 
-fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
+fn tensor_for_text_data(symbol_table: &SymbolTable, filename: &str) -> CliResult<Tensor> {
     let mut file = fs::File::open(filename)
         .map_err(|e| format_err!("Reading tensor from {}, {:?}", filename, e))?;
     let mut data = String::new();
     file.read_to_string(&mut data)?;
 
     let mut lines = data.lines();
-    let proto = parse_spec(lines.next().context("Empty data file")?)?;
+    let proto = parse_spec(symbol_table, lines.next().context("Empty data file")?)?;
     let shape = proto.shape.concretize().unwrap();
 
     let values = lines.flat_map(|l| l.split_whitespace()).collect::<Vec<&str>>();
@@ -156,7 +162,10 @@ fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
 }
 
 /// Parses the `data` command-line argument.
-pub fn for_data(filename: &str) -> CliResult<(Option<String>, InferenceFact)> {
+pub fn for_data(
+    symbol_table: &SymbolTable,
+    filename: &str,
+) -> CliResult<(Option<String>, InferenceFact)> {
     #[allow(unused_imports)]
     use std::convert::TryFrom;
     if filename.ends_with(".pb") {
@@ -180,7 +189,7 @@ pub fn for_data(filename: &str) -> CliResult<(Option<String>, InferenceFact)> {
         let mut npz = ndarray_npy::NpzReader::new(std::fs::File::open(filename)?)?;
         Ok((None, for_npz(&mut npz, inner)?.into()))
     } else {
-        Ok((None, tensor_for_text_data(filename)?.into()))
+        Ok((None, tensor_for_text_data(symbol_table, filename)?.into()))
     }
 }
 
@@ -225,9 +234,12 @@ pub fn for_npz(npz: &mut ndarray_npy::NpzReader<fs::File>, name: &str) -> CliRes
     bail!("Can not extract tensor from {}", name);
 }
 
-pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
+pub fn for_string(
+    symbol_table: &SymbolTable,
+    value: &str,
+) -> CliResult<(Option<String>, InferenceFact)> {
     if value.starts_with("@") {
-        for_data(&value[1..])
+        for_data(symbol_table, &value[1..])
     } else {
         let (name, value) = if value.contains(":") {
             let mut splits = value.split(":");
@@ -237,7 +249,7 @@ pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
         };
         if value.contains("=") {
             let mut split = value.split("=");
-            let spec = parse_spec(split.next().unwrap())?;
+            let spec = parse_spec(symbol_table, split.next().unwrap())?;
             let value = split.next().unwrap().split(",");
             let dt = spec
                 .datum_type
@@ -250,7 +262,7 @@ pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
             let tensor = dispatch_datum!(parse_values(dt)(&*shape, value.collect()))?;
             Ok((name, tensor.into()))
         } else {
-            Ok((name, parse_spec(value)?))
+            Ok((name, parse_spec(symbol_table, value)?))
         }
     }
 }
@@ -323,7 +335,7 @@ pub fn tensor_for_fact(fact: &TypedFact, streaming_dim: Option<usize>) -> CliRes
                     .shape
                     .iter()
                     .map(|d| {
-                        d.eval(&SymbolValues::default().with(s, dim as i64)).to_usize().unwrap()
+                        d.eval(&SymbolValues::default().with(&s, dim as i64)).to_usize().unwrap()
                     })
                     .collect::<TVec<_>>();
                 return Ok(random(&shape, fact.datum_type));
@@ -369,6 +381,7 @@ pub fn random(sizes: &[usize], datum_type: DatumType) -> Tensor {
         U8 => make::<u8>(sizes),
         U16 => make::<u16>(sizes),
         F16 => make::<f32>(sizes).cast_to::<f16>().unwrap().into_owned(),
+        BF16 => make::<f32>(sizes).cast_to_dt(BF16).unwrap().into_owned(),
         F32 => make::<f32>(sizes),
         F64 => make::<f64>(sizes),
         _ => panic!("Can generate random tensor for {:?}", datum_type),
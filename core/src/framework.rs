@@ -33,8 +33,7 @@ where
 
     /// Build a model from a filename.
     fn model_for_path(&self, p: impl AsRef<Path>) -> TractResult<Model> {
-        let mut r = std::fs::File::open(p.as_ref())
-            .with_context(|| format!("Could not open {:?}", p.as_ref()))?;
-        self.model_for_read(&mut r)
+        let proto_model = self.proto_model_for_path(p)?;
+        self.model_for_proto_model(&proto_model)
     }
 }
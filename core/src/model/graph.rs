@@ -41,6 +41,9 @@ where
     /// model properties
     #[educe(Hash(method = "hash_properties"))]
     pub properties: HashMap<String, Arc<Tensor>>,
+    /// symbols used in the model facts, by name
+    #[educe(Hash(ignore))]
+    pub symbol_table: SymbolTable,
 }
 
 fn hash_outlet_labels<H: std::hash::Hasher>(it: &HashMap<OutletId, String>, state: &mut H) {
@@ -73,6 +76,7 @@ where
             outputs: vec![],
             outlet_labels: HashMap::new(),
             properties: HashMap::new(),
+            symbol_table: SymbolTable::default(),
         }
     }
 }
@@ -97,6 +101,11 @@ where
     F: Fact + Hash + Clone + 'static,
     O: fmt::Debug + fmt::Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
 {
+    /// Symbol named `name` in this model, created on first use.
+    pub fn sym(&self, name: &str) -> Symbol {
+        self.symbol_table.sym(name)
+    }
+
     pub fn add_node(
         &mut self,
         name: impl Into<String>,
@@ -0,0 +1,332 @@
+//! Static memory planning.
+//!
+//! Given an evaluation order and the steps at which values are flushed, assign
+//! every intermediate tensor to a buffer of an arena, so that tensors alive at
+//! the same time never share a buffer.
+//!
+//! The arena is owned by a `SimpleState`, and its buffers are reused from one
+//! run to the next.
+use crate::internal::*;
+use crate::ops::konst::Const;
+use std::fmt::{Debug, Display};
+use tract_data::internal::recycle::{self, Buffer};
+
+/// Alignment (in bytes) of every buffer in the arena.
+pub const ARENA_ALIGNMENT: usize = recycle::ALIGNMENT;
+
+/// Placement of one tensor in the arena.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArenaSlot {
+    pub outlet: OutletId,
+    /// Index of the buffer holding the tensor.
+    pub buffer: usize,
+    pub size: usize,
+    /// Step producing the tensor.
+    pub from_step: usize,
+    /// Last step using the tensor (inclusive).
+    pub until_step: usize,
+}
+
+impl ArenaSlot {
+    fn overlaps_in_time(&self, other: &ArenaSlot) -> bool {
+        self.from_step <= other.until_step && other.from_step <= self.until_step
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryPlan {
+    pub slots: Vec<ArenaSlot>,
+    /// Outlets with no known size (symbolic shapes, non-copy types).
+    pub unplanned: Vec<OutletId>,
+    /// Size of each buffer, in bytes.
+    pub buffers: Vec<usize>,
+    /// Size of the arena, in bytes.
+    pub arena_size: usize,
+}
+
+impl MemoryPlan {
+    /// Plan memory for a model evaluated in `order`, with `flush_lists[step]`
+    /// listing the nodes whose outputs are not needed after `step`.
+    ///
+    /// Model inputs, constants and the `outputs` handed to the caller are not
+    /// allocated by the plan, so they are left out.
+    pub fn new<F, O>(
+        model: &Graph<F, O>,
+        order: &[usize],
+        flush_lists: &[TVec<usize>],
+        outputs: &[OutletId],
+        symbols: &SymbolValues,
+    ) -> TractResult<MemoryPlan>
+    where
+        F: Fact + Hash + Clone + 'static,
+        O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+    {
+        let inputs = model.input_outlets()?;
+        let mut until_step = vec![order.len(); model.nodes().len()];
+        for (step, flush) in flush_lists.iter().enumerate() {
+            for &node in flush {
+                until_step[node] = step;
+            }
+        }
+        let mut plan = MemoryPlan::default();
+        let mut slots = vec![];
+        for (step, &n) in order.iter().enumerate() {
+            let node = model.node(n);
+            if inputs.iter().any(|i| i.node == n) || node.op_is::<Const>() {
+                continue;
+            }
+            for slot in 0..node.outputs.len() {
+                let outlet = OutletId::new(n, slot);
+                if outputs.contains(&outlet) {
+                    continue;
+                }
+                if let Some(size) = Self::tensor_size(&node.outputs[slot].fact, symbols)? {
+                    slots.push(ArenaSlot {
+                        outlet,
+                        buffer: 0,
+                        size,
+                        from_step: step,
+                        until_step: until_step[n],
+                    });
+                } else {
+                    plan.unplanned.push(outlet);
+                }
+            }
+        }
+        // greedy by size: biggest tensors first, each in the first buffer
+        // holding no tensor alive at the same time
+        slots.sort_by_key(|s| (std::cmp::Reverse(s.size), s.from_step));
+        let mut by_buffer: Vec<Vec<ArenaSlot>> = vec![];
+        for mut slot in slots {
+            let free = by_buffer
+                .iter()
+                .position(|slots| !slots.iter().any(|other| other.overlaps_in_time(&slot)));
+            slot.buffer = if let Some(b) = free {
+                b
+            } else {
+                plan.buffers.push(slot.size);
+                by_buffer.push(vec![]);
+                plan.buffers.len() - 1
+            };
+            by_buffer[slot.buffer].push(slot);
+        }
+        plan.slots = by_buffer.into_iter().flatten().collect();
+        plan.arena_size = plan.buffers.iter().sum();
+        plan.slots.sort_by_key(|s| (s.from_step, s.outlet.slot));
+        Ok(plan)
+    }
+
+    fn tensor_size<F: Fact>(fact: &F, symbols: &SymbolValues) -> TractResult<Option<usize>> {
+        let fact = if let Ok(fact) = fact.to_typed_fact() { fact } else { return Ok(None) };
+        if !fact.datum_type.is_copy() {
+            return Ok(None);
+        }
+        let mut len = 1;
+        for d in fact.shape.iter() {
+            if let Ok(d) = d.eval(symbols).to_usize() {
+                len *= d
+            } else {
+                return Ok(None);
+            }
+        }
+        Ok(Some(len * fact.datum_type.size_of()))
+    }
+
+    pub fn slot(&self, outlet: OutletId) -> Option<&ArenaSlot> {
+        self.slots.iter().find(|s| s.outlet == outlet)
+    }
+
+    /// Memory used if every planned tensor had its own buffer.
+    pub fn naive_size(&self) -> usize {
+        self.slots.iter().map(|s| s.size).sum()
+    }
+
+    /// Highest total size of the tensors alive at the same step: a lower bound
+    /// for the arena size.
+    pub fn peak_live_size(&self) -> usize {
+        let steps = self.slots.iter().map(|s| s.until_step + 1).max().unwrap_or(0);
+        (0..steps)
+            .map(|step| {
+                self.slots
+                    .iter()
+                    .filter(|s| s.from_step <= step && step <= s.until_step)
+                    .map(|s| s.size)
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl std::fmt::Display for MemoryPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "arena: {} bytes in {} buffers for {} tensors (peak live: {} bytes, naive: {} bytes)",
+            self.arena_size,
+            self.buffers.len(),
+            self.slots.len(),
+            self.peak_live_size(),
+            self.naive_size()
+        )?;
+        if self.unplanned.len() > 0 {
+            write!(f, ", {} unplanned", self.unplanned.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Buffers of a memory plan, kept by a `SimpleState` from one run to the next.
+///
+/// Before a node is evaluated, the arena lends it buffers for its planned
+/// outputs. When a value is flushed and nobody else holds it, its buffer comes
+/// back to the arena.
+#[derive(Debug, Default)]
+pub struct Arena {
+    symbols: Option<SymbolValues>,
+    plan: MemoryPlan,
+    /// Sizes of the planned outputs, by node.
+    sizes: Vec<TVec<usize>>,
+    free: Vec<Buffer>,
+}
+
+impl Clone for Arena {
+    fn clone(&self) -> Arena {
+        Arena::default()
+    }
+}
+
+impl Arena {
+    /// Make sure the arena fits the plan for these symbol values.
+    pub fn prepare<F, O, M>(
+        &mut self,
+        plan: &SimplePlan<F, O, M>,
+        symbols: &SymbolValues,
+    ) -> TractResult<()>
+    where
+        F: Fact + Hash + Clone + 'static,
+        O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+        M: std::borrow::Borrow<Graph<F, O>> + Hash,
+    {
+        if self.symbols.as_ref() == Some(symbols) {
+            return Ok(());
+        }
+        self.plan = plan.memory_plan(symbols)?;
+        self.sizes = vec![tvec!(); plan.model().nodes().len()];
+        for slot in &self.plan.slots {
+            self.sizes[slot.outlet.node].push(slot.size);
+        }
+        self.free =
+            self.plan.buffers.iter().map(|&size| Buffer::new(size)).collect::<TractResult<_>>()?;
+        self.symbols = Some(symbols.clone());
+        Ok(())
+    }
+
+    /// Buffers for the planned outputs of `node`, as long as the arena has
+    /// some. Buffers which went away with a tensor kept by the caller are
+    /// replaced by the memory of the next values flushed.
+    pub fn lend(&mut self, node: usize) -> Vec<Buffer> {
+        let mut lent = vec![];
+        for &size in self.sizes.get(node).into_iter().flatten() {
+            let best = self
+                .free
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.size() >= size)
+                .min_by_key(|(_, b)| b.size())
+                .map(|(ix, _)| ix);
+            if let Some(ix) = best {
+                lent.push(self.free.swap_remove(ix))
+            }
+        }
+        lent
+    }
+
+    /// Take back the buffers lent to a node and not used by its outputs, and
+    /// the memory of the tensors it dropped.
+    pub fn give_back(&mut self, buffers: Vec<Buffer>) {
+        buffers.into_iter().for_each(|b| self.keep(b))
+    }
+
+    /// Take back the memory of a flushed value, unless it is still in use.
+    pub fn recycle(&mut self, value: Arc<Tensor>) {
+        if let Ok(tensor) = Arc::try_unwrap(value) {
+            if let Some(buffer) = tensor.into_buffer() {
+                self.keep(buffer)
+            }
+        }
+    }
+
+    fn keep(&mut self, buffer: Buffer) {
+        if self.free.len() < self.plan.buffers.len() && self.plan.buffers.contains(&buffer.size()) {
+            self.free.push(buffer)
+        }
+    }
+
+    pub fn plan(&self) -> &MemoryPlan {
+        &self.plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    fn chain(len: usize) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let mut wire = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[16]))?;
+        for i in 0..len {
+            wire = model.wire_node(format!("exp.{}", i), math::exp(), &[wire])?[0];
+        }
+        model.set_output_outlets(&[wire])?;
+        Ok(model)
+    }
+
+    #[test]
+    fn chain_reuses_two_buffers() -> TractResult<()> {
+        let model = chain(5)?;
+        let plan = SimplePlan::new(&model)?;
+        let memory = plan.memory_plan(&SymbolValues::default())?;
+        // the last exp is the model output, the caller keeps it
+        assert_eq!(memory.slots.len(), 4);
+        assert_eq!(memory.naive_size(), 4 * 64);
+        assert_eq!(memory.peak_live_size(), 128);
+        assert_eq!(memory.buffers, vec!(64, 64));
+        assert_eq!(memory.arena_size, 128);
+        for pair in memory.slots.windows(2) {
+            assert_ne!(pair[0].buffer, pair[1].buffer);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn live_tensors_do_not_share_buffers() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = model.sym("S");
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[s.to_dim()]))?;
+        let b = model.wire_node("b", math::exp(), &[a])?[0];
+        let c = model.wire_node("c", math::exp(), &[b])?[0];
+        let d = model.wire_node("d", math::add::bin_typed(), &[b, c])?[0];
+        let e = model.wire_node("e", math::exp(), &[d])?[0];
+        let f = model.wire_node("f", math::exp(), &[e])?[0];
+        model.set_output_outlets(&[f])?;
+        let plan = SimplePlan::new(&model)?;
+        assert_eq!(plan.memory_plan(&SymbolValues::default())?.unplanned.len(), 4);
+        let mut symbols = SymbolValues::default();
+        symbols[&s] = Some(10);
+        let memory = plan.memory_plan(&symbols)?;
+        for x in &memory.slots {
+            for y in &memory.slots {
+                if x != y && x.overlaps_in_time(y) {
+                    assert_ne!(x.buffer, y.buffer);
+                }
+            }
+        }
+        // b, c and d are alive while computing d, e reuses a buffer
+        assert_eq!(memory.peak_live_size(), 120);
+        assert_eq!(memory.buffers.len(), 3);
+        assert_eq!(memory.arena_size, 120);
+        Ok(())
+    }
+}
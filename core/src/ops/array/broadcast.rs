@@ -68,6 +68,10 @@ impl OpState for MultiBroadcastToState {
             &inputs[0], &*shape
         ))
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 impl TypedOp for MultiBroadcastTo {
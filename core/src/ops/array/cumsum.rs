@@ -0,0 +1,132 @@
+use crate::internal::*;
+use num_traits::Zero;
+use std::ops::Add;
+use tract_ndarray::prelude::*;
+
+/// Cumulative sum along an axis.
+///
+/// `exclusive` excludes the current element from its own sum (so the first
+/// output is zero), `reverse` sums from the end of the axis.
+#[derive(Debug, Clone, new, Hash)]
+pub struct CumSum {
+    pub axis: usize,
+    pub reverse: bool,
+    pub exclusive: bool,
+}
+
+impl_dyn_hash!(CumSum);
+
+impl CumSum {
+    fn eval_t<T: Datum + Copy + Zero + Add<T, Output = T>>(
+        &self,
+        input: &Tensor,
+    ) -> TractResult<Tensor> {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        for mut lane in output.lanes_mut(Axis(self.axis)) {
+            let len = lane.len();
+            let mut acc = T::zero();
+            for i in 0..len {
+                let ix = if self.reverse { len - 1 - i } else { i };
+                let x = lane[ix];
+                if self.exclusive {
+                    lane[ix] = acc;
+                    acc = acc + x;
+                } else {
+                    acc = acc + x;
+                    lane[ix] = acc;
+                }
+            }
+        }
+        let mut output = output.into_tensor();
+        unsafe { output.set_datum_type(input.datum_type()) };
+        Ok(output)
+    }
+}
+
+impl Op for CumSum {
+    fn name(&self) -> Cow<str> {
+        "CumSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {} reverse: {} exclusive: {}",
+            self.axis, self.reverse, self.exclusive
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for CumSum {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = dispatch_numbers!(Self::eval_t(input.datum_type())(self, &input))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for CumSum {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.axis >= inputs[0].rank() {
+            bail!("Invalid axis {} for input {:?}", self.axis, inputs[0])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[0].shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let count: TDim = inputs[0].shape.iter().product();
+        Ok(tvec!((Cost::FMA(inputs[0].datum_type), count)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank())
+            .filter(|&axis| axis != self.axis)
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        if let Some(axis) = change.transform_axis(self.axis) {
+            let op = Some(Box::new(Self { axis, ..self.clone() }) as _);
+            Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval() -> TractResult<()> {
+        let input = rctensor2(&[[1i64, 2, 3], [4, 5, 6]]);
+        let output = CumSum::new(1, false, false).eval(tvec!(input.clone()))?;
+        assert_eq!(*output[0], tensor2(&[[1i64, 3, 6], [4, 9, 15]]));
+        let output = CumSum::new(1, true, true).eval(tvec!(input.clone()))?;
+        assert_eq!(*output[0], tensor2(&[[5i64, 3, 0], [11, 6, 0]]));
+        let output = CumSum::new(0, false, true).eval(tvec!(input))?;
+        assert_eq!(*output[0], tensor2(&[[0i64, 0, 0], [1, 2, 3]]));
+        Ok(())
+    }
+}
@@ -1,26 +1,36 @@
 /// # Operators on array and shapes
 mod broadcast;
 pub(crate) mod concat;
+mod cumsum;
 mod gather;
 mod gather_elements;
 mod gather_nd;
 mod one_hot;
 mod pad;
+mod range;
 mod reshape;
+mod reverse;
 mod scatter_elements;
 mod scatter_nd;
 mod slice;
 mod tile;
+mod topk;
+mod trilu;
 
 pub use self::broadcast::MultiBroadcastTo;
 pub use self::concat::{ConcatSlice, TypedConcat};
+pub use self::cumsum::CumSum;
 pub use self::gather::Gather;
 pub use self::gather_elements::GatherElements;
 pub use self::gather_nd::GatherNd;
 pub use self::one_hot::OneHot;
 pub use self::pad::{Pad, PadMode};
+pub use self::range::Range;
 pub use self::reshape::FiniteReshape;
+pub use self::reverse::{Reverse, ReverseSequence};
 pub use self::scatter_elements::ScatterElements;
 pub use self::scatter_nd::ScatterNd;
 pub use self::slice::Slice;
 pub use self::tile::Tile;
+pub use self::topk::Topk;
+pub use self::trilu::Trilu;
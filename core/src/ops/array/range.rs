@@ -0,0 +1,221 @@
+use crate::internal::*;
+use num_traits::AsPrimitive;
+use std::ops::{Add, Mul};
+use tract_ndarray::prelude::*;
+
+/// Values from start (included) to end (excluded) by step.
+///
+/// Inputs are the start, end and step scalars. The output length, `len`, is
+/// either computed from constant (and possibly symbolic) bounds, or a fresh
+/// symbol when the bounds are only known at runtime.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Range {
+    pub len: TDim,
+}
+
+impl_dyn_hash!(Range);
+
+impl Range {
+    /// Output length for the given input facts, `fallback` being used when
+    /// the bounds are not known.
+    pub fn len_for_facts(
+        start: &TypedFact,
+        end: &TypedFact,
+        step: &TypedFact,
+        fallback: Symbol,
+    ) -> TractResult<TDim> {
+        let (start, end, step) = match (&start.konst, &end.konst, &step.konst) {
+            (Some(start), Some(end), Some(step)) => (start, end, step),
+            _ => return Ok(fallback.to_dim()),
+        };
+        if start.datum_type() == TDim::datum_type() {
+            let start = start.to_scalar::<TDim>()?;
+            let end = end.to_scalar::<TDim>()?;
+            let step = step.cast_to_scalar::<i64>()?;
+            if step == 0 {
+                bail!("Range step can not be zero")
+            }
+            let len = if step > 0 {
+                (end.clone() - start).div_ceil(step as u64)
+            } else {
+                (start.clone() - end).div_ceil(-step as u64)
+            };
+            Ok(len.to_i64().map(|len| len.max(0).to_dim()).unwrap_or(len))
+        } else {
+            Ok(Self::len(start, end, step)?.to_dim())
+        }
+    }
+
+    fn len(start: &Tensor, end: &Tensor, step: &Tensor) -> TractResult<usize> {
+        let start = start.cast_to_scalar::<f64>()?;
+        let end = end.cast_to_scalar::<f64>()?;
+        let step = step.cast_to_scalar::<f64>()?;
+        if step == 0.0 {
+            bail!("Range step can not be zero")
+        }
+        Ok(((end - start) / step).ceil().max(0.0) as usize)
+    }
+
+    fn eval_t<T>(start: &Tensor, step: &Tensor, len: usize) -> TractResult<Tensor>
+    where
+        T: Datum + Copy + Add<T, Output = T> + Mul<T, Output = T>,
+        usize: AsPrimitive<T>,
+    {
+        let start = *start.to_scalar::<T>()?;
+        let step = *step.to_scalar::<T>()?;
+        Ok(Array1::from_shape_fn(len, |ix| ix.as_() * step + start).into_tensor())
+    }
+
+    fn eval_with_symbols(
+        mut inputs: TVec<Arc<Tensor>>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (start, end, step) = args_3!(inputs);
+        let dt = start.datum_type();
+        let (start, end, step) = if dt == TDim::datum_type() {
+            let resolve = |t: &Tensor| -> TractResult<Tensor> {
+                Ok(tensor0(t.to_scalar::<TDim>()?.eval(values).to_i64()?))
+            };
+            (resolve(&start)?, resolve(&end)?, resolve(&step)?)
+        } else {
+            (
+                start.into_tensor(),
+                end.cast_to_dt(dt)?.into_owned(),
+                step.cast_to_dt(dt)?.into_owned(),
+            )
+        };
+        let len = Self::len(&start, &end, &step)?;
+        let output = dispatch_numbers!(Self::eval_t(start.datum_type())(&start, &step, len))?;
+        Ok(tvec!(output.cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl Op for Range {
+    fn name(&self) -> Cow<str> {
+        "Range".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("len: {}", self.len)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Range {
+    fn is_stateless(&self) -> bool {
+        // TDim bounds may need symbols resolved by the session
+        false
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        Self::eval_with_symbols(inputs, &SymbolValues::default())
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(RangeState)))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RangeState;
+
+impl OpState for RangeState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        _op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        Range::eval_with_symbols(inputs, &session.resolved_symbols)
+    }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
+}
+
+impl TypedOp for Range {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs.iter().any(|i| i.rank() != 0) {
+            bail!("Range expects scalar inputs, got {:?}", inputs)
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &[self.len.clone()])))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model
+            .node_input_facts(node.id)?
+            .iter()
+            .map(|f| f.konst.clone())
+            .collect::<Option<TVec<_>>>();
+        if let Some(Ok(output)) =
+            inputs.map(|inputs| Self::eval_with_symbols(inputs, &SymbolValues::default()))
+        {
+            let mut patch = TypedModelPatch::default();
+            let wire = patch.add_const(&node.name, output[0].clone())?;
+            patch.shunt_outside(model, node.id.into(), wire)?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    fn concretize_dims(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        target.wire_node(&node.name, Self { len: self.len.eval(values) }, &inputs)
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_float() -> TractResult<()> {
+        let op = Range::new(3.to_dim());
+        let output = op.eval(tvec!(rctensor0(1f32), rctensor0(2f32), rctensor0(0.4f32)))?;
+        output[0].close_enough(&tensor1(&[1f32, 1.4, 1.8]), true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_len() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let n = model.sym("n");
+        let start = model.add_const("start", rctensor0(TDim::from(1)))?;
+        let end = model.add_const("end", rctensor0(n.to_dim()))?;
+        let step = model.add_const("step", rctensor0(TDim::from(2)))?;
+        let facts = [start, end, step]
+            .iter()
+            .map(|o| model.outlet_fact(*o).map(|f| f.clone()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let len = Range::len_for_facts(
+            &facts[0],
+            &facts[1],
+            &facts[2],
+            model.symbol_table.new_symbol("r"),
+        )?;
+        assert_eq!(len, (n.to_dim() - 1).div_ceil(2));
+        let range = model.wire_node("range", Range::new(len.clone()), &[start, end, step])?;
+        assert_eq!(model.outlet_fact(range[0])?.shape.to_tvec(), tvec!(len));
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Reverse the order of the elements along some axes.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Reverse {
+    pub axes: TVec<usize>,
+}
+
+impl_dyn_hash!(Reverse);
+
+impl Reverse {
+    fn eval_t<T: Datum>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let mut view = input.to_array_view::<T>()?;
+        for &axis in &self.axes {
+            view.invert_axis(Axis(axis));
+        }
+        let mut output = view.to_owned().into_tensor();
+        unsafe { output.set_datum_type(input.datum_type()) };
+        Ok(output)
+    }
+}
+
+impl Op for Reverse {
+    fn name(&self) -> Cow<str> {
+        "Reverse".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axes: {:?}", self.axes)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Reverse {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = dispatch_datum!(Self::eval_t(input.datum_type())(self, &input))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Reverse {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(ax) = self.axes.iter().find(|&&ax| ax >= inputs[0].rank()) {
+            bail!("Invalid axis {} for input {:?}", ax, inputs[0])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[0].shape)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let fact = model.outlet_fact(node.inputs[0])?;
+        if self.axes.iter().all(|&ax| fact.shape[ax].is_one()) {
+            Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank())
+            .filter(|axis| !self.axes.contains(axis))
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        let mut axes = tvec!();
+        for axis in &self.axes {
+            if let Some(axis) = change.transform_axis(*axis) {
+                axes.push(axis);
+            } else {
+                return Ok(None);
+            }
+        }
+        let op = Some(Box::new(Self { axes }) as _);
+        Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
+    }
+
+    as_op!();
+}
+
+/// Reverse the first `seq_lens[b]` elements along `time_axis`, for each `b`
+/// along `batch_axis`.
+///
+/// The second input is the i64 seq_lens vector, one length per batch item.
+/// Elements past the sequence length are left as is.
+#[derive(Debug, Clone, new, Hash)]
+pub struct ReverseSequence {
+    pub batch_axis: usize,
+    pub time_axis: usize,
+}
+
+impl_dyn_hash!(ReverseSequence);
+
+impl ReverseSequence {
+    fn eval_t<T: Datum>(&self, input: &Tensor, seq_lens: &[i64]) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?;
+        let mut output = input.to_owned();
+        let time_axis =
+            if self.time_axis > self.batch_axis { self.time_axis - 1 } else { self.time_axis };
+        let max_len = input.shape()[self.time_axis];
+        for (b, &len) in seq_lens.iter().enumerate() {
+            if len < 0 || len as usize > max_len {
+                bail!("Invalid sequence length {} for batch item {}", len, b)
+            }
+            let mut reversed = input.index_axis(Axis(self.batch_axis), b);
+            reversed.slice_axis_inplace(Axis(time_axis), (..len as usize).into());
+            reversed.invert_axis(Axis(time_axis));
+            output
+                .index_axis_mut(Axis(self.batch_axis), b)
+                .slice_axis_mut(Axis(time_axis), (..len as usize).into())
+                .assign(&reversed);
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for ReverseSequence {
+    fn name(&self) -> Cow<str> {
+        "ReverseSequence".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("batch axis: {} time axis: {}", self.batch_axis, self.time_axis)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ReverseSequence {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, seq_lens) = args_2!(inputs);
+        let seq_lens = seq_lens.cast_to::<i64>()?;
+        let seq_lens = seq_lens.as_slice::<i64>()?;
+        if seq_lens.len() != input.shape()[self.batch_axis] {
+            bail!(
+                "Expected {} sequence lengths, got {}",
+                input.shape()[self.batch_axis],
+                seq_lens.len()
+            )
+        }
+        let mut output = dispatch_datum!(Self::eval_t(input.datum_type())(self, &input, seq_lens))?;
+        unsafe { output.set_datum_type(input.datum_type()) };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ReverseSequence {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        if self.batch_axis == self.time_axis || self.batch_axis >= rank || self.time_axis >= rank {
+            bail!(
+                "Invalid batch axis {} and time axis {} for input {:?}",
+                self.batch_axis,
+                self.time_axis,
+                inputs[0]
+            )
+        }
+        if inputs[1].rank() != 1 {
+            bail!("Expected a vector of sequence lengths, got {:?}", inputs[1])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[0].shape)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse() -> TractResult<()> {
+        let input = rctensor2(&[[1i32, 2, 3], [4, 5, 6]]);
+        let output = Reverse::new(tvec!(0, 1)).eval(tvec!(input))?;
+        assert_eq!(*output[0], tensor2(&[[6i32, 5, 4], [3, 2, 1]]));
+        Ok(())
+    }
+
+    #[test]
+    fn reverse_sequence() -> TractResult<()> {
+        let input = rctensor2(&[[0f32, 4.0, 8.0], [1.0, 5.0, 9.0], [2.0, 6.0, 10.0]]);
+        let op = ReverseSequence::new(1, 0);
+        let output = op.eval(tvec!(input, rctensor1(&[1i64, 2, 3])))?;
+        assert_eq!(*output[0], tensor2(&[[0f32, 5.0, 10.0], [1.0, 4.0, 9.0], [2.0, 6.0, 8.0]]));
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Top K elements along an axis: values and their (i64) indices.
+///
+/// K is the second input. If it is not known at typing time, output shapes
+/// use `fallback_k` along `axis`.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Topk {
+    pub axis: usize,
+    pub largest: bool,
+    pub sorted: bool,
+    pub fallback_k: TDim,
+}
+
+impl_dyn_hash!(Topk);
+
+impl Op for Topk {
+    fn name(&self) -> Cow<str> {
+        "Topk".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {}, largest: {}, sorted: {}, fallback_k: {}",
+            self.axis, self.largest, self.sorted, self.fallback_k
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Topk {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, k) = args_2!(inputs);
+        let k = k.cast_to_scalar::<i64>()?;
+        if k < 0 || k as usize > input.shape()[self.axis] {
+            bail!("Invalid k ({}) for input of shape {:?} on axis {}", k, input.shape(), self.axis)
+        }
+        let (values, indices) =
+            dispatch_numbers!(Self::eval_t(input.datum_type())(self, &input, k as usize))?;
+        Ok(tvec!(values.into_arc_tensor(), indices.into_arc_tensor()))
+    }
+}
+
+impl Topk {
+    fn eval_t<T: Datum + PartialOrd>(
+        &self,
+        input: &Tensor,
+        k: usize,
+    ) -> TractResult<(Tensor, Tensor)> {
+        let shape = input.shape();
+        let outer = shape[..self.axis].iter().product::<usize>();
+        let inner = shape[self.axis + 1..].iter().product::<usize>();
+        let len = shape[self.axis];
+        let view = input.to_array_view::<T>()?.into_shape((outer, len, inner))?;
+        let mut values = Array3::<T>::default((outer, k, inner));
+        let mut indices = Array3::<i64>::zeros((outer, k, inner));
+        let mut lane: Vec<(usize, &T)> = Vec::with_capacity(len);
+        for o in 0..outer {
+            for i in 0..inner {
+                lane.clear();
+                lane.extend(view.clone().slice_move(s![o, .., i]).into_iter().enumerate());
+                // stable sort: ties are resolved in favour of the lowest index
+                lane.sort_by(|a, b| {
+                    let ord = a.1.partial_cmp(b.1).unwrap_or(Ordering::Equal);
+                    if self.largest {
+                        ord.reverse()
+                    } else {
+                        ord
+                    }
+                });
+                lane.truncate(k);
+                if !self.sorted {
+                    lane.sort_by_key(|pair| pair.0);
+                }
+                for (ix, (pos, value)) in lane.iter().enumerate() {
+                    values[(o, ix, i)] = (*value).clone();
+                    indices[(o, ix, i)] = *pos as i64;
+                }
+            }
+        }
+        let mut output_shape: TVec<usize> = shape.into();
+        output_shape[self.axis] = k;
+        let mut values = values.into_tensor().into_shape(&output_shape)?;
+        unsafe { values.set_datum_type(input.datum_type()) };
+        Ok((values, indices.into_tensor().into_shape(&output_shape)?))
+    }
+}
+
+impl TypedOp for Topk {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[self.axis] = if let Some(k) = &inputs[1].konst {
+            k.cast_to::<TDim>()?.to_scalar::<TDim>()?.clone()
+        } else {
+            self.fallback_k.clone()
+        };
+        Ok(tvec!(
+            TypedFact::dt_shape(inputs[0].datum_type, &*shape),
+            TypedFact::dt_shape(i64::datum_type(), &*shape)
+        ))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let mut axes = vec![];
+        for i in 0..inputs[0].rank() {
+            if i != self.axis {
+                axes.push(AxisInfo {
+                    inputs: tvec!(Some(i), None),
+                    outputs: tvec!(Some(i), Some(i)),
+                    period: 1,
+                    disposable: false,
+                });
+            }
+        }
+        Ok(axes.into_iter().collect())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn largest_sorted() -> TractResult<()> {
+        let op = Topk::new(1, true, true, 2.into());
+        let input = tensor2(&[[1f32, 4., 2., 4.], [3., 0., 5., 1.]]);
+        let result = op.eval(tvec!(input.into_arc_tensor(), rctensor0(3i64)))?;
+        assert_eq!(*result[0], tensor2(&[[4f32, 4., 2.], [5., 3., 1.]]));
+        assert_eq!(*result[1], tensor2(&[[1i64, 3, 2], [2, 0, 3]]));
+        Ok(())
+    }
+
+    #[test]
+    fn smallest_unsorted() -> TractResult<()> {
+        let op = Topk::new(0, false, false, 2.into());
+        let input = tensor2(&[[3i32, 1], [1, 2], [2, 0]]);
+        let result = op.eval(tvec!(input.into_arc_tensor(), rctensor1(&[2i64])))?;
+        assert_eq!(*result[0], tensor2(&[[1i32, 1], [2, 0]]));
+        assert_eq!(*result[1], tensor2(&[[1i64, 0], [2, 2]]));
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_k() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = model.sym("S").to_dim();
+        let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[10]))?;
+        let k = model.add_source("k", TypedFact::dt_shape(i64::datum_type(), &[0usize; 0]))?;
+        let outputs = model.wire_node("topk", Topk::new(0, true, true, s.clone()), &[input, k])?;
+        assert_eq!(model.outlet_fact(outputs[0])?.shape.to_tvec(), tvec!(s.clone()));
+        assert_eq!(model.outlet_fact(outputs[1])?.shape.to_tvec(), tvec!(s));
+        Ok(())
+    }
+}
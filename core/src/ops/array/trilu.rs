@@ -0,0 +1,102 @@
+use crate::internal::*;
+
+/// Keep the upper (or lower) triangular part of the matrices formed by the
+/// two last axes, zeroing the rest.
+///
+/// The second input is the (i64 scalar) diagonal offset k: an upper Trilu
+/// keeps elements with `col - row >= k`, a lower one those with
+/// `col - row <= k`.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Trilu {
+    pub upper: bool,
+}
+
+impl_dyn_hash!(Trilu);
+
+impl Trilu {
+    fn eval_t<T: Datum>(&self, input: &Tensor, k: i64) -> TractResult<Tensor> {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        let rank = output.ndim();
+        for (coords, x) in output.indexed_iter_mut() {
+            let offset = coords[rank - 1] as i64 - coords[rank - 2] as i64;
+            let keep = if self.upper { offset >= k } else { offset <= k };
+            if !keep {
+                *x = T::default();
+            }
+        }
+        let mut output = output.into_tensor();
+        unsafe { output.set_datum_type(input.datum_type()) };
+        Ok(output)
+    }
+}
+
+impl Op for Trilu {
+    fn name(&self) -> Cow<str> {
+        "Trilu".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("upper: {}", self.upper)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Trilu {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, k) = args_2!(inputs);
+        let k = k.cast_to_scalar::<i64>()?;
+        let output = dispatch_datum!(Self::eval_t(input.datum_type())(self, &input, k))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Trilu {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() < 2 {
+            bail!("Trilu expects an input of rank 2 or more, got {:?}", inputs[0])
+        }
+        if inputs[1].rank() != 0 {
+            bail!("Trilu expects a scalar k, got {:?}", inputs[1])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[0].shape)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank() - 2)
+            .map(|axis| AxisInfo {
+                inputs: tvec!(Some(axis), None),
+                outputs: tvec!(Some(axis)),
+                period: 1,
+                disposable: true,
+            })
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval() -> TractResult<()> {
+        let input = rctensor2(&[[1i32, 2, 3], [4, 5, 6], [7, 8, 9]]);
+        let upper = Trilu::new(true).eval(tvec!(input.clone(), rctensor0(1i64)))?;
+        assert_eq!(*upper[0], tensor2(&[[0i32, 2, 3], [0, 0, 6], [0, 0, 0]]));
+        let lower = Trilu::new(false).eval(tvec!(input, rctensor0(-1i64)))?;
+        assert_eq!(*lower[0], tensor2(&[[0i32, 0, 0], [4, 0, 0], [7, 8, 0]]));
+        Ok(())
+    }
+}
@@ -108,6 +108,23 @@ pub trait BinMiniOp:
     }
 }
 dyn_clone::clone_trait_object!(BinMiniOp);
+
+/// Evaluate a binary mini op, computing in f32 when any operand is bf16.
+fn eval_upcasting_bf16(
+    mini_op: &dyn BinMiniOp,
+    a: Arc<Tensor>,
+    b: Arc<Tensor>,
+) -> TractResult<Tensor> {
+    if a.datum_type() != DatumType::BF16 && b.datum_type() != DatumType::BF16 {
+        return mini_op.eval(a, b);
+    }
+    let c_dt = mini_op.result_datum_type(a.datum_type(), b.datum_type())?;
+    let a = a.cast_to::<f32>()?.into_owned().into_arc_tensor();
+    let b = b.cast_to::<f32>()?.into_owned().into_arc_tensor();
+    let c = mini_op.eval(a, b)?;
+    Ok(c.cast_to_dt(c_dt)?.into_owned())
+}
+
 downcast_rs::impl_downcast!(BinMiniOp);
 
 impl Hash for Box<dyn BinMiniOp> {
@@ -142,7 +159,7 @@ impl EvalOp for TypedBinOp {
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let (a, b) = args_2!(inputs);
         debug_assert_eq!(a.rank(), b.rank());
-        Ok(tvec!(self.0.eval(a, b)?.into_arc_tensor()))
+        Ok(tvec!(eval_upcasting_bf16(&*self.0, a, b)?.into_arc_tensor()))
     }
 }
 
@@ -296,6 +313,7 @@ impl TypedOp for TypedBinOp {
         if self.0.result_datum_type(inputs[0].datum_type, inputs[1].datum_type)?
             == inputs[0].datum_type
             && inputs[0] == inputs[1]
+            && inputs[0].datum_type != DatumType::BF16
         {
             Ok(Some(TypedModelPatch::replace_single_op(
                 model,
@@ -367,7 +385,8 @@ impl EvalOp for UnaryOp {
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         debug_assert_eq!(self.a.rank(), inputs[0].rank());
-        Ok(tvec!(self.mini_op.eval(self.a.clone(), inputs.remove(0))?.into_arc_tensor()))
+        let b = inputs.remove(0);
+        Ok(tvec!(eval_upcasting_bf16(&*self.mini_op, self.a.clone(), b)?.into_arc_tensor()))
     }
 }
 
@@ -453,6 +453,10 @@ impl OpState for ReshapeState {
             _ => bail!("Only reshape can be stateful"),
         }
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 impl TypedOp for AxisOp {
@@ -61,9 +61,13 @@ impl QConvProblem {
         let a0 = self.qp.a0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
         let b0 = self.qp.b0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
         let c0 = self.qp.c0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
-        let scale = self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.a_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap();
+        let a_scale = self.qp.a_scale.as_static().unwrap().cast_to::<f32>().unwrap();
+        let a_scale = a_scale.as_slice::<f32>().unwrap();
+        let scale = |co: usize| {
+            self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+                / a_scale[if a_scale.len() > 1 { co } else { 0 }]
+                / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+        };
         let mut temp = ArrayD::<i32>::zeros(&*self.shape_out.shape);
         for n in 0..n {
             for g in 0..self.group {
@@ -109,8 +113,10 @@ impl QConvProblem {
             shape[self.shape_out.c_axis()] = bias.len();
             temp += &bias.clone().into_shape(shape).unwrap();
         }
-        temp.mapv(|i| {
-            (round_ties_to_even(i as f32 / scale as f32) as i32 + c0)
+        let c_axis = self.shape_out.c_axis();
+        ArrayD::from_shape_fn(temp.shape(), |coords| {
+            let i = temp[&coords];
+            (round_ties_to_even(i as f32 / scale(coords[c_axis])) as i32 + c0)
                 .max(std::i8::MIN as i32)
                 .min(std::i8::MAX as i32) as i8
         })
@@ -516,3 +522,46 @@ fn bias_2() {
     .check()
     .unwrap();
 }
+
+#[test]
+fn per_channel_a_scale_0() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a_scale = AttrOrInput::Attr(rctensor1(&[0.5f32, 2.0]));
+    for optim in &[false, true] {
+        QConvProblem {
+            shape_in: CHW.from_n_c_hw(1, 1, &[3]).unwrap(),
+            shape_out: CHW.from_n_c_hw(1, 2, &[3]).unwrap(),
+            kernel_format: OIHW,
+            group: 1,
+            data: arr2(&[[10i8, -3, 7]]).into_dyn(),
+            kernel: arr3(&[[[3i8]], [[5]]]).into_dyn(),
+            bias: Some(arr1(&[2i32, -4]).into_dyn()),
+            qp: qp.clone(),
+            optim: *optim,
+        }
+        .check()
+        .unwrap();
+    }
+}
+
+#[test]
+fn per_channel_a_scale_group() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a_scale = AttrOrInput::Attr(rctensor1(&[0.5f32, 1.0, 2.0, 4.0]));
+    qp.b0 = AttrOrInput::Attr(rctensor0(1i32));
+    for optim in &[false, true] {
+        QConvProblem {
+            shape_in: NCHW.from_n_c_hw(1, 2, &[1]).unwrap(),
+            shape_out: NCHW.from_n_c_hw(1, 4, &[1]).unwrap(),
+            kernel_format: OIHW,
+            group: 2,
+            data: arr3(&[[[7i8], [-9]]]).into_dyn(),
+            kernel: arr3(&[[[1i8]], [[-2]], [[3]], [[4]]]).into_dyn(),
+            bias: None,
+            qp: qp.clone(),
+            optim: *optim,
+        }
+        .check()
+        .unwrap();
+    }
+}
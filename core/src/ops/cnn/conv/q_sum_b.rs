@@ -57,6 +57,10 @@ impl OpState for QSumBState {
         let n = op.n.eval(&session.resolved_symbols).to_usize()?;
         op.eval(inputs, n)
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 impl TypedOp for QSumB {
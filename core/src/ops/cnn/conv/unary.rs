@@ -128,6 +128,7 @@ impl ConvUnary {
 
         let (_, m, k, n, mmm) = self.compute_geo(&b_fact)?;
         let output_shape = self.pool_spec.output_shape(&b_fact.shape)?;
+        let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&output_shape)?;
 
         let params = self
             .q_params
@@ -137,6 +138,10 @@ impl ConvUnary {
             .iter()
             .map(|(par_name, qp)| match qp {
                 AttrOrInput::Input(o) => Ok(wires[*o]),
+                AttrOrInput::Attr(t) if par_name == "a_scale" && t.len() > 1 => model.add_const(
+                    format!("{}_{}", name, par_name),
+                    self.per_output_channel(t, mmm_output_shape.len(), c_axis)?,
+                ),
                 AttrOrInput::Attr(t) => {
                     model.add_const(format!("{}_{}", name, par_name), t.clone())
                 }
@@ -187,7 +192,6 @@ impl ConvUnary {
         }
 
         let b_dt = model.outlet_fact(wires[0])?.datum_type;
-        let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&output_shape)?;
         let mut geometry = MatMulGeometry::from(SymbolicMatMulGeometry {
             b_datum_type: b_dt,
             m: m.to_dim(),
@@ -334,6 +338,17 @@ impl ConvUnary {
         Ok((mmm_output_shape, c_axis, h_axis))
     }
 
+    /// Reshape a per-output-channel tensor (per-axis scale) so it
+    /// broadcasts against the matmul output.
+    fn per_output_channel(&self, t: &Tensor, rank: usize, c_axis: usize) -> TractResult<Tensor> {
+        let mut shape = tvec!(1; rank);
+        shape[c_axis] = self.output_channels() / self.group;
+        if self.group > 1 {
+            shape[c_axis - 1] = self.group;
+        }
+        t.clone().into_shape(&shape)
+    }
+
     fn wire_geo_reshape<D: DimLike>(
         model: &mut TypedModel,
         name: &str,
@@ -569,12 +584,21 @@ impl ConvUnary {
                 }
                 let bias = patch.add_const(format!("{}.bias", &node.name), bias)?;
                 inputs.insert(2, bias);
+                let mut qp = q_params.1.clone();
+                if let AttrOrInput::Attr(a_scale) = &qp.a_scale {
+                    if a_scale.len() > 1 {
+                        let shape = if trans_data { [1, co] } else { [co, 1] };
+                        qp.a_scale = AttrOrInput::Attr(
+                            a_scale.clone().into_tensor().into_shape(&shape)?.into_arc_tensor(),
+                        );
+                    }
+                }
                 let op = QMatMul {
                     a_trans,
                     b_trans: trans_data,
                     c_trans: trans_data,
                     output_type: q_params.0,
-                    params: q_params.1.clone(),
+                    params: qp,
                 };
                 patch.wire_node(&*node.name, op, &inputs)?[0]
             } else {
@@ -0,0 +1,478 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::internal::*;
+use crate::ops::change_axes::AxisOp;
+use crate::ops::matmul::MatMul;
+use crate::ops::nn::{Reduce, Reducer};
+use tract_ndarray::prelude::*;
+use tract_num_traits::{One, Zero};
+
+/// Einstein summation expression, with one label (char) per input and output axis.
+///
+/// Ellipsis must have been resolved to explicit labels by the front-end.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expr {
+    pub inputs: TVec<TVec<char>>,
+    pub output: TVec<char>,
+}
+
+impl Expr {
+    /// Labels appearing in the inputs but not in the output, in order of first appearance.
+    pub fn summed_labels(&self) -> TVec<char> {
+        let mut labels = tvec!();
+        for &l in self.inputs.iter().flatten() {
+            if !self.output.contains(&l) && !labels.contains(&l) {
+                labels.push(l);
+            }
+        }
+        labels
+    }
+
+    /// True if a label appears twice in the same input, or in the output (diagonals).
+    pub fn has_repeated_labels(&self) -> bool {
+        self.inputs
+            .iter()
+            .chain(std::iter::once(&self.output))
+            .any(|labels| labels.iter().enumerate().any(|(ix, l)| labels[ix + 1..].contains(l)))
+    }
+
+    /// Size for each label, broadcasting dimensions of 1.
+    pub fn label_dims<D: DimLike>(&self, shapes: &[&[D]]) -> TractResult<Vec<(char, D)>> {
+        if shapes.len() != self.inputs.len() {
+            bail!("Einsum {} expects {} inputs, got {}", self, self.inputs.len(), shapes.len())
+        }
+        let mut dims: Vec<(char, D)> = vec![];
+        for (labels, shape) in self.inputs.iter().zip(shapes.iter()) {
+            if labels.len() != shape.len() {
+                bail!("Einsum {} can not apply to input of shape {:?}", self, shape)
+            }
+            for (l, d) in labels.iter().zip(shape.iter()) {
+                if let Some(pair) = dims.iter_mut().find(|pair| pair.0 == *l) {
+                    if pair.1 == D::one() {
+                        pair.1 = d.clone();
+                    } else if *d != D::one() && pair.1 != *d {
+                        bail!(
+                            "Einsum {}: inconsistent dimensions for {}: {} and {}",
+                            self,
+                            l,
+                            pair.1,
+                            d
+                        )
+                    }
+                } else {
+                    dims.push((*l, d.clone()));
+                }
+            }
+        }
+        for l in &self.output {
+            if !dims.iter().any(|pair| pair.0 == *l) {
+                bail!("Einsum {}: output label {} does not appear in inputs", self, l)
+            }
+        }
+        Ok(dims)
+    }
+}
+
+impl FromStr for Expr {
+    type Err = TractError;
+    fn from_str(s: &str) -> TractResult<Expr> {
+        let s = s.replace(" ", "");
+        let mut parts = s.splitn(2, "->");
+        let inputs = parts.next().unwrap();
+        let output =
+            parts.next().ok_or_else(|| format_err!("Einsum expression {} has no output", s))?;
+        let labels = |s: &str| -> TractResult<TVec<char>> {
+            if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+                bail!("Unexpected character {:?} in einsum expression", c)
+            }
+            Ok(s.chars().collect())
+        };
+        Ok(Expr {
+            inputs: inputs.split(',').map(labels).collect::<TractResult<_>>()?,
+            output: labels(output)?,
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (ix, input) in self.inputs.iter().enumerate() {
+            if ix > 0 {
+                write!(f, ",")?;
+            }
+            input.iter().try_for_each(|c| write!(f, "{}", c))?;
+        }
+        write!(f, "->")?;
+        self.output.iter().try_for_each(|c| write!(f, "{}", c))
+    }
+}
+
+/// Einstein summation.
+///
+/// Expressions without repeated labels declutter to axis operations and
+/// `Reduce<Sum>` for one input, plus `MatMul` for two f32 inputs. Other
+/// expressions are evaluated by a naive loop over every label.
+#[derive(Debug, Clone, new, Hash)]
+pub struct EinSum {
+    pub expr: Expr,
+}
+
+impl_dyn_hash!(EinSum);
+
+impl Op for EinSum {
+    fn name(&self) -> Cow<str> {
+        "EinSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{}", self.expr)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for EinSum {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let dt = inputs[0].datum_type();
+        if inputs.iter().any(|i| i.datum_type() != dt) {
+            bail!("Einsum {} requires inputs of the same type", self.expr)
+        }
+        let mut output = dispatch_numbers!(Self::eval_t(dt)(self, &inputs))?;
+        unsafe { output.set_datum_type(dt) };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl EinSum {
+    fn eval_t<T>(&self, inputs: &[Arc<Tensor>]) -> TractResult<Tensor>
+    where
+        T: Datum + Copy + Zero + One + std::ops::Mul<Output = T> + std::ops::Add<Output = T>,
+    {
+        let shapes: TVec<&[usize]> = inputs.iter().map(|i| i.shape()).collect();
+        let dims = self.expr.label_dims(&shapes)?;
+        let dim = |l: &char| dims.iter().find(|pair| pair.0 == *l).unwrap().1;
+        // coordinates are iterated over output labels, then summed labels
+        let summed = self.expr.summed_labels();
+        let all: TVec<char> = self.expr.output.iter().chain(summed.iter()).cloned().collect();
+        let positions: TVec<TVec<usize>> = self
+            .expr
+            .inputs
+            .iter()
+            .map(|labels| labels.iter().map(|l| all.iter().position(|a| a == l).unwrap()).collect())
+            .collect();
+        let views =
+            inputs.iter().map(|i| i.to_array_view::<T>()).collect::<TractResult<TVec<_>>>()?;
+        let output_shape: TVec<usize> = self.expr.output.iter().map(dim).collect();
+        let summed_shape: TVec<usize> = summed.iter().map(dim).collect();
+        let rank = self.expr.output.len();
+        let mut coords: TVec<usize> = tvec!(0; all.len());
+        let mut input_coords: TVec<usize> = tvec!();
+        let output = ArrayD::from_shape_fn(&*output_shape, |out_coords| {
+            coords[..rank].copy_from_slice(out_coords.slice());
+            let mut acc = T::zero();
+            for summed_coords in tract_ndarray::indices(&*summed_shape) {
+                coords[rank..].copy_from_slice(summed_coords.slice());
+                let mut product = T::one();
+                for (view, positions) in views.iter().zip(positions.iter()) {
+                    input_coords.clear();
+                    input_coords.extend(positions.iter().zip(view.shape()).map(|(&p, &d)| {
+                        if d == 1 {
+                            0
+                        } else {
+                            coords[p]
+                        }
+                    }));
+                    product = product * view[&*input_coords];
+                }
+                acc = acc + product;
+            }
+            acc
+        });
+        Ok(output.into_tensor())
+    }
+
+    fn wire_reduce(
+        &self,
+        patch: &mut TypedModelPatch,
+        name: &str,
+        mut wire: OutletId,
+        labels: &mut TVec<char>,
+        keep: impl Fn(char) -> bool,
+    ) -> TractResult<OutletId> {
+        let axes: TVec<usize> = (0..labels.len()).filter(|&ax| !keep(labels[ax])).collect();
+        if axes.len() > 0 {
+            wire = patch.wire_node(
+                format!("{}.sum", name),
+                Reduce::new(axes.clone(), Reducer::Sum),
+                &[wire],
+            )?[0];
+            for &axis in axes.iter().rev() {
+                wire =
+                    patch.wire_node(format!("{}.rm-{}", name, axis), AxisOp::Rm(axis), &[wire])?[0];
+                labels.remove(axis);
+            }
+        }
+        Ok(wire)
+    }
+
+    fn wire_permutation(
+        patch: &mut TypedModelPatch,
+        name: &str,
+        mut wire: OutletId,
+        labels: &mut TVec<char>,
+        target: &[char],
+    ) -> TractResult<OutletId> {
+        for (ix, l) in target.iter().enumerate() {
+            let from = labels.iter().position(|c| c == l).unwrap();
+            if from != ix {
+                wire = patch.wire_node(
+                    format!("{}.move-{}", name, l),
+                    AxisOp::Move(from, ix),
+                    &[wire],
+                )?[0];
+                let l = labels.remove(from);
+                labels.insert(ix, l);
+            }
+        }
+        Ok(wire)
+    }
+
+    /// Merge `len` axes from `at` in a single one (inserting it if `len` is 0).
+    fn wire_merge(
+        patch: &mut TypedModelPatch,
+        name: &str,
+        wire: OutletId,
+        at: usize,
+        len: usize,
+    ) -> TractResult<OutletId> {
+        let op = match len {
+            0 => AxisOp::Add(at),
+            1 => return Ok(wire),
+            _ => {
+                let from: TVec<TDim> = patch.outlet_fact(wire)?.shape.to_tvec()[at..][..len].into();
+                let to = tvec!(from.iter().product());
+                AxisOp::Reshape(at, from, to)
+            }
+        };
+        patch.wire_node(name, op, &[wire]).map(|w| w[0])
+    }
+
+    /// Split the axis at `at` in `dims` (removing it if `dims` is empty).
+    fn wire_split(
+        patch: &mut TypedModelPatch,
+        name: &str,
+        wire: OutletId,
+        at: usize,
+        dims: TVec<TDim>,
+    ) -> TractResult<OutletId> {
+        let op = match dims.len() {
+            0 => AxisOp::Rm(at),
+            1 => return Ok(wire),
+            _ => AxisOp::Reshape(at, tvec!(dims.iter().product()), dims),
+        };
+        patch.wire_node(name, op, &[wire]).map(|w| w[0])
+    }
+
+    fn lower_unary(
+        &self,
+        patch: &mut TypedModelPatch,
+        name: &str,
+        wire: OutletId,
+    ) -> TractResult<OutletId> {
+        let mut labels = self.expr.inputs[0].clone();
+        let wire =
+            self.wire_reduce(patch, name, wire, &mut labels, |l| self.expr.output.contains(&l))?;
+        Self::wire_permutation(patch, name, wire, &mut labels, &self.expr.output)
+    }
+
+    fn lower_binary(
+        &self,
+        patch: &mut TypedModelPatch,
+        name: &str,
+        a: OutletId,
+        b: OutletId,
+    ) -> TractResult<Option<OutletId>> {
+        let output = &self.expr.output;
+        let mut la = self.expr.inputs[0].clone();
+        let mut lb = self.expr.inputs[1].clone();
+        // sum over labels that only appear in one input
+        let (la_orig, lb_orig) = (la.clone(), lb.clone());
+        let a = self.wire_reduce(patch, &format!("{}.a", name), a, &mut la, |l| {
+            output.contains(&l) || lb_orig.contains(&l)
+        })?;
+        let b = self.wire_reduce(patch, &format!("{}.b", name), b, &mut lb, |l| {
+            output.contains(&l) || la_orig.contains(&l)
+        })?;
+        let batch: TVec<char> =
+            output.iter().filter(|l| la.contains(l) && lb.contains(l)).cloned().collect();
+        let m: TVec<char> =
+            output.iter().filter(|l| la.contains(l) && !lb.contains(l)).cloned().collect();
+        let n: TVec<char> =
+            output.iter().filter(|l| !la.contains(l) && lb.contains(l)).cloned().collect();
+        let k: TVec<char> = la.iter().filter(|l| !output.contains(l)).cloned().collect();
+        // contracted axes must match exactly, matmul only broadcasts batch axes
+        let a_shape = patch.outlet_fact(a)?.shape.to_tvec();
+        let b_shape = patch.outlet_fact(b)?.shape.to_tvec();
+        for l in &k {
+            let ax_a = la.iter().position(|c| c == l).unwrap();
+            let ax_b = lb.iter().position(|c| c == l).unwrap();
+            if a_shape[ax_a] != b_shape[ax_b] {
+                return Ok(None);
+            }
+        }
+        let a_target: TVec<char> = batch.iter().chain(m.iter()).chain(k.iter()).cloned().collect();
+        let b_target: TVec<char> = batch.iter().chain(k.iter()).chain(n.iter()).cloned().collect();
+        let a = Self::wire_permutation(patch, &format!("{}.a", name), a, &mut la, &a_target)?;
+        let b = Self::wire_permutation(patch, &format!("{}.b", name), b, &mut lb, &b_target)?;
+        let m_dims: TVec<TDim> =
+            patch.outlet_fact(a)?.shape.to_tvec()[batch.len()..][..m.len()].into();
+        let n_dims: TVec<TDim> =
+            patch.outlet_fact(b)?.shape.to_tvec()[batch.len() + k.len()..].into();
+        let a = Self::wire_merge(patch, &format!("{}.a.m", name), a, batch.len(), m.len())?;
+        let a = Self::wire_merge(patch, &format!("{}.a.k", name), a, batch.len() + 1, k.len())?;
+        let b = Self::wire_merge(patch, &format!("{}.b.k", name), b, batch.len(), k.len())?;
+        let b = Self::wire_merge(patch, &format!("{}.b.n", name), b, batch.len() + 1, n.len())?;
+        let c = patch.wire_node(format!("{}.matmul", name), MatMul::default(), &[a, b])?[0];
+        let c = Self::wire_split(patch, &format!("{}.c.n", name), c, batch.len() + 1, n_dims)?;
+        let c = Self::wire_split(patch, &format!("{}.c.m", name), c, batch.len(), m_dims)?;
+        let mut lc: TVec<char> = batch.iter().chain(m.iter()).chain(n.iter()).cloned().collect();
+        Self::wire_permutation(patch, name, c, &mut lc, output).map(Some)
+    }
+}
+
+impl TypedOp for EinSum {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let shapes: TVec<TVec<TDim>> = inputs.iter().map(|i| i.shape.to_tvec()).collect();
+        let shapes: TVec<&[TDim]> = shapes.iter().map(|s| &**s).collect();
+        let dims = self.expr.label_dims(&shapes)?;
+        let shape: TVec<TDim> = self
+            .expr
+            .output
+            .iter()
+            .map(|l| dims.iter().find(|pair| pair.0 == *l).unwrap().1.clone())
+            .collect();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.expr.has_repeated_labels() || self.expr.inputs.len() > 2 {
+            return Ok(None);
+        }
+        let facts = model.node_input_facts(node.id)?;
+        let mut patch = TypedModelPatch::default();
+        let wire = if facts.len() == 1 {
+            let input = patch.tap_model(model, node.inputs[0])?;
+            self.lower_unary(&mut patch, &node.name, input)?
+        } else {
+            // matrix multiplication kernels are f32 only
+            if facts.iter().any(|f| f.datum_type != f32::datum_type()) {
+                return Ok(None);
+            }
+            let a = patch.tap_model(model, node.inputs[0])?;
+            let b = patch.tap_model(model, node.inputs[1])?;
+            if let Some(wire) = self.lower_binary(&mut patch, &node.name, a, b)? {
+                wire
+            } else {
+                return Ok(None);
+            }
+        };
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(expr: &str, inputs: TVec<Tensor>, expected: Tensor) -> TractResult<()> {
+        let op = EinSum::new(expr.parse()?);
+        let lowerable = !op.expr.has_repeated_labels()
+            && (inputs.len() == 1 || inputs.iter().all(|i| i.datum_type() == f32::datum_type()));
+        let found = op.eval(inputs.iter().map(|t| t.clone().into_arc_tensor()).collect())?;
+        found[0].close_enough(&expected, true)?;
+        let mut model = TypedModel::default();
+        let wires = inputs
+            .iter()
+            .enumerate()
+            .map(|(ix, t)| {
+                model.add_source(format!("i{}", ix), TypedFact::dt_shape(t.datum_type(), t.shape()))
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        let output = model.wire_node("einsum", op, &wires)?;
+        model.set_output_outlets(&output)?;
+        let model = model.declutter()?;
+        assert_eq!(model.nodes().iter().any(|n| n.op_is::<EinSum>()), !lowerable);
+        let found = model.into_runnable()?.run(inputs)?;
+        found[0].close_enough(&expected, true)
+    }
+
+    #[test]
+    fn parse_and_display() -> TractResult<()> {
+        let expr: Expr = "bij, bjk -> bik".parse()?;
+        assert_eq!(expr.inputs, tvec!(tvec!('b', 'i', 'j'), tvec!('b', 'j', 'k')));
+        assert_eq!(expr.to_string(), "bij,bjk->bik");
+        assert!("ij".parse::<Expr>().is_err());
+        assert!("iλ->i".parse::<Expr>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn matmul() -> TractResult<()> {
+        check(
+            "ij,jk->ik",
+            tvec!(tensor2(&[[0f32, 1., 2.], [3., 4., 5.]]), tensor2(&[[0f32], [1.], [2.]])),
+            tensor2(&[[5f32], [14.]]),
+        )
+    }
+
+    #[test]
+    fn transpose_and_sum() -> TractResult<()> {
+        check("ij->ji", tvec!(tensor2(&[[1f32, 2.], [3., 4.]])), tensor2(&[[1f32, 3.], [2., 4.]]))?;
+        check("ij->j", tvec!(tensor2(&[[1f32, 2.], [3., 4.]])), tensor1(&[4f32, 6.]))
+    }
+
+    #[test]
+    fn batched_transposed_output() -> TractResult<()> {
+        let a = tensor1(&(0..12).map(|x| x as f32).collect::<Vec<_>>()).into_shape(&[2, 2, 3])?;
+        let b = tensor1(&[1f32, 0., 1., 0., 1., 0.]).into_shape(&[2, 3, 1])?;
+        check("bij,bjk->kbi", tvec!(a, b), tensor1(&[2f32, 8., 7., 10.]).into_shape(&[1, 2, 2])?)
+    }
+
+    #[test]
+    fn outer_and_broadcast() -> TractResult<()> {
+        check(
+            "i,j->ij",
+            tvec!(tensor1(&[1f32, 2.]), tensor1(&[3f32, 4., 5.])),
+            tensor2(&[[3f32, 4., 5.], [6., 8., 10.]]),
+        )?;
+        check(
+            "bi,bi->b",
+            tvec!(tensor2(&[[1f32, 2.]]), tensor2(&[[1f32, 1.], [2., 3.]])),
+            tensor1(&[3f32, 8.]),
+        )?;
+        check(
+            "bi,bi->b",
+            tvec!(tensor2(&[[1f64, 2.]]), tensor2(&[[1f64, 1.], [2., 3.]])),
+            tensor1(&[3f64, 8.]),
+        )
+    }
+
+    #[test]
+    fn diagonal_fallback() -> TractResult<()> {
+        check("ii->i", tvec!(tensor2(&[[1f32, 2.], [3., 4.]])), tensor1(&[1f32, 4.]))?;
+        check("ii->", tvec!(tensor2(&[[1i32, 2], [3, 4]])), tensor0(5i32))
+    }
+}
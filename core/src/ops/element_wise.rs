@@ -84,12 +84,33 @@ impl Op for ElementWiseOp {
 
 impl_dyn_hash!(ElementWiseOp);
 
+impl ElementWiseOp {
+    /// Mini ops with no bf16 implementation compute in f32.
+    fn upcast_bf16(&self, dt: DatumType) -> bool {
+        dt == DatumType::BF16 && self.0.output_type(dt).is_none()
+    }
+
+    fn output_type(&self, input_type: DatumType) -> DatumType {
+        if self.upcast_bf16(input_type) {
+            self.0.output_type(DatumType::F32).unwrap_or(input_type)
+        } else {
+            self.0.output_type(input_type).unwrap_or(input_type)
+        }
+    }
+}
+
 impl EvalOp for ElementWiseOp {
     fn is_stateless(&self) -> bool {
         true
     }
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        if self.upcast_bf16(inputs[0].datum_type()) {
+            let input = inputs[0].cast_to::<f32>()?.into_owned().into_arc_tensor();
+            let output = self.eval(tvec!(input))?.remove(0);
+            let dt = self.output_type(inputs[0].datum_type());
+            return Ok(tvec!(output.cast_to_dt(dt)?.into_owned().into_arc_tensor()));
+        }
         if let Some(_dt) = self.0.output_type(inputs[0].datum_type()) {
             Ok(tvec!(self.0.eval_out_of_place(&inputs[0])?.into_arc_tensor()))
         } else {
@@ -103,9 +124,7 @@ impl EvalOp for ElementWiseOp {
 impl TypedOp for ElementWiseOp {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut fact = inputs[0].clone();
-        if let Some(dt) = self.0.output_type(fact.datum_type) {
-            fact.datum_type = dt;
-        }
+        fact.datum_type = self.output_type(fact.datum_type);
         Ok(tvec!(fact))
     }
 
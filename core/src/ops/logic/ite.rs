@@ -0,0 +1,329 @@
+use crate::internal::*;
+
+/// Conditional execution of one of two sub-models.
+///
+/// Input 0 is the (scalar) condition. Bodies inputs are wired to the op inputs
+/// designated by the input mappings. Both bodies must produce outputs of the
+/// same types and shapes.
+#[derive(Debug, Clone, new, Hash)]
+pub struct IfThenElse {
+    pub then_body: TypedModel,
+    pub then_input_mapping: Vec<usize>,
+    pub else_body: TypedModel,
+    pub else_input_mapping: Vec<usize>,
+}
+
+impl_dyn_hash!(IfThenElse);
+
+impl IfThenElse {
+    pub fn to_codegen_op(&self, optimize_inner: bool) -> TractResult<LirIfThenElse> {
+        let plan = |body: &TypedModel| -> TractResult<_> {
+            let body = if optimize_inner { body.clone().optimize()? } else { body.clone() };
+            Ok(Arc::new(SimplePlan::new(body)?))
+        };
+        Ok(LirIfThenElse {
+            then_plan: plan(&self.then_body)?,
+            then_input_mapping: self.then_input_mapping.clone(),
+            else_plan: plan(&self.else_body)?,
+            else_input_mapping: self.else_input_mapping.clone(),
+        })
+    }
+
+    fn inline_body(
+        model: &TypedModel,
+        node: &TypedNode,
+        body: &TypedModel,
+        input_mapping: &[usize],
+    ) -> TractResult<TypedModelPatch> {
+        let mut patch = TypedModelPatch::default();
+        let mut mapping = HashMap::<OutletId, OutletId>::new();
+        for (ix, slot) in input_mapping.iter().enumerate() {
+            let tap = patch.tap_model(model, node.inputs[*slot])?;
+            mapping.insert(body.input_outlets()?[ix], tap);
+        }
+        for n in body.eval_order()? {
+            let body_node = body.node(n);
+            if body.input_outlets()?.iter().any(|o| o.node == n) {
+                continue;
+            }
+            let inputs = body_node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+            let name = format!("{}.{}", node.name, body_node.name);
+            let wires = patch.wire_node(name, body_node.op.clone(), &inputs)?;
+            for (slot, wire) in wires.iter().enumerate() {
+                mapping.insert(OutletId::new(n, slot), *wire);
+            }
+        }
+        for (ix, output) in body.output_outlets()?.iter().enumerate() {
+            patch.shunt_outside(model, OutletId::new(node.id, ix), mapping[output])?;
+        }
+        Ok(patch)
+    }
+}
+
+impl Op for IfThenElse {
+    fn name(&self) -> Cow<str> {
+        "IfThenElse".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("Then branch inputs: {:?}", self.then_input_mapping),
+            format!("Else branch inputs: {:?}", self.else_input_mapping),
+        ])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for IfThenElse {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(IfThenElseState(self.to_codegen_op(false)?))))
+    }
+}
+
+/// Plans of the branches, built once per session.
+#[derive(Debug, Clone)]
+struct IfThenElseState(LirIfThenElse);
+
+impl OpState for IfThenElseState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        _op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        self.0.eval(inputs)
+    }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
+}
+
+impl TypedOp for IfThenElse {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != bool::datum_type()
+            || inputs[0].shape.iter().any(|d| d != 1.to_dim())
+        {
+            bail!("IfThenElse condition must be a single boolean, got {:?}", inputs[0]);
+        }
+        let then_outputs = self.then_body.output_outlets()?.len();
+        let else_outputs = self.else_body.output_outlets()?.len();
+        if then_outputs != else_outputs {
+            bail!(
+                "Then and else branches have different output counts ({} and {})",
+                then_outputs,
+                else_outputs
+            );
+        }
+        (0..then_outputs)
+            .map(|ix| {
+                let then_fact = self.then_body.output_fact(ix)?;
+                let else_fact = self.else_body.output_fact(ix)?;
+                if then_fact.datum_type != else_fact.datum_type
+                    || then_fact.shape != else_fact.shape
+                {
+                    bail!(
+                        "Then and else branches output #{} mismatch ({:?} and {:?})",
+                        ix,
+                        then_fact,
+                        else_fact
+                    );
+                }
+                Ok(TypedFact::dt_shape(then_fact.datum_type, then_fact.shape.clone()))
+            })
+            .collect()
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(cond) = &model.outlet_fact(node.inputs[0])?.konst {
+            let patch = if cond.cast_to_scalar::<bool>()? {
+                Self::inline_body(model, node, &self.then_body, &self.then_input_mapping)?
+            } else {
+                Self::inline_body(model, node, &self.else_body, &self.else_input_mapping)?
+            };
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    fn concretize_dims(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        let op = Self {
+            then_body: self.then_body.concretize_dims(values)?,
+            else_body: self.else_body.concretize_dims(values)?,
+            ..self.clone()
+        };
+        target.wire_node(&node.name, op, &inputs)
+    }
+
+    fn nested_model_multipliers(&self, _inputs: &[&TypedFact]) -> Vec<(Cow<str>, f64)> {
+        vec![("then".into(), 1.0), ("else".into(), 1.0)]
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        Ok(Some(TypedModelPatch::replace_single_op(
+            model,
+            node,
+            &node.inputs,
+            self.to_codegen_op(true)?,
+        )?))
+    }
+}
+
+/// IfThenElse with both branches planned.
+#[derive(Debug, Clone, Hash)]
+pub struct LirIfThenElse {
+    pub then_plan: Arc<TypedSimplePlan<TypedModel>>,
+    pub then_input_mapping: Vec<usize>,
+    pub else_plan: Arc<TypedSimplePlan<TypedModel>>,
+    pub else_input_mapping: Vec<usize>,
+}
+
+impl_dyn_hash!(LirIfThenElse);
+
+impl Op for LirIfThenElse {
+    fn name(&self) -> Cow<str> {
+        "IfThenElse".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("Then branch inputs: {:?}", self.then_input_mapping),
+            format!("Else branch inputs: {:?}", self.else_input_mapping),
+        ])
+    }
+
+    op_core_lir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LirIfThenElse {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let cond = inputs[0].cast_to_scalar::<bool>()?;
+        let (plan, input_mapping) = if cond {
+            (&self.then_plan, &self.then_input_mapping)
+        } else {
+            (&self.else_plan, &self.else_input_mapping)
+        };
+        let inputs: TVec<Tensor> =
+            input_mapping.iter().map(|slot| inputs[*slot].clone().into_tensor()).collect();
+        plan.run(inputs)
+    }
+}
+
+impl TypedOp for LirIfThenElse {
+    as_op!();
+
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let body = self.then_plan.model();
+        (0..body.output_outlets()?.len())
+            .map(|ix| {
+                let fact = body.output_fact(ix)?;
+                Ok(TypedFact::dt_shape(fact.datum_type, fact.shape.clone()))
+            })
+            .collect()
+    }
+
+    fn nested_model_multipliers(&self, _inputs: &[&TypedFact]) -> Vec<(Cow<str>, f64)> {
+        vec![("then".into(), 1.0), ("else".into(), 1.0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    fn branches() -> TractResult<IfThenElse> {
+        let mut then_body = TypedModel::default();
+        let a = then_body.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let b = then_body.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let sum = then_body.wire_node("sum", math::add::bin_typed(), &[a, b])?;
+        then_body.set_output_outlets(&sum)?;
+        let mut else_body = TypedModel::default();
+        let a = else_body.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let b = else_body.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let prod = else_body.wire_node("prod", math::mul::bin_typed(), &[a, b])?;
+        else_body.set_output_outlets(&prod)?;
+        Ok(IfThenElse::new(then_body, vec![1, 2], else_body, vec![2, 1]))
+    }
+
+    #[test]
+    fn eval_both_branches() -> TractResult<()> {
+        let op = branches()?.to_codegen_op(false)?;
+        let a = rctensor1(&[1f32, 2.0]);
+        let b = rctensor1(&[3f32, 4.0]);
+        let res = op.eval(tvec!(rctensor0(true), a.clone(), b.clone()))?;
+        assert_eq!(res[0], rctensor1(&[4f32, 6.0]));
+        let res = op.eval(tvec!(rctensor0(false), a, b))?;
+        assert_eq!(res[0], rctensor1(&[3f32, 8.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn run_optimized_both_branches() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let cond = model.add_source("cond", TypedFact::dt_scalar(bool::datum_type()))?;
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let ite = model.wire_node("ite", branches()?, &[cond, a, b])?;
+        model.set_output_outlets(&ite)?;
+        let model = model.into_optimized()?;
+        assert!(model.nodes().iter().any(|n| n.op_is::<LirIfThenElse>()));
+        let plan = SimplePlan::new(model)?;
+        let mut state = SimpleState::new(&plan)?;
+        for (cond, expected) in &[(true, [4f32, 6.0]), (false, [3f32, 8.0]), (true, [4f32, 6.0])] {
+            let res =
+                state.run(tvec!(tensor0(*cond), tensor1(&[1f32, 2.0]), tensor1(&[3f32, 4.0])))?;
+            assert_eq!(*res[0], tensor1(expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn declutter_const_condition() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let cond = model.add_const("cond", tensor0(false))?;
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let ite = model.wire_node("ite", branches()?, &[cond, a, b])?;
+        model.set_output_outlets(&ite)?;
+        let model = model.declutter()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<IfThenElse>()));
+        let res =
+            model.into_runnable()?.run(tvec!(tensor1(&[1f32, 2.0]), tensor1(&[3f32, 4.0])))?;
+        assert_eq!(res[0], rctensor1(&[3f32, 8.0]));
+        Ok(())
+    }
+}
@@ -443,6 +443,7 @@ element_wise!(rsqrt, Rsqrt, [f16, f32, f64] => |_, xs| {
     Ok(())
 };
 q: [i8, u8] => |x : f32| x.sqrt().recip();
+declutter: crate::ops::nn::declutter_decomposed_layer_norm;
 validation: Validation::Rounding
 );
 
@@ -580,6 +581,29 @@ element_wise!(sign, Sign, [f16, f32, f64] => |_, xs| {
 };
 q: [i8, u8] => f32::signum);
 
+/// Error function, as a polynomial approximation (Abramowitz and Stegun 7.1.28).
+#[allow(non_upper_case_globals)]
+pub fn erf_f32(x: f32) -> f32 {
+    const a1: f32 = 0.0705230784;
+    const a2: f32 = 0.0422820123;
+    const a3: f32 = 0.0092705272;
+    const a4: f32 = 0.0001520143;
+    const a5: f32 = 0.0002765672;
+    const a6: f32 = 0.0000430638;
+
+    let signum = x.signum();
+    let x = x.abs();
+    let y = a6 * x;
+    let y = (a5 + y) * x;
+    let y = (a4 + y) * x;
+    let y = (a3 + y) * x;
+    let y = (a2 + y) * x;
+    let y = (a1 + y) * x;
+    let y = 1.0 - (y + 1.0).powi(16).recip();
+
+    y.copysign(signum)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -631,4 +655,39 @@ mod tests {
         assert!(op.mini_op.downcast_ref::<FlippedShiftRight>().is_some());
         Ok(())
     }
+
+    #[test]
+    fn bf16_through_f32() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(DatumType::BF16, &[3usize]))?;
+        let x = model.wire_node("exp", exp(), &[x])?[0];
+        let y = model.wire_node("add", add::bin_typed(), &[x, x])?[0];
+        let z = model.wire_node("gt", crate::ops::logic::greater::bin_typed(), &[y, x])?[0];
+        model.set_output_outlets(&[y, z])?;
+        assert_eq!(model.outlet_fact(y)?.datum_type, DatumType::BF16);
+        assert_eq!(model.outlet_fact(z)?.datum_type, bool::datum_type());
+        let input = tensor1(&[0f32, 1., -1.]).cast_to_dt(DatumType::BF16)?.into_owned();
+        let result = SimplePlan::new(&model)?.run(tvec!(input))?;
+        assert_eq!(result[0].datum_type(), DatumType::BF16);
+        let expected = tensor1(&[2f32, 2. * 1f32.exp(), 2. * (-1f32).exp()]);
+        assert_eq!(*result[0], *expected.cast_to_dt(DatumType::BF16)?);
+        assert_eq!(*result[1], tensor1(&[true, true, true]));
+        Ok(())
+    }
+
+    #[test]
+    fn bf16_is_float_like() -> TractResult<()> {
+        let half = |v: &[f32]| -> TractResult<Tensor> {
+            Ok(tensor1(v).cast_to_dt(DatumType::BF16)?.into_owned())
+        };
+        let sum = crate::ops::nn::Reducer::Sum.reduce(&[0], &half(&[1., 2., 3.])?)?;
+        assert_eq!(sum.cast_to_scalar::<f32>()?, 6.0);
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(DatumType::BF16, &[2usize]))?;
+        let y = model.wire_node("mul", mul::unary(half(&[0.5])?.into_arc_tensor()), &[x])?[0];
+        model.set_output_outlets(&[y])?;
+        let result = SimplePlan::new(model.declutter()?)?.run(tvec!(half(&[1., 3.])?))?;
+        assert_eq!(*result[0], half(&[0.5, 1.5])?);
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ use crate::internal::*;
 use ndarray::*;
 
 use tract_linalg::mmm::{FusedSpec, MatMatMul, MatrixStoreSpec, RoundingPolicy, ScratchSpace};
+use tract_linalg::multithread::Executor;
 
 #[derive(PartialEq, Clone, Hash, Debug)]
 pub enum ProtoFusedSpec {
@@ -155,6 +156,7 @@ impl OpState for State {
             eval(
                 op,
                 &geometry,
+                &session.executor,
                 scratch.as_mut(),
                 &inputs,
                 &shape,
@@ -164,6 +166,10 @@ impl OpState for State {
             )
         }
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 impl EvalOp for LirMatMulUnary {
@@ -185,6 +191,7 @@ impl EvalOp for LirMatMulUnary {
         eval(
             self,
             &geometry,
+            &Executor::SingleThread,
             scratch.as_mut(),
             &*inputs,
             self.c_fact.shape.as_concrete().unwrap(),
@@ -198,6 +205,7 @@ impl EvalOp for LirMatMulUnary {
 fn eval(
     op: &LirMatMulUnary,
     geometry: &ConcreteMatMulGeometry,
+    executor: &Executor,
     scratch: &mut dyn ScratchSpace,
     inputs: &[Arc<Tensor>],
     c_shape: &[usize],
@@ -232,7 +240,8 @@ fn eval(
                 }
                 let (pa, fused) = ops.iter().next().unwrap();
                 let f: Vec<FusedSpec> = fused.iter().map(|f| f.resolve(inputs)).collect::<Vec<_>>();
-                op.mmm.run_with_scratch_space(
+                op.mmm.run_with_executor(
+                    executor,
                     geometry.m,
                     geometry.k,
                     geometry.n,
@@ -248,7 +257,8 @@ fn eval(
         } else {
             let (pa, fused) = op.micro_ops.iter().next().unwrap();
             let f: Vec<FusedSpec> = fused.iter().map(|f| f.resolve(inputs)).collect::<Vec<_>>();
-            op.mmm.run_with_scratch_space(
+            op.mmm.run_with_executor(
+                executor,
                 geometry.m,
                 geometry.k,
                 geometry.n,
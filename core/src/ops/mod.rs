@@ -20,6 +20,7 @@ pub mod change_axes;
 pub mod cnn;
 pub mod downsample;
 pub mod dummy;
+pub mod einsum;
 pub mod identity;
 pub mod konst;
 pub mod logic;
@@ -72,6 +73,16 @@ pub trait OpState: fmt::Debug + Send + dyn_clone::DynClone {
         op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Can the state be evaluated with a private copy of the session (inputs,
+    /// resolved symbols and executor), on a worker thread of a
+    /// `ParallelState` for instance ?
+    ///
+    /// States sharing data through the session, like variables, must keep the
+    /// default.
+    fn runs_on_session_copy(&self) -> bool {
+        false
+    }
 }
 dyn_clone::clone_trait_object!(OpState);
 
@@ -0,0 +1,152 @@
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Scaled dot-product attention: softmax(q.kT * scale + mask).v
+///
+/// Inputs are q [.., Lq, D], k [.., Lk, D], v [.., Lk, Dv] sharing the same
+/// leading axes, and an optional mask broadcastable to [.., Lq, Lk], either
+/// boolean (false masks out) or additive. Output is [.., Lq, Dv].
+#[derive(Debug, Clone, new, Educe)]
+#[educe(Hash)]
+pub struct ScaledDotProductAttention {
+    /// Defaults to 1 / sqrt(D).
+    #[educe(Hash(method = "hash_opt_f32"))]
+    pub scale: Option<f32>,
+    /// Mask out keys past the query position (aligned on the top left corner).
+    pub causal: bool,
+}
+
+impl_dyn_hash!(ScaledDotProductAttention);
+
+impl Op for ScaledDotProductAttention {
+    fn name(&self) -> Cow<str> {
+        "ScaledDotProductAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("scale: {:?} causal: {}", self.scale, self.causal)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ScaledDotProductAttention {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let dt = inputs[0].datum_type();
+        let rank = inputs[0].rank();
+        let q = inputs[0].cast_to::<f32>()?;
+        let k = inputs[1].cast_to::<f32>()?;
+        let v = inputs[2].cast_to::<f32>()?;
+        let (lq, d) = (q.shape()[rank - 2], q.shape()[rank - 1]);
+        let (lk, dv) = (k.shape()[rank - 2], v.shape()[rank - 1]);
+        let leading = &q.shape()[..rank - 2];
+        let batch = leading.iter().product::<usize>();
+        let q = q.to_array_view::<f32>()?.into_shape((batch, lq, d))?;
+        let k = k.to_array_view::<f32>()?.into_shape((batch, lk, d))?;
+        let v = v.to_array_view::<f32>()?.into_shape((batch, lk, dv))?;
+        let mask = if let Some(mask) = inputs.get(3) {
+            let mask = if mask.datum_type() == bool::datum_type() {
+                mask.to_array_view::<bool>()?.mapv(|m| if m { 0.0 } else { f32::NEG_INFINITY })
+            } else {
+                mask.cast_to::<f32>()?.to_array_view::<f32>()?.to_owned()
+            };
+            let scores_shape: TVec<usize> = leading.iter().copied().chain(tvec!(lq, lk)).collect();
+            let mask = mask
+                .broadcast(&*scores_shape)
+                .with_context(|| format!("Can not broadcast mask to {:?}", scores_shape))?;
+            Some(mask.to_owned().into_shape((batch, lq, lk))?)
+        } else {
+            None
+        };
+        let scale = self.scale.unwrap_or_else(|| (d as f32).sqrt().recip());
+        let mut output = Array3::<f32>::zeros((batch, lq, dv));
+        for b in 0..batch {
+            let mut scores = q.slice(s![b, .., ..]).dot(&k.slice(s![b, .., ..]).t()) * scale;
+            if let Some(mask) = &mask {
+                scores += &mask.slice(s![b, .., ..]);
+            }
+            for (i, mut row) in scores.outer_iter_mut().enumerate() {
+                if self.causal {
+                    row.slice_mut(s![(i + 1).min(lk)..]).fill(f32::NEG_INFINITY);
+                }
+                let max = row.fold(f32::NEG_INFINITY, |m, &s| m.max(s));
+                if max == f32::NEG_INFINITY {
+                    // everything is masked out
+                    row.fill(0.0);
+                    continue;
+                }
+                row.mapv_inplace(|s| (s - max).exp());
+                let sum = row.sum();
+                row.mapv_inplace(|s| s / sum);
+            }
+            output.slice_mut(s![b, .., ..]).assign(&scores.dot(&v.slice(s![b, .., ..])));
+        }
+        let output_shape: TVec<usize> = leading.iter().copied().chain(tvec!(lq, dv)).collect();
+        let output = output.into_shape(&*output_shape)?.into_tensor().cast_to_dt(dt)?.into_owned();
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ScaledDotProductAttention {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        if rank < 2 || inputs[1].rank() != rank || inputs[2].rank() != rank {
+            bail!("Expect q, k and v of the same rank (at least 2), got {:?}", inputs)
+        }
+        if inputs[0].shape[..rank - 2] != inputs[1].shape[..rank - 2]
+            || inputs[1].shape[..rank - 1] != inputs[2].shape[..rank - 1]
+        {
+            bail!("Inconsistent q, k and v shapes: {:?}", inputs)
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[rank - 1] = inputs[2].shape[rank - 1].clone();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let rank = inputs[0].rank();
+        let dt = inputs[0].datum_type;
+        let scores: TDim =
+            inputs[0].shape[..rank - 1].iter().product::<TDim>() * &inputs[1].shape[rank - 2];
+        let dims = inputs[0].shape[rank - 1].clone() + &inputs[2].shape[rank - 1];
+        Ok(tvec!((Cost::FMA(dt), scores.clone() * dims), (Cost::Div(dt), scores)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn causal() -> TractResult<()> {
+        let q = rctensor3(&[[[1f32, 0.0], [0.0, 1.0]]]);
+        let k = rctensor3(&[[[1f32, 0.0], [0.0, 1.0]]]);
+        let v = rctensor3(&[[[1f32, 2.0], [3.0, 4.0]]]);
+        let op = ScaledDotProductAttention::new(Some(1.0), true);
+        let output = op.eval(tvec!(q, k, v))?;
+        // first query only sees the first key, second one gets softmax([0, 1])
+        let p = 1.0f32.exp() / (1.0 + 1.0f32.exp());
+        let expected = tensor3(&[[[1f32, 2.0], [1.0 + 2.0 * p, 2.0 + 2.0 * p]]]);
+        output[0].close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_mask() -> TractResult<()> {
+        let q = rctensor3(&[[[1f32, 0.0]]]);
+        let k = rctensor3(&[[[1f32, 0.0], [0.0, 1.0]]]);
+        let v = rctensor3(&[[[1f32, 2.0], [3.0, 4.0]]]);
+        let mask = rctensor1(&[false, true]);
+        let op = ScaledDotProductAttention::new(None, false);
+        let output = op.eval(tvec!(q, k, v, mask))?;
+        assert_eq!(*output[0], tensor3(&[[[3f32, 4.0]]]));
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use super::DataFormat;
+use crate::internal::*;
+use crate::ops::change_axes::perm_to_ops;
+
+/// Channel layout of the blocks for DepthToSpace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthToSpaceMode {
+    /// depth, column, row: channels are split as [block, block, c].
+    DCR,
+    /// column, row, depth: channels are split as [c, block, block].
+    CRD,
+}
+
+// Axes are followed by label through the reshapes: n, c, h and w for the
+// data axes, x and y for the block rows and columns.
+fn wire_axis_ops(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    splits: TVec<AxisOp>,
+    (labels, target_labels): (&[char], &[char]),
+    merges: TVec<AxisOp>,
+) -> TractResult<TVec<OutletId>> {
+    let perm = target_labels
+        .iter()
+        .map(|l| labels.iter().position(|x| x == l).unwrap())
+        .collect::<TVec<_>>();
+    let ops = splits.into_iter().chain(perm_to_ops(&perm)).chain(merges);
+    let mut wire = tvec!(input);
+    for (ix, op) in ops.enumerate() {
+        wire = target.wire_node(format!("{}.{}-{}", prefix, op.name(), ix), op, &wire)?;
+    }
+    Ok(wire)
+}
+
+fn fmt_labels(fmt: DataFormat, c: &[char], h: &[char], w: &[char]) -> TVec<char> {
+    let mut labels = tvec!();
+    if fmt.has_n() {
+        labels.push('n');
+    }
+    if !fmt.c_is_last() {
+        labels.extend(c.iter().copied());
+    }
+    labels.extend(h.iter().copied());
+    labels.extend(w.iter().copied());
+    if fmt.c_is_last() {
+        labels.extend(c.iter().copied());
+    }
+    labels
+}
+
+/// Wire a DepthToSpace as a sequence of reshapes and axis moves: channels
+/// are split in blocks of `block_size * block_size` that are spread over the
+/// spatial axes.
+pub fn wire_depth_to_space(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    fmt: DataFormat,
+    block_size: usize,
+    mode: DepthToSpaceMode,
+) -> TractResult<TVec<OutletId>> {
+    let shape = fmt.shape(target.outlet_fact(input)?.shape.to_tvec())?;
+    if shape.hw_rank() != 2 {
+        bail!("DepthToSpace expects two spatial axes, got {:?}", shape)
+    }
+    let c = shape.c().to_usize().context("DepthToSpace expects a known channel count")?;
+    let b = block_size;
+    if b == 0 || c % (b * b) != 0 {
+        bail!("DepthToSpace can not split {} channels in {}x{} blocks", c, b, b)
+    }
+    let (h, w) = (shape.hw_dims()[0].clone(), shape.hw_dims()[1].clone());
+    let (split, c_labels) = match mode {
+        DepthToSpaceMode::DCR => (tvec!(b.into(), b.into(), (c / b / b).into()), ['x', 'y', 'c']),
+        DepthToSpaceMode::CRD => (tvec!((c / b / b).into(), b.into(), b.into()), ['c', 'x', 'y']),
+    };
+    let labels = fmt_labels(fmt, &c_labels, &['h'], &['w']);
+    let target_labels = fmt_labels(fmt, &['c'], &['h', 'x'], &['w', 'y']);
+    let h_axis = target_labels.iter().position(|&l| l == 'h').unwrap();
+    let splits = tvec!(AxisOp::Reshape(shape.c_axis(), tvec!(c.into()), split));
+    let merges = tvec!(
+        AxisOp::Reshape(h_axis, tvec!(h.clone(), b.into()), tvec!(h * b)),
+        AxisOp::Reshape(h_axis + 1, tvec!(w.clone(), b.into()), tvec!(w * b)),
+    );
+    wire_axis_ops(prefix, target, input, splits, (&labels, &target_labels), merges)
+}
+
+/// Wire a SpaceToDepth as a sequence of reshapes and axis moves: spatial
+/// axes are cut in `block_size * block_size` blocks moved to the channels, in
+/// depth, column, row order.
+pub fn wire_space_to_depth(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    fmt: DataFormat,
+    block_size: usize,
+) -> TractResult<TVec<OutletId>> {
+    let shape = fmt.shape(target.outlet_fact(input)?.shape.to_tvec())?;
+    if shape.hw_rank() != 2 {
+        bail!("SpaceToDepth expects two spatial axes, got {:?}", shape)
+    }
+    let b = block_size;
+    let (h, w) = (shape.hw_dims()[0].clone(), shape.hw_dims()[1].clone());
+    for d in &[&h, &w] {
+        if b == 0 || d.to_usize().map(|d| d % b != 0).unwrap_or(false) {
+            bail!("SpaceToDepth can not cut spatial axes of {:?} in {}x{} blocks", shape, b, b)
+        }
+    }
+    let c = shape.c().clone();
+    let labels = fmt_labels(fmt, &['c'], &['h', 'x'], &['w', 'y']);
+    let target_labels = fmt_labels(fmt, &['x', 'y', 'c'], &['h'], &['w']);
+    let h_axis = shape.h_axis();
+    let c_axis = target_labels.iter().position(|&l| l == 'x').unwrap();
+    let splits = tvec!(
+        AxisOp::Reshape(h_axis, tvec!(h.clone()), tvec!(h / b, b.into())),
+        AxisOp::Reshape(h_axis + 2, tvec!(w.clone()), tvec!(w / b, b.into())),
+    );
+    let merges =
+        tvec!(AxisOp::Reshape(c_axis, tvec!(b.into(), b.into(), c.clone()), tvec!(c * b * b)));
+    wire_axis_ops(prefix, target, input, splits, (&labels, &target_labels), merges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::prelude::*;
+
+    fn run(
+        input: Tensor,
+        wire: impl Fn(&mut TypedModel, OutletId) -> TractResult<TVec<OutletId>>,
+    ) -> TractResult<Tensor> {
+        let mut model = TypedModel::default();
+        let source =
+            model.add_source("input", TypedFact::dt_shape(f32::datum_type(), input.shape()))?;
+        let output = wire(&mut model, source)?;
+        model.set_output_outlets(&output)?;
+        let mut output = model.declutter()?.into_runnable()?.run(tvec!(input))?;
+        Ok(output.remove(0).into_tensor())
+    }
+
+    // reference implementations from the ONNX spec, NCHW
+    fn depth_to_space_ref(input: &ArrayD<f32>, b: usize, mode: DepthToSpaceMode) -> ArrayD<f32> {
+        let (n, c, h, w) = (input.shape()[0], input.shape()[1], input.shape()[2], input.shape()[3]);
+        let c_out = c / b / b;
+        ArrayD::from_shape_fn(&[n, c_out, h * b, w * b][..], |ix| {
+            let (y, x) = (ix[2] / b, ix[3] / b);
+            let (by, bx) = (ix[2] % b, ix[3] % b);
+            let c = match mode {
+                DepthToSpaceMode::DCR => (by * b + bx) * c_out + ix[1],
+                DepthToSpaceMode::CRD => (ix[1] * b + by) * b + bx,
+            };
+            input[[ix[0], c, y, x]]
+        })
+    }
+
+    fn input(shape: &[usize]) -> Tensor {
+        let len = shape.iter().product::<usize>();
+        tensor1(&(0..len).map(|i| i as f32).collect::<Vec<_>>()).into_shape(shape).unwrap()
+    }
+
+    #[test]
+    fn depth_to_space_nchw() -> TractResult<()> {
+        for &mode in &[DepthToSpaceMode::DCR, DepthToSpaceMode::CRD] {
+            let input = input(&[2, 8, 2, 3]);
+            let expected = depth_to_space_ref(&input.to_array_view::<f32>()?.to_owned(), 2, mode);
+            let found = run(input, |model, source| {
+                wire_depth_to_space("d2s", model, source, DataFormat::NCHW, 2, mode)
+            })?;
+            assert_eq!(found, expected.into_tensor());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn depth_to_space_nhwc() -> TractResult<()> {
+        let nchw = input(&[2, 8, 2, 3]);
+        let expected =
+            depth_to_space_ref(&nchw.to_array_view::<f32>()?.to_owned(), 2, DepthToSpaceMode::DCR)
+                .permuted_axes(&[0, 2, 3, 1][..]);
+        let nhwc = nchw.permute_axes(&[0, 2, 3, 1])?;
+        let found = run(nhwc, |model, source| {
+            wire_depth_to_space("d2s", model, source, DataFormat::NHWC, 2, DepthToSpaceMode::DCR)
+        })?;
+        assert_eq!(found, expected.into_tensor());
+        Ok(())
+    }
+
+    #[test]
+    fn space_to_depth_is_inverse() -> TractResult<()> {
+        for &fmt in &[DataFormat::NCHW, DataFormat::NHWC] {
+            let input = input(&[1, 4, 6, 4]);
+            let found = run(input.clone(), |model, source| {
+                let s2d = wire_space_to_depth("s2d", model, source, fmt, 2)?;
+                wire_depth_to_space("d2s", model, s2d[0], fmt, 2, DepthToSpaceMode::DCR)
+            })?;
+            assert_eq!(found, input);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::internal::*;
+use crate::ops::binary::{TypedBinOp, UnaryOp};
+use crate::ops::math::{erf_f32, Add, Mul};
+
+element_wise!(gelu, Gelu { approximate: bool },
+    [f32] => |op, xs| {
+        if op.approximate {
+            xs.iter_mut().for_each(|x| *x = gelu_tanh_f32(*x));
+        } else {
+            xs.iter_mut().for_each(|x| *x = gelu_erf_f32(*x));
+        }
+        Ok(())
+    };
+    cost: |dt| {tvec!((Cost::FMA(dt), 10), (Cost::Div(dt), 1))}
+);
+
+/// 0.5 * x * (1 + erf(x / sqrt(2)))
+fn gelu_erf_f32(x: f32) -> f32 {
+    0.5 * x * (1.0 + erf_f32(x * std::f32::consts::FRAC_1_SQRT_2))
+}
+
+/// 0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3)))
+fn gelu_tanh_f32(x: f32) -> f32 {
+    const SQRT_2_OVER_PI: f32 = 0.7978845608;
+    0.5 * x * (1.0 + (SQRT_2_OVER_PI * (x + 0.044715 * x * x * x)).tanh())
+}
+
+fn is_unary_mul_by(node: &TypedNode, value: f32) -> bool {
+    node.op_as::<UnaryOp>()
+        .filter(|op| op.mini_op.is::<Mul>())
+        .and_then(|op| op.a.as_uniform())
+        .and_then(|a| a.cast_to_scalar::<f32>().ok())
+        .map(|a| (a - value).abs() < 1e-5)
+        .unwrap_or(false)
+}
+
+/// Recognize an erf-based GELU in its decluttered decomposed form:
+/// x * 0.5 * (1 + erf(x * 1/sqrt(2))), the multiplications by x and 0.5
+/// coming in any order.
+///
+/// Called from the erf declutter, `node` being the erf.
+pub fn declutter_decomposed_gelu(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let scaled = model.node(node.inputs[0].node);
+    if !is_unary_mul_by(scaled, std::f32::consts::FRAC_1_SQRT_2) {
+        return Ok(None);
+    }
+    let x = scaled.inputs[0];
+    let plus_one =
+        if let Some(succ) = super::single_consumer(model, node) { succ } else { return Ok(None) };
+    let is_plus_one = plus_one
+        .op_as::<UnaryOp>()
+        .filter(|op| op.mini_op.is::<Add>())
+        .and_then(|op| op.a.as_uniform())
+        .and_then(|a| a.cast_to_scalar::<f32>().ok())
+        .map(|a| a == 1.0)
+        .unwrap_or(false);
+    if !is_plus_one {
+        return Ok(None);
+    }
+    let is_mul_of = |node: &TypedNode, a: OutletId, b: OutletId| {
+        node.op_as::<TypedBinOp>().map(|op| op.0.is::<Mul>()).unwrap_or(false)
+            && (node.inputs[..] == [a, b] || node.inputs[..] == [b, a])
+    };
+    let succ = if let Some(succ) = super::single_consumer(model, plus_one) {
+        succ
+    } else {
+        return Ok(None);
+    };
+    let output = if is_unary_mul_by(succ, 0.5) {
+        // ((1 + erf) * 0.5) * x
+        match super::single_consumer(model, succ) {
+            Some(output) if is_mul_of(output, succ.id.into(), x) => output,
+            _ => return Ok(None),
+        }
+    } else if is_mul_of(succ, plus_one.id.into(), x) {
+        // ((1 + erf) * x) * 0.5
+        match super::single_consumer(model, succ) {
+            Some(output) if is_unary_mul_by(output, 0.5) => output,
+            _ => return Ok(None),
+        }
+    } else {
+        // (1 + erf) * (x * 0.5)
+        let half = succ.inputs.iter().find(|i| i.node != plus_one.id).map(|i| model.node(i.node));
+        match half {
+            Some(half)
+                if is_unary_mul_by(half, 0.5)
+                    && half.inputs[0] == x
+                    && is_mul_of(succ, plus_one.id.into(), half.id.into()) =>
+            {
+                succ
+            }
+            _ => return Ok(None),
+        }
+    };
+    if model.outlet_fact(x)?.datum_type != f32::datum_type() {
+        return Ok(None);
+    }
+    let mut patch = TypedModelPatch::default();
+    let wire = patch.tap_model(model, x)?;
+    let wire = patch.wire_node(&output.name, gelu(false), &[wire])?[0];
+    patch.shunt_outside(model, output.id.into(), wire)?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval() -> TractResult<()> {
+        let input = rctensor1(&[-1f32, 0.0, 1.0, 3.0]);
+        let exact = gelu(false).eval(tvec!(input.clone()))?;
+        exact[0].close_enough(&tensor1(&[-0.158655f32, 0.0, 0.841345, 2.995950]), true)?;
+        let approx = gelu(true).eval(tvec!(input))?;
+        approx[0].close_enough(&tensor1(&[-0.158808f32, 0.0, 0.841192, 2.996363]), true)?;
+        Ok(())
+    }
+}
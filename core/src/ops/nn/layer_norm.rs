@@ -0,0 +1,249 @@
+use crate::internal::*;
+use crate::ops::binary::{TypedBinOp, UnaryOp};
+use crate::ops::element_wise::ElementWiseOp;
+use crate::ops::math::{Add, Mul, Square, Sub};
+use crate::ops::nn::{Reduce, Reducer};
+use num_traits::{Float, FromPrimitive};
+use tract_ndarray::prelude::*;
+
+/// Normalize the input to zero mean and unit variance over `axes`.
+///
+/// Computes (x - mean) / sqrt(var + epsilon), mean and var being taken over
+/// `axes`. Scale and bias are left to the surrounding element wise ops.
+#[derive(Debug, Clone, new, Educe)]
+#[educe(Hash)]
+pub struct LayerNorm {
+    pub axes: TVec<usize>,
+    #[educe(Hash(method = "hash_f32"))]
+    pub epsilon: f32,
+}
+
+impl_dyn_hash!(LayerNorm);
+
+impl LayerNorm {
+    fn eval_t<T: Datum + Float + FromPrimitive>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let x = input.to_array_view::<T>()?;
+        let n = self.axes.iter().map(|&ax| x.shape()[ax]).product::<usize>();
+        let n = T::from_usize(n).unwrap();
+        let mean_over_axes = |x: &ArrayD<T>| {
+            let mut sum = x.clone();
+            for &ax in &self.axes {
+                sum = sum.sum_axis(Axis(ax)).insert_axis(Axis(ax));
+            }
+            sum.mapv(|s| s / n)
+        };
+        let centered = &x - &mean_over_axes(&x.to_owned());
+        let var = mean_over_axes(&centered.mapv(|d| d * d));
+        let epsilon = T::from_f32(self.epsilon).unwrap();
+        let output = centered / var.mapv(|v| (v + epsilon).sqrt());
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for LayerNorm {
+    fn name(&self) -> Cow<str> {
+        "LayerNorm".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axes: {:?} epsilon: {}", self.axes, self.epsilon)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LayerNorm {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let dt = input.datum_type();
+        let output = if dt == f64::datum_type() {
+            self.eval_t::<f64>(&input)?
+        } else {
+            self.eval_t::<f32>(&*input.cast_to::<f32>()?)?.cast_to_dt(dt)?.into_owned()
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LayerNorm {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if !inputs[0].datum_type.is_float() {
+            bail!("LayerNorm expects a float input, got {:?}", inputs[0].datum_type)
+        }
+        if let Some(ax) = self.axes.iter().find(|&&ax| ax >= inputs[0].rank()) {
+            bail!("Invalid axis {} for input {:?}", ax, inputs[0])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[0].shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let count: TDim = inputs[0].shape.iter().product();
+        let dt = inputs[0].datum_type;
+        Ok(tvec!((Cost::FMA(dt), count.clone() * 4), (Cost::Div(dt), count)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank())
+            .filter(|axis| !self.axes.contains(axis))
+            .map(|axis| AxisInfo::simple(axis))
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        let mut axes = tvec!();
+        for normalized in &self.axes {
+            if let Some(axis) = change.transform_axis(*normalized) {
+                axes.push(axis);
+            } else {
+                return Ok(None);
+            }
+        }
+        let op = Some(Box::new(Self { axes, ..self.clone() }) as _);
+        Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
+    }
+
+    as_op!();
+}
+
+fn unary_scalar<'m>(
+    model: &'m TypedModel,
+    outlet: OutletId,
+    is_op: impl Fn(&UnaryOp) -> bool,
+) -> Option<(&'m TypedNode, f32)> {
+    let node = model.node(outlet.node);
+    let op = node.op_as::<UnaryOp>().filter(|op| is_op(op))?;
+    let value = op.a.as_uniform()?.cast_to_scalar::<f32>().ok()?;
+    Some((node, value))
+}
+
+/// Mean over some axes, as decluttered: a sum followed by a multiplication by
+/// the inverse of the reduced size. Returns the input and the axes.
+fn mean_input(model: &TypedModel, outlet: OutletId) -> Option<(OutletId, TVec<usize>)> {
+    let (node, inv) = unary_scalar(model, outlet, |op| op.mini_op.is::<Mul>())?;
+    let sum = model.node(node.inputs[0].node);
+    let reduce = sum.op_as::<Reduce>().filter(|r| r.reducer == Reducer::Sum)?;
+    let fact = model.outlet_fact(sum.inputs[0]).ok()?;
+    let n: usize =
+        reduce.axes.iter().map(|&ax| fact.shape[ax].to_usize().ok()).product::<Option<usize>>()?;
+    if (inv * n as f32 - 1.0).abs() > 1e-5 {
+        return None;
+    }
+    Some((sum.inputs[0], reduce.axes.clone()))
+}
+
+/// Recognize a layer normalization in its decluttered decomposed form:
+/// (x - mean(x)) * rsqrt(mean((x - mean(x))^2) + epsilon).
+///
+/// Called from the `Rsqrt` declutter, `node` being the rsqrt.
+pub(crate) fn declutter_decomposed_layer_norm(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let succ = if let Some(succ) = super::single_consumer(model, node) {
+        succ
+    } else {
+        return Ok(None);
+    };
+    if !succ.op_as::<TypedBinOp>().map(|op| op.0.is::<Mul>()).unwrap_or(false) {
+        return Ok(None);
+    }
+    let centered = if succ.inputs[0].node == node.id { succ.inputs[1] } else { succ.inputs[0] };
+    let (epsilon_node, epsilon) =
+        if let Some(it) = unary_scalar(model, node.inputs[0], |op| op.mini_op.is::<Add>()) {
+            it
+        } else {
+            return Ok(None);
+        };
+    let (square, var_axes) = if let Some(it) = mean_input(model, epsilon_node.inputs[0]) {
+        it
+    } else {
+        return Ok(None);
+    };
+    let square = model.node(square.node);
+    if !square.op_as::<ElementWiseOp>().map(|op| op.0.is::<Square>()).unwrap_or(false)
+        || square.inputs[0] != centered
+    {
+        return Ok(None);
+    }
+    let sub = model.node(centered.node);
+    if !sub.op_as::<TypedBinOp>().map(|op| op.0.is::<Sub>()).unwrap_or(false) {
+        return Ok(None);
+    }
+    let x = sub.inputs[0];
+    match mean_input(model, sub.inputs[1]) {
+        Some((input, axes)) if input == x && axes == var_axes => (),
+        _ => return Ok(None),
+    }
+    if !model.outlet_fact(x)?.datum_type.is_float() {
+        return Ok(None);
+    }
+    let mut patch = TypedModelPatch::default();
+    let wire = patch.tap_model(model, x)?;
+    let wire = patch.wire_node(&succ.name, LayerNorm::new(var_axes, epsilon), &[wire])?[0];
+    patch.shunt_outside(model, succ.id.into(), wire)?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn eval() -> TractResult<()> {
+        let op = LayerNorm::new(tvec!(1), 0.0);
+        let output = op.eval(tvec!(rctensor2(&[[1f32, 3.0], [-2.0, 2.0]])))?;
+        assert_eq!(*output[0], tensor2(&[[-1f32, 1.0], [-1.0, 1.0]]));
+        Ok(())
+    }
+
+    #[test]
+    fn fuse_decomposed() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[2, 4]))?;
+        let mean = |model: &mut TypedModel, name: &str, wire: OutletId| -> TractResult<OutletId> {
+            let sum = model.wire_node(
+                format!("{}.sum", name),
+                Reduce::new(tvec!(1), Reducer::Sum),
+                &[wire],
+            )?;
+            let n = model.add_const(format!("{}.n", name), tensor2(&[[4f32]]))?;
+            Ok(model.wire_node(name, math::div::bin_typed(), &[sum[0], n])?[0])
+        };
+        let mean_x = mean(&mut model, "mean", x)?;
+        let centered = model.wire_node("centered", math::sub::bin_typed(), &[x, mean_x])?[0];
+        let two = model.add_const("two", tensor2(&[[2f32]]))?;
+        let square = model.wire_node("square", math::pow::bin_typed(), &[centered, two])?[0];
+        let var = mean(&mut model, "var", square)?;
+        let epsilon = model.add_const("epsilon", tensor2(&[[1e-5f32]]))?;
+        let var = model.wire_node("var_eps", math::add::bin_typed(), &[var, epsilon])?[0];
+        let std = model.wire_node("std", math::sqrt(), &[var])?[0];
+        let output = model.wire_node("output", math::div::bin_typed(), &[centered, std])?;
+        model.set_output_outlets(&output)?;
+
+        let input = tensor2(&[[1f32, 2.0, 3.0, 4.0], [0.0, -1.0, 5.0, 2.0]]);
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let decluttered = model.declutter()?;
+        assert_eq!(decluttered.nodes().len(), 2);
+        assert!(decluttered.nodes()[1].op_is::<LayerNorm>());
+        let found = decluttered.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], true)?;
+        Ok(())
+    }
+}
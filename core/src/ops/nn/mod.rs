@@ -1,11 +1,32 @@
+mod attention;
 mod data_formats;
+mod depth_to_space;
+mod gelu;
+mod layer_norm;
+mod non_max_suppression;
 mod reduce;
+mod roi;
 
+pub use self::attention::ScaledDotProductAttention;
 pub use self::data_formats::{BaseDataShape, DataFormat, DataShape, SymDataShape};
+pub use self::depth_to_space::{wire_depth_to_space, wire_space_to_depth, DepthToSpaceMode};
+pub use self::gelu::{declutter_decomposed_gelu, gelu, Gelu};
+pub(crate) use self::layer_norm::declutter_decomposed_layer_norm;
+pub use self::layer_norm::LayerNorm;
+pub use self::non_max_suppression::{BoxRepr, NonMaxSuppression};
 pub use self::reduce::{Reduce, Reducer};
+pub use self::roi::{CropAndResize, CropAndResizeMethod, MaxRoiPool, RoiAlign, RoiAlignMode};
 
 pub use crate::internal::*;
 
+/// The only node consuming the node output, whatever its input count.
+fn single_consumer<'m>(model: &'m TypedModel, node: &TypedNode) -> Option<&'m TypedNode> {
+    if node.outputs.len() != 1 || node.outputs[0].successors.len() != 1 {
+        return None;
+    }
+    Some(model.node(node.outputs[0].successors[0].node))
+}
+
 element_wise!(sigmoid, Sigmoid, [f32] => |_, xs| {
     (tract_linalg::ops().sigmoid_f32)().run(xs)
 };
@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Box coordinates layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoxRepr {
+    /// [y1, x1, y2, x2], any pair of diagonal corners.
+    TwoPoints,
+    /// [x_center, y_center, width, height].
+    CenterWidthHeight,
+}
+
+impl BoxRepr {
+    /// Box as (y_min, x_min, y_max, x_max).
+    fn corners(&self, b: ArrayView1<f32>) -> (f32, f32, f32, f32) {
+        match self {
+            BoxRepr::TwoPoints => (b[0].min(b[2]), b[1].min(b[3]), b[0].max(b[2]), b[1].max(b[3])),
+            BoxRepr::CenterWidthHeight => {
+                (b[1] - b[3] / 2.0, b[0] - b[2] / 2.0, b[1] + b[3] / 2.0, b[0] + b[2] / 2.0)
+            }
+        }
+    }
+}
+
+/// Greedy selection of the best scoring boxes, skipping the ones overlapping
+/// too much with an already selected one, per batch and class.
+///
+/// Inputs are boxes [batch, boxes, 4], scores [batch, classes, boxes], and
+/// the scalars max_output_boxes_per_class (i64), iou_threshold and
+/// score_threshold (f32). Outputs the selected (batch, class, box) indices as
+/// i64 [selected, 3], the number of selected boxes being a fresh symbol.
+#[derive(Debug, Clone, new, Hash)]
+pub struct NonMaxSuppression {
+    pub box_repr: BoxRepr,
+    pub num_selected_indices_symbol: Symbol,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl NonMaxSuppression {
+    fn suppress_by_iou(&self, a: ArrayView1<f32>, b: ArrayView1<f32>, threshold: f32) -> bool {
+        let a = self.box_repr.corners(a);
+        let b = self.box_repr.corners(b);
+        let area_a = (a.2 - a.0) * (a.3 - a.1);
+        let area_b = (b.2 - b.0) * (b.3 - b.1);
+        if area_a <= 0.0 || area_b <= 0.0 {
+            return false;
+        }
+        let inter_h = a.2.min(b.2) - a.0.max(b.0);
+        let inter_w = a.3.min(b.3) - a.1.max(b.1);
+        if inter_h <= 0.0 || inter_w <= 0.0 {
+            return false;
+        }
+        let inter = inter_h * inter_w;
+        inter / (area_a + area_b - inter) > threshold
+    }
+}
+
+impl Op for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        "NonMaxSuppression".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("box repr: {:?}", self.box_repr)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for NonMaxSuppression {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (boxes, scores, max_output, iou_threshold, score_threshold) = args_5!(inputs);
+        let boxes = boxes.cast_to::<f32>()?;
+        let boxes = boxes.to_array_view::<f32>()?.into_dimensionality::<Ix3>()?;
+        let scores = scores.cast_to::<f32>()?;
+        let scores = scores.to_array_view::<f32>()?.into_dimensionality::<Ix3>()?;
+        let max_output = max_output.cast_to_scalar::<i64>()?.max(0) as usize;
+        let iou_threshold = iou_threshold.cast_to_scalar::<f32>()?;
+        let score_threshold = score_threshold.cast_to_scalar::<f32>()?;
+        if boxes.shape()[0] != scores.shape()[0] || boxes.shape()[1] != scores.shape()[2] {
+            bail!("Inconsistent boxes {:?} and scores {:?} shapes", boxes.shape(), scores.shape())
+        }
+        let mut selected_indices: Vec<[i64; 3]> = vec![];
+        let mut candidates: Vec<(usize, f32)> = vec![];
+        let mut selected: Vec<usize> = vec![];
+        for b in 0..scores.shape()[0] {
+            for c in 0..scores.shape()[1] {
+                candidates.clear();
+                candidates.extend(
+                    scores
+                        .slice(s![b, c, ..])
+                        .iter()
+                        .copied()
+                        .enumerate()
+                        .filter(|(_, score)| *score > score_threshold),
+                );
+                // stable sort: ties are resolved in favour of the lowest index
+                candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+                selected.clear();
+                for &(candidate, _) in &candidates {
+                    if selected.len() >= max_output {
+                        break;
+                    }
+                    if selected.iter().all(|&s| {
+                        !self.suppress_by_iou(
+                            boxes.slice(s![b, s, ..]),
+                            boxes.slice(s![b, candidate, ..]),
+                            iou_threshold,
+                        )
+                    }) {
+                        selected.push(candidate);
+                    }
+                }
+                selected_indices.extend(selected.iter().map(|&s| [b as i64, c as i64, s as i64]));
+            }
+        }
+        let output =
+            Array2::from_shape_fn((selected_indices.len(), 3), |(i, j)| selected_indices[i][j]);
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for NonMaxSuppression {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 3 || inputs[1].rank() != 3 {
+            bail!("Expect boxes and scores of rank 3, got {:?} and {:?}", inputs[0], inputs[1])
+        }
+        Ok(tvec!(TypedFact::dt_shape(
+            i64::datum_type(),
+            &[self.num_selected_indices_symbol.to_dim(), 3.to_dim()]
+        )))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        box_repr: BoxRepr,
+        boxes: Tensor,
+        scores: Tensor,
+        max_output: i64,
+        iou_threshold: f32,
+        score_threshold: f32,
+    ) -> TractResult<Arc<Tensor>> {
+        let op = NonMaxSuppression::new(box_repr, SymbolTable::default().sym("n"));
+        Ok(op
+            .eval(tvec!(
+                boxes.into_arc_tensor(),
+                scores.into_arc_tensor(),
+                rctensor0(max_output),
+                rctensor0(iou_threshold),
+                rctensor0(score_threshold)
+            ))?
+            .remove(0))
+    }
+
+    // from the ONNX backend test suite
+    fn boxes() -> Tensor {
+        tensor3(&[[
+            [0.0f32, 0.0, 1.0, 1.0],
+            [0.0, 0.1, 1.0, 1.1],
+            [0.0, -0.1, 1.0, 0.9],
+            [0.0, 10.0, 1.0, 11.0],
+            [0.0, 10.1, 1.0, 11.1],
+            [0.0, 100.0, 1.0, 101.0],
+        ]])
+    }
+
+    fn scores() -> Tensor {
+        tensor3(&[[[0.9f32, 0.75, 0.6, 0.95, 0.5, 0.3]]])
+    }
+
+    #[test]
+    fn suppress_by_iou() -> TractResult<()> {
+        let result = run(BoxRepr::TwoPoints, boxes(), scores(), 3, 0.5, 0.0)?;
+        assert_eq!(*result, tensor2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn center_point_box() -> TractResult<()> {
+        let boxes = tensor3(&[[
+            [0.5f32, 0.5, 1.0, 1.0],
+            [0.5, 0.6, 1.0, 1.0],
+            [0.5, 0.4, 1.0, 1.0],
+            [0.5, 10.5, 1.0, 1.0],
+            [0.5, 10.6, 1.0, 1.0],
+            [0.5, 100.5, 1.0, 1.0],
+        ]]);
+        let result = run(BoxRepr::CenterWidthHeight, boxes, scores(), 3, 0.5, 0.0)?;
+        assert_eq!(*result, tensor2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn score_threshold() -> TractResult<()> {
+        let result = run(BoxRepr::TwoPoints, boxes(), scores(), 3, 0.5, 0.4)?;
+        assert_eq!(*result, tensor2(&[[0i64, 0, 3], [0, 0, 0]]));
+        Ok(())
+    }
+}
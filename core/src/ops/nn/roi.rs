@@ -0,0 +1,481 @@
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+fn cast_to_f32_array<D: Dimension>(t: &Tensor) -> TractResult<Array<f32, D>> {
+    Ok(t.cast_to::<f32>()?.to_array_view::<f32>()?.into_dimensionality::<D>()?.to_owned())
+}
+
+fn batch_index(t: &Tensor, roi: usize, batch: usize) -> TractResult<usize> {
+    let b = t.cast_to::<i64>()?.as_slice::<i64>()?[roi];
+    if b < 0 || b as usize >= batch {
+        bail!("Invalid batch index {} for roi {} (batch is {})", b, roi, batch)
+    }
+    Ok(b as usize)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoiAlignMode {
+    Avg,
+    Max,
+}
+
+/// Region of interest align, over a NCHW input.
+///
+/// Inputs are X [N, C, H, W], rois [R, 4] as (x1, y1, x2, y2) in input
+/// coordinates (before `spatial_scale`), and batch_indices [R]. Each roi is
+/// divided in output_height x output_width bins, each bin value being pooled
+/// over regularly sampled bilinear interpolations. Output is [R, C,
+/// output_height, output_width].
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct RoiAlign {
+    pub mode: RoiAlignMode,
+    pub output_height: usize,
+    pub output_width: usize,
+    /// Samples per bin on each axis, adaptive if 0.
+    pub sampling_ratio: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub spatial_scale: f32,
+    /// Shift pixel coordinates by -0.5 (as in "half_pixel" ONNX coordinate transformation).
+    pub half_pixel: bool,
+}
+
+impl_dyn_hash!(RoiAlign);
+
+/// Bilinear sample: the four neighbours positions and weights. Out of bounds
+/// samples have zero weights.
+fn bilinear_sample(height: usize, width: usize, y: f32, x: f32) -> [(usize, usize, f32); 4] {
+    if y < -1.0 || y > height as f32 || x < -1.0 || x > width as f32 {
+        return [(0, 0, 0.0); 4];
+    }
+    let (mut y, mut x) = (y.max(0.0), x.max(0.0));
+    let mut y_low = y as usize;
+    let y_high = if y_low >= height - 1 {
+        y_low = height - 1;
+        y = y_low as f32;
+        y_low
+    } else {
+        y_low + 1
+    };
+    let mut x_low = x as usize;
+    let x_high = if x_low >= width - 1 {
+        x_low = width - 1;
+        x = x_low as f32;
+        x_low
+    } else {
+        x_low + 1
+    };
+    let (ly, lx) = (y - y_low as f32, x - x_low as f32);
+    let (hy, hx) = (1.0 - ly, 1.0 - lx);
+    [
+        (y_low, x_low, hy * hx),
+        (y_low, x_high, hy * lx),
+        (y_high, x_low, ly * hx),
+        (y_high, x_high, ly * lx),
+    ]
+}
+
+impl Op for RoiAlign {
+    fn name(&self) -> Cow<str> {
+        "RoiAlign".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "mode: {:?}, output: {}x{}, sampling_ratio: {}, spatial_scale: {}, half_pixel: {}",
+            self.mode,
+            self.output_height,
+            self.output_width,
+            self.sampling_ratio,
+            self.spatial_scale,
+            self.half_pixel
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RoiAlign {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, rois, batch_indices) = args_3!(inputs);
+        let dt = input.datum_type();
+        let input = cast_to_f32_array::<Ix4>(&input)?;
+        let rois = cast_to_f32_array::<Ix2>(&rois)?;
+        let (batch, channels, height, width) = input.dim();
+        let (oh, ow) = (self.output_height, self.output_width);
+        let offset = if self.half_pixel { 0.5 } else { 0.0 };
+        let mut output = Array4::<f32>::zeros((rois.nrows(), channels, oh, ow));
+        for (r, roi) in rois.outer_iter().enumerate() {
+            let b = batch_index(&batch_indices, r, batch)?;
+            let start_x = roi[0] * self.spatial_scale - offset;
+            let start_y = roi[1] * self.spatial_scale - offset;
+            let mut roi_width = roi[2] * self.spatial_scale - offset - start_x;
+            let mut roi_height = roi[3] * self.spatial_scale - offset - start_y;
+            if !self.half_pixel {
+                roi_width = roi_width.max(1.0);
+                roi_height = roi_height.max(1.0);
+            }
+            let bin_height = roi_height / oh as f32;
+            let bin_width = roi_width / ow as f32;
+            let grid_h = if self.sampling_ratio > 0 {
+                self.sampling_ratio
+            } else {
+                (roi_height / oh as f32).ceil() as usize
+            };
+            let grid_w = if self.sampling_ratio > 0 {
+                self.sampling_ratio
+            } else {
+                (roi_width / ow as f32).ceil() as usize
+            };
+            let count = (grid_h * grid_w).max(1) as f32;
+            for ph in 0..oh {
+                for pw in 0..ow {
+                    let mut samples = vec![];
+                    for iy in 0..grid_h {
+                        let y = start_y
+                            + ph as f32 * bin_height
+                            + (iy as f32 + 0.5) * bin_height / grid_h as f32;
+                        for ix in 0..grid_w {
+                            let x = start_x
+                                + pw as f32 * bin_width
+                                + (ix as f32 + 0.5) * bin_width / grid_w as f32;
+                            samples.push(bilinear_sample(height, width, y, x));
+                        }
+                    }
+                    for c in 0..channels {
+                        let plane = input.slice(s![b, c, .., ..]);
+                        let weighted = samples
+                            .iter()
+                            .map(|s| s.iter().map(|&(y, x, w)| w * plane[(y, x)]).collect());
+                        output[(r, c, ph, pw)] = match self.mode {
+                            RoiAlignMode::Avg => {
+                                weighted.map(|w: Vec<f32>| w.iter().sum::<f32>()).sum::<f32>()
+                                    / count
+                            }
+                            RoiAlignMode::Max => weighted
+                                .flat_map(|w: Vec<f32>| w.into_iter())
+                                .fold(None, |acc: Option<f32>, v| {
+                                    Some(acc.map(|acc| acc.max(v)).unwrap_or(v))
+                                })
+                                .unwrap_or(0.0),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(tvec!(output.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for RoiAlign {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 4 || inputs[1].rank() != 2 {
+            bail!(
+                "Expect input of rank 4 and rois of rank 2, got {:?} and {:?}",
+                inputs[0],
+                inputs[1]
+            )
+        }
+        Ok(tvec!(TypedFact::dt_shape(
+            inputs[0].datum_type,
+            &[
+                inputs[1].shape[0].clone(),
+                inputs[0].shape[1].clone(),
+                self.output_height.to_dim(),
+                self.output_width.to_dim()
+            ]
+        )))
+    }
+
+    as_op!();
+}
+
+/// Max pooling over regions of interest, over a NCHW input.
+///
+/// Inputs are X [N, C, H, W] and rois [R, 5] as (batch_index, x1, y1, x2, y2)
+/// in input coordinates (before `spatial_scale`). Output is [R, C,
+/// pooled_height, pooled_width], empty bins being zeros.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct MaxRoiPool {
+    pub pooled_height: usize,
+    pub pooled_width: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub spatial_scale: f32,
+}
+
+impl_dyn_hash!(MaxRoiPool);
+
+impl Op for MaxRoiPool {
+    fn name(&self) -> Cow<str> {
+        "MaxRoiPool".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "pooled: {}x{}, spatial_scale: {}",
+            self.pooled_height, self.pooled_width, self.spatial_scale
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for MaxRoiPool {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, rois) = args_2!(inputs);
+        let dt = input.datum_type();
+        let input = cast_to_f32_array::<Ix4>(&input)?;
+        let rois = cast_to_f32_array::<Ix2>(&rois)?;
+        let (batch, channels, height, width) = input.dim();
+        let (ph, pw) = (self.pooled_height, self.pooled_width);
+        let mut output = Array4::<f32>::zeros((rois.nrows(), channels, ph, pw));
+        for (r, roi) in rois.outer_iter().enumerate() {
+            let b = roi[0] as i64;
+            if b < 0 || b as usize >= batch {
+                bail!("Invalid batch index {} for roi {} (batch is {})", b, r, batch)
+            }
+            let coord = |v: f32| (v * self.spatial_scale).round() as i64;
+            let (start_x, start_y, end_x, end_y) =
+                (coord(roi[1]), coord(roi[2]), coord(roi[3]), coord(roi[4]));
+            let bin_height = (end_y - start_y + 1).max(1) as f32 / ph as f32;
+            let bin_width = (end_x - start_x + 1).max(1) as f32 / pw as f32;
+            let range = |ix: usize, bin: f32, start: i64, len: usize| {
+                let lo = (ix as f32 * bin).floor() as i64 + start;
+                let hi = ((ix + 1) as f32 * bin).ceil() as i64 + start;
+                (lo.max(0).min(len as i64) as usize, hi.max(0).min(len as i64) as usize)
+            };
+            for y in 0..ph {
+                let (y0, y1) = range(y, bin_height, start_y, height);
+                for x in 0..pw {
+                    let (x0, x1) = range(x, bin_width, start_x, width);
+                    if y1 <= y0 || x1 <= x0 {
+                        continue;
+                    }
+                    for c in 0..channels {
+                        output[(r, c, y, x)] = input
+                            .slice(s![b as usize, c, y0..y1, x0..x1])
+                            .iter()
+                            .fold(f32::MIN, |acc, &v| acc.max(v));
+                    }
+                }
+            }
+        }
+        Ok(tvec!(output.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for MaxRoiPool {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 4 || inputs[1].rank() != 2 {
+            bail!(
+                "Expect input of rank 4 and rois of rank 2, got {:?} and {:?}",
+                inputs[0],
+                inputs[1]
+            )
+        }
+        Ok(tvec!(TypedFact::dt_shape(
+            inputs[0].datum_type,
+            &[
+                inputs[1].shape[0].clone(),
+                inputs[0].shape[1].clone(),
+                self.pooled_height.to_dim(),
+                self.pooled_width.to_dim()
+            ]
+        )))
+    }
+
+    as_op!();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CropAndResizeMethod {
+    Bilinear,
+    Nearest,
+}
+
+/// Crops of a NHWC image, resized to a fixed size.
+///
+/// Inputs are image [N, H, W, D], boxes [R, 4] as (y1, x1, y2, x2) in
+/// normalized coordinates, and box_indices [R]. Output is f32 [R,
+/// crop_height, crop_width, D], samples out of the image being
+/// `extrapolation_value`.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct CropAndResize {
+    pub method: CropAndResizeMethod,
+    pub crop_height: usize,
+    pub crop_width: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub extrapolation_value: f32,
+}
+
+impl_dyn_hash!(CropAndResize);
+
+impl Op for CropAndResize {
+    fn name(&self) -> Cow<str> {
+        "CropAndResize".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "method: {:?}, crop: {}x{}, extrapolation_value: {}",
+            self.method, self.crop_height, self.crop_width, self.extrapolation_value
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for CropAndResize {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (image, boxes, box_indices) = args_3!(inputs);
+        let image = cast_to_f32_array::<Ix4>(&image)?;
+        let boxes = cast_to_f32_array::<Ix2>(&boxes)?;
+        let (batch, height, width, depth) = image.dim();
+        let (ch, cw) = (self.crop_height, self.crop_width);
+        let mut output =
+            Array4::<f32>::from_elem((boxes.nrows(), ch, cw, depth), self.extrapolation_value);
+        // sample coordinate on one axis, None if out of the image
+        let coord = |ix: usize, crop: usize, lo: f32, hi: f32, len: usize| {
+            let len = (len - 1) as f32;
+            let c = if crop > 1 {
+                lo * len + ix as f32 * (hi - lo) * len / (crop - 1) as f32
+            } else {
+                0.5 * (lo + hi) * len
+            };
+            Some(c).filter(|c| *c >= 0.0 && *c <= len)
+        };
+        for (r, bx) in boxes.outer_iter().enumerate() {
+            let b = batch_index(&box_indices, r, batch)?;
+            let image = image.index_axis(Axis(0), b);
+            for y in 0..ch {
+                let in_y = if let Some(in_y) = coord(y, ch, bx[0], bx[2], height) {
+                    in_y
+                } else {
+                    continue;
+                };
+                for x in 0..cw {
+                    let in_x = if let Some(in_x) = coord(x, cw, bx[1], bx[3], width) {
+                        in_x
+                    } else {
+                        continue;
+                    };
+                    let mut out = output.slice_mut(s![r, y, x, ..]);
+                    match self.method {
+                        CropAndResizeMethod::Nearest => {
+                            out.assign(&image.slice(s![
+                                in_y.round() as usize,
+                                in_x.round() as usize,
+                                ..
+                            ]));
+                        }
+                        CropAndResizeMethod::Bilinear => {
+                            let (top, bottom) = (in_y.floor() as usize, in_y.ceil() as usize);
+                            let (left, right) = (in_x.floor() as usize, in_x.ceil() as usize);
+                            let (y_lerp, x_lerp) = (in_y - top as f32, in_x - left as f32);
+                            for d in 0..depth {
+                                let tl = image[(top, left, d)];
+                                let tr = image[(top, right, d)];
+                                let bl = image[(bottom, left, d)];
+                                let br = image[(bottom, right, d)];
+                                let top = tl + (tr - tl) * x_lerp;
+                                let bottom = bl + (br - bl) * x_lerp;
+                                out[d] = top + (bottom - top) * y_lerp;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for CropAndResize {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 4 || inputs[1].rank() != 2 {
+            bail!(
+                "Expect image of rank 4 and boxes of rank 2, got {:?} and {:?}",
+                inputs[0],
+                inputs[1]
+            )
+        }
+        Ok(tvec!(TypedFact::dt_shape(
+            f32::datum_type(),
+            &[
+                inputs[1].shape[0].clone(),
+                self.crop_height.to_dim(),
+                self.crop_width.to_dim(),
+                inputs[0].shape[3].clone()
+            ]
+        )))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roi_align_avg() -> TractResult<()> {
+        let op = RoiAlign {
+            mode: RoiAlignMode::Avg,
+            output_height: 1,
+            output_width: 1,
+            sampling_ratio: 2,
+            spatial_scale: 1.0,
+            half_pixel: false,
+        };
+        let input = tensor4(&[[[[0f32, 1.0], [2.0, 3.0]]]]);
+        let rois = tensor2(&[[0f32, 0.0, 1.0, 1.0]]);
+        let result = op.eval(tvec!(input.into(), rois.into(), rctensor1(&[0i64])))?;
+        // samples at 0.25 and 0.75 on each axis
+        assert_eq!(*result[0], tensor4(&[[[[1.5f32]]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn max_roi_pool() -> TractResult<()> {
+        let op = MaxRoiPool { pooled_height: 1, pooled_width: 2, spatial_scale: 1.0 };
+        let input = tensor4(&[[[[0f32, 1.0, 2.0], [3.0, 4.0, 5.0], [6.0, 7.0, 8.0]]]]);
+        let rois = tensor2(&[[0f32, 0.0, 0.0, 1.0, 1.0]]);
+        let result = op.eval(tvec!(input.into(), rois.into()))?;
+        assert_eq!(*result[0], tensor4(&[[[[3f32, 4.0]]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn crop_and_resize() -> TractResult<()> {
+        let op = CropAndResize {
+            method: CropAndResizeMethod::Bilinear,
+            crop_height: 2,
+            crop_width: 2,
+            extrapolation_value: -1.0,
+        };
+        let image = tensor4(&[[[[0f32], [1.0]], [[2.0], [3.0]]]]);
+        let boxes = tensor2(&[[0f32, 0.0, 1.0, 1.0], [0.0, 0.5, 0.5, 1.5]]);
+        let result = op.eval(tvec!(image.into(), boxes.into(), rctensor1(&[0i32, 0])))?;
+        let expected =
+            tensor4(&[[[[0f32], [1.0]], [[2.0], [3.0]]], [[[0.5f32], [-1.0]], [[1.5], [-1.0]]]]);
+        assert_eq!(*result[0], expected);
+        Ok(())
+    }
+}
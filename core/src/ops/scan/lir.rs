@@ -260,8 +260,22 @@ impl OpState for State {
             }
         }
 
+        // no iteration: the last value of a state is its initial value
+        if iters == 0 {
+            let states = op.output_mapping.iter().filter(|m| m.state);
+            for (mapping, state) in states.zip(mutable.hidden_state.iter()) {
+                if let Some(slot) = mapping.last_value_slot {
+                    outputs[slot] = state.clone();
+                }
+            }
+        }
+
         Ok(outputs.into_iter().map(Arc::new).collect())
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 impl TypedOp for LirScan {
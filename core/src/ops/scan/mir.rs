@@ -215,7 +215,15 @@ impl Scan {
     ) -> TractResult<Option<TypedModelPatch>> {
         for (inner_input_id, input) in self.body.input_outlets()?.iter().enumerate() {
             let source_node = self.body.node(input.node);
-            if source_node.outputs[0].successors.len() == 0 {
+            if source_node.outputs[0].successors.len() == 0
+                && !self.body.output_outlets()?.contains(input)
+            {
+                // the last scanned input drives the iteration count
+                if self.input_mapping[inner_input_id].as_scan().is_some()
+                    && self.input_mapping.iter().filter(|m| m.as_scan().is_some()).count() == 1
+                {
+                    continue;
+                }
                 let mut new_inputs = node.inputs.clone();
                 let slot = match &self.input_mapping[inner_input_id] {
                     InputMapping::Full { slot } => Some(slot),
@@ -835,3 +843,48 @@ impl TypedOp for Scan {
         )?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn declutter_keeps_the_scan_input_driving_iterations() -> TractResult<()> {
+        let mut body = TypedModel::default();
+        let _ticks = body.add_source("ticks", TypedFact::dt_shape(f32::datum_type(), &[1]))?;
+        let acc = body.add_source("acc", TypedFact::dt_shape(f32::datum_type(), &[1]))?;
+        let one = body.add_const("one", tensor1(&[1f32]))?;
+        let next = body.wire_node("next", math::add::bin_typed(), &[acc, one])?;
+        body.set_output_outlets(&next)?;
+        let scan = Scan::new(
+            body,
+            vec![
+                InputMapping::Scan { slot: 0, axis: 0, chunk: 1 },
+                InputMapping::State { initializer: StateInitializer::FromInput(1) },
+            ],
+            vec![OutputMapping {
+                state: true,
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: None,
+                last_value_slot: Some(0),
+                full_slot: None,
+            }],
+            None,
+            0,
+        )?;
+        let mut model = TypedModel::default();
+        let ticks = model.add_source("ticks", TypedFact::dt_shape(f32::datum_type(), &[3]))?;
+        let init = model.add_source("init", TypedFact::dt_shape(f32::datum_type(), &[1]))?;
+        let output = model.wire_node("scan", scan, &[ticks, init])?;
+        model.set_output_outlets(&output)?;
+        let model = model.declutter()?;
+        let scan = model.nodes().iter().find_map(|n| n.op_as::<Scan>()).unwrap();
+        assert_eq!(scan.input_mapping.iter().filter(|m| m.as_scan().is_some()).count(), 1);
+        let result =
+            model.into_runnable()?.run(tvec!(tensor1(&[0f32, 0., 0.]), tensor1(&[10f32])))?;
+        assert_eq!(*result[0], tensor1(&[13f32]));
+        Ok(())
+    }
+}
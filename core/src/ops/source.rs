@@ -12,6 +12,10 @@ impl OpState for SourceState {
     ) -> TractResult<TVec<Arc<Tensor>>> {
         Ok(tvec!(session.inputs[&self.0].clone()))
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone, new, Hash)]
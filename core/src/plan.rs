@@ -3,8 +3,10 @@ use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 
 use crate::internal::*;
+use crate::model::memory::{Arena, MemoryPlan};
 use crate::model::order::eval_order_for_nodes;
 use crate::model::{Fact, Graph, OutletId};
+use tract_linalg::multithread::Executor;
 
 #[derive(Default)]
 pub struct SessionState {
@@ -12,6 +14,8 @@ pub struct SessionState {
     pub resolved_symbols: SymbolValues,
     pub tensors: HashMap<String, Tensor>,
     pub cached_mmm_scratch_space: Option<Box<dyn tract_linalg::mmm::ScratchSpace>>,
+    /// How matrix multiplications split their work. Single threaded by default.
+    pub executor: Executor,
 }
 
 impl Clone for SessionState {
@@ -21,6 +25,7 @@ impl Clone for SessionState {
             resolved_symbols: self.resolved_symbols.clone(),
             tensors: self.tensors.clone(),
             cached_mmm_scratch_space: None,
+            executor: self.executor.clone(),
         }
     }
 }
@@ -43,6 +48,8 @@ where
     pub outputs: Vec<OutletId>,
     pub order: Vec<usize>,
     pub flush_lists: Vec<TVec<usize>>,
+    #[educe(Hash(ignore))]
+    pub executor: Executor,
     _casper: PhantomData<(F, O)>,
 }
 
@@ -96,10 +103,18 @@ where
             order,
             flush_lists,
             outputs: outputs.to_vec(),
+            executor: Executor::default(),
             _casper: PhantomData,
         })
     }
 
+    /// Split matrix multiplications of this plan over a pool of `threads` threads.
+    #[cfg(feature = "multithread-mm")]
+    pub fn with_threads(mut self, threads: usize) -> TractResult<SimplePlan<F, O, M>> {
+        self.executor = Executor::multithread(threads)?;
+        Ok(self)
+    }
+
     pub fn run(&self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         let mut state = SimpleState::new(self)?;
         state.run(inputs)
@@ -108,6 +123,14 @@ where
     pub fn model(&self) -> &Graph<F, O> {
         self.model.borrow()
     }
+
+    /// Assign the intermediate tensors of this plan to the buffers of an arena.
+    ///
+    /// Tensors with shapes depending on symbols not given in `symbols` are
+    /// reported as unplanned.
+    pub fn memory_plan(&self, symbols: &SymbolValues) -> TractResult<MemoryPlan> {
+        MemoryPlan::new(self.model(), &self.order, &self.flush_lists, &self.outputs, symbols)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -122,6 +145,11 @@ where
     pub states: Vec<Option<Box<dyn OpState>>>,
     pub session_state: SessionState,
     pub values: Vec<Option<TVec<Arc<Tensor>>>>,
+    arena: Arena,
+    /// Symbols in the output shapes of each node: (slot, axis, symbol).
+    output_symbols: Vec<TVec<(usize, usize, Symbol)>>,
+    /// Symbols resolved from op outputs during the current run.
+    op_symbols: Vec<Symbol>,
     _phantom: PhantomData<(M, F, O)>,
 }
 
@@ -135,18 +163,46 @@ where
     pub fn new(plan: P) -> TractResult<SimpleState<F, O, M, P>> {
         let values = vec![None; plan.borrow().model.borrow().nodes().len()];
         let mut session = SessionState::default();
+        session.executor = plan.borrow().executor.clone();
         let model = plan.borrow().model();
         let states: Vec<Option<Box<dyn OpState>>> = model
             .nodes()
             .iter()
             .map(|n: &Node<F, O>| n.op().state(&mut session, n.id))
             .collect::<TractResult<_>>()?;
-        Ok(SimpleState { plan, states, session_state: session, values, _phantom: PhantomData })
+        let output_symbols = model
+            .nodes()
+            .iter()
+            .map(|n| {
+                let mut symbols = tvec!();
+                for (slot, output) in n.outputs.iter().enumerate() {
+                    if let Ok(fact) = output.fact.to_typed_fact() {
+                        for (axis, dim) in fact.shape.iter().enumerate() {
+                            if let TDim::Sym(s) = dim {
+                                symbols.push((slot, axis, s));
+                            }
+                        }
+                    }
+                }
+                symbols
+            })
+            .collect();
+        Ok(SimpleState {
+            plan,
+            states,
+            session_state: session,
+            values,
+            arena: Arena::default(),
+            output_symbols,
+            op_symbols: vec![],
+            _phantom: PhantomData,
+        })
     }
 
     /// Reset wires state.
     pub fn reset_wires(&mut self) -> TractResult<()> {
-        self.values.iter_mut().for_each(|s| *s = None);
+        let SimpleState { ref mut values, ref mut arena, .. } = self;
+        values.iter_mut().flat_map(|s| s.take()).flatten().for_each(|v| arena.recycle(v));
         Ok(())
     }
 
@@ -183,16 +239,24 @@ where
     {
         let mut result = tvec!();
         {
+            // symbols computed by ops may change from one run to the next
+            for s in self.op_symbols.drain(..) {
+                self.session_state.resolved_symbols[&s] = None;
+            }
             self.set_inputs(inputs)?;
             let &mut SimpleState {
                 ref plan,
                 ref mut session_state,
                 ref mut states,
                 ref mut values,
+                ref mut arena,
+                ref output_symbols,
+                ref mut op_symbols,
                 ..
             } = self;
             let plan = plan.borrow();
             let model = plan.model().borrow();
+            arena.prepare(plan, &session_state.resolved_symbols)?;
             for (step, n) in plan.order.iter().enumerate() {
                 let node = model.node(*n);
                 trace!("Running step {}, node {}", step, node);
@@ -208,7 +272,7 @@ where
 
                 for flush in &plan.flush_lists[step] {
                     trace!("  Ran {} can now flush {}", node, model.node(*flush));
-                    values[*flush] = None;
+                    values[*flush].take().into_iter().flatten().for_each(|v| arena.recycle(v));
                 }
 
                 if cfg!(debug_assertions) {
@@ -234,9 +298,23 @@ where
                     }
                 }
 
-                let vs =
+                let mut buffers = arena.lend(node.id);
+                let vs = recycle::with_buffers(&mut buffers, || {
                     eval(session_state, states[node.id].as_mut().map(|s| &mut **s), node, inputs)
-                        .map_err(|e| e.into())?;
+                });
+                arena.give_back(buffers);
+                let vs = vs.map_err(|e| e.into())?;
+
+                // symbols introduced by the op (Range length, ...) take the value
+                // it computed
+                for (slot, axis, s) in &output_symbols[node.id] {
+                    if session_state.resolved_symbols[s].is_none() {
+                        if let Some(dim) = vs.get(*slot).and_then(|v| v.shape().get(*axis)) {
+                            session_state.resolved_symbols[s] = Some(*dim as i64);
+                            op_symbols.push(s.clone());
+                        }
+                    }
+                }
 
                 if cfg!(debug_assertions) {
                     let facts = model.node_output_facts(node.id)?;
@@ -290,7 +368,7 @@ where
             .ok_or_else(|| format_err!("Invalid input id for model ({}).", input))?;
         fn resolve(symbols: &mut SymbolValues, expected: &TDim, provided: i64) {
             match expected {
-                TDim::Sym(s) => symbols[*s] = Some(provided),
+                TDim::Sym(s) => symbols[s] = Some(provided),
                 TDim::MulInt(x, expr) => resolve(symbols, expr, provided / *x),
                 _ => (),
             }
@@ -417,6 +495,11 @@ where
             .collect())
     }
 
+    /// Memory plan for the symbol values resolved from the inputs set so far.
+    pub fn memory_plan(&self) -> TractResult<MemoryPlan> {
+        self.plan().memory_plan(&self.session_state.resolved_symbols)
+    }
+
     pub fn plan(&self) -> &SimplePlan<F, O, M> {
         &self.plan.borrow()
     }
@@ -426,6 +509,201 @@ where
     }
 }
 
+/// State evaluating independent branches of a plan concurrently.
+///
+/// Nodes are dispatched to `threads` worker threads as soon as all their
+/// inputs are computed. Each worker evaluates op states with its own copy of
+/// the session. States which can not run on a copy of the session run on the
+/// calling thread, in plan order.
+#[derive(Clone, Debug)]
+pub struct ParallelState<F, O, M, P>
+where
+    F: Fact + Hash + Clone + 'static,
+    O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+    M: Borrow<Graph<F, O>> + Hash,
+    P: Borrow<SimplePlan<F, O, M>>,
+{
+    pub state: SimpleState<F, O, M, P>,
+    pub threads: usize,
+    /// Sessions of the workers, kept from one run to the next for their caches.
+    sessions: Vec<SessionState>,
+}
+
+type Job = (usize, Option<Box<dyn OpState>>, TVec<Arc<Tensor>>);
+type Done = (usize, Option<Box<dyn OpState>>, TractResult<TVec<Arc<Tensor>>>);
+
+impl<F, O, M, P> ParallelState<F, O, M, P>
+where
+    F: Fact + Hash + Clone + Send + Sync + 'static,
+    O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + Send + Sync + 'static + Hash,
+    M: Borrow<Graph<F, O>> + Hash,
+    P: Borrow<SimplePlan<F, O, M>> + Clone,
+{
+    pub fn new(plan: P, threads: usize) -> TractResult<ParallelState<F, O, M, P>> {
+        if threads == 0 {
+            bail!("ParallelState needs at least one thread");
+        }
+        let sessions = (0..threads).map(|_| SessionState::default()).collect();
+        Ok(ParallelState { state: SimpleState::new(plan)?, threads, sessions })
+    }
+
+    pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.state.set_inputs(inputs)?;
+        let stateful: Vec<bool> = self.state.states.iter().map(|s| s.is_some()).collect();
+        let result = self.run_scheduled();
+        self.state.reset_wires()?;
+        // states of the nodes in flight are lost when a node fails
+        if self.state.states.iter().zip(stateful).any(|(s, stateful)| stateful && s.is_none()) {
+            self.state.reset_op_states()?;
+        }
+        result
+    }
+
+    fn run_scheduled(&mut self) -> TractResult<TVec<Arc<Tensor>>> {
+        use std::collections::BTreeSet;
+        use std::sync::{mpsc, Mutex};
+
+        let ParallelState { ref mut state, ref mut sessions, .. } = self;
+        let SimpleState { ref plan, ref mut session_state, ref mut states, ref mut values, .. } =
+            state;
+        let plan = plan.borrow();
+        let model = plan.model();
+        let nodes = model.nodes();
+
+        for session in sessions.iter_mut() {
+            session.inputs = session_state.inputs.clone();
+            session.resolved_symbols = session_state.resolved_symbols.clone();
+            session.executor = session_state.executor.clone();
+        }
+
+        let mut step_of = vec![None; nodes.len()];
+        for (step, &n) in plan.order.iter().enumerate() {
+            step_of[n] = Some(step);
+        }
+        // inputs still to be computed for each node
+        let mut missing: Vec<usize> = nodes.iter().map(|n| n.inputs.len()).collect();
+        // remaining uses of each node outputs, outputs of the plan count as one
+        let mut uses = vec![0usize; nodes.len()];
+        for &n in &plan.order {
+            for i in &nodes[n].inputs {
+                uses[i.node] += 1;
+            }
+        }
+        for o in &plan.outputs {
+            uses[o.node] += 1;
+        }
+        // steps of the nodes with a state needing the model session, they are
+        // evaluated in plan order
+        let is_inline: Vec<bool> = plan
+            .order
+            .iter()
+            .map(|&n| states[n].as_ref().map(|s| !s.runs_on_session_copy()).unwrap_or(false))
+            .collect();
+        let inline: Vec<usize> = (0..plan.order.len()).filter(|&step| is_inline[step]).collect();
+        let mut next_inline = 0;
+        let mut ready: BTreeSet<usize> =
+            (0..plan.order.len()).filter(|&step| missing[plan.order[step]] == 0).collect();
+
+        let (job_tx, job_rx) = mpsc::channel::<Job>();
+        let (done_tx, done_rx) = mpsc::channel::<Done>();
+        let job_rx = Mutex::new(job_rx);
+        crossbeam_utils::thread::scope(|scope| {
+            for session in sessions.iter_mut() {
+                let job_rx = &job_rx;
+                let done_tx = done_tx.clone();
+                scope.spawn(move |_| loop {
+                    let job = job_rx.lock().unwrap().recv();
+                    let (id, mut state, inputs) = match job {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    let vs = eval(session, state.as_mut().map(|s| &mut **s), &nodes[id], inputs);
+                    if done_tx.send((id, state, vs)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(done_tx);
+
+            // job_tx is moved in so that workers are released on early return
+            let job_tx = job_tx;
+            let mut in_flight = 0;
+            let mut done = 0;
+            while done < plan.order.len() {
+                let mut inline_job = None;
+                for step in ready.iter().cloned().collect::<Vec<_>>() {
+                    let id = plan.order[step];
+                    let runs_inline = is_inline[step];
+                    if runs_inline && inline[next_inline] != step {
+                        continue;
+                    }
+                    ready.remove(&step);
+                    let node = &nodes[id];
+                    trace!("Running step {}, node {}", step, node);
+                    let mut inputs: TVec<Arc<Tensor>> = tvec![];
+                    for i in &node.inputs {
+                        let prec = values[i.node].as_ref().ok_or_else(|| {
+                            format_err!("Computing {}, precursor {} not done", node, nodes[i.node])
+                        })?;
+                        inputs.push(prec[i.slot].clone());
+                        uses[i.node] -= 1;
+                        if uses[i.node] == 0 {
+                            values[i.node] = None;
+                        }
+                    }
+                    if runs_inline {
+                        inline_job = Some((id, inputs));
+                        break;
+                    }
+                    job_tx
+                        .send((id, states[id].take(), inputs))
+                        .map_err(|_| format_err!("Worker threads are gone"))?;
+                    in_flight += 1;
+                }
+                let (id, vs) = if let Some((id, inputs)) = inline_job {
+                    next_inline += 1;
+                    let vs = eval(
+                        session_state,
+                        states[id].as_mut().map(|s| &mut **s),
+                        &nodes[id],
+                        inputs,
+                    )?;
+                    (id, vs)
+                } else if in_flight > 0 {
+                    let (id, state, vs) = done_rx.recv()?;
+                    states[id] = state;
+                    in_flight -= 1;
+                    (id, vs?)
+                } else {
+                    bail!("No node can be scheduled, {} left", plan.order.len() - done);
+                };
+                for (slot, output) in nodes[id].outputs.iter().enumerate() {
+                    if slot >= vs.len() {
+                        bail!("Evaluating {}: missing output {}", nodes[id], slot);
+                    }
+                    for succ in &output.successors {
+                        if let Some(step) = step_of[succ.node] {
+                            missing[succ.node] -= 1;
+                            if missing[succ.node] == 0 {
+                                ready.insert(step);
+                            }
+                        }
+                    }
+                }
+                values[id] = Some(vs);
+                done += 1;
+            }
+            let mut result = tvec!();
+            for output in &plan.outputs {
+                trace!("Extracting value {:?} ({})", output, nodes[output.node]);
+                result.push(values[output.node].as_ref().unwrap()[output.slot].clone())
+            }
+            Ok(result)
+        })
+        .map_err(|_| format_err!("A worker thread panicked"))?
+    }
+}
+
 pub fn eval<F, O>(
     session_state: &mut SessionState,
     mut state: Option<&mut (dyn OpState + 'static)>,
@@ -443,3 +721,73 @@ where
     .with_context(|| format!("Evaluating {}", node));
     r
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn parallel_state_matches_simple_plan() -> TractResult<()> {
+        use crate::ops::cnn::{ConvUnary, KernelFormat, PaddingSpec, PoolSpec};
+        use crate::ops::matmul::MatMulUnary;
+        use crate::ops::nn::DataFormat;
+        let ramp = |shape: &[usize], mul: f32| -> TractResult<Arc<Tensor>> {
+            let len = shape.iter().product::<usize>();
+            let values: Vec<f32> = (0..len).map(|i| (i % 7) as f32 * mul - 1.0).collect();
+            Ok(tensor1(&values).into_shape(shape)?.into_arc_tensor())
+        };
+        let mut model = TypedModel::default();
+        let image =
+            model.add_source("image", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 8, 8]))?;
+        let matrix =
+            model.add_source("matrix", TypedFact::dt_shape(f32::datum_type(), &[16, 16]))?;
+        let mut convs = tvec!();
+        let mut mms = tvec!();
+        for h in 0..4 {
+            let conv = ConvUnary {
+                pool_spec: PoolSpec {
+                    data_format: DataFormat::NCHW,
+                    kernel_shape: tvec!(3, 3),
+                    padding: PaddingSpec::Valid,
+                    dilations: None,
+                    strides: None,
+                    output_channel_override: Some(4),
+                },
+                kernel_fmt: KernelFormat::OIHW,
+                kernel: ramp(&[4, 3, 3, 3], h as f32 / 8.0)?,
+                group: 1,
+                bias: Some(ramp(&[4], 0.5)?),
+                q_params: None,
+            };
+            convs.push(model.wire_node(format!("conv.{}", h), conv, &[image])?[0]);
+            let mm = MatMulUnary::new(ramp(&[16, 16], h as f32 / 4.0)?, false, false, false);
+            mms.push(model.wire_node(format!("mm.{}", h), mm, &[matrix])?[0]);
+        }
+        let mut outputs = tvec!();
+        for (name, heads) in [("conv", convs), ("mm", mms)].iter() {
+            let mut sum = heads[0];
+            for (ix, h) in heads[1..].iter().enumerate() {
+                let name = format!("{}.sum.{}", name, ix);
+                sum = model.wire_node(name, math::add::bin_typed(), &[sum, *h])?[0];
+            }
+            outputs.push(sum);
+        }
+        model.set_output_outlets(&outputs)?;
+        let model = model.into_optimized()?;
+        let plan = SimplePlan::new(&model)?;
+        let inputs =
+            tvec!(ramp(&[1, 3, 8, 8], 0.25)?.into_tensor(), ramp(&[16, 16], 0.125)?.into_tensor());
+        let expected = plan.run(inputs.clone())?;
+        let mut state = ParallelState::new(&plan, 3)?;
+        // convolutions and matrix multiplications have states, none of them
+        // is pinned to the calling thread
+        let states: Vec<_> = state.state.states.iter().flatten().collect();
+        assert!(states.len() >= 8);
+        assert!(states.iter().all(|s| s.runs_on_session_copy()));
+        for _ in 0..2 {
+            assert_eq!(state.run(inputs.clone())?, expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+//! Checks that a state reuses the buffers of its memory arena from one run
+//! to the next.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tract_core::internal::*;
+use tract_core::ops::matmul::MatMulUnary;
+
+/// Counts the allocations big enough to hold one of the test tensors.
+struct CountingAllocator;
+
+static BIG_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+const BIG: usize = 64 * 64 * 4;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() >= BIG {
+            BIG_ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn no_tensor_allocation_after_first_run() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let mut wire = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[64, 64]))?;
+    for ix in 0..3 {
+        let a = Tensor::zero::<f32>(&[64, 64])?.into_arc_tensor();
+        let op = MatMulUnary::new(a, false, false, false);
+        wire = model.wire_node(format!("mm.{}", ix), op, &[wire])?[0];
+    }
+    model.set_output_outlets(&[wire])?;
+    let model = model.into_optimized()?;
+    let plan = SimplePlan::new(&model)?;
+    let mut state = SimpleState::new(&plan)?;
+    let input = Tensor::zero::<f32>(&[64, 64])?;
+    state.run(tvec!(input.clone()))?;
+    let input = input.clone();
+    BIG_ALLOCATIONS.store(0, Ordering::SeqCst);
+    let output = state.run(tvec!(input))?;
+    // only the output, handed to the caller, is allocated
+    assert_eq!(BIG_ALLOCATIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(output[0].shape(), &[64, 64]);
+    Ok(())
+}
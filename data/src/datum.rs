@@ -1,6 +1,6 @@
 //! `Tensor` is the main data container for tract
 use crate::dim::TDim;
-use crate::f16::f16;
+use crate::f16::{bf16, f16};
 use crate::tensor::litteral::*;
 use crate::tensor::Tensor;
 use crate::TVec;
@@ -97,6 +97,7 @@ pub enum DatumType {
     String,
     QI8(QParams),
     QU8(QParams),
+    BF16,
 }
 
 impl DatumType {
@@ -105,6 +106,8 @@ impl DatumType {
         if *self == String || *self == TDim || *self == Blob || *self == Bool || self.is_quantized()
         {
             tvec!(*self)
+        } else if *self == BF16 {
+            tvec!(BF16, F32, F64)
         } else if self.is_float() {
             [F16, F32, F64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
         } else if self.is_signed() {
@@ -162,7 +165,7 @@ impl DatumType {
 
     pub fn is_float(&self) -> bool {
         match self {
-            DatumType::F16 | DatumType::F32 | DatumType::F64 => true,
+            DatumType::F16 | DatumType::BF16 | DatumType::F32 | DatumType::F64 => true,
             _ => false,
         }
     }
@@ -250,6 +253,7 @@ impl DatumType {
             DatumType::I32 => tensor0(i32::MIN),
             DatumType::I64 => tensor0(i64::MIN),
             DatumType::F16 => tensor0(f16(half::f16::MIN)),
+            DatumType::BF16 => tensor0(bf16(half::bf16::MIN)),
             DatumType::F32 => tensor0(f32::MIN),
             DatumType::F64 => tensor0(f64::MIN),
             _ => panic!("No min value for datum type {:?}", self),
@@ -266,6 +270,7 @@ impl DatumType {
             DatumType::I32 => tensor0(i32::MAX),
             DatumType::I64 => tensor0(i64::MAX),
             DatumType::F16 => tensor0(f16(half::f16::MAX)),
+            DatumType::BF16 => tensor0(bf16(half::bf16::MAX)),
             DatumType::F32 => tensor0(f32::MAX),
             DatumType::F64 => tensor0(f64::MAX),
             _ => panic!("No max value for datum type {:?}", self),
@@ -287,6 +292,7 @@ impl std::str::FromStr for DatumType {
             "U32" | "u32" => Ok(DatumType::U32),
             "U64" | "u64" => Ok(DatumType::U64),
             "F16" | "f16" => Ok(DatumType::F16),
+            "BF16" | "bf16" => Ok(DatumType::BF16),
             "F32" | "f32" => Ok(DatumType::F32),
             "F64" | "f64" => Ok(DatumType::F64),
             "Bool" | "bool" => Ok(DatumType::Bool),
@@ -348,6 +354,7 @@ macro_rules! datum {
 
 datum!(bool, Bool);
 datum!(f16, F16);
+datum!(bf16, BF16);
 datum!(f32, F32);
 datum!(f64, F64);
 datum!(i8, I8);
@@ -5,7 +5,7 @@ use std::ops;
 
 mod tree;
 
-pub use self::tree::{Symbol, SymbolValues, TDim, UndeterminedSymbol};
+pub use self::tree::{Symbol, SymbolTable, SymbolValues, TDim, UndeterminedSymbol};
 type TractError = anyhow::Error;
 type TractResult<T> = anyhow::Result<T>;
 
@@ -181,11 +181,11 @@ mod tests {
     use super::*;
 
     lazy_static::lazy_static! {
-        static ref S: Symbol = crate::dim::Symbol::new('S');
+        static ref S: Symbol = crate::dim::SymbolTable::default().sym("S");
     }
 
     pub fn s() -> TDim {
-        (*S).into()
+        (&*S).into()
     }
 
     #[test]
@@ -215,8 +215,10 @@ mod tests {
 
     #[test]
     fn div_sym_sym_complex() {
+        let table = SymbolTable::default();
+        let (s, b) = (table.sym("s"), table.sym("b"));
         assert_eq!(
-            (256.to_dim() * 's' * 'b').maybe_div(&(1.to_dim() * 's' * 'b')).unwrap(),
+            (256.to_dim() * &s * &b).maybe_div(&(1.to_dim() * &s * &b)).unwrap(),
             (256.into(), 1)
         );
     }
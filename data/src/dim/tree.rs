@@ -1,6 +1,8 @@
 use itertools::Itertools;
 use num_traits::{AsPrimitive, PrimInt, Zero};
 use std::collections::HashMap;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{Arc, Mutex};
 use std::{fmt, ops};
 
 #[derive(Debug)]
@@ -16,66 +18,142 @@ impl std::error::Error for UndeterminedSymbol {}
 
 macro_rules! b( ($e:expr) => { Box::new($e) } );
 
-lazy_static::lazy_static! {
-    static ref SYMBOL_TABLE: std::sync::Mutex<Vec<char>> = std::sync::Mutex::new(Vec::new());
+static SYMBOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Name and asserted bounds of a symbol.
+#[derive(Debug)]
+struct SymbolData {
+    id: usize,
+    name: String,
+    bounds: Mutex<(i64, Option<i64>)>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
-pub struct Symbol(char, usize);
+/// A symbolic dimension, created by a model SymbolTable.
+///
+/// Symbols compare by identity, not by name: the id orders them by creation.
+#[derive(Clone)]
+pub struct Symbol(Arc<SymbolData>);
 
 impl Symbol {
-    pub fn new(c: char) -> Symbol {
-        let mut table = SYMBOL_TABLE.lock().unwrap();
-        table.push(c);
-        Symbol(c, table.len() - 1)
+    fn new(name: impl Into<String>) -> Symbol {
+        let id = SYMBOL_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        // symbols stand for dimensions, so they are never negative
+        Symbol(Arc::new(SymbolData { id, name: name.into(), bounds: Mutex::new((0, None)) }))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Assert the symbol value is greater or equal to `min`.
+    pub fn assert_ge(&self, min: i64) {
+        let mut bounds = self.0.bounds.lock().unwrap();
+        bounds.0 = bounds.0.max(min);
+    }
+
+    /// Assert the symbol value is lower or equal to `max`.
+    pub fn assert_le(&self, max: i64) {
+        let mut bounds = self.0.bounds.lock().unwrap();
+        bounds.1 = Some(bounds.1.map(|m| m.min(max)).unwrap_or(max));
+    }
+
+    /// Asserted inclusive bounds of the symbol value. No upper bound is represented as None.
+    pub fn bounds(&self) -> (i64, Option<i64>) {
+        *self.0.bounds.lock().unwrap()
     }
 }
 
-impl From<char> for Symbol {
-    fn from(c: char) -> Symbol {
-        let mut table = SYMBOL_TABLE.lock().unwrap();
-        if let Some(pos) = table.iter().position(|s| *s == c) {
-            Symbol(c, pos)
-        } else {
-            table.push(c);
-            Symbol(c, table.len() - 1)
-        }
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for Symbol {}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> std::cmp::Ordering {
+        self.0.id.cmp(&other.0.id)
+    }
+}
+
+impl std::hash::Hash for Symbol {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.id.hash(state)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}#{}", self.0.name, self.0.id)
     }
 }
 
-impl From<char> for TDim {
-    fn from(c: char) -> TDim {
-        Symbol::from(c).into()
+impl fmt::Display for Symbol {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.0.name)
     }
 }
 
+/// Symbols of a model, looked up by name.
+///
+/// Clones share the same symbols. Two different tables give different
+/// symbols for the same name, so models do not clash.
 #[derive(Clone, Debug, Default)]
-pub struct SymbolValues(Vec<Option<i64>>);
+pub struct SymbolTable(Arc<Mutex<HashMap<String, Symbol>>>);
+
+impl SymbolTable {
+    /// Symbol for `name`, created on first use.
+    pub fn sym(&self, name: &str) -> Symbol {
+        self.0.lock().unwrap().entry(name.to_string()).or_insert_with(|| Symbol::new(name)).clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.0.lock().unwrap().get(name).cloned()
+    }
+
+    /// A new symbol, distinct from all the others. It is registered as `name`, or as `name_1`,
+    /// `name_2`... if that name is already taken.
+    pub fn new_symbol(&self, name: &str) -> Symbol {
+        let mut table = self.0.lock().unwrap();
+        let mut unique = name.to_string();
+        let mut ix = 0;
+        while table.contains_key(&unique) {
+            ix += 1;
+            unique = format!("{}_{}", name, ix);
+        }
+        let symbol = Symbol::new(unique.clone());
+        table.insert(unique, symbol.clone());
+        symbol
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SymbolValues(HashMap<Symbol, Option<i64>>);
 
 impl SymbolValues {
-    pub fn with(mut self, s: Symbol, v: i64) -> Self {
+    pub fn with(mut self, s: &Symbol, v: i64) -> Self {
         self[s] = Some(v);
         self
     }
 }
 
-impl std::ops::Index<Symbol> for SymbolValues {
+impl<'a> std::ops::Index<&'a Symbol> for SymbolValues {
     type Output = Option<i64>;
-    fn index(&self, index: Symbol) -> &Self::Output {
-        if index.1 < self.0.len() {
-            &self.0[index.1]
-        } else {
-            &None
-        }
+    fn index(&self, index: &'a Symbol) -> &Self::Output {
+        self.0.get(index).unwrap_or(&None)
     }
 }
 
-impl std::ops::IndexMut<Symbol> for SymbolValues {
-    fn index_mut(&mut self, index: Symbol) -> &mut Self::Output {
-        if index.1 >= self.0.len() {
-            self.0.resize_with(index.1 + 1, Default::default)
-        }
-        &mut self.0[index.1]
+impl<'a> std::ops::IndexMut<&'a Symbol> for SymbolValues {
+    fn index_mut(&mut self, index: &'a Symbol) -> &mut Self::Output {
+        self.0.entry(index.clone()).or_insert(None)
     }
 }
 
@@ -87,6 +165,8 @@ pub enum TDim {
     Mul(Vec<TDim>),
     MulInt(i64, Box<TDim>),
     Div(Box<TDim>, u64),
+    Min(Vec<TDim>),
+    Max(Vec<TDim>),
 }
 
 use TDim::*;
@@ -94,12 +174,14 @@ use TDim::*;
 impl fmt::Display for TDim {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match &self {
-            Sym(sym) => write!(fmt, "{}", sym.0),
+            Sym(sym) => write!(fmt, "{}", sym),
             Val(it) => write!(fmt, "{}", it),
             Add(it) => write!(fmt, "{}", it.iter().map(|x| format!("{}", x)).join("+")),
             Mul(it) => write!(fmt, "{}", it.iter().map(|x| format!("{}", x)).join("*")),
             MulInt(a, b) => write!(fmt, "{}*{}", a, b),
             Div(a, b) => write!(fmt, "({})/{}", a, b),
+            Min(it) => write!(fmt, "min({})", it.iter().map(|x| format!("{}", x)).join(",")),
+            Max(it) => write!(fmt, "max({})", it.iter().map(|x| format!("{}", x)).join(",")),
         }
     }
 }
@@ -119,12 +201,14 @@ impl TDim {
 
     pub fn eval(&self, values: &SymbolValues) -> TDim {
         match self {
-            Sym(sym) => values[*sym].map(|s| Val(s)).unwrap_or(Sym(*sym)),
+            Sym(sym) => values[sym].map(|s| Val(s)).unwrap_or_else(|| Sym(sym.clone())),
             Val(v) => Val(*v),
             Add(terms) => terms.iter().fold(Val(0), |acc, it| -> TDim { acc + it.eval(values) }),
             Mul(terms) => terms.iter().fold(Val(1), |acc, it| -> TDim { acc * it.eval(values) }),
             Div(a, q) => a.eval(values) / *q as i64,
             MulInt(p, a) => a.eval(values) * *p,
+            Min(terms) => Min(terms.iter().map(|t| t.eval(values)).collect()).simplify(),
+            Max(terms) => Max(terms.iter().map(|t| t.eval(values)).collect()).simplify(),
         }
     }
 
@@ -147,13 +231,14 @@ impl TDim {
             Mul(terms) => 3 * terms.iter().map(TDim::cost).sum::<usize>(),
             Div(a, _) => 3 * a.cost(),
             MulInt(_, a) => 2 * a.cost(),
+            Min(terms) | Max(terms) => 2 * terms.iter().map(TDim::cost).sum::<usize>(),
         }
     }
 
     fn wiggle(&self) -> Vec<TDim> {
         use self::TDim::*;
         match self {
-            Sym(_) | Val(_) | Mul(_) => vec![self.clone()],
+            Sym(_) | Val(_) | Mul(_) | Min(_) | Max(_) => vec![self.clone()],
             Add(terms) => {
                 let mut forms = vec![];
                 let sub_wiggle = terms.iter().map(|e| e.wiggle()).multi_cartesian_product();
@@ -363,10 +448,51 @@ impl TDim {
                     Div(b!(a), q)
                 }
             }
+            Min(terms) => Self::simplify_min_max(terms, false),
+            Max(terms) => Self::simplify_min_max(terms, true),
             _ => self,
         }
     }
 
+    fn simplify_min_max(terms: Vec<TDim>, max: bool) -> TDim {
+        let mut flat = vec![];
+        let mut konst: Option<i64> = None;
+        let mut todo = terms;
+        while let Some(term) = todo.pop() {
+            match term.simplify() {
+                Min(inner) if !max => todo.extend(inner),
+                Max(inner) if max => todo.extend(inner),
+                Val(v) => {
+                    konst = Some(konst.map(|k| if max { k.max(v) } else { k.min(v) }).unwrap_or(v))
+                }
+                it => flat.push(it),
+            }
+        }
+        flat.extend(konst.map(Val));
+        flat.sort();
+        flat.dedup();
+        // drop the terms another one provably dominates
+        while let Some(ix) = (0..flat.len()).find(|&ix| {
+            flat.iter().enumerate().any(|(other, t)| {
+                other != ix
+                    && if max {
+                        (t.clone() - &flat[ix]).prove_positive_or_zero()
+                    } else {
+                        (flat[ix].clone() - t).prove_positive_or_zero()
+                    }
+            })
+        }) {
+            flat.remove(ix);
+        }
+        if flat.len() == 1 {
+            flat.remove(0)
+        } else if max {
+            Max(flat)
+        } else {
+            Min(flat)
+        }
+    }
+
     fn gcd(&self) -> u64 {
         use self::TDim::*;
         use num_integer::Integer;
@@ -386,6 +512,10 @@ impl TDim {
                     1
                 }
             }
+            Min(terms) | Max(terms) => {
+                let (head, tail) = terms.split_first().unwrap();
+                tail.iter().fold(head.gcd(), |a, b| a.gcd(&b.gcd()))
+            }
         }
     }
 
@@ -409,6 +539,8 @@ impl TDim {
                 }
             }
             Div(a, q) => Div(a.clone(), q * d),
+            Min(terms) => Min(terms.iter().map(|t| t.div(d)).collect()),
+            Max(terms) => Max(terms.iter().map(|t| t.div(d)).collect()),
         }
     }
 
@@ -416,11 +548,11 @@ impl TDim {
         TDim::Div(Box::new(Add(vec![self, Val(rhs as i64 - 1)])), rhs).reduce()
     }
 
-    pub fn slope(&self, sym: Symbol) -> (i64, u64) {
-        fn slope_rec(d: &TDim, sym: Symbol) -> (i64, i64) {
+    pub fn slope(&self, sym: &Symbol) -> (i64, u64) {
+        fn slope_rec(d: &TDim, sym: &Symbol) -> (i64, i64) {
             match d {
                 Val(_) => (0, 1),
-                Sym(s) => ((sym == *s) as i64, 1),
+                Sym(s) => ((sym == s) as i64, 1),
                 Add(terms) => terms
                     .iter()
                     .map(|d| slope_rec(d, sym))
@@ -437,6 +569,16 @@ impl TDim {
                     let (n, d) = slope_rec(a, sym);
                     (n, d * *q as i64)
                 }
+                // asymptotic slope: the steepest term wins a max, the flattest a min
+                Min(terms) | Max(terms) => {
+                    let slopes = terms.iter().map(|t| slope_rec(t, sym));
+                    let cmp = |a: &(i64, i64), b: &(i64, i64)| (a.0 * b.1).cmp(&(b.0 * a.1));
+                    if let Max(_) = d {
+                        slopes.max_by(cmp).unwrap()
+                    } else {
+                        slopes.min_by(cmp).unwrap()
+                    }
+                }
             }
         }
         let (p, q) = slope_rec(self, sym);
@@ -446,15 +588,102 @@ impl TDim {
     pub fn symbols(&self) -> std::collections::HashSet<Symbol> {
         match self {
             Val(_) => maplit::hashset!(),
-            Sym(s) => maplit::hashset!(*s),
-            Add(terms) | Mul(terms) => terms.iter().fold(maplit::hashset!(), |mut set, v| {
-                set.extend(v.symbols().into_iter());
-                set
-            }),
+            Sym(s) => maplit::hashset!(s.clone()),
+            Add(terms) | Mul(terms) | Min(terms) | Max(terms) => {
+                terms.iter().fold(maplit::hashset!(), |mut set, v| {
+                    set.extend(v.symbols().into_iter());
+                    set
+                })
+            }
             MulInt(_, a) => a.symbols(),
             Div(a, _) => a.symbols(),
         }
     }
+
+    pub fn mini(self, other: impl Into<TDim>) -> TDim {
+        Min(vec![self, other.into()]).reduce()
+    }
+
+    pub fn maxi(self, other: impl Into<TDim>) -> TDim {
+        Max(vec![self, other.into()]).reduce()
+    }
+
+    /// Lowest value the expression can take given the symbol assertions, if bounded.
+    pub fn low_inclusive_bound(&self) -> Option<i64> {
+        self.bounds().0
+    }
+
+    /// Highest value the expression can take given the symbol assertions, if bounded.
+    pub fn high_inclusive_bound(&self) -> Option<i64> {
+        self.bounds().1
+    }
+
+    pub fn prove_positive_or_zero(&self) -> bool {
+        self.low_inclusive_bound().map(|low| low >= 0).unwrap_or(false)
+    }
+
+    pub fn prove_strict_positive(&self) -> bool {
+        self.low_inclusive_bound().map(|low| low > 0).unwrap_or(false)
+    }
+
+    fn bounds(&self) -> (Option<i64>, Option<i64>) {
+        // unbounded if any of the terms is
+        fn all(
+            mut it: impl Iterator<Item = Option<i64>>,
+            f: impl Fn(i64, i64) -> Option<i64>,
+        ) -> Option<i64> {
+            let first = it.next()??;
+            it.try_fold(first, |a, b| f(a, b?))
+        }
+        // bounded if any of the terms is
+        fn any(it: impl Iterator<Item = Option<i64>>, f: impl Fn(i64, i64) -> i64) -> Option<i64> {
+            it.flatten().fold(None, |acc, b| Some(acc.map(|a| f(a, b)).unwrap_or(b)))
+        }
+        match self {
+            Val(v) => (Some(*v), Some(*v)),
+            Sym(s) => {
+                let (min, max) = s.bounds();
+                (Some(min), max)
+            }
+            Add(terms) => (
+                all(terms.iter().map(|t| t.bounds().0), i64::checked_add),
+                all(terms.iter().map(|t| t.bounds().1), i64::checked_add),
+            ),
+            Mul(terms) => {
+                let bounds = terms.iter().map(|t| t.bounds()).collect::<Vec<_>>();
+                if bounds.iter().all(|b| b.0.map(|low| low >= 0).unwrap_or(false)) {
+                    (
+                        all(bounds.iter().map(|b| b.0), i64::checked_mul),
+                        all(bounds.iter().map(|b| b.1), i64::checked_mul),
+                    )
+                } else {
+                    (None, None)
+                }
+            }
+            MulInt(p, a) => {
+                let (low, high) = a.bounds();
+                let low = low.and_then(|l| l.checked_mul(*p));
+                let high = high.and_then(|h| h.checked_mul(*p));
+                if *p >= 0 {
+                    (low, high)
+                } else {
+                    (high, low)
+                }
+            }
+            Div(a, q) => {
+                let (low, high) = a.bounds();
+                (low.map(|l| l / *q as i64), high.map(|h| h / *q as i64))
+            }
+            Min(terms) => (
+                all(terms.iter().map(|t| t.bounds().0), |a, b| Some(a.min(b))),
+                any(terms.iter().map(|t| t.bounds().1), i64::min),
+            ),
+            Max(terms) => (
+                any(terms.iter().map(|t| t.bounds().0), i64::max),
+                all(terms.iter().map(|t| t.bounds().1), |a, b| Some(a.max(b))),
+            ),
+        }
+    }
 }
 
 pub(super) fn reduce_ratio(mut p: i64, mut q: i64) -> (i64, u64) {
@@ -539,7 +768,7 @@ impl From<Symbol> for TDim {
 
 impl<'a> From<&'a Symbol> for TDim {
     fn from(it: &'a Symbol) -> Self {
-        TDim::Sym(*it)
+        TDim::Sym(it.clone())
     }
 }
 
@@ -691,11 +920,11 @@ mod tests {
     macro_rules! b( ($e:expr) => { Box::new($e) } );
 
     lazy_static::lazy_static! {
-        static ref S: Symbol = crate::dim::Symbol::new('S');
+        static ref S: Symbol = SymbolTable::default().sym("S");
     }
 
     fn s() -> TDim {
-        (*S).into()
+        (&*S).into()
     }
 
     fn neg(a: &TDim) -> TDim {
@@ -776,11 +1005,78 @@ mod tests {
 
     #[test]
     fn substitution() {
-        let x = Symbol::new('x');
-        let e: TDim = x.into();
-        assert_eq!(e.eval(&SymbolValues::default().with(x, 2)).to_i64().unwrap(), 2);
+        let x = SymbolTable::default().sym("x");
+        let e: TDim = x.clone().into();
+        assert_eq!(e.eval(&SymbolValues::default().with(&x, 2)).to_i64().unwrap(), 2);
         let e = e + 3;
-        assert_eq!(e.eval(&SymbolValues::default().with(x, 2)).to_i64().unwrap(), 5);
+        assert_eq!(e.eval(&SymbolValues::default().with(&x, 2)).to_i64().unwrap(), 5);
+    }
+
+    #[test]
+    fn min_max_of_values() {
+        assert_eq!(TDim::from(3).mini(5), Val(3));
+        assert_eq!(TDim::from(3).maxi(5), Val(5));
+        assert_eq!(Max(vec![Val(1), Max(vec![Val(4), Val(2)])]).reduce(), Val(4));
+    }
+
+    #[test]
+    fn min_max_use_symbol_bounds() {
+        let s = SymbolTable::default().sym("s");
+        assert_eq!(TDim::from(&s).maxi(0), TDim::from(&s));
+        assert_eq!(TDim::from(&s).mini(0), Val(0));
+        assert_eq!(format!("{}", TDim::from(&s).maxi(1)), "max(s,1)");
+        s.assert_ge(1);
+        assert_eq!(TDim::from(&s).maxi(1), TDim::from(&s));
+        assert_eq!((TDim::from(&s) + 2).mini(TDim::from(&s)), TDim::from(&s));
+        assert!((TDim::from(&s) - 1).prove_positive_or_zero());
+        assert!(!(TDim::from(&s) - 2).prove_positive_or_zero());
+        s.assert_le(8);
+        assert_eq!(TDim::from(&s).mini(10), TDim::from(&s));
+        assert_eq!(TDim::from(&s).mini(5).high_inclusive_bound(), Some(5));
+    }
+
+    #[test]
+    fn eval_min_max() {
+        let s = SymbolTable::default().sym("s");
+        let e = TDim::from(&s).mini(10).div_ceil(3);
+        assert_eq!(e.eval(&SymbolValues::default().with(&s, 4)), Val(2));
+        assert_eq!(e.eval(&SymbolValues::default().with(&s, 20)), Val(4));
+    }
+
+    #[test]
+    fn slope_of_max() {
+        let s = SymbolTable::default().sym("s");
+        assert_eq!((TDim::from(&s) * 2).maxi(&s).slope(&s), (2, 1));
+        assert_eq!((TDim::from(&s) * 2).mini(&s).slope(&s), (1, 1));
+    }
+
+    #[test]
+    fn symbol_tables_are_scoped() {
+        let a = SymbolTable::default();
+        let b = SymbolTable::default();
+        let batch = a.sym("batch_size");
+        assert_eq!(a.sym("batch_size"), batch);
+        assert_eq!(a.clone().get("batch_size"), Some(batch.clone()));
+        assert_ne!(b.sym("batch_size"), batch);
+        assert_ne!(format!("{:?}", b.sym("batch_size")), format!("{:?}", batch));
+        b.sym("batch_size").assert_ge(4);
+        assert_eq!(batch.bounds(), (0, None));
+        assert_eq!(b.sym("batch_size").bounds(), (4, None));
+        assert_eq!(format!("{}", TDim::from(&batch) * 2), "2*batch_size");
+    }
+
+    #[test]
+    fn new_symbols_are_registered_under_unique_names() {
+        let table = SymbolTable::default();
+        let k = table.new_symbol("k");
+        let k_1 = table.new_symbol("k");
+        assert_ne!(k, k_1);
+        assert_eq!(format!("{}", k_1), "k_1");
+        assert_eq!(table.get("k"), Some(k));
+        assert_eq!(table.get("k_1"), Some(k_1));
+        let k_2 = table.sym("k_2");
+        assert_eq!(format!("{}", table.new_symbol("k")), "k_3");
+        assert_eq!(table.get("k_2"), Some(k_2));
     }
 
     #[test]
@@ -799,8 +1095,9 @@ mod tests {
     fn reduce_muls() {
         let e: TDim = Val(1) * s();
         assert_eq!(e, s());
-        let e: TDim = s() * 'b' * 1;
-        assert_eq!(e, s() * 'b');
+        let b = SymbolTable::default().sym("b");
+        let e: TDim = s() * &b * 1;
+        assert_eq!(e, s() * &b);
     }
 
     #[test]
@@ -10,6 +10,12 @@ impl SloppyHash for crate::f16::f16 {
     }
 }
 
+impl SloppyHash for crate::f16::bf16 {
+    fn sloppy_hash<S: Hasher>(&self, state: &mut S) {
+        unsafe { std::mem::transmute_copy::<crate::f16::bf16, i16>(self).hash(state) }
+    }
+}
+
 impl SloppyHash for f32 {
     fn sloppy_hash<S: Hasher>(&self, state: &mut S) {
         self.to_bits().hash(state)
@@ -14,7 +14,7 @@ pub type TVec<T> = smallvec::SmallVec<[T; 4]>;
 
 pub mod prelude {
     pub use crate::datum::{Blob, Datum, DatumType, QParams};
-    pub use crate::dim::{Symbol, SymbolValues, TDim, ToDim};
+    pub use crate::dim::{Symbol, SymbolTable, SymbolValues, TDim, ToDim};
     pub use crate::f16::*;
     pub use crate::tensor::litteral::*;
     pub use crate::tensor::{natural_strides, IntoArcTensor, IntoTensor, Tensor};
@@ -31,6 +31,7 @@ pub mod internal {
     pub use crate::datum::ClampCast;
     pub use crate::dim::{DimLike, TDim, ToDim};
     pub use crate::prelude::*;
+    pub use crate::tensor::recycle;
     pub use crate::tensor::view::TensorView;
     pub use ndarray as tract_ndarray;
     pub use smallvec as tract_smallvec;
@@ -33,6 +33,7 @@ macro_rules! dispatch_datum {
             DatumType::I32  => $($path)::*::<i32>($($args),*),
             DatumType::I64  => $($path)::*::<i64>($($args),*),
             DatumType::F16  => $($path)::*::<f16>($($args),*),
+            DatumType::BF16 => $($path)::*::<bf16>($($args),*),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             DatumType::Blob => $($path)::*::<Blob>($($args),*),
@@ -59,6 +60,7 @@ macro_rules! dispatch_datum_by_size {
             DatumType::I32  => $($path)::*::<i32>($($args),*),
             DatumType::I64  => $($path)::*::<i64>($($args),*),
             DatumType::F16  => $($path)::*::<i16>($($args),*),
+            DatumType::BF16 => $($path)::*::<i16>($($args),*),
             DatumType::F32  => $($path)::*::<i32>($($args),*),
             DatumType::F64  => $($path)::*::<i64>($($args),*),
             DatumType::Blob => $($path)::*::<Blob>($($args),*),
@@ -85,6 +87,7 @@ macro_rules! dispatch_copy {
             DatumType::I32  => $($path)::*::<i32>($($args),*),
             DatumType::I64  => $($path)::*::<i64>($($args),*),
             DatumType::F16  => $($path)::*::<f16>($($args),*),
+            DatumType::BF16 => $($path)::*::<bf16>($($args),*),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             DatumType::QI8(_)  => $($path)::*::<i8>($($args),*),
@@ -109,6 +112,7 @@ macro_rules! dispatch_copy_by_size {
             DatumType::I32  => $($path)::*::<i32>($($args),*),
             DatumType::I64  => $($path)::*::<i64>($($args),*),
             DatumType::F16  => $($path)::*::<i16>($($args),*),
+            DatumType::BF16 => $($path)::*::<i16>($($args),*),
             DatumType::F32  => $($path)::*::<i32>($($args),*),
             DatumType::F64  => $($path)::*::<i64>($($args),*),
             DatumType::QI8(_)  => $($path)::*::<i8>($($args),*),
@@ -132,6 +136,7 @@ macro_rules! dispatch_numbers {
             DatumType::I32  => $($path)::*::<i32>($($args),*),
             DatumType::I64  => $($path)::*::<i64>($($args),*),
             DatumType::F16  => $($path)::*::<f16>($($args),*),
+            DatumType::BF16 => $($path)::*::<bf16>($($args),*),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             DatumType::QI8(_)  => $($path)::*::<i8>($($args),*),
@@ -147,6 +152,7 @@ macro_rules! dispatch_floatlike {
         use $crate::prelude::DatumType;
         match $dt {
             DatumType::F16  => $($path)::*::<f32>($($args),*), // FIXME !!!
+            DatumType::BF16 => $($path)::*::<bf16>($($args),*),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             _ => $crate::anyhow::bail!("{:?} is not float-like", $dt)
@@ -160,6 +166,7 @@ macro_rules! dispatch_signed {
         use $crate::prelude::DatumType;
         match $dt {
             DatumType::F16  => $($path)::*::<f32>($($args),*), // FIXME !!!
+            DatumType::BF16 => $($path)::*::<bf16>($($args),*),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             DatumType::I8   => $($path)::*::<i8>($($args),*),
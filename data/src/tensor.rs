@@ -1,7 +1,7 @@
 //! `Tensor`, tract main data object of interest.
 use crate::datum::{scale_by, Blob, ClampCast, Datum, DatumType};
 use crate::dim::TDim;
-use crate::f16::f16;
+use crate::f16::{bf16, f16};
 use crate::TVec;
 use itertools::Itertools;
 use ndarray::prelude::*;
@@ -16,6 +16,7 @@ use std::ops::Range;
 use std::sync::Arc;
 
 pub mod litteral;
+pub mod recycle;
 pub mod view;
 
 /// Tensor is a concrete tensor in tract.
@@ -47,7 +48,7 @@ impl Hash for Tensor {
                 U16 => self.as_slice_unchecked::<u16>().hash(state),
                 U32 => self.as_slice_unchecked::<u32>().hash(state),
                 U64 => self.as_slice_unchecked::<u64>().hash(state),
-                F16 => self.as_slice_unchecked::<i16>().hash(state),
+                F16 | BF16 => self.as_slice_unchecked::<i16>().hash(state),
                 F32 => self.as_slice_unchecked::<i32>().hash(state),
                 F64 => self.as_slice_unchecked::<i64>().hash(state),
                 TDim => self.as_slice_unchecked::<crate::dim::TDim>().hash(state),
@@ -99,6 +100,9 @@ impl Drop for Tensor {
             }
         }
         if !self.data.is_null() && self.layout.size() > 0 {
+            if self.dt.is_copy() && recycle::reclaim(self.data, self.layout) {
+                return;
+            }
             unsafe { alloc::dealloc(self.data, self.layout) }
         }
     }
@@ -139,13 +143,15 @@ impl Tensor {
         assert!(dt.is_copy());
         let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
         let layout = alloc::Layout::from_size_align(bytes, alignment)?;
-        let data = if bytes == 0 {
-            std::ptr::null()
+        let (data, layout) = if bytes == 0 {
+            (std::ptr::null_mut(), layout)
+        } else if let Some(buffer) = recycle::take(bytes, alignment) {
+            buffer.into_raw_parts()
         } else {
             let ptr = alloc::alloc(layout);
             assert!(!ptr.is_null());
-            ptr
-        } as *mut u8;
+            (ptr, layout)
+        };
         let mut tensor = Tensor { strides: tvec!(), layout, dt, shape: shape.into(), data };
         #[cfg(debug_assertions)]
         {
@@ -171,7 +177,7 @@ impl Tensor {
         // map all copy types to the i* of the same size
         let mut tensor = unsafe {
             match dt {
-                DatumType::F16 => i16::stack_tensors(axis, &tensors),
+                DatumType::F16 | DatumType::BF16 => i16::stack_tensors(axis, &tensors),
                 DatumType::F32 => i32::stack_tensors(axis, &tensors),
                 DatumType::F64 => i64::stack_tensors(axis, &tensors),
                 DatumType::Bool => i8::stack_tensors(axis, &tensors),
@@ -655,21 +661,35 @@ impl Tensor {
 
     /// Access the data as a slice.
     pub fn as_slice<D: Datum>(&self) -> anyhow::Result<&[D]> {
-        unsafe { Ok(std::slice::from_raw_parts::<D>(self.as_ptr()?, self.len())) }
+        let ptr = self.as_ptr()?;
+        if self.data.is_null() {
+            return Ok(&[]);
+        }
+        unsafe { Ok(std::slice::from_raw_parts::<D>(ptr, self.len())) }
     }
 
     /// Access the data as a mutable slice.
     pub fn as_slice_mut<D: Datum>(&mut self) -> anyhow::Result<&mut [D]> {
-        unsafe { Ok(std::slice::from_raw_parts_mut::<D>(self.as_ptr_mut()?, self.len())) }
+        let ptr = self.as_ptr_mut()?;
+        if self.data.is_null() {
+            return Ok(&mut []);
+        }
+        unsafe { Ok(std::slice::from_raw_parts_mut::<D>(ptr, self.len())) }
     }
 
     /// Access the data as a slice.
     pub unsafe fn as_slice_unchecked<D: Datum>(&self) -> &[D] {
+        if self.data.is_null() {
+            return &[];
+        }
         std::slice::from_raw_parts::<D>(self.data as *const D, self.len())
     }
 
     /// Access the data as a mutable slice.
     pub unsafe fn as_slice_mut_unchecked<D: Datum>(&mut self) -> &mut [D] {
+        if self.data.is_null() {
+            return &mut [];
+        }
         std::slice::from_raw_parts_mut::<D>(self.data as *mut D, self.len())
     }
 
@@ -688,11 +708,34 @@ impl Tensor {
     }
 
     pub unsafe fn as_bytes(&self) -> &[u8] {
-        std::slice::from_raw_parts(self.data, self.layout.size())
+        if self.data.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts(self.data, self.len() * self.dt.size_of())
     }
 
     pub unsafe fn as_bytes_mut(&mut self) -> &mut [u8] {
-        std::slice::from_raw_parts_mut(self.data, self.layout.size())
+        if self.data.is_null() {
+            return &mut [];
+        }
+        std::slice::from_raw_parts_mut(self.data, self.len() * self.dt.size_of())
+    }
+
+    /// Give up the tensor memory, so that it can be used by another tensor.
+    ///
+    /// Only tensors of copy types allocated at the recycling alignment own a
+    /// reusable buffer.
+    pub fn into_buffer(mut self) -> Option<recycle::Buffer> {
+        if !self.dt.is_copy()
+            || self.data.is_null()
+            || self.layout.size() == 0
+            || self.layout.align() != recycle::ALIGNMENT
+        {
+            return None;
+        }
+        let buffer = unsafe { recycle::Buffer::from_raw_parts(self.data, self.layout) };
+        self.data = std::ptr::null_mut();
+        Some(buffer)
     }
 
     unsafe fn is_uniform_t<T: Datum>(&self) -> bool {
@@ -817,6 +860,7 @@ impl Tensor {
                             DatumType::U32 => self.natural_cast::<$source, u32>(&mut result),
                             DatumType::U64 => self.natural_cast::<$source, u64>(&mut result),
                             DatumType::F16 => self.natural_cast::<$source, f16>(&mut result),
+                            DatumType::BF16 => self.natural_cast::<$source, bf16>(&mut result),
                             DatumType::F32 => self.natural_cast::<$source, f32>(&mut result),
                             DatumType::F64 => self.natural_cast::<$source, f64>(&mut result),
                             DatumType::TDim => {
@@ -845,6 +889,7 @@ impl Tensor {
                 n!(i32);
                 n!(i64);
                 n!(f16);
+                n!(bf16);
                 n!(f32);
                 n!(f64);
             } else {
@@ -0,0 +1,153 @@
+//! Recycling of tensor buffers.
+//!
+//! A caller owning a few memory blocks can lend them to the tensors allocated
+//! while it runs some code (typically one operator evaluation). The blocks
+//! which have not been used, and the memory of the tensors dropped meanwhile,
+//! are handed back to the caller.
+use std::alloc;
+use std::cell::RefCell;
+
+/// Alignment (in bytes) of the recycled buffers.
+pub const ALIGNMENT: usize = 64;
+
+/// An aligned, uninitialized, memory block.
+#[derive(Debug)]
+pub struct Buffer {
+    data: *mut u8,
+    layout: alloc::Layout,
+}
+
+unsafe impl Send for Buffer {}
+unsafe impl Sync for Buffer {}
+
+impl Buffer {
+    pub fn new(size: usize) -> anyhow::Result<Buffer> {
+        let layout = alloc::Layout::from_size_align(size, ALIGNMENT)?;
+        let data = if size == 0 {
+            std::ptr::null_mut()
+        } else {
+            let ptr = unsafe { alloc::alloc(layout) };
+            assert!(!ptr.is_null());
+            ptr
+        };
+        Ok(Buffer { data, layout })
+    }
+
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+
+    pub(crate) unsafe fn from_raw_parts(data: *mut u8, layout: alloc::Layout) -> Buffer {
+        Buffer { data, layout }
+    }
+
+    pub(crate) fn into_raw_parts(self) -> (*mut u8, alloc::Layout) {
+        let parts = (self.data, self.layout);
+        std::mem::forget(self);
+        parts
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        if !self.data.is_null() && self.layout.size() > 0 {
+            unsafe { alloc::dealloc(self.data, self.layout) }
+        }
+    }
+}
+
+thread_local! {
+    static LENT: RefCell<Option<Vec<Buffer>>> = RefCell::new(None);
+}
+
+/// Run `f`, letting the tensors it allocates take their memory from
+/// `buffers`. The buffers that have not been taken, and the memory of the
+/// tensors dropped by `f` which fits the recycling alignment, are given back.
+pub fn with_buffers<R>(buffers: &mut Vec<Buffer>, f: impl FnOnce() -> R) -> R {
+    let lent = Some(std::mem::take(buffers));
+    let outer = LENT.with(|cell| std::mem::replace(&mut *cell.borrow_mut(), lent));
+    let result = f();
+    *buffers =
+        LENT.with(|cell| std::mem::replace(&mut *cell.borrow_mut(), outer)).unwrap_or_default();
+    result
+}
+
+/// Smallest lent buffer fitting `size` bytes at `alignment`, if any.
+pub(crate) fn take(size: usize, alignment: usize) -> Option<Buffer> {
+    if alignment > ALIGNMENT {
+        return None;
+    }
+    LENT.try_with(|cell| {
+        let mut cell = cell.try_borrow_mut().ok()?;
+        let lent = cell.as_mut()?;
+        let ix = lent
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.size() >= size)
+            .min_by_key(|(_, b)| b.size())
+            .map(|(ix, _)| ix)?;
+        Some(lent.swap_remove(ix))
+    })
+    .ok()
+    .flatten()
+}
+
+/// Keep the memory of a dropped tensor if buffers are currently lent.
+pub(crate) fn reclaim(data: *mut u8, layout: alloc::Layout) -> bool {
+    if layout.align() != ALIGNMENT {
+        return false;
+    }
+    LENT.try_with(|cell| {
+        if let Ok(mut cell) = cell.try_borrow_mut() {
+            if let Some(lent) = cell.as_mut() {
+                lent.push(Buffer { data, layout });
+                return true;
+            }
+        }
+        false
+    })
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn tensor_takes_lent_buffer() -> anyhow::Result<()> {
+        let buffer = Buffer::new(64)?;
+        let ptr = buffer.data;
+        let mut buffers = vec![buffer];
+        let t = with_buffers(&mut buffers, || unsafe { Tensor::uninitialized::<f32>(&[4]) })?;
+        assert!(buffers.is_empty());
+        assert_eq!(unsafe { t.as_bytes() }.len(), 16);
+        let buffer = t.into_buffer().unwrap();
+        assert_eq!(buffer.data, ptr);
+        assert_eq!(buffer.size(), 64);
+        Ok(())
+    }
+
+    #[test]
+    fn dropped_tensor_memory_is_given_back() -> anyhow::Result<()> {
+        let mut buffers = vec![Buffer::new(64)?];
+        let ptr = buffers[0].data;
+        with_buffers(&mut buffers, || -> anyhow::Result<()> {
+            let t = unsafe { Tensor::uninitialized::<f32>(&[4])? };
+            std::mem::drop(t);
+            Ok(())
+        })?;
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(buffers[0].data, ptr);
+        Ok(())
+    }
+
+    #[test]
+    fn buffer_too_small_is_given_back() -> anyhow::Result<()> {
+        let mut buffers = vec![Buffer::new(8)?];
+        let t = with_buffers(&mut buffers, || unsafe { Tensor::uninitialized::<f32>(&[4]) })?;
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(t.len(), 4);
+        Ok(())
+    }
+}
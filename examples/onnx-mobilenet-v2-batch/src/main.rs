@@ -1,10 +1,10 @@
 use tract_onnx::prelude::*;
 
 fn main() -> TractResult<()> {
-    let batch = Symbol::new('N');
-    let model = tract_onnx::onnx()
-        // load the model
-        .model_for_path("mobilenetv2-7.onnx")?
+    // load the model
+    let model = tract_onnx::onnx().model_for_path("mobilenetv2-7.onnx")?;
+    let batch = model.sym("N");
+    let model = model
         // specify input type and shape
         .with_input_fact(
             0,
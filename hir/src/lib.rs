@@ -24,6 +24,7 @@ pub mod ops {
     pub mod cnn;
     pub mod downsample;
     pub mod dummy;
+    pub mod einsum;
     pub mod element_wise;
     pub mod expandable;
     pub mod identity;
@@ -34,6 +35,7 @@ pub mod ops {
     pub mod nn;
     pub use tract_core::ops::quant;
     pub mod scan;
+    pub mod sequence;
     pub mod source;
     pub mod unimpl;
 }
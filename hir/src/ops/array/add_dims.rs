@@ -10,7 +10,7 @@ pub struct AddDims {
 impl_dyn_hash!(AddDims);
 
 impl AddDims {
-    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TVec<D> {
+    pub fn compute_shape<D: DimLike>(&self, input: &[D]) -> TVec<D> {
         let rank = input.len() as isize;
         let mut shape: TVec<D> = input.iter().cloned().collect();
         let axes = self
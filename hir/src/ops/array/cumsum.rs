@@ -0,0 +1,49 @@
+use crate::internal::*;
+
+/// Cumulative sum, the axis being given by the second input.
+#[derive(Debug, Clone, new, Hash)]
+pub struct CumSum {
+    pub reverse: bool,
+    pub exclusive: bool,
+}
+
+impl_dyn_hash!(CumSum);
+
+impl Expansion for CumSum {
+    fn name(&self) -> Cow<str> {
+        "CumSum".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 0)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = if let Some(axis) = &model.outlet_fact(inputs[1])?.konst {
+            axis.cast_to_scalar::<i64>()?
+        } else {
+            bail!("CumSum axis must be a constant")
+        };
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        let op = tract_core::ops::array::CumSum::new(axis, self.reverse, self.exclusive);
+        model.wire_node(prefix, op, &inputs[0..1])
+    }
+}
@@ -4,12 +4,14 @@ mod concat;
 mod constant_like;
 mod constant_of_shape;
 mod crop;
+mod cumsum;
 mod flatten;
 mod gather;
 mod gather_elements;
 mod gather_nd;
 mod pad;
 pub mod permute_axes;
+mod range;
 mod reshape;
 mod rm_dims;
 mod scatter_elements;
@@ -28,12 +30,14 @@ pub use concat::{Concat, ConcatSlice, TypedConcat};
 pub use constant_like::{ConstantLike, EyeLike};
 pub use constant_of_shape::ConstantOfShape;
 pub use crop::Crop;
+pub use cumsum::CumSum;
 pub use flatten::Flatten;
 pub use gather::Gather;
 pub use gather_elements::GatherElements;
 pub use gather_nd::GatherNd;
 pub use pad::{Pad, PadMode};
 pub use permute_axes::PermuteAxes;
+pub use range::Range;
 pub use reshape::Reshape;
 pub use rm_dims::RmDims;
 pub use scatter_elements::ScatterElements;
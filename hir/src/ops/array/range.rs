@@ -0,0 +1,107 @@
+use crate::internal::*;
+use tract_core::ops::cast::cast;
+
+/// Range over start, end and step inputs.
+///
+/// `len` is the symbol used for the output length when the bounds are only
+/// known at runtime.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Range {
+    len: Symbol,
+}
+
+impl_dyn_hash!(Range);
+
+/// Bounds computed from shapes are TDim, mixing them with plain integers.
+fn common_datum_type(dts: &[DatumType]) -> DatumType {
+    if dts.contains(&TDim::datum_type()) {
+        TDim::datum_type()
+    } else {
+        dts[0]
+    }
+}
+
+impl Expansion for Range {
+    fn name(&self) -> Cow<str> {
+        "Range".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.rank, 0)?;
+        }
+        s.equals(&outputs[0].rank, 1)?;
+        s.given_3(
+            &inputs[0].datum_type,
+            &inputs[1].datum_type,
+            &inputs[2].datum_type,
+            move |s, start, end, step| {
+                s.equals(&outputs[0].datum_type, common_datum_type(&[start, end, step]))
+            },
+        )?;
+        s.given_3(
+            &inputs[0].value,
+            &inputs[1].value,
+            &inputs[2].value,
+            move |s, start, end, step| {
+                let dt =
+                    common_datum_type(&[start.datum_type(), end.datum_type(), step.datum_type()]);
+                let facts = [start, end, step]
+                    .iter()
+                    .map(|t| Ok(TypedFact::from(t.cast_to_dt(dt)?.into_owned())))
+                    .collect::<TractResult<TVec<_>>>()?;
+                let len = tract_core::ops::array::Range::len_for_facts(
+                    &facts[0],
+                    &facts[1],
+                    &facts[2],
+                    self.len.clone(),
+                )?;
+                s.equals(&outputs[0].shape[0], len)
+            },
+        )?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let dts = inputs
+            .iter()
+            .map(|i| Ok(model.outlet_fact(*i)?.datum_type))
+            .collect::<TractResult<TVec<_>>>()?;
+        let dt = common_datum_type(&dts);
+        let mut wires = tvec!();
+        for (ix, input) in inputs.iter().enumerate() {
+            if dts[ix] != dt {
+                wires.push(
+                    model.wire_node(format!("{}.cast-{}", prefix, ix), cast(dt), &[*input])?[0],
+                );
+            } else {
+                wires.push(*input);
+            }
+        }
+        let facts = wires
+            .iter()
+            .map(|w| model.outlet_fact(*w).map(|f| f.clone()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let len = tract_core::ops::array::Range::len_for_facts(
+            &facts[0],
+            &facts[1],
+            &facts[2],
+            self.len.clone(),
+        )?;
+        model.wire_node(prefix, tract_core::ops::array::Range::new(len), &wires)
+    }
+}
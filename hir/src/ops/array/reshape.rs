@@ -148,8 +148,12 @@ mod tests {
     use super::*;
     use AxisOp::*;
 
+    thread_local! {
+        static S: Symbol = SymbolTable::default().sym("S");
+    }
+
     fn stream() -> TDim {
-        Symbol::from('S').into()
+        S.with(|s| s.into())
     }
 
     macro_rules! s {
@@ -192,7 +196,9 @@ mod tests {
 
     #[test]
     fn compute_bug_2() {
-        assert_eq!(&*compute_shape(s!['s', 'b', 2, 128], s!(0, 0, -1)).unwrap(), s!['s', 'b', 256])
+        let table = SymbolTable::default();
+        let (s, b) = (table.sym("s"), table.sym("b"));
+        assert_eq!(&*compute_shape(s![&s, &b, 2, 128], s!(0, 0, -1)).unwrap(), s![&s, &b, 256])
     }
 
     #[test]
@@ -11,7 +11,7 @@ pub struct Split {
 impl_dyn_hash!(Split);
 
 impl Split {
-    fn split_dims<D: DimLike>(&self, input: &D) -> TractResult<TVec<D>> {
+    pub fn split_dims<D: DimLike>(&self, input: &D) -> TractResult<TVec<D>> {
         if let Some(ref split) = self.split.as_ref() {
             Ok(split.iter().map(|&d| D::from(d)).collect())
         } else {
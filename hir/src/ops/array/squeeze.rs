@@ -11,7 +11,7 @@ pub struct Squeeze {
 impl_dyn_hash!(Squeeze);
 
 impl Squeeze {
-    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TractResult<TVec<D>> {
+    pub fn compute_shape<D: DimLike>(&self, input: &[D]) -> TractResult<TVec<D>> {
         if let Some(ref axes) = self.axes {
             let axes = axes
                 .iter()
@@ -85,7 +85,7 @@ impl StridedSlice {
                 let symbols = bound.symbols();
                 if symbols.len() == 1 {
                     let sym = symbols.into_iter().nth(0).unwrap();
-                    let values = SymbolValues::default().with(sym, 100_000_000);
+                    let values = SymbolValues::default().with(&sym, 100_000_000);
                     bound.eval(&values).to_isize().unwrap() < 0
                 } else {
                     false
@@ -309,8 +309,12 @@ mod tests {
     use super::*;
     use tract_ndarray::{arr1, arr2, arr3};
 
+    thread_local! {
+        static S: Symbol = SymbolTable::default().sym("S");
+    }
+
     fn s() -> TDim {
-        Symbol::from('S').into()
+        S.with(|s| s.into())
     }
 
     pub fn strided_slice(begin_mask: i64, end_mask: i64, shrink_axis_mask: i64) -> StridedSlice {
@@ -0,0 +1,165 @@
+use crate::infer::*;
+use crate::internal::*;
+
+pub use tract_core::ops::einsum::Expr;
+
+#[derive(Debug, Clone, PartialEq, Hash)]
+struct Term {
+    labels: TVec<char>,
+    ellipsis: Option<usize>,
+}
+
+impl Term {
+    fn parse(s: &str) -> TractResult<Term> {
+        let (labels, ellipsis) = if let Some(ix) = s.find("...") {
+            (format!("{}{}", &s[..ix], &s[ix + 3..]), Some(s[..ix].chars().count()))
+        } else {
+            (s.to_string(), None)
+        };
+        if let Some(c) = labels.chars().find(|c| !c.is_ascii_alphabetic()) {
+            bail!("Unexpected character {:?} in einsum equation", c)
+        }
+        Ok(Term { labels: labels.chars().collect(), ellipsis })
+    }
+}
+
+/// Einsum in numpy (and ONNX) syntax: supports ellipsis and implicit output.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct EinSum {
+    inputs: TVec<Term>,
+    output: Option<Term>,
+}
+
+impl_dyn_hash!(EinSum);
+
+impl EinSum {
+    pub fn parse(equation: &str) -> TractResult<EinSum> {
+        let equation = equation.replace(" ", "");
+        let mut parts = equation.splitn(2, "->");
+        let inputs = parts.next().unwrap();
+        let output = parts.next().map(Term::parse).transpose()?;
+        let inputs = inputs.split(',').map(Term::parse).collect::<TractResult<_>>()?;
+        Ok(EinSum { inputs, output })
+    }
+
+    /// Resolve ellipsis and implicit output for inputs of the given ranks.
+    pub fn expr(&self, ranks: &[usize]) -> TractResult<Expr> {
+        if ranks.len() != self.inputs.len() {
+            bail!("Einsum expects {} inputs, got {}", self.inputs.len(), ranks.len())
+        }
+        let mut ellipsis_rank = 0;
+        for (term, &rank) in self.inputs.iter().zip(ranks.iter()) {
+            if term.ellipsis.is_some() && rank >= term.labels.len() {
+                ellipsis_rank = ellipsis_rank.max(rank - term.labels.len());
+            } else if rank != term.labels.len() {
+                bail!("Einsum term {:?} can not apply to an input of rank {}", term.labels, rank)
+            }
+        }
+        let used: TVec<char> = self.inputs.iter().flat_map(|t| t.labels.iter().cloned()).collect();
+        let fresh: TVec<char> = ('A'..='Z')
+            .chain('a'..='z')
+            .filter(|c| !used.contains(c))
+            .take(ellipsis_rank)
+            .collect();
+        if fresh.len() < ellipsis_rank {
+            bail!("Einsum: ran out of labels to expand ellipsis")
+        }
+        let expand = |term: &Term, rank: usize| -> TVec<char> {
+            let mut labels = term.labels.clone();
+            if let Some(ix) = term.ellipsis {
+                let ellipsis = &fresh[ellipsis_rank - (rank - term.labels.len())..];
+                labels.insert_many(ix, ellipsis.iter().cloned());
+            }
+            labels
+        };
+        let inputs: TVec<TVec<char>> =
+            self.inputs.iter().zip(ranks.iter()).map(|(t, &r)| expand(t, r)).collect();
+        let output = if let Some(output) = &self.output {
+            expand(output, output.labels.len() + ellipsis_rank)
+        } else {
+            let mut once: TVec<char> = used
+                .iter()
+                .filter(|l| used.iter().filter(|u| u == l).count() == 1)
+                .cloned()
+                .collect();
+            once.sort();
+            fresh.iter().cloned().chain(once.into_iter()).collect()
+        };
+        Ok(Expr { inputs, output })
+    }
+}
+
+impl Expansion for EinSum {
+    fn name(&self) -> Cow<str> {
+        "EinSum".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, self.inputs.len())?;
+        check_output_arity(&outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+        }
+        s.given_all(inputs.iter().map(|i| &i.rank), move |s, ranks| {
+            let ranks: TVec<usize> = ranks.iter().map(|&r| r as usize).collect();
+            let expr = self.expr(&ranks)?;
+            s.equals(&outputs[0].rank, expr.output.len() as i64)
+        })?;
+        s.given_all(inputs.iter().map(|i| &i.shape), move |s, shapes: Vec<TVec<TDim>>| {
+            let ranks: TVec<usize> = shapes.iter().map(|s| s.len()).collect();
+            let expr = self.expr(&ranks)?;
+            let shapes: TVec<&[TDim]> = shapes.iter().map(|s| &**s).collect();
+            let dims = expr.label_dims(&shapes)?;
+            let shape: TVec<TDim> = expr
+                .output
+                .iter()
+                .map(|l| dims.iter().find(|pair| pair.0 == *l).unwrap().1.clone())
+                .collect();
+            s.equals(&outputs[0].shape, shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let ranks = inputs
+            .iter()
+            .map(|i| Ok(target.outlet_fact(*i)?.rank()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let expr = self.expr(&ranks)?;
+        target.wire_node(prefix, tract_core::ops::einsum::EinSum::new(expr), inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implicit_output() -> TractResult<()> {
+        let expr = EinSum::parse("ij,jk")?.expr(&[2, 2])?;
+        assert_eq!(expr.to_string(), "ij,jk->ik");
+        let expr = EinSum::parse("ba")?.expr(&[2])?;
+        assert_eq!(expr.to_string(), "ba->ab");
+        Ok(())
+    }
+
+    #[test]
+    fn ellipsis() -> TractResult<()> {
+        let expr = EinSum::parse("...ij, ...jk -> ...ik")?.expr(&[4, 3])?;
+        assert_eq!(expr.to_string(), "ABij,Bjk->ABik");
+        let expr = EinSum::parse("i...->...")?.expr(&[3])?;
+        assert_eq!(expr.to_string(), "iAB->AB");
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::infer::*;
+use crate::internal::*;
+use tract_core::ops::nn::{wire_depth_to_space, wire_space_to_depth, DataFormat, DepthToSpaceMode};
+
+#[derive(Clone, Debug, new, Hash)]
+pub struct DepthToSpace {
+    block_size: usize,
+    mode: DepthToSpaceMode,
+    data_format: DataFormat,
+}
+impl_dyn_hash!(DepthToSpace);
+
+impl Expansion for DepthToSpace {
+    fn name(&self) -> Cow<str> {
+        "DepthToSpace".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.given(&inputs[0].shape, move |s, shape| {
+            let b = self.block_size;
+            let shape = self.data_format.shape(shape)?;
+            let hw: TVec<TDim> = shape.hw_dims().iter().map(|d| d.clone() * b).collect();
+            let output = self.data_format.from_n_c_hw(
+                shape.n().cloned().unwrap_or_else(|| 1.into()),
+                shape.c().clone() / (b * b),
+                hw,
+            )?;
+            s.equals(&outputs[0].shape, output.shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        wire_depth_to_space(prefix, target, inputs[0], self.data_format, self.block_size, self.mode)
+    }
+}
+
+#[derive(Clone, Debug, new, Hash)]
+pub struct SpaceToDepth {
+    block_size: usize,
+    data_format: DataFormat,
+}
+impl_dyn_hash!(SpaceToDepth);
+
+impl Expansion for SpaceToDepth {
+    fn name(&self) -> Cow<str> {
+        "SpaceToDepth".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.given(&inputs[0].shape, move |s, shape| {
+            let b = self.block_size;
+            let shape = self.data_format.shape(shape)?;
+            let hw: TVec<TDim> = shape.hw_dims().iter().map(|d| d.clone() / b).collect();
+            let output = self.data_format.from_n_c_hw(
+                shape.n().cloned().unwrap_or_else(|| 1.into()),
+                shape.c().clone() * (b * b),
+                hw,
+            )?;
+            s.equals(&outputs[0].shape, output.shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        wire_space_to_depth(prefix, target, inputs[0], self.data_format, self.block_size)
+    }
+}
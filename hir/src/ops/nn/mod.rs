@@ -1,9 +1,11 @@
+mod depth_to_space;
 mod global_pools;
 mod layer_max;
 mod reduce;
 
+pub use depth_to_space::{DepthToSpace, SpaceToDepth};
 pub use global_pools::*;
 pub use layer_max::*;
 pub use reduce::{Reduce, Reducer};
 
-pub use tract_core::ops::nn::{sigmoid, DataFormat};
+pub use tract_core::ops::nn::{sigmoid, DataFormat, DepthToSpaceMode};
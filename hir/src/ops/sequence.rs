@@ -0,0 +1,387 @@
+//! Sequences and optionals.
+//!
+//! A sequence of tensors sharing type and shape is represented as a single
+//! tensor, its elements stacked along a new leading axis. An optional is a
+//! sequence of zero or one element. Sequence ops are then plain tensor ops once
+//! the sequence length is known.
+use crate::infer::*;
+use crate::internal::*;
+use crate::ops::array::{ConcatSlice, TypedConcat};
+
+/// Fact for a sequence of `len` tensors matching `element`.
+pub fn sequence_fact(element: &InferenceFact, len: DimFact) -> InferenceFact {
+    let mut dims: TVec<DimFact> = tvec!(len);
+    dims.extend(element.shape.dims().cloned());
+    let shape =
+        if element.shape.is_open() { ShapeFactoid::open(dims) } else { ShapeFactoid::closed(dims) };
+    InferenceFact { datum_type: element.datum_type.clone(), shape, value: ValueFact::default() }
+}
+
+#[derive(Debug, Clone, new, Default, Hash)]
+pub struct SequenceConstruct;
+impl_dyn_hash!(SequenceConstruct);
+
+impl Expansion for SequenceConstruct {
+    fn name(&self) -> Cow<str> {
+        "SequenceConstruct".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(&outputs, 1)?;
+        if inputs.len() == 0 {
+            bail!("SequenceConstruct expects at least one input");
+        }
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+        s.equals(&outputs[0].shape[0], inputs.len().to_dim())?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+            s.equals(&input.rank, &inputs[0].rank)?;
+        }
+        s.given(&inputs[0].rank, move |s, rank| {
+            for input in inputs {
+                for axis in 0..rank as usize {
+                    s.equals(&input.shape[axis], &outputs[0].shape[axis + 1])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut wires = tvec!();
+        for (ix, input) in inputs.iter().enumerate() {
+            wires.push(
+                model.wire_node(format!("{}.add-{}", prefix, ix), AxisOp::Add(0), &[*input])?[0],
+            );
+        }
+        let op = TypedConcat::new(0, tvec!(ConcatSlice::Var; wires.len()));
+        model.wire_node(prefix, op, &wires)
+    }
+}
+
+#[derive(Debug, Clone, new, Default, Hash)]
+pub struct SequenceAt;
+impl_dyn_hash!(SequenceAt);
+
+impl Expansion for SequenceAt {
+    fn name(&self) -> Cow<str> {
+        "SequenceAt".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(inputs[0].rank.bex() - 1, outputs[0].rank.bex())?;
+        s.given(&outputs[0].rank, move |s, rank| {
+            for axis in 0..rank as usize {
+                s.equals(&inputs[0].shape[axis + 1], &outputs[0].shape[axis])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let position = model.wire_node(
+            format!("{}.position", prefix),
+            crate::ops::cast(i64::datum_type()),
+            &[inputs[1]],
+        )?[0];
+        model.wire_node(prefix, tract_core::ops::array::Gather { axis: 0 }, &[inputs[0], position])
+    }
+}
+
+/// Split a tensor in a sequence of chunks along `axis`.
+///
+/// Without split input, chunks have a size of one, and the split axis is kept
+/// or not according to `keepdims`. A split input must be constant, and
+/// describe chunks of identical sizes.
+#[derive(Debug, Clone, new, Default, Hash)]
+pub struct SplitToSequence {
+    pub axis: isize,
+    pub keepdims: bool,
+    pub has_split_input: bool,
+}
+impl_dyn_hash!(SplitToSequence);
+
+impl SplitToSequence {
+    fn resolve_axis(&self, rank: usize) -> usize {
+        (if self.axis < 0 { self.axis + rank as isize } else { self.axis }) as usize
+    }
+
+    /// Sequence length and chunk size.
+    fn chunks(&self, dim: &TDim, split: Option<&Tensor>) -> TractResult<(TDim, TDim)> {
+        let split = if let Some(split) = split {
+            split
+        } else {
+            return Ok((dim.clone(), 1.to_dim()));
+        };
+        let rank = split.rank();
+        let split = split.cast_to::<i64>()?;
+        let split = split.as_slice::<i64>()?;
+        if split.len() == 0 || split.iter().any(|&s| s != split[0]) {
+            bail!("SplitToSequence only supports chunks of identical sizes, got {:?}", split)
+        }
+        let chunk = split[0].to_dim();
+        let (len, rem) = dim.maybe_div(&chunk)?;
+        if rem != 1 || (rank == 1 && len != split.len().to_dim()) {
+            bail!("SplitToSequence can not split {} in chunks of {:?}", dim, split)
+        }
+        Ok((len, chunk))
+    }
+
+    fn output_shape(&self, shape: &[TDim], split: Option<&Tensor>) -> TractResult<TVec<TDim>> {
+        let axis = self.resolve_axis(shape.len());
+        let (len, chunk) = self.chunks(&shape[axis], split)?;
+        let mut output: TVec<TDim> = shape.into();
+        if split.is_none() && !self.keepdims {
+            output.remove(axis);
+        } else {
+            output[axis] = chunk;
+        }
+        output.insert(0, len);
+        Ok(output)
+    }
+}
+
+impl Expansion for SplitToSequence {
+    fn name(&self) -> Cow<str> {
+        "SplitToSequence".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1 + self.has_split_input as usize)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        if self.has_split_input {
+            s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+            s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, split| {
+                s.equals(&outputs[0].shape, self.output_shape(&shape, Some(&*split))?)
+            })
+        } else {
+            let rank = inputs[0].rank.bex() + (self.keepdims as i64);
+            s.equals(&outputs[0].rank, rank)?;
+            s.given(&inputs[0].shape, move |s, shape| {
+                s.equals(&outputs[0].shape, self.output_shape(&shape, None)?)
+            })
+        }
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let axis = self.resolve_axis(fact.rank());
+        let split = if self.has_split_input {
+            let split = model.outlet_fact(inputs[1])?.konst.clone();
+            Some(split.context("SplitToSequence requires a constant split input")?)
+        } else {
+            None
+        };
+        let (len, chunk) = self.chunks(&fact.shape[axis], split.as_deref())?;
+        let mut wire = inputs[0];
+        if split.is_some() || self.keepdims {
+            let op = AxisOp::Reshape(axis, tvec!(fact.shape[axis].clone()), tvec!(len, chunk));
+            wire = model.wire_node(format!("{}.reshape", prefix), op, &[wire])?[0];
+        }
+        if axis != 0 {
+            wire = model.wire_node(prefix, AxisOp::Move(axis, 0), &[wire])?[0];
+        }
+        Ok(tvec!(wire))
+    }
+}
+
+/// Concatenate the elements of a sequence along an existing axis, or stack
+/// them along a new one.
+#[derive(Debug, Clone, new, Default, Hash)]
+pub struct ConcatFromSequence {
+    pub axis: isize,
+    pub new_axis: bool,
+}
+impl_dyn_hash!(ConcatFromSequence);
+
+impl ConcatFromSequence {
+    /// Resolve the axis against the rank of the sequence elements.
+    fn resolve_axis(&self, rank: usize) -> usize {
+        let rank = rank + self.new_axis as usize;
+        (if self.axis < 0 { self.axis + rank as isize } else { self.axis }) as usize
+    }
+
+    fn output_shape(&self, shape: &[TDim]) -> TVec<TDim> {
+        let axis = self.resolve_axis(shape.len() - 1);
+        let mut output: TVec<TDim> = shape[1..].into();
+        if self.new_axis {
+            output.insert(axis, shape[0].clone());
+        } else {
+            output[axis] = output[axis].clone() * &shape[0];
+        }
+        output
+    }
+}
+
+impl Expansion for ConcatFromSequence {
+    fn name(&self) -> Cow<str> {
+        "ConcatFromSequence".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        let rank = inputs[0].rank.bex() - (!self.new_axis as i64);
+        s.equals(&outputs[0].rank, rank)?;
+        s.given(&inputs[0].shape, move |s, shape| {
+            s.equals(&outputs[0].shape, self.output_shape(&shape))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let shape = model.outlet_fact(inputs[0])?.shape.to_tvec();
+        let axis = self.resolve_axis(shape.len() - 1);
+        let mut wire = inputs[0];
+        if axis != 0 {
+            wire = model.wire_node(format!("{}.move", prefix), AxisOp::Move(0, axis), &[wire])?[0];
+        }
+        if !self.new_axis {
+            let from = tvec!(shape[0].clone(), shape[axis + 1].clone());
+            let op = AxisOp::Reshape(axis, from, tvec!(shape[0].clone() * &shape[axis + 1]));
+            wire = model.wire_node(format!("{}.merge", prefix), op, &[wire])?[0];
+        }
+        Ok(tvec!(wire))
+    }
+}
+
+/// Tells if an optional holds an element. Requires the optional length to be
+/// known.
+#[derive(Debug, Clone, new, Default, Hash)]
+pub struct OptionalHasElement;
+impl_dyn_hash!(OptionalHasElement);
+
+impl Expansion for OptionalHasElement {
+    fn name(&self) -> Cow<str> {
+        "OptionalHasElement".into()
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, bool::datum_type())?;
+        s.equals(&outputs[0].rank, 0)?;
+        s.given(&inputs[0].shape[0], move |s, len| {
+            s.equals(&outputs[0].value, rctensor0(len != 0.to_dim()))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let len = model.outlet_fact(inputs[0])?.shape[0].to_usize()?;
+        Ok(tvec!(model.add_const(prefix, rctensor0(len != 0))?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(op: impl Expansion, inputs: TVec<Tensor>) -> TractResult<Tensor> {
+        let inputs = inputs.into_iter().map(|t| t.into_arc_tensor()).collect();
+        Ok(expand(op).eval(inputs)?.remove(0).into_tensor())
+    }
+
+    #[test]
+    fn construct_and_at() -> TractResult<()> {
+        let seq = eval(SequenceConstruct, tvec!(tensor1(&[0f32, 1.0]), tensor1(&[2f32, 3.0])))?;
+        assert_eq!(seq, tensor2(&[[0f32, 1.0], [2.0, 3.0]]));
+        assert_eq!(eval(SequenceAt, tvec!(seq.clone(), tensor0(-1i32)))?, tensor1(&[2f32, 3.0]));
+        assert_eq!(eval(SequenceAt, tvec!(seq, tensor0(0i64)))?, tensor1(&[0f32, 1.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn split_and_concat_roundtrip() -> TractResult<()> {
+        let input = tensor2(&[[0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]);
+        let seq = eval(SplitToSequence::new(1, false, false), tvec!(input.clone()))?;
+        assert_eq!(seq, tensor2(&[[0f32, 3.0], [1.0, 4.0], [2.0, 5.0]]));
+        assert_eq!(eval(ConcatFromSequence::new(-1, true), tvec!(seq))?, input);
+        let seq = eval(SplitToSequence::new(1, true, false), tvec!(input.clone()))?;
+        assert_eq!(seq.shape(), &[3, 2, 1]);
+        assert_eq!(eval(ConcatFromSequence::new(1, false), tvec!(seq))?, input);
+        Ok(())
+    }
+
+    #[test]
+    fn split_in_chunks() -> TractResult<()> {
+        let input = tensor1(&[0f32, 1.0, 2.0, 3.0]);
+        let seq = eval(SplitToSequence::new(0, true, true), tvec!(input.clone(), tensor0(2i64)))?;
+        assert_eq!(seq, tensor2(&[[0f32, 1.0], [2.0, 3.0]]));
+        assert_eq!(eval(ConcatFromSequence::new(0, false), tvec!(seq))?, input);
+        let uneven = tvec!(input, tensor1(&[1i64, 3]));
+        assert!(eval(SplitToSequence::new(0, true, true), uneven).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn optional_has_element() -> TractResult<()> {
+        let some = Tensor::zero::<f32>(&[1, 3])?;
+        assert_eq!(eval(OptionalHasElement, tvec!(some))?, tensor0(true));
+        Ok(())
+    }
+}
@@ -7,6 +7,112 @@ pub struct KaldiProtoModel {
     pub config_lines: ConfigLines,
     pub components: HashMap<String, Component>,
     pub adjust_final_offset: isize,
+    /// Present when reading an acoustic model (final.mdl) instead of a raw nnet3.
+    pub transition_model: Option<TransitionModel>,
+    /// Pdf priors trailing an acoustic model, if not empty.
+    pub priors: Option<Tensor>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransitionModel {
+    pub topology: HmmTopology,
+    pub tuples: Vec<TransitionTuple>,
+    pub log_probs: Tensor,
+    /// Pdf-id of each transition-id. Transition-ids start at 1, entry 0 is unused.
+    pub transition_id_to_pdf_id: Vec<usize>,
+    /// Transition state (1-based index in tuples) of each transition-id, entry 0 is unused.
+    pub transition_id_to_transition_state: Vec<usize>,
+    pub num_pdfs: usize,
+}
+
+impl TransitionModel {
+    pub fn new(
+        topology: HmmTopology,
+        tuples: Vec<TransitionTuple>,
+        log_probs: Tensor,
+    ) -> TractResult<TransitionModel> {
+        let mut transition_id_to_pdf_id = vec![0];
+        let mut transition_id_to_transition_state = vec![0];
+        for (ix, tuple) in tuples.iter().enumerate() {
+            let state =
+                topology.topology_for_phone(tuple.phone)?.get(tuple.hmm_state).with_context(
+                    || format!("No HMM state {} for phone {}", tuple.hmm_state, tuple.phone),
+                )?;
+            for &(dest, _) in &state.transitions {
+                let self_loop = dest == tuple.hmm_state;
+                transition_id_to_pdf_id.push(if self_loop {
+                    tuple.self_loop_pdf
+                } else {
+                    tuple.forward_pdf
+                });
+                transition_id_to_transition_state.push(ix + 1);
+            }
+        }
+        let num_pdfs =
+            tuples.iter().map(|t| t.forward_pdf.max(t.self_loop_pdf) + 1).max().unwrap_or(0);
+        Ok(TransitionModel {
+            topology,
+            tuples,
+            log_probs,
+            transition_id_to_pdf_id,
+            transition_id_to_transition_state,
+            num_pdfs,
+        })
+    }
+
+    pub fn num_transition_ids(&self) -> usize {
+        self.transition_id_to_pdf_id.len() - 1
+    }
+
+    pub fn transition_id_to_pdf_id(&self, transition_id: usize) -> Option<usize> {
+        if transition_id == 0 {
+            return None;
+        }
+        self.transition_id_to_pdf_id.get(transition_id).cloned()
+    }
+
+    pub fn transition_id_to_phone(&self, transition_id: usize) -> Option<usize> {
+        if transition_id == 0 {
+            return None;
+        }
+        let state = *self.transition_id_to_transition_state.get(transition_id)?;
+        Some(self.tuples[state - 1].phone)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionTuple {
+    pub phone: usize,
+    pub hmm_state: usize,
+    pub forward_pdf: usize,
+    pub self_loop_pdf: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HmmTopology {
+    pub entries: Vec<Vec<HmmState>>,
+    pub phone_to_entry: Vec<Option<usize>>,
+}
+
+impl HmmTopology {
+    pub fn topology_for_phone(&self, phone: usize) -> TractResult<&[HmmState]> {
+        let entry = self
+            .phone_to_entry
+            .get(phone)
+            .cloned()
+            .flatten()
+            .with_context(|| format!("No topology for phone {}", phone))?;
+        Ok(&self.entries[entry])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HmmState {
+    /// None for the final state
+    pub forward_pdf_class: Option<usize>,
+    pub self_loop_pdf_class: Option<usize>,
+    /// Destination state and probability
+    pub transitions: Vec<(usize, f32)>,
 }
 
 #[derive(Clone, Debug)]
@@ -167,6 +273,24 @@ pub struct ParsingContext<'a> {
     pub proto_model: &'a KaldiProtoModel,
 }
 
+impl<'a> ParsingContext<'a> {
+    /// The component node line and component behind a node name.
+    pub fn component_node(&self, name: &str) -> TractResult<(&'a ComponentNode, &'a Component)> {
+        let node = self.proto_model.config_lines.nodes.iter().find(|l| l.0 == name);
+        let line = if let Some((_, NodeLine::Component(line))) = node {
+            line
+        } else {
+            bail!("Could not find component {}", name);
+        };
+        let component = self
+            .proto_model
+            .components
+            .get(&line.component)
+            .with_context(|| format!("Could not find component {}", line.component))?;
+        Ok((line, component))
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct KaldiOpRegister(
     pub HashMap<String, fn(&ParsingContext, node: &str) -> TractResult<Box<dyn InferenceOp>>>,
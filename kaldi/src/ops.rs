@@ -11,23 +11,131 @@ macro_rules! op_kaldi {
 }
 
 pub(crate) mod affine;
+mod dropout;
+mod elementwise_product;
 pub(crate) mod lstm_nonlin;
 pub(crate) mod memory;
 mod renorm;
+mod scale_and_offset;
 
 pub const AFFINE: &'static [&'static str] =
-    &["FixedAffineComponent", "NaturalGradientAffineComponent"];
+    &["FixedAffineComponent", "NaturalGradientAffineComponent", "LinearComponent"];
 
 pub fn register_all_ops(reg: &mut KaldiOpRegister) {
     for affine in AFFINE {
         reg.insert(affine, affine::affine_component);
     }
-    reg.insert("BackpropTruncationComponent", |_, _| {
-        Ok(Box::new(tract_hir::ops::identity::Identity::default()))
+    for identity in &["BackpropTruncationComponent", "GeneralDropoutComponent", "NoOpComponent"] {
+        reg.insert(identity, |_, _| Ok(Box::new(tract_hir::ops::identity::Identity::default())));
+    }
+    reg.insert("BatchNormComponent", scale_and_offset::batch_norm);
+    reg.insert("DropoutMaskComponent", dropout::dropout_mask);
+    reg.insert("ElementwiseProductComponent", elementwise_product::elementwise_product);
+    reg.insert("LogSoftmaxComponent", |_, _| {
+        Ok(expand(tract_hir::ops::nn::LayerLogSoftmax::new(1)))
     });
     reg.insert("NormalizeComponent", renorm::renorm);
     reg.insert("LstmNonlinearityComponent", lstm_nonlin::lstm_nonlin);
     reg.insert("RectifiedLinearComponent", |_, _| {
         Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), None)))
     });
+    reg.insert("ScaleAndOffsetComponent", scale_and_offset::scale_and_offset);
+    reg.insert("SigmoidComponent", |_, _| Ok(Box::new(tract_hir::ops::nn::sigmoid())));
+    reg.insert("TanhComponent", |_, _| Ok(Box::new(tract_hir::ops::math::tanh())));
+    reg.insert("TdnnComponent", affine::tdnn_component);
+}
+
+#[cfg(test)]
+mod tests {
+    use tract_hir::prelude::InferenceModelExt;
+    use tract_pulse::internal::*;
+
+    fn model(nnet3: &str) -> TypedModel {
+        let kaldi = crate::kaldi();
+        let model = kaldi.model_for_read(&mut nnet3.as_bytes()).unwrap();
+        model.into_typed().unwrap().declutter().unwrap()
+    }
+
+    const TDNN_BATCHNORM: &str = r#"<Nnet3>
+input-node name=input dim=2
+component-node name=tdnn component=tdnn input=input
+component-node name=bn component=bn input=tdnn
+output-node name=output input=bn
+
+<NumComponents> 2
+<ComponentName> tdnn <TdnnComponent> <LearningRate> 0.001 <TimeOffsets> [ -1 1 ]
+<LinearParams> [
+  1 0 0 1 ]
+<BiasParams> [ 0.5 ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T <NumSamplesHistory> 2000
+<AlphaInOut> 4 4 <RankInOut> 20 80 </TdnnComponent>
+<ComponentName> bn <BatchNormComponent> <Dim> 1 <BlockDim> 1 <Epsilon> 1 <TargetRms> 1
+<TestMode> F <Count> 12 <StatsMean> [ 30 ]
+<StatsVar> [ 99 ]
+</BatchNormComponent>
+</Nnet3>"#;
+
+    #[test]
+    fn tdnn_batchnorm() {
+        let model = model(TDNN_BATCHNORM);
+        let input = tensor2(&[[1f32, 10.], [2., 20.], [3., 30.], [4., 40.]]);
+        let output = model.into_runnable().unwrap().run(tvec!(input)).unwrap();
+        // x0(t-1) + x1(t+1) + 0.5, then (y - 30) / sqrt(99 + 1)
+        output[0].close_enough(&tensor2(&[[0.15f32], [1.25]]), true).unwrap();
+    }
+
+    #[test]
+    fn tdnn_batchnorm_pulse() {
+        let model = model(TDNN_BATCHNORM);
+        let pulsed = PulsedModel::new(&model, 1).unwrap();
+        assert_eq!(pulsed.output_fact(0).unwrap().delay, 2);
+    }
+
+    const PRODUCT_DROPOUT: &str = r#"<Nnet3>
+input-node name=input dim=4
+component-node name=linear component=linear input=input
+component-node name=sigmoid component=sigmoid input=linear
+component-node name=product component=product input=sigmoid
+component-node name=mask component=mask input=input
+component-node name=dropout component=dropout input=product
+component-node name=noop component=noop input=dropout
+output-node name=output input=Append(noop, mask)
+
+<NumComponents> 6
+<ComponentName> linear <LinearComponent> <LearningRate> 0.001 <Params> [
+  1 0 0 0
+  0 1 0 0
+  0 0 1 0
+  0 0 0 1 ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T <NumSamplesHistory> 2000 <Alpha> 4
+<RankInOut> 20 80 </LinearComponent>
+<ComponentName> sigmoid <SigmoidComponent> <Dim> 4 <ValueAvg> [ ]
+<DerivAvg> [ ]
+<Count> 0 <OderivRms> [ ]
+<OderivCount> 0 <NumDimsSelfRepaired> 0 <NumDimsProcessed> 0 </SigmoidComponent>
+<ComponentName> product <ElementwiseProductComponent> <InputDim> 4 <OutputDim> 2 </ElementwiseProductComponent>
+<ComponentName> mask <DropoutMaskComponent> <OutputDim> 3 <DropoutProportion> 0.25 <Continuous> <TestMode> T </DropoutMaskComponent>
+<ComponentName> dropout <GeneralDropoutComponent> <Dim> 2 <BlockDim> 2 <TimePeriod> 0 <DropoutProportion> 0.5 <Continuous> <TestMode> T </GeneralDropoutComponent>
+<ComponentName> noop <NoOpComponent> <Dim> 2 <BackpropScale> 1 </NoOpComponent>
+</Nnet3>"#;
+
+    #[test]
+    fn product_dropout() {
+        let model = model(PRODUCT_DROPOUT);
+        let input = tensor2(&[[0f32, 0., 0., 0.], [0., 100., 0., 0.]]);
+        let output = model.into_runnable().unwrap().run(tvec!(input)).unwrap();
+        output[0]
+            .close_enough(
+                &tensor2(&[[0.25f32, 0.25, 0.75, 0.75, 0.75], [0.25, 0.5, 0.75, 0.75, 0.75]]),
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn product_dropout_pulse() {
+        let model = model(PRODUCT_DROPOUT);
+        let pulsed = PulsedModel::new(&model, 2).unwrap();
+        assert_eq!(pulsed.output_fact(0).unwrap().shape, tvec!(2.to_dim(), 5.to_dim()).into());
+    }
 }
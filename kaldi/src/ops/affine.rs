@@ -1,39 +1,81 @@
 use tract_hir::internal::*;
+use tract_ndarray::prelude::*;
 
-use crate::model::NodeLine;
 use crate::model::ParsingContext;
 
 pub fn affine_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
-    let node = &ctx.proto_model.config_lines.nodes.iter().find(|l| l.0 == name);
-    let line = if let Some((_, NodeLine::Component(line))) = node {
-        line
+    let (line, component) = ctx.component_node(name)?;
+    let (kernel_len, dilation) = line.input.as_conv_shape_dilation().unwrap_or((1, 1));
+    let (kernel, bias): (&Tensor, _) = if component.klass == "LinearComponent" {
+        (component.attributes.get("Params").context("missing attribute Params")?, None)
     } else {
-        bail!("Could not find component {}", name);
+        (
+            component.attributes.get("LinearParams").context("missing attribute LinearParams")?,
+            Some(component.attributes.get("BiasParams").context("missing attribute BiasParams")?),
+        )
     };
-    let component = &ctx.proto_model.components[&line.component];
-    let (kernel_len, dilation) = line.input.as_conv_shape_dilation().unwrap_or((1, 1));
-    let kernel: &Tensor =
-        component.attributes.get("LinearParams").context("missing attribute LinearParams")?;
-    let bias = component.attributes.get("BiasParams").context("missing attribute BiasParams")?;
     // O•TI -> t -> TI•O -> T•I•O = HWIO
     let o_ti = kernel.to_array_view::<f32>()?;
-    let t_i_o_shape = (kernel_len, kernel.len() / kernel_len / bias.len(), bias.len());
+    let output_dim = kernel.shape()[0];
+    let t_i_o_shape = (kernel_len, kernel.len() / kernel_len / output_dim, output_dim);
     let t_i_o =
         tract_ndarray::Array::from_shape_vec(t_i_o_shape, o_ti.t().iter().cloned().collect())?;
     Ok(expand(Affine {
         kernel_len,
         dilation,
         linear_params: t_i_o.into_arc_tensor(),
-        bias_params: Arc::clone(bias),
+        bias_params: bias.map(Arc::clone),
     }))
 }
 
+pub fn tdnn_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, component) = ctx.component_node(name)?;
+    let offsets =
+        component.attributes.get("TimeOffsets").context("missing attribute TimeOffsets")?;
+    let offsets = offsets.cast_to::<i64>()?;
+    let offsets = offsets.as_slice::<i64>()?;
+    if offsets.is_empty() || offsets.windows(2).any(|w| w[0] >= w[1]) {
+        bail!("TimeOffsets are expected sorted and unique, got {:?}", offsets);
+    }
+    let kernel: &Tensor =
+        component.attributes.get("LinearParams").context("missing attribute LinearParams")?;
+    let bias = component.attributes.get("BiasParams").context("missing attribute BiasParams")?;
+    // offsets are the taps of a dilated convolution, missing taps get a zero kernel
+    let dilation = offsets.windows(2).fold(0, |d, w| gcd(d, w[1] - w[0])).max(1);
+    let kernel_len = ((offsets[offsets.len() - 1] - offsets[0]) / dilation) as usize + 1;
+    let o_ti = kernel.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+    let output_dim = o_ti.shape()[0];
+    let input_dim = o_ti.shape()[1] / offsets.len();
+    let mut t_i_o = Array3::<f32>::zeros((kernel_len, input_dim, output_dim));
+    for (ix, offset) in offsets.iter().enumerate() {
+        let t = ((offset - offsets[0]) / dilation) as usize;
+        t_i_o
+            .index_axis_mut(Axis(0), t)
+            .assign(&o_ti.slice(s![.., ix * input_dim..(ix + 1) * input_dim]).t());
+    }
+    Ok(expand(Affine {
+        kernel_len,
+        dilation: dilation as usize,
+        linear_params: t_i_o.into_arc_tensor(),
+        // empty without use-bias
+        bias_params: if bias.len() > 0 { Some(Arc::clone(bias)) } else { None },
+    }))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 #[derive(Clone, Debug, new, Hash)]
 struct Affine {
     kernel_len: usize,
     dilation: usize,
     linear_params: Arc<Tensor>, // TIO
-    bias_params: Arc<Tensor>,
+    bias_params: Option<Arc<Tensor>>,
 }
 
 impl_dyn_hash!(Affine);
@@ -96,12 +138,12 @@ impl Expansion for Affine {
                     PaddingSpec::Valid,
                     Some(tvec!(self.dilation)),
                     None,
-                    Some(self.bias_params.len()),
+                    Some(self.linear_params.shape()[2]),
                 ),
                 kernel_fmt: KernelFormat::HWIO,
                 kernel: self.linear_params.clone(),
                 group: 1,
-                bias: Some(self.bias_params.clone().into_arc_tensor()),
+                bias: self.bias_params.clone(),
                 q_params: None,
             },
             inputs,
@@ -0,0 +1,80 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn dropout_mask(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, component) = ctx.component_node(name)?;
+    let output_dim = component
+        .attributes
+        .get("OutputDim")
+        .context("missing attribute OutputDim")?
+        .cast_to_scalar::<i64>()?;
+    let proportion = *component
+        .attributes
+        .get("DropoutProportion")
+        .context("missing attribute DropoutProportion")?
+        .to_scalar::<f32>()?;
+    Ok(expand(DropoutMask::new(output_dim as usize, 1.0 - proportion)))
+}
+
+/// Test mode dropout mask: a constant, with as many frames as the input.
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct DropoutMask {
+    output_dim: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    value: f32,
+}
+
+impl_dyn_hash!(DropoutMask);
+
+impl Expansion for DropoutMask {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "DropoutMask".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], self.output_dim.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::{cast::cast, logic};
+        // ones with the input frames, whatever the input values, and
+        // pulsifiable as it only involves the input
+        let wire = model.wire_node(
+            prefix.to_string() + ".column",
+            tract_hir::ops::array::Slice::new(1, 0, 1),
+            inputs,
+        )?;
+        let wire =
+            model.wire_node(prefix.to_string() + ".bool", cast(bool::datum_type()), &wire)?;
+        let wire = model.wire_node(
+            prefix.to_string() + ".true",
+            logic::or::unary(tensor2(&[[true]]).into_arc_tensor()),
+            &wire,
+        )?;
+        let ones = model.wire_node(prefix.to_string() + ".ones", cast(f32::datum_type()), &wire)?;
+        let mask = tensor1(&vec![self.value; self.output_dim]).broadcast_into_rank(2)?;
+        model.wire_node(prefix, tract_hir::ops::math::mul::unary(mask.into_arc_tensor()), &ones)
+    }
+}
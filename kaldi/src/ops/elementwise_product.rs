@@ -0,0 +1,85 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn elementwise_product(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, component) = ctx.component_node(name)?;
+    let dim = |name: &str| -> TractResult<usize> {
+        let value = component
+            .attributes
+            .get(name)
+            .with_context(|| format!("missing attribute {}", name))?;
+        Ok(value.cast_to_scalar::<i64>()? as usize)
+    };
+    let input_dim = dim("InputDim")?;
+    let output_dim = dim("OutputDim")?;
+    if output_dim == 0 || input_dim % output_dim != 0 {
+        bail!("InputDim {} is not a multiple of OutputDim {}", input_dim, output_dim);
+    }
+    Ok(expand(ElementwiseProduct::new(input_dim, output_dim)))
+}
+
+/// Multiplies together the input_dim / output_dim consecutive chunks of the
+/// input.
+#[derive(Clone, Debug, new, Hash)]
+pub struct ElementwiseProduct {
+    input_dim: usize,
+    output_dim: usize,
+}
+
+impl_dyn_hash!(ElementwiseProduct);
+
+impl Expansion for ElementwiseProduct {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ElementwiseProduct".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], self.input_dim.to_dim())?;
+        s.equals(&outputs[0].shape[1], self.output_dim.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut wire = None;
+        for ix in 0..self.input_dim / self.output_dim {
+            let chunk = model.wire_node(
+                format!("{}.chunk-{}", prefix, ix),
+                tract_hir::ops::array::Slice::new(
+                    1,
+                    ix * self.output_dim,
+                    (ix + 1) * self.output_dim,
+                ),
+                inputs,
+            )?[0];
+            wire = Some(if let Some(product) = wire {
+                model.wire_node(
+                    format!("{}.product-{}", prefix, ix),
+                    tract_hir::ops::math::mul::bin_typed(),
+                    &[product, chunk],
+                )?[0]
+            } else {
+                chunk
+            });
+        }
+        Ok(tvec!(wire.unwrap()))
+    }
+}
@@ -0,0 +1,90 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn scale_and_offset(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, component) = ctx.component_node(name)?;
+    let scales = component.attributes.get("Scales").context("missing attribute Scales")?;
+    let offsets = component.attributes.get("Offsets").context("missing attribute Offsets")?;
+    Ok(expand(ScaleAndOffset::new(Arc::clone(scales), Arc::clone(offsets))))
+}
+
+pub fn batch_norm(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, component) = ctx.component_node(name)?;
+    let scalar = |name: &str| -> TractResult<f32> {
+        let value = component
+            .attributes
+            .get(name)
+            .with_context(|| format!("missing attribute {}", name))?;
+        Ok(*value.to_scalar::<f32>()?)
+    };
+    let epsilon = scalar("Epsilon")?;
+    let target_rms = scalar("TargetRms")?;
+    let mean = component.attributes.get("StatsMean").context("missing attribute StatsMean")?;
+    let var = component.attributes.get("StatsVar").context("missing attribute StatsVar")?;
+    // test mode: y = (x - mean) * target_rms / sqrt(var + epsilon)
+    let scales = var.to_array_view::<f32>()?.mapv(|v| target_rms / (v + epsilon).sqrt());
+    let offsets = &scales * &mean.to_array_view::<f32>()? * -1.0;
+    Ok(expand(ScaleAndOffset::new(scales.into_arc_tensor(), offsets.into_arc_tensor())))
+}
+
+/// Per-dimension affine transform. Scales and offsets cover a block of
+/// dimensions, repeated over the whole input dimension.
+#[derive(Clone, Debug, new, Hash)]
+pub struct ScaleAndOffset {
+    scales: Arc<Tensor>,
+    offsets: Arc<Tensor>,
+}
+
+impl_dyn_hash!(ScaleAndOffset);
+
+impl ScaleAndOffset {
+    fn tiled(block: &Tensor, dim: usize) -> TractResult<Arc<Tensor>> {
+        if block.len() == 0 || dim % block.len() != 0 {
+            bail!("Input dimension {} is not a multiple of block dimension {}", dim, block.len());
+        }
+        let block = block.to_array_view::<f32>()?;
+        let tiled: Vec<f32> = block.iter().cycle().take(dim).cloned().collect();
+        Ok(tensor1(&tiled).broadcast_into_rank(2)?.into_arc_tensor())
+    }
+}
+
+impl Expansion for ScaleAndOffset {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ScaleAndOffset".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let dim = model.outlet_fact(inputs[0])?.shape[1].to_usize()?;
+        let scales = Self::tiled(&self.scales, dim)?;
+        let offsets = Self::tiled(&self.offsets, dim)?;
+        let scaled = model.wire_node(
+            prefix.to_string() + ".scale",
+            tract_hir::ops::math::mul::unary(scales),
+            inputs,
+        )?;
+        model.wire_node(prefix, tract_hir::ops::math::add::unary(offsets), &scaled)
+    }
+}
@@ -12,7 +12,7 @@ use nom::{
 
 use std::collections::HashMap;
 
-use crate::model::{Component, KaldiProtoModel};
+use crate::model::{Component, KaldiProtoModel, TransitionModel};
 
 use tract_itertools::Itertools;
 
@@ -21,17 +21,29 @@ mod components;
 mod config_lines;
 mod descriptor;
 mod text;
+mod transition_model;
 
 pub fn nnet3(slice: &[u8]) -> TractResult<KaldiProtoModel> {
-    let (_, (config, components)) = parse_top_level(slice).map_err(|e| match e {
-        nom::Err::Error(err) => format_err!(
-            "Parsing kaldi enveloppe at: {:?}",
-            err.input.iter().take(120).map(|b| format!("{}", *b as char)).join("")
-        ),
-        e => format_err!("{:?}", e),
-    })?;
+    let (_, (transition_model, config, components, priors)) =
+        parse_top_level(slice).map_err(|e| match e {
+            nom::Err::Error(err) | nom::Err::Failure(err) => format_err!(
+                "Parsing kaldi enveloppe at: {:?}",
+                err.input.iter().take(120).map(|b| format!("{}", *b as char)).join("")
+            ),
+            e => format_err!("{:?}", e),
+        })?;
     let config_lines = config_lines::parse_config(config)?;
-    Ok(KaldiProtoModel { config_lines, components, adjust_final_offset: 0 })
+    let transition_model = transition_model
+        .map(|(topology, tuples, log_probs)| TransitionModel::new(topology, tuples, log_probs))
+        .transpose()?;
+    let priors = priors.filter(|p| p.len() > 0);
+    Ok(KaldiProtoModel {
+        config_lines,
+        components,
+        adjust_final_offset: 0,
+        transition_model,
+        priors,
+    })
 }
 
 pub fn if_then_else<'a, T>(
@@ -42,8 +54,17 @@ pub fn if_then_else<'a, T>(
     map(pair(cond(condition, then), cond(!condition, otherwise)), |(a, b)| a.or(b).unwrap())
 }
 
-fn parse_top_level(i: &[u8]) -> IResult<&[u8], (&str, HashMap<String, Component>)> {
+type TopLevel<'a> = (
+    Option<(crate::model::HmmTopology, Vec<crate::model::TransitionTuple>, Tensor)>,
+    &'a str,
+    HashMap<String, Component>,
+    Option<Tensor>,
+);
+
+// a raw nnet3, or an acoustic model: transition model, nnet3, context and priors
+fn parse_top_level(i: &[u8]) -> IResult<&[u8], TopLevel> {
     let (i, bin) = map(opt(tag([0, 0x42])), |o| Option::is_some(&o))(i)?;
+    let (i, transition_model) = opt(transition_model::transition_model(bin))(i)?;
     let (i, _) = open(i, "Nnet3")?;
     let (i, config_lines) = map_res(take_until("<NumComponents>"), std::str::from_utf8)(i)?;
     let (i, num_components) = num_components(bin, i)?;
@@ -57,7 +78,17 @@ fn parse_top_level(i: &[u8]) -> IResult<&[u8], (&str, HashMap<String, Component>
         components.insert(name.to_owned(), comp);
     }
     let (i, _) = close(i, "Nnet3")?;
-    Ok((i, (config_lines, components)))
+    let (i, priors) = opt(preceded(
+        tuple((
+            |i| open(i, "LeftContext"),
+            multispaced(integer(bin)),
+            |i| open(i, "RightContext"),
+            multispaced(integer(bin)),
+            |i| open(i, "Priors"),
+        )),
+        transition_model::float_vector(bin),
+    ))(i)?;
+    Ok((i, (transition_model, config_lines, components, priors)))
 }
 
 fn num_components(bin: bool, i: &[u8]) -> IResult<&[u8], usize> {
@@ -14,3 +14,30 @@ fn attribute<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], (String, Arc<Ten
     let (i, value) = COMPONENTS[klass][name].parse_bin(i)?;
     Ok((i, (name.to_string(), value.into_arc_tensor())))
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::super::components::KaldiAttributeKind;
+    use super::super::nnet3;
+    use super::*;
+
+    #[test]
+    fn relu_D10_T11_bin() {
+        let slice = std::fs::read("test_cases/relu_D10_T11/model.raw").unwrap();
+        nnet3(&slice).unwrap();
+    }
+
+    #[test]
+    fn float_vector() {
+        let (_, t) =
+            KaldiAttributeKind::FloatVector.parse_bin(b"FV\x04\x01\0\0\0\0\0\x80\x3f").unwrap();
+        assert_eq!(t, tensor1(&[1.0f32]));
+    }
+
+    #[test]
+    fn empty_float_vector() {
+        let (_, t) = KaldiAttributeKind::FloatVector.parse_bin(b"FV\x04\0\0\0\0").unwrap();
+        assert_eq!(t, tensor1::<f32>(&[]));
+    }
+}
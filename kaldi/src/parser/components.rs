@@ -10,15 +10,20 @@ use nom::{
     bytes::complete::*,
     combinator::*,
     multi::many_m_n,
-    number::complete::{le_f32, le_f64},
+    number::complete::{le_f32, le_f64, le_i32},
     sequence::*,
     IResult,
 };
 
 pub enum KaldiAttributeKind {
     Bool,
+    /// A bool that may be written as a bare token, meaning true
+    Flag,
     Int,
+    IntPair,
+    IntVector,
     Float,
+    FloatPair,
     FloatVector,
     FloatMatrix,
 }
@@ -30,8 +35,16 @@ impl KaldiAttributeKind {
                 map(tag("F"), |_| Tensor::from(false)),
                 map(tag("T"), |_| Tensor::from(true)),
             ))(i),
+            Flag => map(opt(alt((tag("F"), tag("T")))), |v| Tensor::from(v != Some(&b"F"[..])))(i),
             Int => map(super::integer(true), Tensor::from)(i),
+            IntPair => {
+                map(pair(super::integer(true), super::integer(true)), |(a, b)| tensor1(&[a, b]))(i)
+            }
+            IntVector => Self::parse_int_vector(i),
             Float => map(Self::parse_float_value, Tensor::from)(i),
+            FloatPair => map(pair(Self::parse_float_value, Self::parse_float_value), |(a, b)| {
+                tensor1(&[a, b])
+            })(i),
             FloatVector => preceded(multispaced(tag("FV")), Self::parse_float_vector)(i),
             FloatMatrix => preceded(multispaced(tag("FM")), Self::parse_float_matrix)(i),
         }
@@ -41,11 +54,22 @@ impl KaldiAttributeKind {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
 
+    // WriteIntegerVector: element size, then a raw i32 length and raw elements
+    fn parse_int_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = preceded(tag([4]), le_i32)(i)?;
+        // FIXME pending merge of https://github.com/Geal/nom/pull/995
+        if len == 0 {
+            Ok((i, tensor1::<i32>(&[])))
+        } else {
+            map(many_m_n(len as usize, len as usize, le_i32), |data| tensor1(&*data))(i)
+        }
+    }
+
     fn parse_float_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
         let (i, len) = super::integer(true)(i)?;
         // FIXME pending merge of https://github.com/Geal/nom/pull/995
         if len == 0 {
-            Ok((i, tensor1(&[0.0f32])))
+            Ok((i, tensor1::<f32>(&[])))
         } else {
             map(many_m_n(len as usize, len as usize, le_f32), |data| tensor1(&*data))(i)
         }
@@ -71,6 +95,38 @@ impl KaldiAttributeKind {
 
 use KaldiAttributeKind::*;
 
+// written by WriteUpdatableCommon before the component specific attributes
+fn updatable(
+    mut attributes: HashMap<&'static str, KaldiAttributeKind>,
+) -> HashMap<&'static str, KaldiAttributeKind> {
+    attributes.extend(hashmap! {
+        "LearningRateFactor" => Float,
+        "IsGradient" => Bool,
+        "MaxChange" => Float,
+        "L2Regularize" => Float,
+        "LearningRate" => Float,
+    });
+    attributes
+}
+
+// statistics written by NonlinearComponent::Write
+fn nonlinear() -> HashMap<&'static str, KaldiAttributeKind> {
+    hashmap! {
+        "Dim" => Int,
+        "BlockDim" => Int,
+        "ValueAvg" => FloatVector,
+        "DerivAvg" => FloatVector,
+        "Count" => Float,
+        "OderivRms" => FloatVector,
+        "OderivCount" => Float,
+        "NumDimsSelfRepaired" => Float,
+        "NumDimsProcessed" => Float,
+        "SelfRepairLowerThreshold" => Float,
+        "SelfRepairUpperThreshold" => Float,
+        "SelfRepairScale" => Float,
+    }
+}
+
 lazy_static::lazy_static! {
     pub static ref COMPONENTS: HashMap<&'static str, HashMap<&'static str, KaldiAttributeKind>> = hashmap! {
         "FixedAffineComponent" => hashmap! {
@@ -90,6 +146,41 @@ lazy_static::lazy_static! {
             "Alpha" => Float,
             "IsGradient" => Bool,
         },
+        "LinearComponent" => updatable(hashmap! {
+            "Params" => FloatMatrix,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumSamplesHistory" => Float,
+            "Alpha" => Float,
+            "RankInOut" => IntPair,
+        }),
+        "TdnnComponent" => updatable(hashmap! {
+            "TimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumSamplesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        }),
+        "ScaleAndOffsetComponent" => updatable(hashmap! {
+            "Dim" => Int,
+            "Scales" => FloatVector,
+            "Offsets" => FloatVector,
+            "UseNaturalGradient" => Bool,
+            "Rank" => Int,
+        }),
+        "BatchNormComponent" => hashmap! {
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "Epsilon" => Float,
+            "TargetRms" => Float,
+            "TestMode" => Bool,
+            "Count" => Float,
+            "StatsMean" => FloatVector,
+            "StatsVar" => FloatVector,
+        },
         "NormalizeComponent" => hashmap!{
             "InputDim" => Int,
             "TargetRms" => Float,
@@ -123,22 +214,33 @@ lazy_static::lazy_static! {
             "NumElementsProcessed" => Float,
             "NumZeroingBoundaries" => Float,
         },
-        "LogSoftmaxComponent" => hashmap!{
+        "ElementwiseProductComponent" => hashmap!{
+            "InputDim" => Int,
+            "OutputDim" => Int,
+        },
+        "NoOpComponent" => hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Int,
-            "NumDimsSelfRepaired" => Int,
-            "NumDimsProcessed" => Int,
+            "BackpropScale" => Float,
         },
-        "RectifiedLinearComponent" => hashmap!{
+        "GeneralDropoutComponent" => hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Float,
-            "NumDimsSelfRepaired" => Float,
-            "NumDimsProcessed" => Float,
-            "SelfRepairScale" => Float,
-        }
+            "BlockDim" => Int,
+            "TimePeriod" => Int,
+            "DropoutProportion" => Float,
+            "SpecAugmentMaxProportion" => Float,
+            "SpecAugmentMaxRegions" => Int,
+            "Continuous" => Flag,
+            "TestMode" => Flag,
+        },
+        "DropoutMaskComponent" => hashmap!{
+            "OutputDim" => Int,
+            "DropoutProportion" => Float,
+            "Continuous" => Flag,
+            "TestMode" => Flag,
+        },
+        "LogSoftmaxComponent" => nonlinear(),
+        "RectifiedLinearComponent" => nonlinear(),
+        "SigmoidComponent" => nonlinear(),
+        "TanhComponent" => nonlinear(),
     };
 }
@@ -9,14 +9,22 @@ use nom::{
 use super::{integer, multispaced, open_any, spaced};
 
 pub fn attributes(i: &[u8]) -> IResult<&[u8], HashMap<String, Arc<Tensor>>> {
-    let (i, attributes) = nom::multi::many0(map(pair(open_any, tensor), |(k, v)| {
-        (k.to_string(), v.into_arc_tensor())
+    // a token with no value is a flag (like <Continuous>)
+    let (i, attributes) = nom::multi::many0(map(pair(open_any, opt(tensor)), |(k, v)| {
+        (k.to_string(), v.unwrap_or_else(|| Tensor::from(true)).into_arc_tensor())
     }))(i)?;
     Ok((i, attributes.into_iter().collect()))
 }
 
 pub fn tensor(i: &[u8]) -> IResult<&[u8], Tensor> {
-    nom::branch::alt((scalar, vector, matrix))(i)
+    nom::branch::alt((scalars, scalar, vector, matrix))(i)
+}
+
+// several values for the same token, like <RankInOut> 20 80
+pub fn scalars(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map(pair(float, nom::multi::many1(preceded(space1, float))), |(first, others)| {
+        tensor1(&*std::iter::once(first).chain(others).collect::<Vec<_>>())
+    })(i)
 }
 
 pub fn scalar(i: &[u8]) -> IResult<&[u8], Tensor> {
@@ -94,4 +102,10 @@ output-node name=output input=fixed1
         let slice = std::fs::read("test_cases/fixed_affine_40x10_T40_S3/model.raw.txt").unwrap();
         nnet3(&slice).unwrap();
     }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(tensor(b"20 80 <Next>").unwrap().1, tensor1(&[20.0f32, 80.0]));
+        assert_eq!(tensor(b"20 <Next>").unwrap().1, tensor0(20.0f32));
+    }
 }
@@ -0,0 +1,305 @@
+use tract_hir::internal::*;
+
+use nom::IResult;
+use nom::{
+    branch::alt,
+    bytes::complete::*,
+    combinator::*,
+    multi::{many0, many_m_n},
+    number::complete::{float, le_f32, le_f64},
+    sequence::*,
+};
+
+use crate::model::{HmmState, HmmTopology, TransitionTuple};
+
+use super::components::KaldiAttributeKind;
+use super::{close, integer, multispaced, open};
+
+pub type TransitionModelParts = (HmmTopology, Vec<TransitionTuple>, Tensor);
+
+pub fn transition_model(bin: bool) -> impl Fn(&[u8]) -> IResult<&[u8], TransitionModelParts> {
+    move |i: &[u8]| {
+        let (i, _) = open(i, "TransitionModel")?;
+        // past the opening tag, errors are not an absent transition model
+        cut(|i| transition_model_body(bin, i))(i)
+    }
+}
+
+fn transition_model_body(bin: bool, i: &[u8]) -> IResult<&[u8], TransitionModelParts> {
+    let (i, topology) = if bin { bin_topology(i)? } else { text_topology(i)? };
+    // Triples for HMM topologies, Tuples with a self-loop pdf otherwise
+    let (i, hmm) =
+        alt((map(|i| open(i, "Triples"), |_| true), map(|i| open(i, "Tuples"), |_| false)))(i)?;
+    let (i, len) = uinteger(bin)(i)?;
+    let (i, tuples) = many_m_n(len, len, transition_tuple(bin, hmm))(i)?;
+    let (i, _) = close(i, if hmm { "Triples" } else { "Tuples" })?;
+    let (i, log_probs) =
+        delimited(|i| open(i, "LogProbs"), float_vector(bin), |i| close(i, "LogProbs"))(i)?;
+    let (i, _) = close(i, "TransitionModel")?;
+    Ok((i, (topology, tuples, log_probs)))
+}
+
+pub fn float_vector(bin: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Tensor> {
+    move |i: &[u8]| {
+        if bin {
+            KaldiAttributeKind::FloatVector.parse_bin(i)
+        } else {
+            multispaced(super::text::vector)(i)
+        }
+    }
+}
+
+fn transition_tuple(bin: bool, hmm: bool) -> impl FnMut(&[u8]) -> IResult<&[u8], TransitionTuple> {
+    move |i: &[u8]| {
+        let (i, (phone, hmm_state, forward_pdf)) =
+            tuple((uinteger(bin), uinteger(bin), uinteger(bin)))(i)?;
+        let (i, self_loop_pdf) = if hmm { (i, forward_pdf) } else { uinteger(bin)(i)? };
+        Ok((i, TransitionTuple { phone, hmm_state, forward_pdf, self_loop_pdf }))
+    }
+}
+
+fn uinteger<'a>(bin: bool) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], usize> {
+    map_res(multispaced(integer(bin)), std::convert::TryFrom::try_from)
+}
+
+// kNoPdf (-1) marks the final state
+fn pdf_class<'a>(bin: bool) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], Option<usize>> {
+    map(multispaced(integer(bin)), |i| if i < 0 { None } else { Some(i as usize) })
+}
+
+fn real<'a>(bin: bool) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], f32> {
+    super::if_then_else(
+        bin,
+        alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32))),
+        multispaced(float),
+    )
+}
+
+fn transition<'a>(bin: bool) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], (usize, f32)> {
+    pair(uinteger(bin), real(bin))
+}
+
+fn int_vector(i: &[u8]) -> IResult<&[u8], Vec<i32>> {
+    map_res(
+        |i| KaldiAttributeKind::IntVector.parse_bin(i),
+        |t| t.as_slice::<i32>().map(|s| s.to_vec()),
+    )(i)
+}
+
+fn bin_topology(i: &[u8]) -> IResult<&[u8], HmmTopology> {
+    let (i, _) = open(i, "Topology")?;
+    let (i, _phones) = int_vector(i)?;
+    let (i, phone_to_entry) = int_vector(i)?;
+    // a -1 before the entry count flags the self-loop pdf classes
+    let (i, (hmm, len)) = alt((
+        map(preceded(verify(integer(true), |m| *m == -1), uinteger(true)), |len| (false, len)),
+        map(uinteger(true), |len| (true, len)),
+    ))(i)?;
+    let state = move |i| {
+        let (i, forward_pdf_class) = pdf_class(true)(i)?;
+        let (i, self_loop_pdf_class) =
+            if hmm { (i, forward_pdf_class) } else { pdf_class(true)(i)? };
+        let (i, len) = uinteger(true)(i)?;
+        let (i, transitions) = many_m_n(len, len, transition(true))(i)?;
+        Ok((i, HmmState { forward_pdf_class, self_loop_pdf_class, transitions }))
+    };
+    let entry = |i| {
+        let (i, len) = uinteger(true)(i)?;
+        many_m_n(len, len, state)(i)
+    };
+    let (i, entries) = many_m_n(len, len, entry)(i)?;
+    let (i, _) = close(i, "Topology")?;
+    let phone_to_entry =
+        phone_to_entry.into_iter().map(|e| if e < 0 { None } else { Some(e as usize) }).collect();
+    Ok((i, HmmTopology { entries, phone_to_entry }))
+}
+
+fn text_topology(i: &[u8]) -> IResult<&[u8], HmmTopology> {
+    let (i, _) = open(i, "Topology")?;
+    let (i, entries) = many0(text_topology_entry)(i)?;
+    let (i, _) = close(i, "Topology")?;
+    let mut phone_to_entry = vec![];
+    for (ix, (phones, _)) in entries.iter().enumerate() {
+        for &phone in phones {
+            if phone_to_entry.len() <= phone {
+                phone_to_entry.resize(phone + 1, None);
+            }
+            phone_to_entry[phone] = Some(ix);
+        }
+    }
+    let entries = entries.into_iter().map(|(_, states)| states).collect();
+    Ok((i, HmmTopology { entries, phone_to_entry }))
+}
+
+fn text_topology_entry(i: &[u8]) -> IResult<&[u8], (Vec<usize>, Vec<HmmState>)> {
+    let (i, _) = open(i, "TopologyEntry")?;
+    let (i, phones) =
+        delimited(|i| open(i, "ForPhones"), many0(uinteger(false)), |i| close(i, "ForPhones"))(i)?;
+    let (i, states) = many0(text_state)(i)?;
+    let (i, _) = close(i, "TopologyEntry")?;
+    Ok((i, (phones, states)))
+}
+
+fn text_state(i: &[u8]) -> IResult<&[u8], HmmState> {
+    let (i, _) = open(i, "State")?;
+    let (i, _index) = uinteger(false)(i)?;
+    let (i, pdf_classes) = opt(alt((
+        map(preceded(|i| open(i, "PdfClass"), pdf_class(false)), |pdf| (pdf, pdf)),
+        pair(
+            preceded(|i| open(i, "ForwardPdfClass"), pdf_class(false)),
+            preceded(|i| open(i, "SelfLoopPdfClass"), pdf_class(false)),
+        ),
+    )))(i)?;
+    let (forward_pdf_class, self_loop_pdf_class) = pdf_classes.unwrap_or((None, None));
+    let (i, transitions) = many0(preceded(|i| open(i, "Transition"), transition(false)))(i)?;
+    let (i, _) = close(i, "State")?;
+    Ok((i, HmmState { forward_pdf_class, self_loop_pdf_class, transitions }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::nnet3;
+
+    const NNET3: &str = r#"<Nnet3>
+input-node name=input dim=3
+component-node name=relu1 input=input component=relu1
+output-node name=output input=relu1
+
+<NumComponents> 1
+<ComponentName> relu1 <RectifiedLinearComponent> <Dim> 3 </RectifiedLinearComponent>
+</Nnet3>"#;
+
+    #[test]
+    fn text_mdl() {
+        let mdl = format!(
+            r#"<TransitionModel>
+<Topology>
+<TopologyEntry>
+<ForPhones>
+1 2
+</ForPhones>
+<State> 0 <PdfClass> 0 <Transition> 0 0.75 <Transition> 1 0.25 </State>
+<State> 1 </State>
+</TopologyEntry>
+</Topology>
+<Triples> 2
+1 0 0
+2 0 1
+</Triples>
+<LogProbs>
+ [ 0 -0.28 -1.38 -0.28 -1.38 ]
+</LogProbs>
+</TransitionModel>
+{}
+<LeftContext> 0 <RightContext> 0 <Priors> [ 0.6 0.4 ]
+"#,
+            NNET3
+        );
+        let model = nnet3(mdl.as_bytes()).unwrap();
+        let tm = model.transition_model.unwrap();
+        assert_eq!(tm.num_pdfs, 2);
+        assert_eq!(tm.num_transition_ids(), 4);
+        assert_eq!(tm.transition_id_to_pdf_id, vec![0, 0, 0, 1, 1]);
+        assert_eq!(tm.transition_id_to_phone(3), Some(2));
+        assert_eq!(tm.log_probs.len(), 5);
+        assert_eq!(model.priors.unwrap().as_slice::<f32>().unwrap(), &[0.6, 0.4]);
+        assert_eq!(model.components.len(), 1);
+    }
+
+    #[test]
+    fn truncated_transition_model_is_an_error() {
+        let mdl = format!("<TransitionModel>\n<Topology>\n</Topology>\n{}", NNET3);
+        // the error is reported past the topology, where <Triples> or <Tuples> is expected
+        let err = nnet3(mdl.as_bytes()).unwrap_err().to_string();
+        assert!(err.starts_with("Parsing kaldi enveloppe at: \"Nnet3>"), "{}", err);
+    }
+
+    #[test]
+    fn negative_count_is_an_error() {
+        let mdl = format!(
+            "<TransitionModel>\n<Topology>\n</Topology>\n<Triples> -1\n</Triples>\n{}",
+            NNET3
+        );
+        let err = nnet3(mdl.as_bytes()).unwrap_err().to_string();
+        assert!(err.starts_with("Parsing kaldi enveloppe at: \"-1"), "{}", err);
+    }
+
+    #[test]
+    fn raw_nnet3_has_no_transition_model() {
+        let model = nnet3(NNET3.as_bytes()).unwrap();
+        assert!(model.transition_model.is_none());
+        assert!(model.priors.is_none());
+    }
+
+    #[test]
+    fn bin_mdl() {
+        let mut mdl: Vec<u8> = vec![0, b'B'];
+        let token = |mdl: &mut Vec<u8>, t: &str| mdl.extend(format!("{} ", t).bytes());
+        let int = |mdl: &mut Vec<u8>, i: i32| {
+            mdl.push(4);
+            mdl.extend(&i.to_le_bytes());
+        };
+        let float = |mdl: &mut Vec<u8>, f: f32| {
+            mdl.push(4);
+            mdl.extend(&f.to_le_bytes());
+        };
+        let int_vector = |mdl: &mut Vec<u8>, v: &[i32]| {
+            mdl.push(4);
+            mdl.extend(&(v.len() as i32).to_le_bytes());
+            v.iter().for_each(|i| mdl.extend(&i.to_le_bytes()));
+        };
+        let float_vector = |mdl: &mut Vec<u8>, v: &[f32]| {
+            token(mdl, "FV");
+            int(mdl, v.len() as i32);
+            v.iter().for_each(|f| mdl.extend(&f.to_le_bytes()));
+        };
+        token(&mut mdl, "<TransitionModel>");
+        token(&mut mdl, "<Topology>");
+        int_vector(&mut mdl, &[1]);
+        int_vector(&mut mdl, &[-1, 0]);
+        int(&mut mdl, -1); // self-loop pdf classes
+        int(&mut mdl, 1); // entries
+        int(&mut mdl, 2); // states
+        for (forward, self_loop, transitions) in
+            &[(0, 1, vec![(0, 0.5f32), (1, 0.5)]), (-1, -1, vec![])]
+        {
+            int(&mut mdl, *forward);
+            int(&mut mdl, *self_loop);
+            int(&mut mdl, transitions.len() as i32);
+            for (dest, prob) in transitions {
+                int(&mut mdl, *dest);
+                float(&mut mdl, *prob);
+            }
+        }
+        token(&mut mdl, "</Topology>");
+        token(&mut mdl, "<Tuples>");
+        int(&mut mdl, 1);
+        [1, 0, 0, 1].iter().for_each(|i| int(&mut mdl, *i));
+        token(&mut mdl, "</Tuples>");
+        token(&mut mdl, "<LogProbs>");
+        float_vector(&mut mdl, &[0.0, -0.69, -0.69]);
+        token(&mut mdl, "</LogProbs>");
+        token(&mut mdl, "</TransitionModel>");
+        let config = NNET3.split("<NumComponents>").next().unwrap();
+        mdl.extend(config.bytes());
+        token(&mut mdl, "<NumComponents>");
+        int(&mut mdl, 1);
+        token(&mut mdl, "<ComponentName> relu1 <RectifiedLinearComponent> <Dim>");
+        int(&mut mdl, 3);
+        token(&mut mdl, "</RectifiedLinearComponent> </Nnet3> <LeftContext>");
+        int(&mut mdl, 0);
+        token(&mut mdl, "<RightContext>");
+        int(&mut mdl, 0);
+        token(&mut mdl, "<Priors>");
+        float_vector(&mut mdl, &[]);
+
+        let model = nnet3(&mdl).unwrap();
+        let tm = model.transition_model.unwrap();
+        assert_eq!(tm.num_pdfs, 2);
+        // self-loop first, then forward
+        assert_eq!(tm.transition_id_to_pdf_id, vec![0, 1, 0]);
+        assert_eq!(tm.topology.entries[0][0].self_loop_pdf_class, Some(1));
+        assert_eq!(tm.topology.entries[0][1].forward_pdf_class, None);
+        assert!(model.priors.is_none());
+    }
+}
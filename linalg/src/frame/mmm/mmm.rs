@@ -1,6 +1,7 @@
 use super::ScratchSpaceFusedNonLinear;
 use super::*;
 use crate::frame::Packer;
+use crate::multithread::Executor;
 use num_traits::{AsPrimitive, Zero};
 use std::fmt;
 use std::fmt::Debug;
@@ -67,6 +68,22 @@ pub trait MatMatMul:
         c: &mut MatrixStore,
         non_linear: &[FusedSpec],
     ) -> anyhow::Result<()>;
+
+    /// Same as `run_with_scratch_space`, but tiles may be computed in parallel
+    /// depending on the executor. `scratch` is only used by the calling
+    /// thread.
+    unsafe fn run_with_executor(
+        &self,
+        executor: &Executor,
+        m: usize,
+        k: usize,
+        n: usize,
+        scratch: &mut dyn ScratchSpace,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &mut MatrixStore,
+        non_linear: &[FusedSpec],
+    ) -> anyhow::Result<()>;
 }
 
 dyn_clone::clone_trait_object!(MatMatMul);
@@ -252,115 +269,129 @@ where
         non_linear: &[FusedSpec],
     ) -> anyhow::Result<()> {
         use anyhow::Context;
-        let mr = K::mr();
-        let nr = K::nr();
         let scratch = scratch
             .downcast_mut::<ScratchSpaceFusedNonLinear<TI>>()
             .context("Wrong scratch space type")?;
         let ref linear = LinearSpec::k(k);
-        for ia in 0..m / mr {
-            let ref a = a.panel_a(ia);
-            if K::nr() == 1 && n == 1 {
-                let ref b = b.panel_b(0);
-                self.prefetch(a, b);
-                scratch.clear();
-                let non_linear = scratch.for_tile::<K>(&non_linear, ia, 0, c);
-                let ref direct_c = c.tile_c(ia, 0);
-                let err = K::kernel(&MatMatMulKerSpec {
-                    a: a as _,
-                    b: b as _,
-                    c: direct_c as _,
-                    linear,
-                    non_linear,
-                });
-                debug_assert_eq!(err, 0, "Kernel return error {}", err);
-            } else {
-                for ib in 0..n / nr {
-                    let ref b = b.panel_b(ib);
-                    self.prefetch(a, b);
-                    scratch.clear();
-                    let ref direct_c = c.tile_c(ia, ib);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, ia, ib, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: a as _,
-                        b: b as _,
-                        c: direct_c as _,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                }
-                if n % nr != 0 {
-                    let ref b = b.panel_b(n / nr);
-                    self.prefetch(a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, ia, n / nr, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(ia, n / nr, mr, n % nr, &tmpc);
-                }
+        for ia in 0..(m + K::mr() - 1) / K::mr() {
+            for ib in 0..(n + K::nr() - 1) / K::nr() {
+                self.run_tile(m, n, ia, ib, scratch, linear, a, b, c, non_linear);
             }
         }
-        if m % mr != 0 {
-            let ref panel_a = a.panel_a(m / mr);
-            if K::nr() == 1 && n == 1 {
-                let ref b = b.panel_b(0);
-                self.prefetch(panel_a, b);
-                scratch.clear();
-                let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, 0, c);
-                let err = K::kernel(&MatMatMulKerSpec {
-                    a: panel_a as _,
-                    b: b as _,
-                    c: &tmpc,
-                    linear,
-                    non_linear,
+        Ok(())
+    }
+
+    unsafe fn run_with_executor(
+        &self,
+        executor: &Executor,
+        m: usize,
+        k: usize,
+        n: usize,
+        scratch: &mut dyn ScratchSpace,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &mut MatrixStore,
+        non_linear: &[FusedSpec],
+    ) -> anyhow::Result<()> {
+        match executor {
+            Executor::SingleThread => {
+                self.run_with_scratch_space(m, k, n, scratch, a, b, c, non_linear)
+            }
+            #[cfg(feature = "multithread-mm")]
+            Executor::MultiThread(pool) => {
+                use rayon::prelude::*;
+                let ref linear = LinearSpec::k(k);
+                let stores = SharedStores(a, b, c);
+                let tiles_right = (n + K::nr() - 1) / K::nr();
+                let tiles = (m + K::mr() - 1) / K::mr() * tiles_right;
+                pool.install(|| {
+                    (0..tiles).into_par_iter().for_each_init(
+                        ScratchSpaceFusedNonLinear::<TI>::default,
+                        |scratch, tile| {
+                            let SharedStores(a, b, c) = &stores;
+                            self.run_tile(
+                                m,
+                                n,
+                                tile / tiles_right,
+                                tile % tiles_right,
+                                scratch,
+                                linear,
+                                a,
+                                b,
+                                c,
+                                non_linear,
+                            )
+                        },
+                    )
                 });
-                debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                c.set_from_tile(m / mr, 0, m % mr, nr, &tmpc);
-            } else {
-                for ib in 0..n / nr {
-                    let ref b = b.panel_b(ib);
-                    self.prefetch(panel_a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, ib, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: panel_a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(m / mr, ib, m % mr, nr, &tmpc);
-                }
-                if n % nr != 0 {
-                    let ref b = b.panel_b(n / nr);
-                    self.prefetch(panel_a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, n / nr, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: panel_a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(m / mr, n / nr, m % mr, n % nr, &tmpc);
-                }
+                Ok(())
             }
         }
-        Ok(())
+    }
+}
+
+/// Tiles write to disjoint parts of c, so stores can be shared between threads.
+#[cfg(feature = "multithread-mm")]
+struct SharedStores<'a, 's, 't>(
+    &'a MatrixStore<'s, 't>,
+    &'a MatrixStore<'s, 't>,
+    &'a MatrixStore<'s, 't>,
+);
+
+#[cfg(feature = "multithread-mm")]
+unsafe impl<'a, 's, 't> Sync for SharedStores<'a, 's, 't> {}
+
+impl<K, TI> MatMatMulImpl<K, TI>
+where
+    TI: Datum + Copy + Add + Mul<Output = TI> + Zero + Debug + 'static + Neg<Output = TI>,
+    K: MatMatMulKer<TI> + 'static,
+{
+    #[inline]
+    unsafe fn run_tile(
+        &self,
+        m: usize,
+        n: usize,
+        ia: usize,
+        ib: usize,
+        scratch: &mut ScratchSpaceFusedNonLinear<TI>,
+        linear: &LinearSpec,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &MatrixStore,
+        non_linear: &[FusedSpec],
+    ) {
+        let mr = K::mr();
+        let nr = K::nr();
+        let ref panel_a = a.panel_a(ia);
+        let ref panel_b = b.panel_b(ib);
+        self.prefetch(panel_a, panel_b);
+        scratch.clear();
+        let height = mr.min(m - ia * mr);
+        let width = nr.min(n - ib * nr);
+        if height == mr && width == nr {
+            let ref direct_c = c.tile_c(ia, ib);
+            let non_linear = scratch.for_tile::<K>(&non_linear, ia, ib, c);
+            let err = K::kernel(&MatMatMulKerSpec {
+                a: panel_a as _,
+                b: panel_b as _,
+                c: direct_c as _,
+                linear,
+                non_linear,
+            });
+            debug_assert_eq!(err, 0, "Kernel return error {}", err);
+        } else {
+            let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
+            let non_linear = scratch.for_tile::<K>(&non_linear, ia, ib, c);
+            let err = K::kernel(&MatMatMulKerSpec {
+                a: panel_a as _,
+                b: panel_b as _,
+                c: &tmpc,
+                linear,
+                non_linear,
+            });
+            debug_assert_eq!(err, 0, "Kernel return error {}", err);
+            c.set_from_tile(ia, ib, height, width, &tmpc);
+        }
     }
 }
 
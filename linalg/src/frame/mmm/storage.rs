@@ -191,7 +191,7 @@ impl<'s, 't> MatrixStore<'s, 't> {
 
     #[inline]
     pub(super) unsafe fn set_from_tile(
-        &mut self,
+        &self,
         down: usize,
         right: usize,
         height: usize,
@@ -207,7 +207,7 @@ impl<'s, 't> MatrixStore<'s, 't> {
 
     #[inline]
     unsafe fn set_from_tile_t<T: Datum + Copy>(
-        &mut self,
+        &self,
         down: usize,
         right: usize,
         height: usize,
@@ -0,0 +1,101 @@
+//! Executors for the matrix multiplication tile loop.
+//!
+//! Single-threaded execution is the default. With the `multithread-mm`
+//! feature, tiles can be spread over a rayon thread pool.
+
+#[derive(Clone, Debug)]
+pub enum Executor {
+    SingleThread,
+    #[cfg(feature = "multithread-mm")]
+    MultiThread(std::sync::Arc<rayon::ThreadPool>),
+}
+
+impl Default for Executor {
+    fn default() -> Executor {
+        Executor::SingleThread
+    }
+}
+
+impl Executor {
+    /// An executor running tiles on a dedicated pool of `threads` threads.
+    #[cfg(feature = "multithread-mm")]
+    pub fn multithread(threads: usize) -> tract_data::anyhow::Result<Executor> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|ix| format!("tract-mmm-{}", ix))
+            .build()?;
+        Ok(Executor::MultiThread(std::sync::Arc::new(pool)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic::GenericMmm4x4;
+    use crate::mmm::*;
+    use tract_data::anyhow;
+    use tract_data::internal::*;
+
+    fn operands() -> (Tensor, Tensor) {
+        let (m, k, n) = (13, 7, 11);
+        let a = Tensor::from(tract_ndarray::Array2::from_shape_fn((m, k), |(i, j)| {
+            (i * k + j) as f32 - 20.
+        }));
+        let b = Tensor::from(tract_ndarray::Array2::from_shape_fn((k, n), |(i, j)| {
+            (i as f32 - j as f32) / 4.
+        }));
+        (a, b)
+    }
+
+    fn run(executor: &Executor, a: &Tensor, b: &Tensor) -> anyhow::Result<Tensor> {
+        let (m, k, n) = (a.shape()[0], a.shape()[1], b.shape()[1]);
+        let op = MatMatMulImpl::<GenericMmm4x4<f32, f32, f32, f32>, f32>::new();
+        unsafe {
+            let mut packed_a = Tensor::uninitialized_aligned::<f32>(
+                &[op.a_pack(k).len(m)],
+                op.a_pack(k).alignment(),
+            )?;
+            op.a_pack(k).pack(packed_a.view_mut(), a.view(), 1, 0);
+            let mut packed_b = Tensor::uninitialized_aligned::<f32>(
+                &[op.b_pack(k).len(n)],
+                op.b_pack(k).alignment(),
+            )?;
+            op.b_pack(k).pack(packed_b.view_mut(), b.view(), 0, 1);
+            let mut c = Tensor::zero::<f32>(&[m, n])?;
+            let mut scratch = op.allocate_scratch_space();
+            op.run_with_executor(
+                executor,
+                m,
+                k,
+                n,
+                &mut *scratch,
+                &op.a_packed(4, k).wrap(&packed_a.view()),
+                &op.b_packed(4, k).wrap(&packed_b.view()),
+                &mut op.c_from_data_and_strides(4, n as isize, 1).wrap(&c.view_mut()),
+                &[],
+            )?;
+            Ok(c)
+        }
+    }
+
+    #[test]
+    fn single_thread_matches_ndarray() -> anyhow::Result<()> {
+        let (a, b) = operands();
+        let expected = a
+            .to_array_view::<f32>()?
+            .into_dimensionality::<tract_ndarray::Ix2>()?
+            .dot(&b.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix2>()?);
+        assert_eq!(run(&Executor::SingleThread, &a, &b)?, expected.into_tensor());
+        Ok(())
+    }
+
+    #[cfg(feature = "multithread-mm")]
+    #[test]
+    fn multithread_matches_single_thread() -> anyhow::Result<()> {
+        let (a, b) = operands();
+        let single = run(&Executor::SingleThread, &a, &b)?;
+        let multi = run(&Executor::multithread(3)?, &a, &b)?;
+        assert_eq!(single, multi);
+        Ok(())
+    }
+}
@@ -23,8 +23,16 @@ impl<'mb> ModelBuilder<'mb> {
     }
 
     fn translate(&mut self) -> TractResult<()> {
+        let mut symbols = HashMap::new();
         for ext in &self.proto_model.doc.extension {
             match &*ext[0] {
+                "tract_symbol" => {
+                    if ext.len() != 2 {
+                        bail!("Expected a symbol name, got {}", ext.join(" "))
+                    }
+                    let sym = self.model.symbol_table.sym(&ext[1]);
+                    symbols.insert(ext[1].to_string(), Value::Dim(sym.to_dim()));
+                }
                 "tract_registry" => {
                     if self.framework.registries.iter().any(|reg| reg.id == ext[1]) {
                         self.registries.push(ext[1].to_string())
@@ -35,7 +43,7 @@ impl<'mb> ModelBuilder<'mb> {
                 _ => warn!("Ignore unknown extension {}", ext.join(" ")),
             };
         }
-        self.scopes.push(HashMap::new());
+        self.scopes.push(symbols);
         self.wire_body(&self.proto_model.doc.graph_def.body)?;
         let vars = self.scopes.pop().unwrap();
 
@@ -1,17 +1,28 @@
 use crate::internal::*;
 use tract_core::ops;
 
+mod attention;
 mod broadcast;
 mod cast;
+mod cumsum;
 mod downsample;
 mod gather;
+mod gelu;
+mod ite;
+mod layer_norm;
+mod non_max_suppression;
 mod one_hot;
 mod qconv;
 mod qmatmul;
+mod range;
 mod reduce;
+mod reverse;
+mod roi;
 mod scan;
 mod scatter;
 mod source;
+mod topk;
+mod trilu;
 
 pub fn register(registry: &mut Registry) {
     registry.register_unit_element_wise("tract_core_tan", &ops::math::Tan {});
@@ -38,15 +49,26 @@ pub fn register(registry: &mut Registry) {
         &ops::math::ShiftRight,
         &ops::math::FlippedShiftRight,
     );
+    attention::register(registry);
     broadcast::register(registry);
     cast::register(registry);
+    cumsum::register(registry);
     downsample::register(registry);
     gather::register(registry);
+    gelu::register(registry);
+    ite::register(registry);
+    layer_norm::register(registry);
+    non_max_suppression::register(registry);
     one_hot::register(registry);
     qconv::register(registry);
     qmatmul::register(registry);
+    range::register(registry);
     reduce::register(registry);
+    reverse::register(registry);
+    roi::register(registry);
     scatter::register(registry);
     scan::register(registry);
     source::register(registry);
+    topk::register(registry);
+    trilu::register(registry);
 }
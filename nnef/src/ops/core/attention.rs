@@ -0,0 +1,55 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::ScaledDotProductAttention;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<ScaledDotProductAttention>(), ser_attention);
+    registry.register_primitive(
+        "tract_core_scaled_dot_product_attention",
+        &[
+            TypeName::Scalar.tensor().named("q"),
+            TypeName::Scalar.tensor().named("k"),
+            TypeName::Scalar.tensor().named("v"),
+            TypeName::Scalar.tensor().named("mask").default(0),
+            // zero stands for the default 1 / sqrt(D)
+            TypeName::Scalar.named("scale").default(0.0),
+            TypeName::Logical.named("causal").default(false),
+        ],
+        de_attention,
+    );
+}
+
+fn ser_attention(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ScaledDotProductAttention>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_scaled_dot_product_attention",
+        &inputs,
+        &[("scale", numeric(op.scale.unwrap_or(0.0))), ("causal", logical(op.causal))],
+    )))
+}
+
+fn de_attention(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let mut inputs: TVec<OutletId> = tvec!();
+    for name in &["q", "k", "v"] {
+        inputs.push(invocation.named_arg_as(builder, name)?);
+    }
+    let mask: OutletId = invocation.named_arg_as(builder, "mask")?;
+    let fact = builder.model.outlet_fact(mask)?;
+    let no_mask = fact.rank() == 0
+        && fact
+            .konst
+            .as_ref()
+            .map(|k| k.cast_to_scalar::<f32>().ok() == Some(0.0))
+            .unwrap_or(false);
+    if !no_mask {
+        inputs.push(mask);
+    }
+    let scale: f32 = invocation.named_arg_as(builder, "scale")?;
+    let scale = if scale == 0.0 { None } else { Some(scale) };
+    let causal = invocation.named_arg_as(builder, "causal")?;
+    builder.wire(ScaledDotProductAttention { scale, causal }, &inputs)
+}
@@ -0,0 +1,42 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::CumSum;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<CumSum>(), ser_cumsum);
+    registry.register_primitive(
+        "tract_core_cumsum",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("reverse").default(false),
+            TypeName::Logical.named("exclusive").default(false),
+        ],
+        de_cumsum,
+    );
+}
+
+fn ser_cumsum(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<CumSum>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_core_cumsum",
+        &[input],
+        &[
+            ("axis", numeric(op.axis)),
+            ("reverse", logical(op.reverse)),
+            ("exclusive", logical(op.exclusive)),
+        ],
+    )))
+}
+
+fn de_cumsum(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let reverse = invocation.named_arg_as(builder, "reverse")?;
+    let exclusive = invocation.named_arg_as(builder, "exclusive")?;
+    builder.wire(CumSum { axis, reverse, exclusive }, &[input])
+}
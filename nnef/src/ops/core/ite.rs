@@ -0,0 +1,130 @@
+use crate::deser::Value;
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::logic::IfThenElse;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<IfThenElse>(), ser_if_then_else);
+    registry.register_primitive(
+        "tract_core_if",
+        &[
+            TypeName::Logical.tensor().named("cond"),
+            TypeName::String.named("then_body"),
+            TypeName::Scalar.tensor().array().named("then_inputs"),
+            TypeName::String.named("else_body"),
+            TypeName::Scalar.tensor().array().named("else_inputs"),
+        ],
+        de_if_then_else,
+    );
+}
+
+fn ser_body(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    body: &TypedModel,
+    input_mapping: &[usize],
+) -> TractResult<(String, RValue)> {
+    let (mut fragment, body_tensors) = crate::ser::to_fragment_def(ast, body)?;
+    fragment.decl.id = format!("if_body_{}", ast.fragments.len());
+    let mut inputs = vec![];
+    for (ix, param) in fragment.decl.parameters.iter().enumerate() {
+        if let Some(slot) = input_mapping.get(ix) {
+            inputs.push(ast.mapping[&node.inputs[*slot]].as_ref().clone());
+        } else {
+            let tensor = body_tensors
+                .iter()
+                .find(|t| t.parameter_id == param.id)
+                .with_context(|| format!("Unbound body parameter {}", param.id))?;
+            inputs.push(ast.konst_variable(&tensor.label, &tensor.value)?.as_ref().clone());
+        }
+    }
+    let id = fragment.decl.id.clone();
+    ast.fragments.insert(id.clone(), fragment);
+    Ok((id, array(inputs)))
+}
+
+fn ser_if_then_else(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<IfThenElse>().unwrap();
+    let cond = ast.mapping[&node.inputs[0]].clone();
+    let (then_body, then_inputs) = ser_body(ast, node, &op.then_body, &op.then_input_mapping)?;
+    let (else_body, else_inputs) = ser_body(ast, node, &op.else_body, &op.else_input_mapping)?;
+    Ok(Some(invocation(
+        "tract_core_if",
+        &[cond],
+        &[
+            ("then_body", string(then_body)),
+            ("then_inputs", then_inputs),
+            ("else_body", string(else_body)),
+            ("else_inputs", else_inputs),
+        ],
+    )))
+}
+
+fn de_body(
+    builder: &mut ModelBuilder,
+    fragment_name: &str,
+    outer_inputs: &[OutletId],
+) -> TractResult<TypedModel> {
+    let fragment = builder
+        .proto_model
+        .doc
+        .fragments
+        .iter()
+        .find(|n| n.decl.id == fragment_name)
+        .ok_or_else(|| format_err!("Cound not find fragment `{}'", fragment_name))?;
+    if fragment.decl.parameters.len() != outer_inputs.len() {
+        bail!(
+            "Fragment `{}' expects {} inputs, got {}",
+            fragment_name,
+            fragment.decl.parameters.len(),
+            outer_inputs.len()
+        );
+    }
+    let mut body = ModelBuilder::new(builder.framework, builder.proto_model);
+    body.scopes.push(HashMap::new());
+    for (par, wire) in fragment.decl.parameters.iter().zip(outer_inputs.iter()) {
+        let fact = builder.model.outlet_fact(*wire)?;
+        let fact = TypedFact::dt_shape(fact.datum_type, fact.shape.clone());
+        body.scopes
+            .last_mut()
+            .unwrap()
+            .insert(par.id.clone(), Value::Wire(body.model.add_source(par.id.to_string(), fact)?));
+    }
+    body.wire_body(fragment.body.as_deref().unwrap())?;
+    let body_outputs = fragment
+        .decl
+        .results
+        .iter()
+        .map(|r| {
+            body.scopes
+                .last()
+                .unwrap()
+                .get(&r.id)
+                .with_context(|| format!("Could not find variable for if output named `{}'", r.id))?
+                .to::<OutletId>(builder)
+        })
+        .collect::<TractResult<Vec<OutletId>>>()?;
+    body.model.set_output_outlets(&body_outputs)?;
+    Ok(body.model)
+}
+
+fn de_if_then_else(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let cond: OutletId = invocation.named_arg_as(builder, "cond")?;
+    let then_name: String = invocation.named_arg_as(builder, "then_body")?;
+    let then_inputs: TVec<OutletId> = invocation.named_arg_as(builder, "then_inputs")?;
+    let else_name: String = invocation.named_arg_as(builder, "else_body")?;
+    let else_inputs: TVec<OutletId> = invocation.named_arg_as(builder, "else_inputs")?;
+    let then_body = de_body(builder, &then_name, &then_inputs)?;
+    let else_body = de_body(builder, &else_name, &else_inputs)?;
+    let then_input_mapping = (1..=then_inputs.len()).collect();
+    let else_input_mapping =
+        (then_inputs.len() + 1..=then_inputs.len() + else_inputs.len()).collect();
+    let op = IfThenElse { then_body, then_input_mapping, else_body, else_input_mapping };
+    let mut inputs: TVec<OutletId> = tvec!(cond);
+    inputs.extend(then_inputs.into_iter());
+    inputs.extend(else_inputs.into_iter());
+    builder.wire(op, &inputs)
+}
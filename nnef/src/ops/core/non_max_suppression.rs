@@ -0,0 +1,57 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::{BoxRepr, NonMaxSuppression};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<NonMaxSuppression>(), ser_nms);
+    registry.register_primitive(
+        "tract_core_non_max_suppression",
+        &[
+            TypeName::Scalar.tensor().named("boxes"),
+            TypeName::Scalar.tensor().named("scores"),
+            TypeName::Integer.tensor().named("max_output_boxes_per_class"),
+            TypeName::Scalar.tensor().named("iou_threshold"),
+            TypeName::Scalar.tensor().named("score_threshold"),
+            TypeName::Logical.named("center_point_box").default(false),
+            TypeName::Integer.named("num_selected_indices"),
+        ],
+        de_nms,
+    );
+}
+
+fn ser_nms(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<NonMaxSuppression>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    let num_selected_indices = ast.dim(&op.num_selected_indices_symbol.to_dim())?;
+    Ok(Some(invocation(
+        "tract_core_non_max_suppression",
+        &inputs,
+        &[
+            ("center_point_box", logical(op.box_repr == BoxRepr::CenterWidthHeight)),
+            ("num_selected_indices", num_selected_indices),
+        ],
+    )))
+}
+
+fn de_nms(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let mut inputs = tvec!();
+    for name in
+        &["boxes", "scores", "max_output_boxes_per_class", "iou_threshold", "score_threshold"]
+    {
+        inputs.push(invocation.named_arg_as(builder, name)?);
+    }
+    let box_repr = if invocation.named_arg_as(builder, "center_point_box")? {
+        BoxRepr::CenterWidthHeight
+    } else {
+        BoxRepr::TwoPoints
+    };
+    let num_selected_indices_symbol =
+        match invocation.named_arg_as::<TDim>(builder, "num_selected_indices")? {
+            TDim::Sym(s) => s,
+            other => bail!("Expected a symbol for num_selected_indices, got {}", other),
+        };
+    builder.wire(NonMaxSuppression { box_repr, num_selected_indices_symbol }, &inputs)
+}
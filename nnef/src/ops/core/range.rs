@@ -0,0 +1,46 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::Range;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Range>(), ser_range);
+    registry.register_primitive(
+        "tract_core_range",
+        &[
+            TypeName::Scalar.tensor().named("start"),
+            TypeName::Scalar.tensor().named("end"),
+            TypeName::Scalar.tensor().named("step"),
+            TypeName::Integer.named("len").default(0),
+        ],
+        de_range,
+    );
+}
+
+fn ser_range(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Range>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    // the length is recomputed from the inputs on load, only a fresh symbol
+    // needs to be kept
+    let len = if let TDim::Sym(_) = op.len { ast.dim(&op.len)? } else { numeric(0) };
+    Ok(Some(invocation("tract_core_range", &inputs, &[("len", len)])))
+}
+
+fn de_range(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let start = invocation.named_arg_as(builder, "start")?;
+    let end = invocation.named_arg_as(builder, "end")?;
+    let step = invocation.named_arg_as(builder, "step")?;
+    let fallback = match invocation.named_arg_as::<TDim>(builder, "len")? {
+        TDim::Sym(s) => s,
+        _ => builder.model.symbol_table.new_symbol("range"),
+    };
+    let len = Range::len_for_facts(
+        builder.model.outlet_fact(start)?,
+        builder.model.outlet_fact(end)?,
+        builder.model.outlet_fact(step)?,
+        fallback,
+    )?;
+    builder.wire(Range::new(len), &[start, end, step])
+}
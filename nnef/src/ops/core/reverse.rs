@@ -0,0 +1,59 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::{Reverse, ReverseSequence};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Reverse>(), ser_reverse);
+    registry.register_primitive(
+        "tract_core_reverse",
+        &[TypeName::Scalar.tensor().named("input"), TypeName::Integer.array().named("axes")],
+        de_reverse,
+    );
+    registry.register_dumper(TypeId::of::<ReverseSequence>(), ser_reverse_sequence);
+    registry.register_primitive(
+        "tract_core_reverse_sequence",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("seq_lens"),
+            TypeName::Integer.named("batch_axis"),
+            TypeName::Integer.named("time_axis"),
+        ],
+        de_reverse_sequence,
+    );
+}
+
+fn ser_reverse(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Reverse>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation("tract_core_reverse", &[input], &[("axes", ints(&op.axes))])))
+}
+
+fn de_reverse(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let axes: TVec<usize> = invocation.named_arg_as(builder, "axes")?;
+    builder.wire(Reverse { axes }, &[input])
+}
+
+fn ser_reverse_sequence(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ReverseSequence>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_reverse_sequence",
+        &inputs,
+        &[("batch_axis", numeric(op.batch_axis)), ("time_axis", numeric(op.time_axis))],
+    )))
+}
+
+fn de_reverse_sequence(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let seq_lens = invocation.named_arg_as(builder, "seq_lens")?;
+    let batch_axis = invocation.named_arg_as(builder, "batch_axis")?;
+    let time_axis = invocation.named_arg_as(builder, "time_axis")?;
+    builder.wire(ReverseSequence { batch_axis, time_axis }, &[input, seq_lens])
+}
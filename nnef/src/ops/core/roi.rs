@@ -0,0 +1,160 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<RoiAlign>(), ser_roi_align);
+    registry.register_primitive(
+        "tract_core_roi_align",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("rois"),
+            TypeName::Integer.tensor().named("batch_indices"),
+            TypeName::String.named("mode").default("avg"),
+            TypeName::Integer.named("output_height").default(1),
+            TypeName::Integer.named("output_width").default(1),
+            TypeName::Integer.named("sampling_ratio").default(0),
+            TypeName::Scalar.named("spatial_scale").default(1.0),
+            TypeName::Logical.named("half_pixel").default(true),
+        ],
+        de_roi_align,
+    );
+    registry.register_dumper(TypeId::of::<MaxRoiPool>(), ser_max_roi_pool);
+    registry.register_primitive(
+        "tract_core_max_roi_pool",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("rois"),
+            TypeName::Integer.named("pooled_height"),
+            TypeName::Integer.named("pooled_width"),
+            TypeName::Scalar.named("spatial_scale").default(1.0),
+        ],
+        de_max_roi_pool,
+    );
+    registry.register_dumper(TypeId::of::<CropAndResize>(), ser_crop_and_resize);
+    registry.register_primitive(
+        "tract_core_crop_and_resize",
+        &[
+            TypeName::Scalar.tensor().named("image"),
+            TypeName::Scalar.tensor().named("boxes"),
+            TypeName::Integer.tensor().named("box_indices"),
+            TypeName::String.named("method").default("bilinear"),
+            TypeName::Integer.named("crop_height"),
+            TypeName::Integer.named("crop_width"),
+            TypeName::Scalar.named("extrapolation_value").default(0.0),
+        ],
+        de_crop_and_resize,
+    );
+}
+
+fn ser_roi_align(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<RoiAlign>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    let mode = match op.mode {
+        RoiAlignMode::Avg => "avg",
+        RoiAlignMode::Max => "max",
+    };
+    Ok(Some(invocation(
+        "tract_core_roi_align",
+        &inputs,
+        &[
+            ("mode", string(mode)),
+            ("output_height", numeric(op.output_height)),
+            ("output_width", numeric(op.output_width)),
+            ("sampling_ratio", numeric(op.sampling_ratio)),
+            ("spatial_scale", numeric(op.spatial_scale)),
+            ("half_pixel", logical(op.half_pixel)),
+        ],
+    )))
+}
+
+fn de_roi_align(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let rois = invocation.named_arg_as(builder, "rois")?;
+    let batch_indices = invocation.named_arg_as(builder, "batch_indices")?;
+    let mode = match &*invocation.named_arg_as::<String>(builder, "mode")? {
+        "avg" => RoiAlignMode::Avg,
+        "max" => RoiAlignMode::Max,
+        other => bail!("Unsupported roi align mode {}", other),
+    };
+    let op = RoiAlign {
+        mode,
+        output_height: invocation.named_arg_as(builder, "output_height")?,
+        output_width: invocation.named_arg_as(builder, "output_width")?,
+        sampling_ratio: invocation.named_arg_as(builder, "sampling_ratio")?,
+        spatial_scale: invocation.named_arg_as(builder, "spatial_scale")?,
+        half_pixel: invocation.named_arg_as(builder, "half_pixel")?,
+    };
+    builder.wire(op, &[input, rois, batch_indices])
+}
+
+fn ser_max_roi_pool(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<MaxRoiPool>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_max_roi_pool",
+        &inputs,
+        &[
+            ("pooled_height", numeric(op.pooled_height)),
+            ("pooled_width", numeric(op.pooled_width)),
+            ("spatial_scale", numeric(op.spatial_scale)),
+        ],
+    )))
+}
+
+fn de_max_roi_pool(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let rois = invocation.named_arg_as(builder, "rois")?;
+    let op = MaxRoiPool {
+        pooled_height: invocation.named_arg_as(builder, "pooled_height")?,
+        pooled_width: invocation.named_arg_as(builder, "pooled_width")?,
+        spatial_scale: invocation.named_arg_as(builder, "spatial_scale")?,
+    };
+    builder.wire(op, &[input, rois])
+}
+
+fn ser_crop_and_resize(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<CropAndResize>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    let method = match op.method {
+        CropAndResizeMethod::Bilinear => "bilinear",
+        CropAndResizeMethod::Nearest => "nearest",
+    };
+    Ok(Some(invocation(
+        "tract_core_crop_and_resize",
+        &inputs,
+        &[
+            ("method", string(method)),
+            ("crop_height", numeric(op.crop_height)),
+            ("crop_width", numeric(op.crop_width)),
+            ("extrapolation_value", numeric(op.extrapolation_value)),
+        ],
+    )))
+}
+
+fn de_crop_and_resize(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let image = invocation.named_arg_as(builder, "image")?;
+    let boxes = invocation.named_arg_as(builder, "boxes")?;
+    let box_indices = invocation.named_arg_as(builder, "box_indices")?;
+    let method = match &*invocation.named_arg_as::<String>(builder, "method")? {
+        "bilinear" => CropAndResizeMethod::Bilinear,
+        "nearest" => CropAndResizeMethod::Nearest,
+        other => bail!("Unsupported crop and resize method {}", other),
+    };
+    let op = CropAndResize {
+        method,
+        crop_height: invocation.named_arg_as(builder, "crop_height")?,
+        crop_width: invocation.named_arg_as(builder, "crop_width")?,
+        extrapolation_value: invocation.named_arg_as(builder, "extrapolation_value")?,
+    };
+    builder.wire(op, &[image, boxes, box_indices])
+}
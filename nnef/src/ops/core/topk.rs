@@ -0,0 +1,49 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::Topk;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Topk>(), ser_topk);
+    registry.register_primitive(
+        "tract_core_topk",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("k"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("largest"),
+            TypeName::Logical.named("sorted").default(true),
+            TypeName::Integer.named("fallback_k"),
+        ],
+        de_topk,
+    );
+}
+
+fn ser_topk(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Topk>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let k = ast.mapping[&node.inputs[1]].clone();
+    let fallback_k = ast.dim(&op.fallback_k)?;
+    Ok(Some(invocation(
+        "tract_core_topk",
+        &[input, k],
+        &[
+            ("axis", numeric(op.axis)),
+            ("largest", logical(op.largest)),
+            ("sorted", logical(op.sorted)),
+            ("fallback_k", fallback_k),
+        ],
+    )))
+}
+
+fn de_topk(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let largest = invocation.named_arg_as(builder, "largest")?;
+    let sorted = invocation.named_arg_as(builder, "sorted")?;
+    let fallback_k = invocation.named_arg_as::<TDim>(builder, "fallback_k")?;
+    builder.wire(Topk { axis, largest, sorted, fallback_k }, &[input, k])
+}
@@ -0,0 +1,32 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::Trilu;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Trilu>(), ser_trilu);
+    registry.register_primitive(
+        "tract_core_trilu",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("k"),
+            TypeName::Logical.named("upper").default(true),
+        ],
+        de_trilu,
+    );
+}
+
+fn ser_trilu(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Trilu>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation("tract_core_trilu", &inputs, &[("upper", logical(op.upper))])))
+}
+
+fn de_trilu(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let upper = invocation.named_arg_as(builder, "upper")?;
+    builder.wire(Trilu { upper }, &[input, k])
+}
@@ -5,16 +5,18 @@ use tract_core::ops::cnn::PoolSpec;
 use tract_core::ops::nn::DataFormat;
 
 pub fn source(
-    _ast: &mut IntoAst,
+    ast: &mut IntoAst,
     _node: &TypedNode,
     op: &ops::source::TypedSource,
 ) -> TractResult<Option<Arc<RValue>>> {
     if op.fact.datum_type == DatumType::F32 {
-        Ok(Some(invocation(
-            "external",
-            &[],
-            &[("shape", ints(&*op.fact.shape.as_concrete().unwrap()))],
-        )))
+        let shape = op
+            .fact
+            .shape
+            .iter()
+            .map(|d| ast.dim(&d))
+            .collect::<TractResult<Vec<_>>>()?;
+        Ok(Some(invocation("external", &[], &[("shape", array(shape))])))
     } else {
         Ok(None)
     }
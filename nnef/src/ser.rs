@@ -31,6 +31,7 @@ pub struct IntoAst<'a> {
     pub quantization: HashMap<String, QuantFormat>,
     pub fragments: HashMap<String, FragmentDef>,
     pub body: Vec<Assignment>,
+    pub symbols: Vec<Symbol>,
 }
 
 pub struct RequiredTensorParameter {
@@ -54,6 +55,7 @@ impl<'a> IntoAst<'a> {
             quantization: Default::default(),
             fragments: Default::default(),
             body: Default::default(),
+            symbols: Default::default(),
             parent: None,
         }
     }
@@ -180,6 +182,9 @@ impl<'a> IntoAst<'a> {
             id = "_".to_string() + &id;
         }
         let mut extension = vec![];
+        for sym in &self.symbols {
+            extension.push(vec!["tract_symbol".to_string(), sym.name().to_string()]);
+        }
         self.registries.sort();
         for reg in self.registries {
             if reg != "tract_nnef" {
@@ -294,6 +299,20 @@ impl<'a> IntoAst<'a> {
         }
     }
 
+    /// Dimension as an integer literal or a symbol identifier, declaring the symbol if needed.
+    pub fn dim(&mut self, dim: &TDim) -> TractResult<RValue> {
+        match dim {
+            TDim::Val(v) => Ok(numeric(v)),
+            TDim::Sym(s) => {
+                if !self.symbols.contains(s) {
+                    self.symbols.push(s.clone());
+                }
+                Ok(ident(s.name()))
+            }
+            _ => bail!("Can not serialize dimension {}", dim),
+        }
+    }
+
     pub fn konst(
         &mut self,
         name: impl Into<String>,
@@ -61,6 +61,7 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
             (0, 4, 16) => DatumType::I16,
             (0, 4, 32) => DatumType::I32,
             (0, 4, 64) => DatumType::I64,
+            (TRACT_ITEM_TYPE_VENDOR, 0, 16) => DatumType::BF16,
             (TRACT_ITEM_TYPE_VENDOR, 0x1000, 0xFFFF) => DatumType::String,
             _ => bail!(
                 "Unsupported type in tensor type:{} bits_per_item:{}",
@@ -103,7 +104,11 @@ pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResul
         }
         header.data_size_bytes = (tensor.len() * tensor.datum_type().size_of()) as u32;
         header.bits_per_item = (tensor.datum_type().size_of() * 8) as u32;
-        header.item_type = if tensor.datum_type().is_float() {
+        header.item_type = if tensor.datum_type() == DatumType::BF16 {
+            // bfloat16 is not in the NNEF spec: flag it as a vendor float
+            header.item_type_vendor = TRACT_ITEM_TYPE_VENDOR;
+            0
+        } else if tensor.datum_type().is_float() {
             0
         } else if tensor.datum_type().is_signed() {
             4
@@ -138,4 +143,14 @@ mod test {
     fn header_is_128_bytes() {
         assert_eq!(std::mem::size_of::<Header>(), 128);
     }
+
+    #[test]
+    fn bf16_roundtrip() -> TractResult<()> {
+        let tensor = tensor1(&[1f32, -2.5, 0.125]).cast_to_dt(DatumType::BF16)?.into_owned();
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &tensor)?;
+        let read = read_tensor(&*buffer)?;
+        assert_eq!(read, tensor);
+        Ok(())
+    }
 }
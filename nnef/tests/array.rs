@@ -0,0 +1,79 @@
+use tract_core::ops::array::{CumSum, Range, Reverse, ReverseSequence, Topk, Trilu};
+use tract_nnef::internal::*;
+
+fn round_trip(model: &TypedModel) -> TractResult<TypedModel> {
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(model, &mut buffer)?;
+    nnef.model_for_read(&mut &*buffer)
+}
+
+#[test]
+fn range_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let start = model.add_const("start", rctensor0(1i64))?;
+    let end = model.add_source("end", TypedFact::dt_scalar(i64::datum_type()))?;
+    let step = model.add_const("step", rctensor0(2i64))?;
+    let facts = [start, end, step]
+        .iter()
+        .map(|o| model.outlet_fact(*o).map(|f| f.clone()))
+        .collect::<TractResult<TVec<_>>>()?;
+    let len = Range::len_for_facts(
+        &facts[0],
+        &facts[1],
+        &facts[2],
+        model.symbol_table.new_symbol("len"),
+    )?;
+    let range = model.wire_node("range", Range::new(len), &[start, end, step])?;
+    model.set_output_outlets(&range)?;
+
+    let reloaded = round_trip(&model)?;
+    let len = reloaded.symbol_table.get("len").unwrap();
+    let fact = reloaded.outlet_fact(reloaded.output_outlets()?[0])?;
+    assert_eq!(fact.shape.to_tvec(), tvec!(len.to_dim()));
+    let result = reloaded.into_runnable()?.run(tvec!(tensor0(8i64)))?;
+    assert_eq!(*result[0], tensor1(&[1i64, 3, 5, 7]));
+    Ok(())
+}
+
+#[test]
+fn topk_with_symbolic_k_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[5]))?;
+    let k = model.add_source("count", TypedFact::dt_scalar(i64::datum_type()))?;
+    let fallback_k = model.symbol_table.new_symbol("k").to_dim();
+    let topk = model.wire_node("topk", Topk::new(0, true, true, fallback_k), &[x, k])?;
+    model.set_output_outlets(&topk)?;
+
+    let reloaded = round_trip(&model)?;
+    let k = reloaded.symbol_table.get("k").unwrap();
+    let fact = reloaded.outlet_fact(reloaded.output_outlets()?[0])?;
+    assert_eq!(fact.shape.to_tvec(), tvec!(k.to_dim()));
+    let input = tensor1(&[3f32, 1.0, 4.0, 1.0, 5.0]);
+    let result = reloaded.into_runnable()?.run(tvec!(input, tensor0(2i64)))?;
+    assert_eq!(*result[0], tensor1(&[5f32, 4.0]));
+    assert_eq!(*result[1], tensor1(&[4i64, 2]));
+    Ok(())
+}
+
+#[test]
+fn array_ops_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[3, 3]))?;
+    let seq_lens = model.add_const("seq_lens", rctensor1(&[1i64, 2, 3]))?;
+    let k = model.add_const("k", rctensor0(0i64))?;
+    let wire = model.wire_node("cumsum", CumSum::new(1, true, false), &[x])?[0];
+    let wire = model.wire_node("reverse", Reverse::new(tvec!(0)), &[wire])?[0];
+    let wire = model.wire_node("trilu", Trilu::new(false), &[wire, k])?[0];
+    let wire =
+        model.wire_node("reverse_sequence", ReverseSequence::new(0, 1), &[wire, seq_lens])?;
+    model.set_output_outlets(&wire)?;
+
+    let reloaded = round_trip(&model)?;
+    let input = tensor2(&[[1f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    let expected = model.into_runnable()?.run(tvec!(input.clone()))?;
+    let found = reloaded.into_runnable()?.run(tvec!(input))?;
+    assert_eq!(found[0], expected[0]);
+    assert_eq!(*found[0], tensor2(&[[24f32, 0.0, 0.0], [11.0, 15.0, 0.0], [3.0, 5.0, 6.0]]));
+    Ok(())
+}
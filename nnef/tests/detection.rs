@@ -0,0 +1,31 @@
+use tract_core::ops::nn::{BoxRepr, NonMaxSuppression};
+use tract_nnef::internal::*;
+
+#[test]
+fn non_max_suppression_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let boxes = model.add_source("boxes", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 4]))?;
+    let scores = model.add_source("scores", TypedFact::dt_shape(f32::datum_type(), &[1, 1, 3]))?;
+    let max_output = model.add_const("max_output", rctensor0(2i64))?;
+    let iou_threshold = model.add_const("iou_threshold", rctensor0(0.5f32))?;
+    let score_threshold = model.add_const("score_threshold", rctensor0(0f32))?;
+    let op = NonMaxSuppression::new(BoxRepr::TwoPoints, model.symbol_table.new_symbol("selected"));
+    let selected =
+        model.wire_node("nms", op, &[boxes, scores, max_output, iou_threshold, score_threshold])?;
+    model.set_output_outlets(&selected)?;
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer)?;
+    let reloaded = nnef.model_for_read(&mut &*buffer)?;
+
+    let selected = reloaded.symbol_table.get("selected").unwrap();
+    let fact = reloaded.outlet_fact(reloaded.output_outlets()?[0])?;
+    assert_eq!(fact.shape.to_tvec(), tvec!(selected.to_dim(), 3.to_dim()));
+
+    let boxes = tensor3(&[[[0f32, 0.0, 1.0, 1.0], [0.0, 0.1, 1.0, 1.1], [0.0, 2.0, 1.0, 3.0]]]);
+    let scores = tensor3(&[[[0.9f32, 0.8, 0.7]]]);
+    let result = reloaded.into_runnable()?.run(tvec!(boxes, scores))?;
+    assert_eq!(*result[0], tensor2(&[[0i64, 0, 0], [0, 0, 2]]));
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use tract_core::ops::logic::IfThenElse;
+use tract_core::ops::math;
+use tract_nnef::internal::*;
+
+fn round_trip(model: &TypedModel) -> TractResult<TypedModel> {
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(model, &mut buffer)?;
+    nnef.model_for_read(&mut &*buffer)
+}
+
+fn branch(op: Box<dyn TypedOp>) -> TractResult<TypedModel> {
+    let mut body = TypedModel::default();
+    let a = body.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+    let b = body.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+    let c = body.add_const("c", tensor1(&[10f32, 20.0]))?;
+    let wire = body.wire_node("op", op, &[a, b])?;
+    let wire = body.wire_node("offset", math::add::bin_typed(), &[wire[0], c])?;
+    body.set_output_outlets(&wire)?;
+    Ok(body)
+}
+
+#[test]
+fn if_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let cond = model.add_source("cond", TypedFact::dt_shape(bool::datum_type(), &[1]))?;
+    let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+    let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+    let op = IfThenElse::new(
+        branch(Box::new(math::add::bin_typed()))?,
+        vec![1, 2],
+        branch(Box::new(math::mul::bin_typed()))?,
+        vec![2, 1],
+    );
+    let ite = model.wire_node("ite", op, &[cond, a, b])?;
+    model.set_output_outlets(&ite)?;
+
+    let reloaded = round_trip(&model)?;
+    assert!(reloaded.nodes().iter().any(|n| n.op_is::<IfThenElse>()));
+    let plan = SimplePlan::new(reloaded)?;
+    for (cond, expected) in &[(true, [14f32, 26.0]), (false, [13f32, 28.0])] {
+        let inputs = tvec!(tensor1(&[*cond]), tensor1(&[1f32, 2.0]), tensor1(&[3f32, 4.0]));
+        let found = plan.run(inputs.clone())?;
+        let expected_ref = model.clone().into_runnable()?.run(inputs)?;
+        assert_eq!(*found[0], tensor1(expected));
+        assert_eq!(found[0], expected_ref[0]);
+    }
+    Ok(())
+}
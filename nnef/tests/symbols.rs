@@ -0,0 +1,57 @@
+use tract_core::ops::array::Range;
+use tract_nnef::internal::*;
+
+#[test]
+fn symbolic_external_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let batch = model.sym("batch_size");
+    let shape = [batch.to_dim(), 3.to_dim()];
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+    model.set_output_outlets(&[input])?;
+
+    let nnef = tract_nnef::nnef();
+    let proto = tract_nnef::ser::to_proto_model(&nnef, &model)?;
+    assert!(proto.doc.extension.contains(&vec!["tract_symbol".to_string(), "batch_size".into()]));
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer)?;
+    let reloaded = nnef.model_for_read(&mut &*buffer)?;
+
+    let batch = reloaded.symbol_table.get("batch_size").unwrap();
+    let fact = reloaded.outlet_fact(reloaded.input_outlets()?[0])?;
+    assert_eq!(fact.shape.to_tvec(), tvec!(batch.to_dim(), 3.to_dim()));
+    Ok(())
+}
+
+#[test]
+fn fresh_symbols_stay_distinct_on_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let start = model.add_const("start", rctensor0(0i64))?;
+    let step = model.add_const("step", rctensor0(1i64))?;
+    let mut outputs = tvec!();
+    for name in &["a", "b"] {
+        let end = model.add_source(*name, TypedFact::dt_scalar(i64::datum_type()))?;
+        let len = model.symbol_table.new_symbol("len").to_dim();
+        let range =
+            model.wire_node(format!("range_{}", name), Range::new(len), &[start, end, step])?;
+        outputs.push(range[0]);
+    }
+    model.set_output_outlets(&outputs)?;
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer)?;
+    let reloaded = nnef.model_for_read(&mut &*buffer)?;
+
+    let len = reloaded.symbol_table.get("len").unwrap();
+    let len_1 = reloaded.symbol_table.get("len_1").unwrap();
+    assert_ne!(len, len_1);
+    assert_eq!(
+        reloaded.outlet_fact(reloaded.output_outlets()?[0])?.shape.to_tvec(),
+        tvec!(len.to_dim())
+    );
+    assert_eq!(
+        reloaded.outlet_fact(reloaded.output_outlets()?[1])?.shape.to_tvec(),
+        tvec!(len_1.to_dim())
+    );
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use tract_core::ops::nn::{gelu, LayerNorm, ScaledDotProductAttention};
+use tract_nnef::internal::*;
+
+#[test]
+fn transformer_ops_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 4]))?;
+    let normed = model.wire_node("norm", LayerNorm::new(tvec!(2), 1e-5), &[x])?;
+    let activated = model.wire_node("gelu", gelu(true), &normed)?;
+    let mask = model.add_const("mask", tensor2(&[[0f32, -1e9, 0.0]]))?;
+    let attention = model.wire_node(
+        "attention",
+        ScaledDotProductAttention::new(None, true),
+        &[activated[0], activated[0], x, mask],
+    )?;
+    model.set_output_outlets(&attention)?;
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer)?;
+    let reloaded = nnef.model_for_read(&mut &*buffer)?;
+    assert_eq!(reloaded.nodes().len(), model.nodes().len());
+
+    let input = tensor3(&[[[1f32, 2.0, 3.0, 4.0], [0.0, -1.0, 5.0, 2.0], [1.0, 1.0, 0.0, 0.0]]]);
+    let expected = model.into_runnable()?.run(tvec!(input.clone()))?;
+    let found = reloaded.into_runnable()?.run(tvec!(input))?;
+    found[0].close_enough(&expected[0], true)?;
+    Ok(())
+}
@@ -1,31 +1,43 @@
 use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::math::erf_f32;
+use tract_nnef::tract_core::ops::nn::declutter_decomposed_gelu;
 
 tract_core::element_wise!(erf, Erf,
     [f32] => |_, xs| {
         xs.iter_mut().for_each(|x| *x = erf_f32(*x));
         Ok(())
     };
+    declutter: declutter_decomposed_gelu;
     prefix: "onnx."
 );
 
-#[allow(non_upper_case_globals)]
-fn erf_f32(x: f32) -> f32 {
-    const a1: f32 = 0.0705230784;
-    const a2: f32 = 0.0422820123;
-    const a3: f32 = 0.0092705272;
-    const a4: f32 = 0.0001520143;
-    const a5: f32 = 0.0002765672;
-    const a6: f32 = 0.0000430638;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ops::math;
 
-    let signum = x.signum();
-    let x = x.abs();
-    let y = a6 * x;
-    let y = (a5 + y) * x;
-    let y = (a4 + y) * x;
-    let y = (a3 + y) * x;
-    let y = (a2 + y) * x;
-    let y = (a1 + y) * x;
-    let y = 1.0 - (y + 1.0).powi(16).recip();
+    #[test]
+    fn fuse_decomposed_gelu() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[4]))?;
+        let sqrt_2 = model.add_const("sqrt_2", tensor1(&[2f32.sqrt()]))?;
+        let scaled = model.wire_node("scaled", math::div::bin_typed(), &[x, sqrt_2])?;
+        let erf = model.wire_node("erf", erf(), &scaled)?;
+        let one = model.add_const("one", tensor1(&[1f32]))?;
+        let plus_one = model.wire_node("plus_one", math::add::bin_typed(), &[erf[0], one])?;
+        let mul = model.wire_node("mul", math::mul::bin_typed(), &[x, plus_one[0]])?;
+        let half = model.add_const("half", tensor1(&[0.5f32]))?;
+        let output = model.wire_node("output", math::mul::bin_typed(), &[mul[0], half])?;
+        model.set_output_outlets(&output)?;
 
-    y.copysign(signum)
+        let input = tensor1(&[-1f32, 0.0, 1.0, 3.0]);
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let decluttered = model.declutter()?;
+        assert_eq!(decluttered.nodes().len(), 2);
+        let gelu = decluttered.nodes()[1].op_as::<tract_core::ops::element_wise::ElementWiseOp>();
+        assert!(gelu.unwrap().0.is::<tract_core::ops::nn::Gelu>());
+        let found = decluttered.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], true)?;
+        Ok(())
+    }
 }
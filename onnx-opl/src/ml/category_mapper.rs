@@ -253,8 +253,8 @@ fn load_reverse_lookup(
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
     let input = invocation.named_arg_as(builder, "input")?;
-    let keys: isize = invocation.named_arg_as(builder, "keys")?;
-    let fallback_value = invocation.named_arg_as(builder, "fallback")?;
-    let op = ReverseLookup::new(fallback_value, keys as i32)?;
+    let keys = invocation.named_arg_as(builder, "keys")?;
+    let fallback_value: isize = invocation.named_arg_as(builder, "fallback")?;
+    let op = ReverseLookup::new(keys, fallback_value as i32)?;
     builder.wire(op, &[input])
 }
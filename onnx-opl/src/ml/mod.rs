@@ -1,12 +1,15 @@
 use tract_nnef::internal::*;
 
 pub mod category_mapper;
+pub mod svm;
 pub mod tree;
 pub mod tree_ensemble_classifier;
 
 pub use category_mapper::{DirectLookup, ReverseLookup};
+pub use svm::{Kernel, KernelType, SvmClassifier, SvmRegressor};
 
 pub fn register(registry: &mut Registry) {
     category_mapper::register(registry);
+    svm::register(registry);
     tree_ensemble_classifier::register(registry);
 }
@@ -0,0 +1,536 @@
+use tract_nnef::internal::*;
+
+use tract_ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_onnx_ml_svm_classifier",
+        &parameters_classifier(),
+        load_classifier,
+    );
+    registry.register_dumper(TypeId::of::<SvmClassifier>(), dump_classifier);
+    registry.register_primitive(
+        "tract_onnx_ml_svm_regressor",
+        &parameters_regressor(),
+        load_regressor,
+    );
+    registry.register_dumper(TypeId::of::<SvmRegressor>(), dump_regressor);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KernelType {
+    Linear,
+    Poly,
+    Rbf,
+    Sigmoid,
+}
+
+impl KernelType {
+    pub fn parse(s: &str) -> TractResult<KernelType> {
+        match s {
+            "LINEAR" => Ok(KernelType::Linear),
+            "POLY" => Ok(KernelType::Poly),
+            "RBF" => Ok(KernelType::Rbf),
+            "SIGMOID" => Ok(KernelType::Sigmoid),
+            _ => bail!("Invalid kernel type: {}", s),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KernelType::Linear => "LINEAR",
+            KernelType::Poly => "POLY",
+            KernelType::Rbf => "RBF",
+            KernelType::Sigmoid => "SIGMOID",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct Kernel {
+    pub kernel_type: KernelType,
+    #[educe(Hash(method = "hash_f32"))]
+    pub gamma: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub coef0: f32,
+    pub degree: i32,
+}
+
+impl Kernel {
+    pub fn compute(&self, x: &ArrayView1<f32>, v: &ArrayView1<f32>) -> f32 {
+        match self.kernel_type {
+            KernelType::Linear => x.dot(v),
+            KernelType::Poly => (self.gamma * x.dot(v) + self.coef0).powi(self.degree),
+            KernelType::Rbf => {
+                let d2: f32 = x.iter().zip(v.iter()).map(|(x, v)| (x - v) * (x - v)).sum();
+                (-self.gamma * d2).exp()
+            }
+            KernelType::Sigmoid => (self.gamma * x.dot(v) + self.coef0).tanh(),
+        }
+    }
+
+    /// Kernel values of every input row against every vector: [N, V].
+    fn compute_all(&self, input: &ArrayView2<f32>, vectors: &ArrayView2<f32>) -> Array2<f32> {
+        Array2::from_shape_fn((input.nrows(), vectors.nrows()), |(n, v)| {
+            self.compute(&input.row(n), &vectors.row(v))
+        })
+    }
+}
+
+fn input_matrix(input: &Tensor) -> TractResult<Array2<f32>> {
+    let input = input.cast_to::<f32>()?;
+    let input = input.to_array_view::<f32>()?;
+    let rows = if input.ndim() == 1 { 1 } else { input.shape()[0] };
+    Ok(input.to_shape((rows, input.len() / rows.max(1)))?.to_owned())
+}
+
+/// Support vector classifier.
+///
+/// In "linear" mode (no vectors per class), `vectors` holds one row of
+/// coefficients per class, and the scores are the kernel of the input against
+/// them, plus `rho[0]`. Otherwise, `vectors` holds the support vectors, and
+/// classes are voted one-vs-one. Scores are the decision values of each pair,
+/// or the class probabilities if the Platt coefficients are given.
+///
+/// Outputs the winning class index (i32, [N]) and the scores (f32, [N, _]).
+#[derive(Clone, Debug, Hash)]
+pub struct SvmClassifier {
+    pub kernel: Kernel,
+    pub n_classes: usize,
+    pub vectors: Arc<Tensor>,
+    pub vectors_per_class: Option<TVec<usize>>,
+    pub coefficients: Arc<Tensor>,
+    pub rho: Arc<Tensor>,
+    pub probabilities: Option<(Arc<Tensor>, Arc<Tensor>)>,
+}
+
+impl_dyn_hash!(SvmClassifier);
+
+impl SvmClassifier {
+    pub fn n_scores(&self) -> usize {
+        if self.vectors_per_class.is_none() || self.probabilities.is_some() {
+            self.n_classes
+        } else {
+            self.n_classes * (self.n_classes - 1) / 2
+        }
+    }
+
+    fn eval_linear(&self, input: &ArrayView2<f32>) -> TractResult<(Vec<i32>, Array2<f32>)> {
+        let rho = self.rho.as_slice::<f32>()?[0];
+        let vectors = self.vectors.to_array_view::<f32>()?.into_dimensionality()?;
+        let scores = self.kernel.compute_all(input, &vectors) + rho;
+        let labels = if self.n_classes == 2 && vectors.nrows() == 1 {
+            scores.column(0).iter().map(|&s| (s > 0.0) as i32).collect()
+        } else {
+            scores.outer_iter().map(|row| argmax(row.iter().copied())).collect()
+        };
+        Ok((labels, scores))
+    }
+
+    fn eval_svc(
+        &self,
+        input: &ArrayView2<f32>,
+        vectors_per_class: &[usize],
+    ) -> TractResult<(Vec<i32>, Array2<f32>)> {
+        let vectors = self.vectors.to_array_view::<f32>()?.into_dimensionality()?;
+        let coefficients = self
+            .coefficients
+            .to_array_view::<f32>()?
+            .into_dimensionality::<tract_ndarray::Ix2>()?;
+        let rho = self.rho.as_slice::<f32>()?;
+        let kernels = self.kernel.compute_all(input, &vectors);
+        let starts: Vec<usize> = vectors_per_class
+            .iter()
+            .scan(0, |start, &n| {
+                *start += n;
+                Some(*start - n)
+            })
+            .collect();
+        let n = self.n_classes;
+        let mut labels = vec![];
+        let mut scores = Array2::zeros((input.nrows(), self.n_scores()));
+        for (ix, kernels) in kernels.outer_iter().enumerate() {
+            let mut votes = vec![0usize; n];
+            let mut decisions = vec![];
+            let mut pair = 0;
+            for i in 0..n {
+                for j in i + 1..n {
+                    let si = starts[i]..starts[i] + vectors_per_class[i];
+                    let sj = starts[j]..starts[j] + vectors_per_class[j];
+                    let sum: f32 = si.map(|v| coefficients[(j - 1, v)] * kernels[v]).sum::<f32>()
+                        + sj.map(|v| coefficients[(i, v)] * kernels[v]).sum::<f32>()
+                        + rho[pair];
+                    decisions.push(sum);
+                    votes[if sum > 0.0 { i } else { j }] += 1;
+                    pair += 1;
+                }
+            }
+            if let Some((prob_a, prob_b)) = &self.probabilities {
+                let probs = self.probabilities(&decisions, prob_a.as_slice()?, prob_b.as_slice()?);
+                labels.push(argmax(probs.iter().copied()));
+                scores.row_mut(ix).iter_mut().zip(probs.iter()).for_each(|(s, p)| *s = *p);
+            } else {
+                labels.push(argmax(votes.iter().map(|&v| v as f32)));
+                scores.row_mut(ix).iter_mut().zip(decisions.iter()).for_each(|(s, d)| *s = *d);
+            }
+        }
+        Ok((labels, scores))
+    }
+
+    /// Class probabilities from the pairwise decision values (Platt scaling
+    /// and pairwise coupling, as in libsvm).
+    fn probabilities(&self, decisions: &[f32], prob_a: &[f32], prob_b: &[f32]) -> Vec<f32> {
+        let n = self.n_classes;
+        let mut r = vec![0f32; n * n];
+        let mut pair = 0;
+        for i in 0..n {
+            for j in i + 1..n {
+                let p = 1.0 / (1.0 + (decisions[pair] * prob_a[pair] + prob_b[pair]).exp());
+                let p = p.max(1e-7).min(1.0 - 1e-7);
+                r[i * n + j] = p;
+                r[j * n + i] = 1.0 - p;
+                pair += 1;
+            }
+        }
+        let mut q = vec![0f32; n * n];
+        for i in 0..n {
+            for j in 0..i {
+                q[i * n + i] += r[j * n + i] * r[j * n + i];
+                q[i * n + j] = q[j * n + i];
+            }
+            for j in i + 1..n {
+                q[i * n + i] += r[j * n + i] * r[j * n + i];
+                q[i * n + j] = -r[j * n + i] * r[i * n + j];
+            }
+        }
+        let mut p = vec![1.0 / n as f32; n];
+        let mut qp = vec![0f32; n];
+        let eps = 0.005 / n as f32;
+        for _ in 0..100 {
+            let mut pqp = 0.0;
+            for i in 0..n {
+                qp[i] = (0..n).map(|j| q[i * n + j] * p[j]).sum();
+                pqp += p[i] * qp[i];
+            }
+            if qp.iter().all(|qp| (qp - pqp).abs() < eps) {
+                break;
+            }
+            for i in 0..n {
+                let diff = (pqp - qp[i]) / q[i * n + i];
+                p[i] += diff;
+                pqp = (pqp + diff * (diff * q[i * n + i] + 2.0 * qp[i]))
+                    / (1.0 + diff)
+                    / (1.0 + diff);
+                for j in 0..n {
+                    qp[j] = (qp[j] + diff * q[i * n + j]) / (1.0 + diff);
+                    p[j] /= 1.0 + diff;
+                }
+            }
+        }
+        p
+    }
+}
+
+fn argmax(it: impl Iterator<Item = f32>) -> i32 {
+    let mut best = (0, f32::MIN);
+    for (ix, v) in it.enumerate() {
+        if v > best.1 {
+            best = (ix, v);
+        }
+    }
+    best.0 as i32
+}
+
+impl Op for SvmClassifier {
+    fn name(&self) -> Cow<str> {
+        "SvmClassifier".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for SvmClassifier {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input_matrix(&input)?;
+        let (labels, scores) = if let Some(vectors_per_class) = &self.vectors_per_class {
+            self.eval_svc(&input.view(), vectors_per_class)?
+        } else {
+            self.eval_linear(&input.view())?
+        };
+        Ok(tvec!(rctensor1(&labels), scores.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SvmClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(
+            TypedFact::dt_shape(i32::datum_type(), &[n.clone()]),
+            TypedFact::dt_shape(f32::datum_type(), &[n.clone(), self.n_scores().into()])
+        ))
+    }
+
+    as_op!();
+}
+
+/// Support vector regressor: the sum of the `coefficients`, weighted by the
+/// kernel of the input against `vectors`, plus `rho`.
+///
+/// In one class mode, outputs the sign of the score.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct SvmRegressor {
+    pub kernel: Kernel,
+    pub vectors: Arc<Tensor>,
+    pub coefficients: Arc<Tensor>,
+    #[educe(Hash(method = "hash_f32"))]
+    pub rho: f32,
+    pub one_class: bool,
+}
+
+impl_dyn_hash!(SvmRegressor);
+
+impl Op for SvmRegressor {
+    fn name(&self) -> Cow<str> {
+        "SvmRegressor".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for SvmRegressor {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input_matrix(&input)?;
+        let vectors = self.vectors.to_array_view::<f32>()?.into_dimensionality()?;
+        let coefficients = self
+            .coefficients
+            .to_array_view::<f32>()?
+            .into_dimensionality::<tract_ndarray::Ix1>()?;
+        let mut scores =
+            self.kernel.compute_all(&input.view(), &vectors).dot(&coefficients) + self.rho;
+        if self.one_class {
+            scores.mapv_inplace(|s| if s > 0.0 { 1.0 } else { -1.0 });
+        }
+        Ok(tvec!(scores.insert_axis(Axis(1)).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SvmRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &[n.clone(), 1.to_dim()])))
+    }
+
+    as_op!();
+}
+
+fn kernel_parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::String.named("kernel_type"),
+        TypeName::Scalar.named("gamma"),
+        TypeName::Scalar.named("coef0"),
+        TypeName::Integer.named("degree"),
+    ]
+}
+
+fn dump_kernel(kernel: &Kernel) -> Vec<(&'static str, RValue)> {
+    vec![
+        ("kernel_type", string(kernel.kernel_type.as_str())),
+        ("gamma", numeric(kernel.gamma)),
+        ("coef0", numeric(kernel.coef0)),
+        ("degree", numeric(kernel.degree)),
+    ]
+}
+
+fn load_kernel(builder: &mut ModelBuilder, invocation: &ResolvedInvocation) -> TractResult<Kernel> {
+    let kernel_type: String = invocation.named_arg_as(builder, "kernel_type")?;
+    let degree: i64 = invocation.named_arg_as(builder, "degree")?;
+    Ok(Kernel {
+        kernel_type: KernelType::parse(&kernel_type)?,
+        gamma: invocation.named_arg_as(builder, "gamma")?,
+        coef0: invocation.named_arg_as(builder, "coef0")?,
+        degree: degree as i32,
+    })
+}
+
+fn parameters_classifier() -> Vec<Parameter> {
+    let mut params = vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("vectors"),
+        TypeName::Integer.array().named("vectors_per_class"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.tensor().named("rho"),
+        TypeName::Scalar.tensor().named("prob_a").default(0),
+        TypeName::Scalar.tensor().named("prob_b").default(0),
+        TypeName::Integer.named("n_classes"),
+    ];
+    params.extend(kernel_parameters());
+    params
+}
+
+fn dump_classifier(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<SvmClassifier>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let vectors = ast.konst_variable(format!("{}_vectors", node.name), &op.vectors)?;
+    let coefficients =
+        ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let rho = ast.konst_variable(format!("{}_rho", node.name), &op.rho)?;
+    // linear mode is dumped as an empty list of vectors per class
+    let vectors_per_class = op
+        .vectors_per_class
+        .as_ref()
+        .map(|v| v.iter().map(numeric).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut attributes = vec![
+        ("vectors_per_class", RValue::Array(vectors_per_class)),
+        ("n_classes", numeric(op.n_classes)),
+    ];
+    attributes.extend(dump_kernel(&op.kernel));
+    let mut inputs = vec![input, vectors, coefficients, rho];
+    if let Some((prob_a, prob_b)) = &op.probabilities {
+        inputs.push(ast.konst_variable(format!("{}_prob_a", node.name), prob_a)?);
+        inputs.push(ast.konst_variable(format!("{}_prob_b", node.name), prob_b)?);
+    }
+    Ok(Some(invocation("tract_onnx_ml_svm_classifier", &inputs, &attributes)))
+}
+
+fn load_classifier(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let vectors = invocation.named_arg_as(builder, "vectors")?;
+    let vectors_per_class: TVec<usize> = invocation.named_arg_as(builder, "vectors_per_class")?;
+    let coefficients = invocation.named_arg_as(builder, "coefficients")?;
+    let rho = invocation.named_arg_as(builder, "rho")?;
+    let prob_a: Arc<Tensor> = invocation.named_arg_as(builder, "prob_a")?;
+    let prob_b: Arc<Tensor> = invocation.named_arg_as(builder, "prob_b")?;
+    let probabilities = if prob_a.rank() > 0 { Some((prob_a, prob_b)) } else { None };
+    let op = SvmClassifier {
+        kernel: load_kernel(builder, invocation)?,
+        n_classes: invocation.named_arg_as(builder, "n_classes")?,
+        vectors,
+        vectors_per_class: Some(vectors_per_class).filter(|v| v.len() > 0),
+        coefficients,
+        rho,
+        probabilities,
+    };
+    builder.wire(op, &[input])
+}
+
+fn parameters_regressor() -> Vec<Parameter> {
+    let mut params = vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("vectors"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.named("rho"),
+        TypeName::Logical.named("one_class"),
+    ];
+    params.extend(kernel_parameters());
+    params
+}
+
+fn dump_regressor(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<SvmRegressor>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let vectors = ast.konst_variable(format!("{}_vectors", node.name), &op.vectors)?;
+    let coefficients =
+        ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let mut attributes = vec![("rho", numeric(op.rho)), ("one_class", logical(op.one_class))];
+    attributes.extend(dump_kernel(&op.kernel));
+    Ok(Some(invocation(
+        "tract_onnx_ml_svm_regressor",
+        &[input, vectors, coefficients],
+        &attributes,
+    )))
+}
+
+fn load_regressor(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let op = SvmRegressor {
+        kernel: load_kernel(builder, invocation)?,
+        vectors: invocation.named_arg_as(builder, "vectors")?,
+        coefficients: invocation.named_arg_as(builder, "coefficients")?,
+        rho: invocation.named_arg_as(builder, "rho")?,
+        one_class: invocation.named_arg_as(builder, "one_class")?,
+    };
+    builder.wire(op, &[input])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear() -> Kernel {
+        Kernel { kernel_type: KernelType::Linear, gamma: 0.0, coef0: 0.0, degree: 0 }
+    }
+
+    #[test]
+    fn svc_binary() -> TractResult<()> {
+        let op = SvmClassifier {
+            kernel: linear(),
+            n_classes: 2,
+            vectors: rctensor2(&[[1f32], [-1.0]]),
+            vectors_per_class: Some(tvec!(1, 1)),
+            coefficients: rctensor2(&[[1f32, -1.0]]),
+            rho: rctensor1(&[0f32]),
+            probabilities: None,
+        };
+        let result = op.eval(tvec!(rctensor2(&[[2f32], [-2.0]])))?;
+        assert_eq!(*result[0], tensor1(&[0i32, 1]));
+        assert_eq!(*result[1], tensor2(&[[4f32], [-4.0]]));
+        Ok(())
+    }
+
+    #[test]
+    fn svc_linear_mode() -> TractResult<()> {
+        let op = SvmClassifier {
+            kernel: linear(),
+            n_classes: 3,
+            vectors: rctensor2(&[[1f32, 0.0], [0.0, 1.0], [-1.0, -1.0]]),
+            vectors_per_class: None,
+            coefficients: rctensor2(&[[1f32, 0.0], [0.0, 1.0], [-1.0, -1.0]]),
+            rho: rctensor1(&[0.5f32]),
+            probabilities: None,
+        };
+        let result = op.eval(tvec!(rctensor2(&[[1f32, 2.0]])))?;
+        assert_eq!(*result[0], tensor1(&[1i32]));
+        assert_eq!(*result[1], tensor2(&[[1.5f32, 2.5, -2.5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn svr_rbf() -> TractResult<()> {
+        let op = SvmRegressor {
+            kernel: Kernel { kernel_type: KernelType::Rbf, gamma: 1.0, coef0: 0.0, degree: 0 },
+            vectors: rctensor2(&[[0f32]]),
+            coefficients: rctensor1(&[2f32]),
+            rho: 1.0,
+            one_class: false,
+        };
+        let result = op.eval(tvec!(rctensor2(&[[0f32], [1.0]])))?;
+        result[0].close_enough(&tensor2(&[[3f32], [2.0 * (-1f32).exp() + 1.0]]), true)
+    }
+}
@@ -2,10 +2,13 @@ use std::convert::TryInto;
 use std::{fs, path};
 
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeBounds;
 
 use tract_hir::internal::*;
 
 use crate::pb;
+use crate::tensor::translate_type;
 use prost::Message;
 
 pub fn optional_inputs(pb: &pb::NodeProto) -> impl Iterator<Item = Option<usize>> + '_ {
@@ -38,6 +41,10 @@ pub struct ParsingContext<'a> {
     pub framework: &'a Onnx,
     pub model: &'a pb::ModelProto,
     pub parent_graphs: Vec<&'a pb::GraphProto>,
+    /// Operator sets the nodes resolve against: the model ones, or the ones
+    /// of the function the nodes have been inlined from.
+    pub opset_import: &'a [pb::OperatorSetIdProto],
+    pub symbol_table: SymbolTable,
 }
 
 #[derive(Clone, Debug)]
@@ -52,6 +59,7 @@ impl<'a> ParsingContext<'a> {
         let mut ctx = self.clone();
         ctx.parent_graphs.push(graph);
         let mut model = InferenceModel::default();
+        model.symbol_table = ctx.symbol_table.clone();
         let mut unresolved_inputs = vec![];
         let mut closures_to_wire = vec![];
         let mut initializers: HashMap<&str, Tensor> = graph
@@ -69,13 +77,7 @@ impl<'a> ParsingContext<'a> {
                 let id = model.add_const(input.name.to_owned(), init)?;
                 outlets_by_name.insert(input.name.to_owned(), id);
             } else {
-                let fact = input.r#type.as_ref().unwrap().value.as_ref().unwrap();
-                #[allow(irrefutable_let_patterns)]
-                let fact: InferenceFact = if let pb::type_proto::Value::TensorType(fact) = fact {
-                    fact.try_into()?
-                } else {
-                    bail!("Can not parse tensor type");
-                };
+                let fact = translate_type(&ctx.symbol_table, input.r#type.as_ref().unwrap())?;
                 trace!("Input: {} is a source ({:?})", input.name, fact);
                 let id = model.add_source(&*input.name, fact)?;
                 outlets_by_name.insert(input.name.to_owned(), id);
@@ -89,7 +91,8 @@ impl<'a> ParsingContext<'a> {
             outlets_by_name.insert(name.to_string(), id);
         }
         let consts = model.nodes().len();
-        for pbnode in graph.node.iter() {
+        let nodes = ctx.inline_functions(&graph.node, &[])?;
+        for (pbnode, func) in nodes.iter() {
             let name = if pbnode.name != "" {
                 pbnode.name.to_string()
             } else if pbnode.output.len() > 0 && pbnode.output[0] != "" {
@@ -105,20 +108,24 @@ impl<'a> ParsingContext<'a> {
                 .map(|_| InferenceFact::default())
                 .collect();
             trace!("  outputs {:?}", pbnode.output);
-            let (op, closures) = match self.framework.op_register.0.get(&pbnode.op_type) {
-                Some(builder) => (builder)(&ctx, pbnode).with_context(|| {
-                    format!("Building node {} ({})", pbnode.name, pbnode.op_type)
-                })?,
-                None => (
-                    tract_hir::ops::unimpl::UnimplementedOp::new(
-                        pbnode.output.len(),
-                        &*pbnode.op_type,
-                        format!("{:?}", pbnode),
-                    )
-                    .into(),
-                    vec![],
-                ),
-            };
+            let func_ctx = func.map(|func| ctx.for_function(func));
+            let node_ctx = func_ctx.as_ref().unwrap_or(&ctx);
+            let opset = node_ctx.opset_version(&pbnode.domain);
+            let (op, closures) =
+                match self.framework.op_register.get(&pbnode.domain, &pbnode.op_type, opset)? {
+                    Some(builder) => (builder)(node_ctx, pbnode).with_context(|| {
+                        format!("Building node {} ({})", pbnode.name, pbnode.op_type)
+                    })?,
+                    None => (
+                        tract_hir::ops::unimpl::UnimplementedOp::new(
+                            pbnode.output.len(),
+                            &*pbnode.op_type,
+                            format!("{:?}", pbnode),
+                        )
+                        .into(),
+                        vec![],
+                    ),
+                };
             let id = model.add_node(name, op, facts)?;
             for (ix, output) in pbnode.output.iter().filter(|s| !s.is_empty()).enumerate() {
                 outlets_by_name.insert(output.to_owned(), OutletId::new(id, ix));
@@ -129,7 +136,7 @@ impl<'a> ParsingContext<'a> {
                 closures_to_wire.push((id, closure))
             }
         }
-        for (id, pbnode) in graph.node.iter().enumerate() {
+        for (id, (pbnode, _)) in nodes.iter().enumerate() {
             for (ix, input) in pbnode.input.iter().filter(|s| !s.is_empty()).enumerate() {
                 if !outlets_by_name.contains_key(&*input) {
                     let id = model.add_source(input.clone(), InferenceFact::default())?;
@@ -152,12 +159,7 @@ impl<'a> ParsingContext<'a> {
         }
         let mut outputs = vec![];
         for output in graph.output.iter() {
-            let fact = if let Some(fact) = output.r#type.as_ref().unwrap().value.as_ref() {
-                let pb::type_proto::Value::TensorType(fact) = fact;
-                fact.try_into()?
-            } else {
-                InferenceFact::default()
-            };
+            let fact = translate_type(&ctx.symbol_table, output.r#type.as_ref().unwrap())?;
             let outlet = outlets_by_name[&*output.name];
             outputs.push(outlet);
             model.set_outlet_label(outlet, output.name.clone())?;
@@ -167,29 +169,279 @@ impl<'a> ParsingContext<'a> {
         let result = ParseResult { model, unresolved_inputs, outlets_by_name };
         Ok(result)
     }
+
+    /// Operator set version imported by the model for an op domain, 0 if the
+    /// model does not import it.
+    pub fn opset_version(&self, domain: &str) -> i64 {
+        let domain = OnnxOpRegister::normalize_domain(domain);
+        if domain == "" {
+            return self.onnx_operator_set_version;
+        }
+        self.opset_import
+            .iter()
+            .find(|import| import.domain == domain)
+            .map(|import| import.version)
+            .unwrap_or(0)
+    }
+
+    /// Context for the nodes inlined from `func`, resolving ops against the
+    /// operator sets the function imports. The default domain falls back to
+    /// the model operator set if the function does not import it.
+    fn for_function(&self, func: &'a pb::FunctionProto) -> ParsingContext<'a> {
+        let mut ctx = self.clone();
+        ctx.opset_import = &func.opset_import;
+        if let Some(import) = func
+            .opset_import
+            .iter()
+            .find(|import| OnnxOpRegister::normalize_domain(&import.domain) == "")
+        {
+            ctx.onnx_operator_set_version = import.version;
+        }
+        ctx
+    }
+
+    fn function(&self, domain: &str, name: &str) -> Option<&'a pb::FunctionProto> {
+        let domain = OnnxOpRegister::normalize_domain(domain);
+        self.model
+            .functions
+            .iter()
+            .find(|f| f.name == name && OnnxOpRegister::normalize_domain(&f.domain) == domain)
+    }
+
+    /// Replace calls to model-local functions by the function bodies.
+    ///
+    /// Registered ops take precedence over functions. Values internal to a
+    /// function are prefixed by the calling node name, and attributes
+    /// referring to the caller (`ref_attr_name`) are substituted. Each node
+    /// comes with the function it has been inlined from, if any.
+    fn inline_functions(
+        &self,
+        nodes: &[pb::NodeProto],
+        callers: &[&str],
+    ) -> TractResult<Vec<(pb::NodeProto, Option<&'a pb::FunctionProto>)>> {
+        let mut inlined = vec![];
+        for (ix, node) in nodes.iter().enumerate() {
+            let func = if self.framework.op_register.contains(&node.domain, &node.op_type) {
+                None
+            } else {
+                self.function(&node.domain, &node.op_type)
+            };
+            let func = if let Some(func) = func {
+                func
+            } else {
+                inlined.push((node.clone(), None));
+                continue;
+            };
+            if callers.contains(&&*func.name) {
+                bail!("Recursive call to function {} in {}", func.name, node.name);
+            }
+            let prefix = if node.name != "" {
+                node.name.clone()
+            } else if node.output.len() > 0 && node.output[0] != "" {
+                node.output[0].clone()
+            } else {
+                format!("{}-{}", ix, node.op_type)
+            };
+            let mut renames = HashMap::<&str, &str>::new();
+            for (formal, actual) in func.input.iter().zip(node.input.iter()) {
+                renames.insert(formal, actual);
+            }
+            for formal in func.input.iter().skip(node.input.len()) {
+                renames.insert(formal, "");
+            }
+            for (formal, actual) in func.output.iter().zip(node.output.iter()) {
+                if actual != "" {
+                    renames.insert(formal, actual);
+                }
+            }
+            let rename = |name: &String| -> String {
+                if name == "" {
+                    String::new()
+                } else if let Some(actual) = renames.get(&**name) {
+                    actual.to_string()
+                } else {
+                    format!("{}.{}", prefix, name)
+                }
+            };
+            let body = func
+                .node
+                .iter()
+                .enumerate()
+                .map(|(ix, inner)| {
+                    let name = if inner.name != "" {
+                        inner.name.clone()
+                    } else {
+                        format!("{}-{}", ix, inner.op_type)
+                    };
+                    let attribute = inner
+                        .attribute
+                        .iter()
+                        .filter_map(|attr| {
+                            if attr.ref_attr_name == "" {
+                                Some(attr.clone())
+                            } else {
+                                node.attribute.iter().find(|a| a.name == attr.ref_attr_name).map(
+                                    |a| pb::AttributeProto { name: attr.name.clone(), ..a.clone() },
+                                )
+                            }
+                        })
+                        .collect();
+                    pb::NodeProto {
+                        name: format!("{}.{}", prefix, name),
+                        input: inner.input.iter().map(rename).collect(),
+                        output: inner.output.iter().map(rename).collect(),
+                        attribute,
+                        ..inner.clone()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut callers = callers.to_vec();
+            callers.push(&func.name);
+            inlined.extend(
+                self.inline_functions(&body, &callers)?
+                    .into_iter()
+                    .map(|(node, inner)| (node, inner.or(Some(func)))),
+            );
+        }
+        Ok(inlined)
+    }
+}
+
+pub type OnnxOpBuilder =
+    fn(&ParsingContext, node: &pb::NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>;
+
+/// Range of operator set versions a builder applies to, bounds included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OpsetRange {
+    pub since: i64,
+    pub until: Option<i64>,
+}
+
+impl OpsetRange {
+    pub fn new(versions: impl RangeBounds<i64>) -> OpsetRange {
+        use std::ops::Bound::*;
+        let since = match versions.start_bound() {
+            Included(v) => *v,
+            Excluded(v) => v + 1,
+            Unbounded => 1,
+        };
+        let until = match versions.end_bound() {
+            Included(v) => Some(*v),
+            Excluded(v) => Some(v - 1),
+            Unbounded => None,
+        };
+        OpsetRange { since, until }
+    }
+
+    pub fn all() -> OpsetRange {
+        OpsetRange::new(..)
+    }
+
+    pub fn contains(&self, version: i64) -> bool {
+        self.since <= version && self.until.map(|until| version <= until).unwrap_or(true)
+    }
+
+    pub fn overlaps(&self, other: &OpsetRange) -> bool {
+        self.until.map(|until| other.since <= until).unwrap_or(true)
+            && other.until.map(|until| self.since <= until).unwrap_or(true)
+    }
+}
+
+impl fmt::Display for OpsetRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.until {
+            Some(until) if until == self.since => write!(f, "{}", self.since),
+            Some(until) => write!(f, "{}-{}", self.since, until),
+            None => write!(f, "{}+", self.since),
+        }
+    }
 }
 
+/// Op builders, by (domain, op_type), each covering a range of operator set
+/// versions. The default ONNX domain is the empty string, "ai.onnx" is
+/// accepted as an alias.
 #[derive(Clone, Default)]
-pub struct OnnxOpRegister(
-    pub  HashMap<
-        String,
-        fn(
-            &ParsingContext,
-            node: &pb::NodeProto,
-        ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>,
-    >,
-);
+pub struct OnnxOpRegister(pub HashMap<(String, String), Vec<(OpsetRange, OnnxOpBuilder)>>);
 
 impl OnnxOpRegister {
-    pub fn insert(
+    fn normalize_domain(domain: &str) -> &str {
+        if domain == "ai.onnx" {
+            ""
+        } else {
+            domain
+        }
+    }
+
+    /// Register an op in the default ONNX domain, for all operator sets.
+    pub fn insert(&mut self, s: &'static str, builder: OnnxOpBuilder) {
+        self.insert_versioned(s, .., builder)
+    }
+
+    /// Register an op in the default ONNX domain, for a range of operator
+    /// sets (`10..`, `1..10`, ...).
+    pub fn insert_versioned(
         &mut self,
         s: &'static str,
-        builder: fn(
-            &ParsingContext,
-            node: &pb::NodeProto,
-        ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>,
+        versions: impl RangeBounds<i64>,
+        builder: OnnxOpBuilder,
     ) {
-        self.0.insert(s.into(), builder);
+        self.insert_with_domain("", s, versions, builder)
+    }
+
+    /// Register an op in any domain. Builders previously registered for an
+    /// overlapping range of versions are replaced.
+    pub fn insert_with_domain(
+        &mut self,
+        domain: &str,
+        op_type: &str,
+        versions: impl RangeBounds<i64>,
+        builder: OnnxOpBuilder,
+    ) {
+        let versions = OpsetRange::new(versions);
+        let key = (Self::normalize_domain(domain).to_string(), op_type.to_string());
+        let builders = self.0.entry(key).or_insert_with(Vec::new);
+        builders.retain(|(range, _)| !range.overlaps(&versions));
+        builders.push((versions, builder));
+        builders.sort_by_key(|(range, _)| range.since);
+    }
+
+    pub fn contains(&self, domain: &str, op_type: &str) -> bool {
+        self.0.contains_key(&(Self::normalize_domain(domain).to_string(), op_type.to_string()))
+    }
+
+    /// Find the builder for an op at a given operator set version. A version
+    /// of 0 (no opset import for the domain) picks the most recent builder.
+    ///
+    /// Returns None for unknown ops, and an error if the op is known but not
+    /// for this version.
+    pub fn get(
+        &self,
+        domain: &str,
+        op_type: &str,
+        opset: i64,
+    ) -> TractResult<Option<&OnnxOpBuilder>> {
+        let builders = if let Some(builders) =
+            self.0.get(&(Self::normalize_domain(domain).to_string(), op_type.to_string()))
+        {
+            builders
+        } else {
+            return Ok(None);
+        };
+        let found = if opset == 0 {
+            builders.last()
+        } else {
+            builders.iter().find(|(range, _)| range.contains(opset))
+        };
+        if let Some((_, builder)) = found {
+            Ok(Some(builder))
+        } else {
+            bail!(
+                "Operator {} is not supported at opset {} (supported opsets: {})",
+                op_type,
+                opset,
+                builders.iter().map(|(range, _)| range.to_string()).collect::<Vec<_>>().join(", ")
+            )
+        }
     }
 }
 
@@ -206,7 +458,8 @@ impl Onnx {
             .find(|import| import.domain == "" || import.domain == "ai.onnx")
             .map(|op| op.version)
             .unwrap_or(0);
-        let graph = proto.graph.as_ref().ok_or_else(|| anyhow!("model proto does not contain a graph"))?;
+        let graph =
+            proto.graph.as_ref().ok_or_else(|| anyhow!("model proto does not contain a graph"))?;
         debug!("ONNX operator set version: {:?}", onnx_operator_set_version);
         if onnx_operator_set_version != 0
             && (onnx_operator_set_version < 9 || onnx_operator_set_version > 12)
@@ -220,6 +473,8 @@ impl Onnx {
             model: proto,
             parent_graphs: vec![],
             onnx_operator_set_version,
+            opset_import: &proto.opset_import,
+            symbol_table: SymbolTable::default(),
         };
         ctx.parse_graph(graph)
     }
@@ -228,10 +483,13 @@ impl Onnx {
 impl Framework<pb::ModelProto, InferenceModel> for Onnx {
     fn proto_model_for_path(&self, p: impl AsRef<path::Path>) -> TractResult<pb::ModelProto> {
         #[cfg(not(target_arch = "wasm32"))]
-        let map = unsafe { mapr::Mmap::map(&fs::File::open(p)?)? };
+        let map = unsafe { mapr::Mmap::map(&fs::File::open(p.as_ref())?)? };
         #[cfg(target_arch = "wasm32")]
-        let map = fs::read(p)?;
-        Ok(crate::pb::ModelProto::decode(&*map)?)
+        let map = fs::read(p.as_ref())?;
+        let mut proto = crate::pb::ModelProto::decode(&*map)?;
+        let dir = p.as_ref().parent().unwrap_or_else(|| path::Path::new("."));
+        crate::tensor::ExternalDataLoader::new(dir).load_model(&mut proto)?;
+        Ok(proto)
     }
 
     fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<pb::ModelProto> {
@@ -249,3 +507,167 @@ impl Framework<pb::ModelProto, InferenceModel> for Onnx {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pb::*;
+
+    fn node(op_type: &str, domain: &str, input: &[&str], output: &[&str]) -> NodeProto {
+        NodeProto {
+            op_type: op_type.into(),
+            domain: domain.into(),
+            input: input.iter().map(|s| s.to_string()).collect(),
+            output: output.iter().map(|s| s.to_string()).collect(),
+            ..NodeProto::default()
+        }
+    }
+
+    fn model(node: NodeProto, functions: Vec<FunctionProto>) -> ModelProto {
+        let dim = |d| tensor_shape_proto::Dimension {
+            value: Some(tensor_shape_proto::dimension::Value::DimValue(d)),
+            ..tensor_shape_proto::Dimension::default()
+        };
+        let input = ValueInfoProto {
+            name: "x".into(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                    elem_type: tensor_proto::DataType::Float as i32,
+                    shape: Some(TensorShapeProto { dim: vec![dim(2)] }),
+                })),
+                ..TypeProto::default()
+            }),
+            ..ValueInfoProto::default()
+        };
+        let output = ValueInfoProto {
+            name: "y".into(),
+            r#type: Some(TypeProto::default()),
+            ..ValueInfoProto::default()
+        };
+        ModelProto {
+            opset_import: vec![OperatorSetIdProto { domain: "".into(), version: 11 }],
+            graph: Some(GraphProto {
+                node: vec![node],
+                input: vec![input],
+                output: vec![output],
+                ..GraphProto::default()
+            }),
+            functions,
+            ..ModelProto::default()
+        }
+    }
+
+    #[test]
+    fn inline_function_with_attribute_reference() -> TractResult<()> {
+        let alpha = AttributeProto {
+            name: "alpha".into(),
+            ref_attr_name: "slope".into(),
+            r#type: attribute_proto::AttributeType::Float as i32,
+            ..AttributeProto::default()
+        };
+        let leaky = NodeProto { attribute: vec![alpha], ..node("LeakyRelu", "", &["X"], &["t"]) };
+        let function = FunctionProto {
+            name: "DoubleLeakyRelu".into(),
+            domain: "custom".into(),
+            input: vec!["X".into()],
+            output: vec!["Y".into()],
+            attribute: vec!["slope".into()],
+            node: vec![leaky, node("Add", "", &["t", "t"], &["Y"])],
+            ..FunctionProto::default()
+        };
+        let slope = AttributeProto {
+            name: "slope".into(),
+            r#type: attribute_proto::AttributeType::Float as i32,
+            f: 0.5,
+            ..AttributeProto::default()
+        };
+        let call = NodeProto {
+            name: "call".into(),
+            attribute: vec![slope],
+            ..node("DoubleLeakyRelu", "custom", &["x"], &["y"])
+        };
+        let model = crate::onnx().model_for_proto_model(&model(call, vec![function]))?;
+        assert!(model.node_by_name("call.0-LeakyRelu").is_ok());
+        let output = model.into_runnable()?.run(tvec!(tensor1(&[-1f32, 2.0])))?;
+        assert_eq!(output[0], rctensor1(&[-1f32, 4.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn function_body_uses_function_opsets() -> TractResult<()> {
+        let mut onnx = crate::onnx();
+        onnx.op_register.insert_with_domain("com.example", "Neg", 2.., |_, _| {
+            Ok((Box::new(tract_hir::ops::math::neg()), vec![]))
+        });
+        let function = FunctionProto {
+            name: "Negate".into(),
+            domain: "custom".into(),
+            input: vec!["X".into()],
+            output: vec!["Y".into()],
+            node: vec![node("Neg", "com.example", &["X"], &["Y"])],
+            opset_import: vec![OperatorSetIdProto { domain: "com.example".into(), version: 2 }],
+            ..FunctionProto::default()
+        };
+        let mut proto = model(node("Negate", "custom", &["x"], &["y"]), vec![function]);
+        proto.opset_import.push(OperatorSetIdProto { domain: "com.example".into(), version: 1 });
+        let model = onnx.model_for_proto_model(&proto)?;
+        let output = model.into_runnable()?.run(tvec!(tensor1(&[-1f32, 2.0])))?;
+        assert_eq!(output[0], rctensor1(&[1f32, -2.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn ops_are_looked_up_by_domain() -> TractResult<()> {
+        let unimplemented = |proto: &ModelProto, onnx: &Onnx| -> TractResult<bool> {
+            let model = onnx.model_for_proto_model(proto)?;
+            Ok(model.node(1).op_is::<tract_hir::ops::unimpl::UnimplementedOp>())
+        };
+        let mut onnx = crate::onnx();
+        let custom = model(node("Neg", "com.example", &["x"], &["y"]), vec![]);
+        assert!(unimplemented(&custom, &onnx)?);
+        onnx.op_register.insert_with_domain("com.example", "Neg", .., |_, _| {
+            Ok((Box::new(tract_hir::ops::math::neg()), vec![]))
+        });
+        assert!(!unimplemented(&custom, &onnx)?);
+        let aliased = model(node("Neg", "ai.onnx", &["x"], &["y"]), vec![]);
+        assert!(!unimplemented(&aliased, &onnx)?);
+        Ok(())
+    }
+
+    #[test]
+    fn builders_are_chosen_by_opset() -> TractResult<()> {
+        let mut proto = model(node("Squeeze", "", &["x", "axes"], &["y"]), vec![]);
+        let op_name = |proto: &ModelProto| -> TractResult<String> {
+            Ok(crate::onnx().parse(proto)?.model.node(1).op().name().to_string())
+        };
+        assert_eq!(op_name(&proto)?, "Squeeze");
+        proto.opset_import[0].version = 13;
+        assert_eq!(op_name(&proto)?, "Squeeze13");
+        let mut proto = model(node("Pad", "", &["x"], &["y"]), vec![]);
+        proto.opset_import[0].version = 1;
+        let err = crate::onnx().model_for_proto_model(&proto).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Operator Pad is not supported at opset 1 (supported opsets: 2-10, 11+)"
+        );
+        proto.opset_import.clear();
+        assert!(crate::onnx().model_for_proto_model(&proto).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn opset_ranges() {
+        let mut reg = OnnxOpRegister::default();
+        reg.insert_versioned("Op", 1..10, |_, _| bail!("v1"));
+        reg.insert_versioned("Op", 10.., |_, _| bail!("v10"));
+        reg.insert_versioned("Op", 13.., |_, _| bail!("v13"));
+        let ranges = reg.0[&("".to_string(), "Op".to_string())]
+            .iter()
+            .map(|(range, _)| range.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec!["1-9", "13+"]);
+        assert!(reg.get("", "Op", 9).unwrap().is_some());
+        assert!(reg.get("", "Op", 11).is_err());
+        assert!(reg.get("", "Other", 11).unwrap().is_none());
+    }
+}
@@ -2,10 +2,16 @@ mod compress;
 mod nonzero;
 mod one_hot;
 mod pad;
+mod reverse_sequence;
 mod slice;
+mod split;
+mod squeeze;
+mod topk;
+mod trilu;
 
 use tract_hir::internal::*;
 use tract_hir::ops::array;
+use tract_hir::ops::nn::{self, DepthToSpaceMode};
 
 use crate::model::{OnnxOpRegister, ParsingContext};
 use crate::pb::*;
@@ -15,27 +21,45 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Concat", concat);
     reg.insert("ConstantLike", constant_like);
     reg.insert("ConstantOfShape", constant_of_shape);
+    reg.insert("CumSum", cumsum);
+    reg.insert("DepthToSpace", depth_to_space);
     reg.insert("Expand", |_, _| Ok((expand(array::MultiBroadcastTo::default()), vec![])));
     reg.insert("EyeLike", eye_like);
     reg.insert("Flatten", flatten);
     reg.insert("Gather", gather);
     reg.insert("GatherElements", gather_elements);
     reg.insert("GatherND", gather_nd);
-    reg.insert("NonZero", |_, _| Ok((Box::new(nonzero::NonZero::non_zero()), vec![])));
+    reg.insert("NonZero", |ctx, _| {
+        Ok((Box::new(nonzero::NonZero::non_zero(ctx.symbol_table.new_symbol("x"))), vec![]))
+    });
     reg.insert("OneHot", one_hot::one_hot);
-    reg.insert("Pad", pad::pad);
+    reg.insert_versioned("Pad", 2..11, pad::pad_2);
+    reg.insert_versioned("Pad", 11.., pad::pad_11);
+    reg.insert("Range", |ctx, _| {
+        Ok((expand(array::Range::new(ctx.symbol_table.new_symbol("range"))), vec![]))
+    });
     reg.insert("Reshape", |_, _| Ok((expand(array::Reshape::default()), vec![])));
+    reg.insert("ReverseSequence", reverse_sequence::reverse_sequence);
     reg.insert("Scatter", scatter_elements);
     reg.insert("ScatterElements", scatter_elements);
     reg.insert("ScatterND", |_, _| Ok((Box::new(array::ScatterNd), vec![])));
     reg.insert("Shape", |_, _| Ok((expand(array::Shape::new(DatumType::I64)), vec![])));
     reg.insert("Size", |_, _| Ok((expand(array::Size::new(DatumType::I64)), vec![])));
-    reg.insert("Slice", slice::slice);
-    reg.insert("Split", split);
-    reg.insert("Squeeze", squeeze);
+    reg.insert_versioned("Slice", 1..10, slice::slice1);
+    reg.insert_versioned("Slice", 10.., slice::slice10);
+    reg.insert("SpaceToDepth", space_to_depth);
+    // split and axes are inputs from opset 13
+    reg.insert_versioned("Split", 1..13, split);
+    reg.insert_versioned("Split", 13.., split::split_13);
+    reg.insert_versioned("Squeeze", 1..13, squeeze);
+    reg.insert_versioned("Squeeze", 13.., squeeze::squeeze_13);
     reg.insert("Tile", |_, _| Ok((expand(array::Tile::default()), vec![])));
+    reg.insert_versioned("TopK", 1..10, topk::topk_1);
+    reg.insert_versioned("TopK", 10.., topk::topk_10);
     reg.insert("Transpose", transpose);
-    reg.insert("Unsqueeze", unsqueeze);
+    reg.insert("Trilu", trilu::trilu);
+    reg.insert_versioned("Unsqueeze", 1..13, unsqueeze);
+    reg.insert_versioned("Unsqueeze", 13.., squeeze::unsqueeze_13);
 }
 
 pub fn concat(
@@ -79,6 +103,28 @@ pub fn constant_of_shape(
     Ok((expand(array::ConstantOfShape::new(value)), vec![]))
 }
 
+pub fn cumsum(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let reverse = node.get_attr_opt("reverse")?.unwrap_or(0i64) == 1;
+    let exclusive = node.get_attr_opt("exclusive")?.unwrap_or(0i64) == 1;
+    Ok((expand(array::CumSum::new(reverse, exclusive)), vec![]))
+}
+
+pub fn depth_to_space(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let block_size = node.get_attr("blocksize")?;
+    let mode = match node.get_attr_opt("mode")?.unwrap_or("DCR") {
+        "DCR" => DepthToSpaceMode::DCR,
+        "CRD" => DepthToSpaceMode::CRD,
+        other => bail!("Unsupported DepthToSpace mode {}", other),
+    };
+    Ok((expand(nn::DepthToSpace::new(block_size, mode, nn::DataFormat::NCHW)), vec![]))
+}
+
 pub fn eye_like(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -128,6 +174,14 @@ pub fn scatter_elements(
     Ok((expand(array::ScatterElements::new(axis)), vec![]))
 }
 
+pub fn space_to_depth(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let block_size = node.get_attr("blocksize")?;
+    Ok((expand(nn::SpaceToDepth::new(block_size, nn::DataFormat::NCHW)), vec![]))
+}
+
 pub fn split(
     _ctx: &ParsingContext,
     node: &NodeProto,
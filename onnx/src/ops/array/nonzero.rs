@@ -7,8 +7,8 @@ pub struct NonZero(Symbol);
 impl_dyn_hash!(NonZero);
 
 impl NonZero {
-    pub fn non_zero() -> NonZero {
-        NonZero(Symbol::new('x'))
+    pub fn non_zero(symbol: Symbol) -> NonZero {
+        NonZero(symbol)
     }
 }
 
@@ -2,19 +2,7 @@ use crate::model::ParsingContext;
 use crate::pb::*;
 use tract_hir::internal::*;
 
-pub fn slice(
-    ctx: &ParsingContext,
-    node: &NodeProto,
-) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let v = ctx.onnx_operator_set_version;
-    if v >= 1 && v < 10 {
-        slice1(ctx, node)
-    } else {
-        slice10(ctx, node)
-    }
-}
-
-fn slice1(
+pub fn slice1(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
@@ -66,18 +54,8 @@ impl Expansion for Slice1 {
                 } else {
                     Some((self.starts[axis].into(), self.ends[axis].into()))
                 };
-                if let Some((mut b, mut e)) = spec {
-                    if let Ok(d) = d.to_isize() {
-                        if b > d {
-                            b = d.into();
-                        }
-                        if e > d {
-                            e = d.into();
-                        }
-                    }
-                    let b = if b < 0 { d.bex() + TDim::from(b) } else { TDim::from(b).bex() };
-                    let e = if e < 0 { d.bex() + TDim::from(e) } else { TDim::from(e).bex() };
-                    s.equals(&outputs[0].shape[axis], e - b)
+                if let Some((b, e)) = spec {
+                    s.equals(&outputs[0].shape[axis], clamp(d, e) - clamp(d, b))
                 } else {
                     s.equals(&outputs[0].shape[axis], &shape[axis])
                 }
@@ -97,18 +75,13 @@ impl Expansion for Slice1 {
         for (ix, (&b, &e)) in self.starts.iter().zip(self.ends.iter()).enumerate() {
             let axis = self.axes.as_ref().map(|axes| axes[ix]).unwrap_or(ix);
             let dim = &input.shape[axis];
-            if let Ok(dim) = dim.to_isize() {
-                let b = (if b >= 0 { b.min(dim) } else { dim + b }) as usize;
-                let e = (if e >= 0 { e.min(dim) } else { dim + e }) as usize;
-                if b > 0 || e < dim as usize {
-                    wire = target.wire_node(
-                        format!("{}.axis-{}", prefix, axis),
-                        tract_hir::ops::array::Slice::new(axis, b, e),
-                        [wire].as_ref(),
-                    )?[0];
-                }
-            } else {
-                bail!("Can't translate slice: axis={} dim={} b={} e={}", axis, dim, b, e)
+            let (b, e) = (clamp(dim, b), clamp(dim, e));
+            if b != 0.to_dim() || &e != dim {
+                wire = target.wire_node(
+                    format!("{}.axis-{}", prefix, axis),
+                    tract_hir::ops::array::Slice::new(axis, b, e),
+                    [wire].as_ref(),
+                )?[0];
             }
         }
         target.rename_node(wire.node, &*prefix)?;
@@ -116,7 +89,16 @@ impl Expansion for Slice1 {
     }
 }
 
-fn slice10(
+/// Resolve a possibly negative bound against the axis dimension, clamping it to [0, dim].
+fn clamp(dim: &TDim, bound: isize) -> TDim {
+    if bound < 0 {
+        (dim.clone() + bound as i64).maxi(0)
+    } else {
+        TDim::from(bound).mini(dim.clone())
+    }
+}
+
+pub fn slice10(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
@@ -132,3 +114,39 @@ fn slice10(
         vec![],
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(op: Slice1, input: Tensor) -> TractResult<Tensor> {
+        let mut model = InferenceModel::default();
+        let source = model.add_source("input", InferenceFact::from(input.clone()))?;
+        let slice = model.wire_node("slice", expand(op), &[source])?;
+        model.set_output_outlets(&slice)?;
+        let output = model.into_runnable()?.run(tvec!(input))?;
+        Ok(output[0].clone().into_tensor())
+    }
+
+    #[test]
+    fn clamp_bounds() {
+        let d = TDim::from(5);
+        assert_eq!(clamp(&d, -2), 3.to_dim());
+        assert_eq!(clamp(&d, -100), 0.to_dim());
+        assert_eq!(clamp(&d, 100), 5.to_dim());
+    }
+
+    #[test]
+    fn slice_with_large_negative_start() -> TractResult<()> {
+        let op = Slice1::new(None, vec![-100], vec![2]);
+        assert_eq!(run(op, tensor1(&[1f32, 2., 3., 4., 5.]))?, tensor1(&[1f32, 2.]));
+        Ok(())
+    }
+
+    #[test]
+    fn slice_with_large_negative_end() -> TractResult<()> {
+        let op = Slice1::new(None, vec![0], vec![-100]);
+        assert_eq!(run(op, tensor1(&[1f32, 2., 3., 4., 5.]))?.shape(), &[0]);
+        Ok(())
+    }
+}
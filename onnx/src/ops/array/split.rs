@@ -0,0 +1,108 @@
+use crate::model::{optional_inputs, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_hir::ops::array;
+
+pub fn split_13(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let outputs = node.output.len();
+    if optional_inputs(node).nth(1).flatten().is_some() {
+        Ok((expand(Split13 { axis, outputs }), vec![]))
+    } else {
+        Ok((expand(array::Split::new(axis, outputs, None)), vec![]))
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Split13 {
+    axis: isize,
+    outputs: usize,
+}
+
+impl_dyn_hash!(Split13);
+
+impl Split13 {
+    fn split(&self, split: &Tensor) -> TractResult<array::Split> {
+        let split = split.cast_to::<i64>()?;
+        let split = split.as_slice::<i64>()?.iter().map(|&d| d as usize).collect::<Vec<_>>();
+        if split.len() != self.outputs {
+            bail!("Split expects {} lengths, got {:?}", self.outputs, split)
+        }
+        Ok(array::Split::new(self.axis, self.outputs, Some(split)))
+    }
+}
+
+impl Expansion for Split13 {
+    fn name(&self) -> Cow<str> {
+        "Split13".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, self.outputs)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], self.outputs.to_dim())?;
+        (0..self.outputs).try_for_each(|i| {
+            s.equals(&inputs[0].datum_type, &outputs[i].datum_type)?;
+            s.equals(&inputs[0].rank, &outputs[i].rank)
+        })?;
+        s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, split| {
+            let axis =
+                if self.axis < 0 { self.axis + shape.len() as isize } else { self.axis } as usize;
+            let dims = self.split(&split)?.split_dims(&shape[axis])?;
+            for i in 0..self.outputs {
+                let mut shape = shape.clone();
+                shape[axis] = dims[i].clone();
+                s.equals(&outputs[i].shape, shape)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.outputs)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let split = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("Expect Split lengths to be constant")?;
+        self.split(&split)?.wire(prefix, target, &inputs[0..1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_lengths_from_input() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let input = model.add_source("input", InferenceFact::dt_shape(f32::datum_type(), &[5]))?;
+        let split = model.add_const("split", rctensor1(&[2i64, 3]))?;
+        let op = expand(Split13 { axis: 0, outputs: 2 });
+        let outputs = model.wire_node("split", op, &[input, split])?;
+        model.set_output_outlets(&outputs)?;
+        let outputs = model.into_runnable()?.run(tvec!(tensor1(&[1f32, 2., 3., 4., 5.])))?;
+        assert_eq!(*outputs[0], tensor1(&[1f32, 2.]));
+        assert_eq!(*outputs[1], tensor1(&[3f32, 4., 5.]));
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::model::{optional_inputs, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_hir::ops::array;
+
+pub fn squeeze_13(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    if optional_inputs(node).nth(1).flatten().is_some() {
+        Ok((expand(Squeeze13), vec![]))
+    } else {
+        Ok((expand(array::Squeeze::new(None)), vec![]))
+    }
+}
+
+pub fn unsqueeze_13(
+    _ctx: &ParsingContext,
+    _node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((expand(Unsqueeze13), vec![]))
+}
+
+fn axes(axes: &Tensor) -> TractResult<Vec<isize>> {
+    let axes = axes.cast_to::<i64>()?;
+    Ok(axes.as_slice::<i64>()?.iter().map(|&a| a as isize).collect())
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Squeeze13;
+
+impl_dyn_hash!(Squeeze13);
+
+impl Expansion for Squeeze13 {
+    fn name(&self) -> Cow<str> {
+        "Squeeze13".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.given(&inputs[1].value, move |s, axes| {
+            s.equals(&outputs[0].rank, (&inputs[0].rank).bex() - axes.len() as i64)
+        })?;
+        s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, axes| {
+            let op = array::Squeeze::new(Some(self::axes(&axes)?));
+            s.equals(&outputs[0].shape, op.compute_shape(&shape)?)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axes = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("Expect Squeeze axes to be constant")?;
+        array::Squeeze::new(Some(self::axes(&axes)?)).wire(prefix, target, &inputs[0..1])
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Unsqueeze13;
+
+impl_dyn_hash!(Unsqueeze13);
+
+impl Expansion for Unsqueeze13 {
+    fn name(&self) -> Cow<str> {
+        "Unsqueeze13".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.given(&inputs[1].value, move |s, axes| {
+            s.equals(&outputs[0].rank, (&inputs[0].rank).bex() + axes.len() as i64)
+        })?;
+        s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, axes| {
+            let op = array::AddDims::new(self::axes(&axes)?);
+            s.equals(&outputs[0].shape, op.compute_shape(&shape))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axes = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("Expect Unsqueeze axes to be constant")?;
+        array::AddDims::new(self::axes(&axes)?).wire(prefix, target, &inputs[0..1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(op: Box<dyn InferenceOp>, input: Tensor, axes: &[i64]) -> TractResult<Tensor> {
+        let mut model = InferenceModel::default();
+        let source = model.add_source("input", InferenceFact::dt(f32::datum_type()))?;
+        let axes = model.add_const("axes", rctensor1(axes))?;
+        let output = model.wire_node("op", op, &[source, axes])?;
+        model.set_output_outlets(&output)?;
+        let output = model.into_runnable()?.run(tvec!(input))?;
+        Ok(output[0].clone().into_tensor())
+    }
+
+    #[test]
+    fn squeeze_axes_from_input() -> TractResult<()> {
+        let input = Tensor::zero::<f32>(&[1, 3, 1, 2])?;
+        let output = run(expand(Squeeze13), input, &[0, -2])?;
+        assert_eq!(output.shape(), &[3, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn unsqueeze_axes_from_input() -> TractResult<()> {
+        let input = Tensor::zero::<f32>(&[3, 2])?;
+        let output = run(expand(Unsqueeze13), input, &[0, 3])?;
+        assert_eq!(output.shape(), &[1, 3, 2, 1]);
+        Ok(())
+    }
+}
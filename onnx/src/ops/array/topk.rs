@@ -0,0 +1,124 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::pb::NodeProto;
+
+pub fn topk_1(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    topk(ctx, node, Some(node.get_attr("k")?))
+}
+
+pub fn topk_10(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    topk(ctx, node, None)
+}
+
+fn topk(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+    k: Option<i64>,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let largest = node.get_attr_opt("largest")?.unwrap_or(1i64) == 1;
+    let sorted = node.get_attr_opt("sorted")?.unwrap_or(1i64) == 1;
+    Ok((expand(Topk::new(axis, k, largest, sorted, ctx.symbol_table.new_symbol("k"))), vec![]))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+struct Topk {
+    axis: i64,
+    k: Option<i64>,
+    largest: bool,
+    sorted: bool,
+    fallback_k: Symbol,
+}
+
+impl_dyn_hash!(Topk);
+
+impl Topk {
+    fn resolve_axis(&self, rank: i64) -> usize {
+        (if self.axis < 0 { self.axis + rank } else { self.axis }) as usize
+    }
+}
+
+impl Expansion for Topk {
+    fn name(&self) -> Cow<str> {
+        "Topk".into()
+    }
+
+    op_onnx!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let k = if let Some(k) = self.k {
+            model.add_const(format!("{}.k", prefix), rctensor0(k))?
+        } else {
+            let fact = model.outlet_fact(inputs[1])?;
+            if fact.rank() != 0 {
+                model.wire_node(
+                    format!("{}.k", prefix),
+                    tract_core::ops::change_axes::AxisOp::Rm(0),
+                    &[inputs[1]],
+                )?[0]
+            } else {
+                inputs[1]
+            }
+        };
+        let op = tract_core::ops::array::Topk {
+            axis: self.resolve_axis(rank as i64),
+            largest: self.largest,
+            sorted: self.sorted,
+            fallback_k: (&self.fallback_k).into(),
+        };
+        model.wire_node(prefix, op, &[inputs[0], k])
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, if self.k.is_some() { 1 } else { 2 })?;
+        check_output_arity(&outputs, 2)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&outputs[1].datum_type, i64::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].rank, &outputs[1].rank)?;
+        s.equals(&outputs[0].shape, &outputs[1].shape)?;
+        if self.k.is_none() {
+            s.equals(&inputs[1].rank, 1)?;
+            s.equals(&inputs[1].shape[0], 1.to_dim())?;
+        }
+        s.given(&inputs[0].rank, move |s, rank| {
+            let axis = self.resolve_axis(rank);
+            for ix in 0..rank as usize {
+                if ix != axis {
+                    s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+                }
+            }
+            if let Some(k) = self.k {
+                s.equals(&outputs[0].shape[axis], k.to_dim())?;
+            } else {
+                s.given(&inputs[1].value, move |s, value| {
+                    let k = value.cast_to::<i64>()?.as_slice::<i64>()?[0];
+                    s.equals(&outputs[0].shape[axis], k.to_dim())
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use crate::model::{optional_inputs, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+
+pub fn trilu(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let upper = node.get_attr_opt("upper")?.unwrap_or(1i64) == 1;
+    let optional_k_input = optional_inputs(node).nth(1).flatten();
+    Ok((expand(Trilu { upper, optional_k_input }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Trilu {
+    upper: bool,
+    optional_k_input: Option<usize>,
+}
+
+impl_dyn_hash!(Trilu);
+
+impl Expansion for Trilu {
+    fn name(&self) -> Cow<str> {
+        "Trilu".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1 + self.optional_k_input.is_some() as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        if let Some(k) = self.optional_k_input {
+            s.equals(&inputs[k].datum_type, i64::datum_type())?;
+            s.equals(&inputs[k].rank, 0)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let k = if let Some(k) = self.optional_k_input {
+            inputs[k]
+        } else {
+            model.add_const(format!("{}.k", prefix), rctensor0(0i64))?
+        };
+        model.wire_node(
+            prefix,
+            tract_hir::tract_core::ops::array::Trilu::new(self.upper),
+            &[inputs[0], k],
+        )
+    }
+}
@@ -1,4 +1,5 @@
-use crate::model::OnnxOpRegister;
+use crate::model::{OnnxOpRegister, ParseResult, ParsingContext};
+use crate::pb::*;
 use tract_hir::internal::*;
 use tract_hir::ops;
 
@@ -15,4 +16,144 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("GreaterOrEqual", |_, _| Ok((ops::logic::GreaterEqual.into_hir(), vec![])));
 
     reg.insert("Where", |_, _| Ok((Box::new(ops::logic::Iff::default()), vec![])));
+
+    reg.insert("If", if_then_else);
+}
+
+pub fn if_then_else(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let graph: &GraphProto = node.get_attr("then_branch")?;
+    let ParseResult { model: then_body, unresolved_inputs: then_closures, .. } =
+        ctx.parse_graph(graph)?;
+    let graph: &GraphProto = node.get_attr("else_branch")?;
+    let ParseResult { model: else_body, unresolved_inputs: else_closures, .. } =
+        ctx.parse_graph(graph)?;
+    let mut closures = then_closures.clone();
+    for closure in &else_closures {
+        if !closures.contains(closure) {
+            closures.push(closure.clone());
+        }
+    }
+    let then_input_mapping =
+        then_closures.iter().map(|c| 1 + closures.iter().position(|d| d == c).unwrap()).collect();
+    let else_input_mapping =
+        else_closures.iter().map(|c| 1 + closures.iter().position(|d| d == c).unwrap()).collect();
+    Ok((Box::new(If { then_body, then_input_mapping, else_body, else_input_mapping }), closures))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct If {
+    pub then_body: InferenceModel,
+    then_input_mapping: Vec<usize>,
+    pub else_body: InferenceModel,
+    else_input_mapping: Vec<usize>,
+}
+
+impl_dyn_hash!(If);
+
+impl Op for If {
+    fn name(&self) -> Cow<str> {
+        "If".into()
+    }
+
+    op_onnx!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for If {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let cond = inputs[0].cast_to_scalar::<bool>()?;
+        let (body, input_mapping) = if cond {
+            (&self.then_body, &self.then_input_mapping)
+        } else {
+            (&self.else_body, &self.else_input_mapping)
+        };
+        let inputs: TVec<Tensor> =
+            input_mapping.iter().map(|slot| inputs[*slot].clone().into_tensor()).collect();
+        SimplePlan::new(body)?.run(inputs)
+    }
+}
+
+impl InferenceOp for If {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        if outputs.len() != self.then_body.output_outlets()?.len()
+            || outputs.len() != self.else_body.output_outlets()?.len()
+        {
+            bail!("If node and its branches must have the same number of outputs");
+        }
+        inputs[0].datum_type.unify_with(&bool::datum_type().into())?;
+        loop {
+            let mut changed = false;
+            for (body, mapping) in &mut [
+                (&mut self.then_body, &self.then_input_mapping),
+                (&mut self.else_body, &self.else_input_mapping),
+            ] {
+                for (ix, slot) in mapping.iter().enumerate() {
+                    let inner = body.input_fact_mut(ix)?;
+                    changed |= inputs[*slot].datum_type.unify_with_mut(&mut inner.datum_type)?;
+                    changed |= inputs[*slot].shape.unify_with_mut(&mut inner.shape)?;
+                }
+                changed |= body.analyse(false).context("analysing If branch")?;
+            }
+            for (ix, output) in outputs.iter_mut().enumerate() {
+                let mut then_fact = self.then_body.output_fact(ix)?.clone();
+                let mut else_fact = self.else_body.output_fact(ix)?.clone();
+                changed |= output.datum_type.unify_with_mut(&mut then_fact.datum_type)?;
+                changed |= output.datum_type.unify_with_mut(&mut else_fact.datum_type)?;
+                // branches may legitimately produce different shapes
+                if let Ok(shape) = then_fact.shape.unify(&else_fact.shape) {
+                    changed |= output.shape.unify_with(&shape)?;
+                }
+                changed |= self.then_body.output_fact(ix)? != &then_fact;
+                changed |= self.else_body.output_fact(ix)? != &else_fact;
+                self.then_body.set_output_fact(ix, then_fact)?;
+                self.else_body.set_output_fact(ix, else_fact)?;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.then_body.output_outlets()?.len())
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let then_body = self.then_body.clone().into_typed().context("typing then branch")?;
+        let else_body = self.else_body.clone().into_typed().context("typing else branch")?;
+        target.wire_node(
+            &*node.name,
+            tract_core::ops::logic::IfThenElse {
+                then_body,
+                then_input_mapping: self.then_input_mapping.clone(),
+                else_body,
+                else_input_mapping: self.else_input_mapping.clone(),
+            },
+            &inputs,
+        )
+    }
+
+    as_op!();
 }
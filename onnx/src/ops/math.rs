@@ -27,7 +27,8 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Ceil", |_, _| Ok((Box::new(ops::math::ceil()), vec![])));
     reg.insert("Floor", |_, _| Ok((Box::new(ops::math::floor()), vec![])));
     reg.insert("Round", |_, _| Ok((Box::new(ops::math::round_half_to_even()), vec![])));
-    reg.insert("Clip", clip::clip);
+    reg.insert_versioned("Clip", 6..11, clip::clip_6);
+    reg.insert_versioned("Clip", 11.., clip::clip_11);
 
     reg.insert("Cos", |_, _| Ok((Box::new(ops::math::cos()), vec![])));
     reg.insert("Sin", |_, _| Ok((Box::new(ops::math::sin()), vec![])));
@@ -57,12 +58,21 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
 
     reg.insert("Pow", pow::pow);
 
+    reg.insert("Einsum", einsum);
     reg.insert("MatMul", |_, _| Ok((expand(ops::matmul::MatMulInference::default()), vec![])));
     reg.insert("MatMulInteger", mat_mul_integer::mat_mul_integer);
     reg.insert("QLinearMatMul", mat_mul_integer::q_linear_mat_mul);
     reg.insert("Gemm", gemm::gemm);
 }
 
+fn einsum(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let equation: &str = node.get_attr("equation")?;
+    Ok((expand(ops::einsum::EinSum::parse(equation)?), vec![]))
+}
+
 fn isinf(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -4,7 +4,7 @@ use tract_hir::internal::*;
 use tract_onnx_opl::ml::*;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
-    reg.insert("CategoryMapper", category_mapper);
+    reg.insert_with_domain("ai.onnx.ml", "CategoryMapper", .., category_mapper);
 }
 
 #[derive(Debug, Clone, Hash)]
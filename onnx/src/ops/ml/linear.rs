@@ -0,0 +1,250 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_ndarray::{concatenate, Array1, Array2, Axis};
+
+use super::{get_post_transform, get_vec_attr_opt, parse_class_data};
+use super::{wire_labels, wire_post_transform, PostTransform};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_with_domain("ai.onnx.ml", "LinearClassifier", .., linear_classifier);
+    reg.insert_with_domain("ai.onnx.ml", "LinearRegressor", .., linear_regressor);
+}
+
+/// Parse the coefficients as a [targets, features] matrix and the intercepts.
+fn parse_linear(node: &NodeProto, targets: usize) -> TractResult<(Array2<f32>, Array1<f32>)> {
+    let coefficients = node.get_attr_vec::<f32>("coefficients")?;
+    node.expect_attr("coefficients", coefficients.len() % targets == 0, || {
+        format!("a multiple of {} values, got {}", targets, coefficients.len())
+    })?;
+    let coefficients =
+        Array2::from_shape_vec((targets, coefficients.len() / targets), coefficients)?;
+    let intercepts = get_vec_attr_opt::<f32>(node, "intercepts", targets)?
+        .map(Array1::from)
+        .unwrap_or_else(|| Array1::zeros(targets));
+    Ok((coefficients, intercepts))
+}
+
+fn linear_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let class_labels = parse_class_data(node)?;
+    let targets = node
+        .get_attr_opt_slice::<f32>("intercepts")?
+        .map(|i| i.len())
+        .unwrap_or_else(|| class_labels.len());
+    let (mut coefficients, mut intercepts) = parse_linear(node, targets)?;
+    if targets == 1 && class_labels.len() == 2 {
+        // binary classifier with a single score: second class is scored s,
+        // first one is scored -s
+        coefficients = concatenate(Axis(0), &[(-&coefficients).view(), coefficients.view()])?;
+        intercepts = concatenate(Axis(0), &[(-&intercepts).view(), intercepts.view()])?;
+    }
+    node.expect(
+        coefficients.nrows() == class_labels.len(),
+        "as many coefficients rows as class labels",
+    )?;
+    let linear = Linear {
+        coefficients: coefficients.into_arc_tensor(),
+        intercepts: intercepts.into_arc_tensor(),
+        post_transform: get_post_transform(node)?,
+    };
+    Ok((expand(LinearClassifier { linear, class_labels }), vec![]))
+}
+
+fn linear_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let targets = node.get_attr_opt("targets")?.unwrap_or(1);
+    let (coefficients, intercepts) = parse_linear(node, targets)?;
+    let linear = Linear {
+        coefficients: coefficients.into_arc_tensor(),
+        intercepts: intercepts.into_arc_tensor(),
+        post_transform: get_post_transform(node)?,
+    };
+    Ok((expand(LinearRegressor { linear }), vec![]))
+}
+
+/// Scores as X·Cᵀ + intercepts, followed by post transform.
+#[derive(Debug, Clone, Hash)]
+pub struct Linear {
+    pub coefficients: Arc<Tensor>,
+    pub intercepts: Arc<Tensor>,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl Linear {
+    fn targets(&self) -> usize {
+        self.coefficients.shape()[0]
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        scores: &'p TensorProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], self.coefficients.shape()[1].to_dim())?;
+        s.equals(&scores.datum_type, DatumType::F32)?;
+        s.equals(&scores.rank, 2)?;
+        s.equals(&scores.shape[0], &inputs[0].shape[0])?;
+        s.equals(&scores.shape[1], self.targets().to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let input = model.wire_node(
+            format!("{}.cast", prefix),
+            tract_core::ops::cast::cast(f32::datum_type()),
+            inputs,
+        )?;
+        let scores = model.wire_node(
+            format!("{}.matmul", prefix),
+            tract_core::ops::matmul::MatMulUnary::new(self.coefficients.clone(), false, true, true),
+            &input,
+        )?;
+        let scores = model.wire_node(
+            format!("{}.intercepts", prefix),
+            tract_core::ops::math::add::unary(
+                (*self.intercepts).clone().broadcast_into_rank(2)?.into_arc_tensor(),
+            ),
+            &scores,
+        )?;
+        wire_post_transform(prefix, model, scores, self.post_transform)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct LinearClassifier {
+    pub linear: Linear,
+    pub class_labels: Arc<Tensor>,
+}
+
+impl_dyn_hash!(LinearClassifier);
+
+impl Expansion for LinearClassifier {
+    fn name(&self) -> Cow<str> {
+        "LinearClassifier".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 2)?;
+        s.equals(&outputs[0].datum_type, self.class_labels.datum_type())?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        self.linear.rules(s, inputs, &outputs[1])
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let scores = self.linear.wire(prefix, model, inputs)?;
+        let labels = wire_labels(prefix, model, scores[0], &self.class_labels)?;
+        Ok(tvec!(labels, scores[0]))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct LinearRegressor {
+    pub linear: Linear,
+}
+
+impl_dyn_hash!(LinearRegressor);
+
+impl Expansion for LinearRegressor {
+    fn name(&self) -> Cow<str> {
+        "LinearRegressor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        self.linear.rules(s, inputs, &outputs[0])
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        self.linear.wire(prefix, model, inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use tract_hir::internal::*;
+
+    #[test]
+    fn linear_classifier() -> TractResult<()> {
+        let attributes = vec![
+            ints("classlabels_int64s", &[10, 20, 30]),
+            floats("coefficients", &[1., 0., 0., 1., -1., -1.]),
+            floats("intercepts", &[0., 0., 0.5]),
+        ];
+        let x = tensor2(&[[2f32, 1.], [-1., -2.]]);
+        let outputs = run("LinearClassifier", attributes, &["label", "scores"], x)?;
+        assert_eq!(*outputs[0], tensor1(&[10i64, 30]));
+        assert_eq!(*outputs[1], tensor2(&[[2f32, 1., -2.5], [-1., -2., 3.5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn binary_linear_classifier() -> TractResult<()> {
+        let attributes = vec![
+            ints("classlabels_int64s", &[0, 1]),
+            floats("coefficients", &[1., -1.]),
+            floats("intercepts", &[0.5]),
+        ];
+        let x = tensor2(&[[2f32, 1.], [0., 1.]]);
+        let outputs = run("LinearClassifier", attributes, &["label", "scores"], x)?;
+        assert_eq!(*outputs[0], tensor1(&[1i64, 0]));
+        assert_eq!(*outputs[1], tensor2(&[[-1.5f32, 1.5], [0.5, -0.5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn linear_regressor() -> TractResult<()> {
+        let attributes = vec![
+            int("targets", 2),
+            floats("coefficients", &[1., 2., 3., 4.]),
+            floats("intercepts", &[1., -1.]),
+        ];
+        let x = tensor2(&[[1f32, 1.]]);
+        let outputs = run("LinearRegressor", attributes, &["y"], x)?;
+        assert_eq!(*outputs[0], tensor2(&[[4f32, 6.]]));
+        Ok(())
+    }
+}
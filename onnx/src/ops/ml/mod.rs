@@ -1,9 +1,229 @@
 mod category_mapper;
+mod linear;
+mod one_hot_encoder;
+mod preprocessing;
+mod svm;
 mod tree_ensemble_classifier;
+mod tree_ensemble_regressor;
 
 use crate::model::OnnxOpRegister;
+use crate::pb::NodeProto;
+use crate::pb_helpers::*;
+use tract_hir::internal::*;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     category_mapper::register_all_ops(reg);
+    linear::register_all_ops(reg);
+    one_hot_encoder::register_all_ops(reg);
+    preprocessing::register_all_ops(reg);
+    svm::register_all_ops(reg);
     tree_ensemble_classifier::register_all_ops(reg);
+    tree_ensemble_regressor::register_all_ops(reg);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PostTransform {
+    Softmax,
+    Logistic,
+    // SoftmaxZero,
+    // Probit, // probit, especially multinomial, is p.i.t.a. - so let's ignore it for now
+}
+
+pub fn parse_post_transform(s: &str) -> TractResult<Option<PostTransform>> {
+    match s {
+        "NONE" => Ok(None),
+        "SOFTMAX" => Ok(Some(PostTransform::Softmax)),
+        "LOGISTIC" => Ok(Some(PostTransform::Logistic)),
+        "PROBIT" | "SOFTMAX_ZERO" => bail!("PROBIT and SOFTMAX_ZERO unsupported"),
+        _ => bail!("Invalid post transform: {}", s),
+    }
+}
+
+fn get_post_transform(node: &NodeProto) -> TractResult<Option<PostTransform>> {
+    Ok(node.get_attr_opt("post_transform")?.map(parse_post_transform).transpose()?.unwrap_or(None))
+}
+
+fn get_vec_attr<'a, T>(node: &'a NodeProto, attr: &str, n: usize) -> TractResult<Vec<T>>
+where
+    T: AttrTVecType<'a>,
+{
+    let vec = node.get_attr_vec(attr)?;
+    node.expect_attr(attr, vec.len() == n, || format!("length {}, got {}", vec.len(), n))?;
+    Ok(vec)
+}
+
+fn get_vec_attr_opt<'a, T>(node: &'a NodeProto, attr: &str, n: usize) -> TractResult<Option<Vec<T>>>
+where
+    T: AttrTVecType<'a>,
+{
+    match node.get_attr_opt_vec(attr)? {
+        Some(vec) => {
+            node.expect_attr(attr, vec.len() == n, || {
+                format!("length {} (or undefined), got {}", vec.len(), n)
+            })?;
+            Ok(Some(vec))
+        }
+        None => Ok(None),
+    }
+}
+
+fn parse_class_data(node: &NodeProto) -> TractResult<Arc<Tensor>> {
+    // parse n_classes from protobuf
+    let ints = node.get_attr_opt_slice::<i64>("classlabels_int64s")?;
+    let strs = node.get_attr_opt_tvec::<&str>("classlabels_strings")?;
+    match (ints, strs) {
+        (Some(n), None) => Ok(rctensor1(n)),
+        (None, Some(n)) => Ok(rctensor1(&n.iter().map(|d| d.to_string()).collect::<Vec<_>>())),
+        (None, None) => {
+            bail!("cannot find neither 'classlabels_int64s' not 'classlabels_strings'")
+        }
+        (Some(_), Some(_)) => {
+            bail!("only one of 'classlabels_int64s' and 'classlabels_strings' can be set")
+        }
+    }
+}
+
+fn wire_post_transform(
+    prefix: &str,
+    model: &mut TypedModel,
+    scores: TVec<OutletId>,
+    post_transform: Option<PostTransform>,
+) -> TractResult<TVec<OutletId>> {
+    match post_transform {
+        None => Ok(scores),
+        Some(PostTransform::Softmax) => tract_hir::ops::nn::LayerSoftmax::new(1).wire(
+            &format!("{}.softmax", prefix),
+            model,
+            &scores,
+        ),
+        Some(PostTransform::Logistic) => model.wire_node(
+            &format!("{}.logistic", prefix),
+            tract_core::ops::nn::sigmoid(),
+            &scores,
+        ),
+    }
+}
+
+/// Labels of the best scoring classes.
+fn wire_labels(
+    prefix: &str,
+    model: &mut TypedModel,
+    scores: OutletId,
+    class_labels: &Arc<Tensor>,
+) -> TractResult<OutletId> {
+    use tract_core::ops::nn::*;
+    let winners = model.wire_node(
+        format!("{}.argmax", prefix),
+        Reduce::new(tvec!(1), Reducer::ArgMax(false)),
+        &[scores],
+    )?;
+    let reduced = model.wire_node(
+        format!("{}.rm_axis", prefix),
+        tract_core::ops::change_axes::AxisOp::Rm(1),
+        &winners,
+    )?;
+    let casted = model.wire_node(
+        format!("{}.casted", prefix),
+        tract_core::ops::cast::cast(i32::datum_type()),
+        &reduced,
+    )?;
+    wire_lookup(prefix, model, casted[0], class_labels)
+}
+
+/// Labels from class indexes.
+fn wire_lookup(
+    prefix: &str,
+    model: &mut TypedModel,
+    indexes: OutletId,
+    class_labels: &Arc<Tensor>,
+) -> TractResult<OutletId> {
+    Ok(model.wire_node(
+        format!("{}.labels", prefix),
+        tract_onnx_opl::ml::DirectLookup::new(
+            class_labels.clone(),
+            Tensor::zero_dt(class_labels.datum_type(), &[])?.into_arc_tensor(),
+        )?,
+        &[indexes],
+    )?[0])
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::pb::attribute_proto::AttributeType;
+    use crate::pb::*;
+    use tract_hir::internal::*;
+
+    fn attr(name: &str, r#type: AttributeType) -> AttributeProto {
+        AttributeProto { name: name.into(), r#type: r#type as i32, ..AttributeProto::default() }
+    }
+
+    pub fn int(name: &str, i: i64) -> AttributeProto {
+        AttributeProto { i, ..attr(name, AttributeType::Int) }
+    }
+
+    pub fn string(name: &str, s: &str) -> AttributeProto {
+        AttributeProto { s: s.as_bytes().to_vec(), ..attr(name, AttributeType::String) }
+    }
+
+    pub fn ints(name: &str, ints: &[i64]) -> AttributeProto {
+        AttributeProto { ints: ints.to_vec(), ..attr(name, AttributeType::Ints) }
+    }
+
+    pub fn floats(name: &str, floats: &[f32]) -> AttributeProto {
+        AttributeProto { floats: floats.to_vec(), ..attr(name, AttributeType::Floats) }
+    }
+
+    pub fn strings(name: &str, strings: &[&str]) -> AttributeProto {
+        let strings = strings.iter().map(|s| s.as_bytes().to_vec()).collect();
+        AttributeProto { strings, ..attr(name, AttributeType::Strings) }
+    }
+
+    /// Run a model made of a single ai.onnx.ml node, reading "x" and writing `outputs`.
+    pub fn run(
+        op_type: &str,
+        attribute: Vec<AttributeProto>,
+        outputs: &[&str],
+        x: Tensor,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let node = NodeProto {
+            op_type: op_type.into(),
+            domain: "ai.onnx.ml".into(),
+            input: vec!["x".into()],
+            output: outputs.iter().map(|s| s.to_string()).collect(),
+            attribute,
+            ..NodeProto::default()
+        };
+        let value = |name: &str| ValueInfoProto {
+            name: name.into(),
+            r#type: Some(TypeProto::default()),
+            ..ValueInfoProto::default()
+        };
+        let input = ValueInfoProto {
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                    elem_type: tensor_proto::DataType::Float as i32,
+                    shape: None,
+                })),
+                ..TypeProto::default()
+            }),
+            ..value("x")
+        };
+        let proto = ModelProto {
+            opset_import: vec![
+                OperatorSetIdProto { domain: "".into(), version: 11 },
+                OperatorSetIdProto { domain: "ai.onnx.ml".into(), version: 1 },
+            ],
+            graph: Some(GraphProto {
+                node: vec![node],
+                input: vec![input],
+                output: outputs.iter().map(|o| value(o)).collect(),
+                ..GraphProto::default()
+            }),
+            ..ModelProto::default()
+        };
+        let model = crate::onnx().model_for_proto_model(&proto)?;
+        let model =
+            model.with_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), x.shape()))?;
+        model.into_runnable()?.run(tvec!(x))
+    }
 }
@@ -0,0 +1,94 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_with_domain("ai.onnx.ml", "OneHotEncoder", .., one_hot_encoder);
+}
+
+fn one_hot_encoder(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let ints = node.get_attr_opt_slice::<i64>("cats_int64s")?;
+    let strs = node.get_attr_opt_tvec::<&str>("cats_strings")?;
+    let categories = match (ints, strs) {
+        (Some(ints), None) => rctensor1(ints),
+        (None, Some(strs)) => rctensor1(&strs.iter().map(|s| s.to_string()).collect::<Vec<_>>()),
+        _ => bail!("exactly one of cats_int64s and cats_strings must be set"),
+    };
+    // zeros=0 asks for an error on unknown categories: tract encodes them as
+    // all zeros in both cases
+    Ok((expand(OneHotEncoder { categories }), vec![]))
+}
+
+/// One hot encoding of categories, on a new last axis. Unknown categories are
+/// encoded as zeros.
+#[derive(Debug, Clone, Hash)]
+pub struct OneHotEncoder {
+    pub categories: Arc<Tensor>,
+}
+
+impl_dyn_hash!(OneHotEncoder);
+
+impl Expansion for OneHotEncoder {
+    fn name(&self) -> Cow<str> {
+        "OneHotEncoder".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            let rank = rank as usize;
+            for axis in 0..rank {
+                s.equals(&outputs[0].shape[axis], &inputs[0].shape[axis])?;
+            }
+            s.equals(&outputs[0].shape[rank], self.categories.len().to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let n = self.categories.len();
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let mut wire = inputs[0];
+        if fact.datum_type != self.categories.datum_type() {
+            wire = model.wire_node(
+                format!("{}.cast", prefix),
+                tract_core::ops::cast::cast(self.categories.datum_type()),
+                &[wire],
+            )?[0];
+        }
+        // unknown categories go to an extra column, dropped after encoding
+        let indexes = model.wire_node(
+            format!("{}.indexes", prefix),
+            tract_onnx_opl::ml::ReverseLookup::new(self.categories.clone(), n as i32)?,
+            &[wire],
+        )?;
+        let one_hot = model.wire_node(
+            format!("{}.one_hot", prefix),
+            tract_core::ops::array::OneHot {
+                axis: fact.rank(),
+                dim: n + 1,
+                off: rctensor0(0f32),
+                on: rctensor0(1f32),
+            },
+            &indexes,
+        )?;
+        model.wire_node(prefix, tract_core::ops::array::Slice::new(fact.rank(), 0, n), &one_hot)
+    }
+}
@@ -0,0 +1,318 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_hir::ops;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_with_domain("ai.onnx.ml", "ArrayFeatureExtractor", .., |_, _| {
+        Ok((expand(ops::array::Gather::new(-1)), vec![]))
+    });
+    reg.insert_with_domain("ai.onnx.ml", "Binarizer", .., binarizer);
+    reg.insert_with_domain("ai.onnx.ml", "Imputer", .., imputer);
+    reg.insert_with_domain("ai.onnx.ml", "Normalizer", .., normalizer);
+    reg.insert_with_domain("ai.onnx.ml", "Scaler", .., scaler);
+    // tract has no map type: ZipMap lets the probabilities through, keys are
+    // the class labels, in order
+    reg.insert_with_domain("ai.onnx.ml", "ZipMap", .., |_, _| {
+        Ok((Box::new(ops::identity::Identity::default()), vec![]))
+    });
+}
+
+fn binarizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let threshold = node.get_attr_opt("threshold")?.unwrap_or(0f32);
+    Ok((expand(Binarizer { threshold }), vec![]))
+}
+
+fn imputer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let floats = node.get_attr_opt_vec::<f32>("imputed_value_floats")?;
+    let ints = node.get_attr_opt_vec::<i64>("imputed_value_int64s")?;
+    let (imputed, replaced) = match (floats, ints) {
+        (Some(floats), None) => {
+            let replaced = node.get_attr_opt("replaced_value_float")?.unwrap_or(0f32);
+            (rctensor1(&floats), rctensor0(replaced))
+        }
+        (None, Some(ints)) => {
+            let replaced = node.get_attr_opt("replaced_value_int64")?.unwrap_or(0i64);
+            (rctensor1(&ints), rctensor0(replaced))
+        }
+        _ => bail!("exactly one of imputed_value_floats and imputed_value_int64s must be set"),
+    };
+    Ok((expand(Imputer { imputed, replaced }), vec![]))
+}
+
+fn normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let norm = match node.get_attr_opt("norm")?.unwrap_or("MAX") {
+        "MAX" => Norm::Max,
+        "L1" => Norm::L1,
+        "L2" => Norm::L2,
+        other => bail!("Invalid norm: {}", other),
+    };
+    Ok((expand(Normalizer { norm }), vec![]))
+}
+
+fn scaler(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let offset = node.get_attr_opt_vec::<f32>("offset")?.unwrap_or_else(|| vec![0.0]);
+    let scale = node.get_attr_opt_vec::<f32>("scale")?.unwrap_or_else(|| vec![1.0]);
+    Ok((expand(Scaler { offset: rctensor1(&offset), scale: rctensor1(&scale) }), vec![]))
+}
+
+fn wire_cast_f32(
+    prefix: &str,
+    model: &mut TypedModel,
+    inputs: &[OutletId],
+) -> TractResult<TVec<OutletId>> {
+    model.wire_node(
+        format!("{}.cast", prefix),
+        tract_core::ops::cast::cast(f32::datum_type()),
+        inputs,
+    )
+}
+
+/// Y = (X - offset) * scale, as floats. Offset and scale are given per feature
+/// (last axis), or for all features at once.
+#[derive(Debug, Clone, Hash)]
+pub struct Scaler {
+    pub offset: Arc<Tensor>,
+    pub scale: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Scaler);
+
+impl Expansion for Scaler {
+    fn name(&self) -> Cow<str> {
+        "Scaler".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_core::ops::math;
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let wire = wire_cast_f32(prefix, model, inputs)?;
+        let minus_offset = self.offset.to_array_view::<f32>()?.mapv(|x| -x).into_tensor();
+        let wire = model.wire_node(
+            format!("{}.offset", prefix),
+            math::add::unary(minus_offset.broadcast_into_rank(rank)?.into_arc_tensor()),
+            &wire,
+        )?;
+        model.wire_node(
+            format!("{}.scale", prefix),
+            math::mul::unary((*self.scale).clone().broadcast_into_rank(rank)?.into_arc_tensor()),
+            &wire,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Norm {
+    Max,
+    L1,
+    L2,
+}
+
+/// Normalize each row (last axis) by its max, L1 norm or L2 norm.
+#[derive(Debug, Clone, Hash)]
+pub struct Normalizer {
+    pub norm: Norm,
+}
+
+impl_dyn_hash!(Normalizer);
+
+impl Expansion for Normalizer {
+    fn name(&self) -> Cow<str> {
+        "Normalizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_core::ops::math;
+        use tract_core::ops::nn::{Reduce, Reducer};
+        let axis = model.outlet_fact(inputs[0])?.rank() - 1;
+        let input = wire_cast_f32(prefix, model, inputs)?;
+        let mut norm = input.clone();
+        match self.norm {
+            Norm::Max => (),
+            Norm::L1 => norm = model.wire_node(format!("{}.abs", prefix), math::abs(), &norm)?,
+            Norm::L2 => {
+                norm = model.wire_node(format!("{}.square", prefix), math::square(), &norm)?
+            }
+        }
+        let reducer = if self.norm == Norm::Max { Reducer::Max } else { Reducer::Sum };
+        norm = model.wire_node(
+            format!("{}.reduce", prefix),
+            Reduce::new(tvec!(axis), reducer),
+            &norm,
+        )?;
+        if self.norm == Norm::L2 {
+            norm = model.wire_node(format!("{}.sqrt", prefix), math::sqrt(), &norm)?;
+        }
+        model.wire_node(prefix, math::div::bin_typed(), &[input[0], norm[0]])
+    }
+}
+
+/// Y = X > threshold ? 1 : 0, in the input type.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct Binarizer {
+    #[educe(Hash(method = "hash_f32"))]
+    pub threshold: f32,
+}
+
+impl_dyn_hash!(Binarizer);
+
+impl Expansion for Binarizer {
+    fn name(&self) -> Cow<str> {
+        "Binarizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let threshold = tensor0(self.threshold)
+            .cast_to_dt(fact.datum_type)?
+            .into_owned()
+            .broadcast_into_rank(fact.rank())?;
+        // unary ops take the constant as first operand: threshold < X
+        let wire = model.wire_node(
+            format!("{}.greater", prefix),
+            tract_core::ops::logic::lesser::unary(threshold.into_arc_tensor()),
+            inputs,
+        )?;
+        model.wire_node(prefix, tract_core::ops::cast::cast(fact.datum_type), &wire)
+    }
+}
+
+/// Replace the missing values (`replaced`, or NaN) with the per feature (last
+/// axis) or global `imputed` values.
+#[derive(Debug, Clone, Hash)]
+pub struct Imputer {
+    pub imputed: Arc<Tensor>,
+    pub replaced: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Imputer);
+
+impl Expansion for Imputer {
+    fn name(&self) -> Cow<str> {
+        "Imputer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let replaced_is_nan = self.replaced.to_scalar::<f32>().map(|r| r.is_nan()).unwrap_or(false);
+        let missing = if replaced_is_nan {
+            let input = wire_cast_f32(prefix, model, inputs)?;
+            model.wire_node(
+                format!("{}.is_nan", prefix),
+                tract_onnx_opl::is_nan::is_nan(),
+                &input,
+            )?
+        } else {
+            let replaced = self
+                .replaced
+                .cast_to_dt(fact.datum_type)?
+                .into_owned()
+                .broadcast_into_rank(fact.rank())?;
+            model.wire_node(
+                format!("{}.is_missing", prefix),
+                tract_core::ops::logic::equals::unary(replaced.into_arc_tensor()),
+                inputs,
+            )?
+        };
+        let imputed = self
+            .imputed
+            .cast_to_dt(fact.datum_type)?
+            .into_owned()
+            .broadcast_into_rank(fact.rank())?;
+        let imputed = model.add_const(format!("{}.imputed", prefix), imputed)?;
+        model.wire_node(prefix, tract_core::ops::logic::Iff, &[missing[0], imputed, inputs[0]])
+    }
+}
@@ -0,0 +1,202 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::svm::{Kernel, KernelType};
+
+use super::{get_post_transform, get_vec_attr, get_vec_attr_opt, parse_class_data};
+use super::{wire_lookup, wire_post_transform, PostTransform};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_with_domain("ai.onnx.ml", "SVMClassifier", .., svm_classifier);
+    reg.insert_with_domain("ai.onnx.ml", "SVMRegressor", .., svm_regressor);
+}
+
+fn parse_kernel(node: &NodeProto) -> TractResult<Kernel> {
+    let kernel_type = KernelType::parse(node.get_attr_opt("kernel_type")?.unwrap_or("LINEAR"))?;
+    let params = get_vec_attr_opt::<f32>(node, "kernel_params", 3)?.unwrap_or_else(|| vec![0.0; 3]);
+    Ok(Kernel { kernel_type, gamma: params[0], coef0: params[1], degree: params[2] as i32 })
+}
+
+/// Reshape a flat attribute as a matrix of `rows` rows.
+fn get_matrix_attr(node: &NodeProto, attr: &str, rows: usize) -> TractResult<Arc<Tensor>> {
+    let values = node.get_attr_vec::<f32>(attr)?;
+    node.expect_attr(attr, rows > 0 && values.len() % rows == 0, || {
+        format!("a multiple of {} values, got {}", rows, values.len())
+    })?;
+    let cols = values.len() / rows;
+    Ok(tensor1(&values).into_shape(&[rows, cols])?.into_arc_tensor())
+}
+
+fn svm_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let class_labels = parse_class_data(node)?;
+    let n_classes = class_labels.len();
+    let kernel = parse_kernel(node)?;
+    let vectors_per_class = node
+        .get_attr_opt_vec::<usize>("vectors_per_class")?
+        .filter(|v| v.iter().sum::<usize>() > 0);
+    let (vectors, coefficients, n_rho) = if let Some(vectors_per_class) = &vectors_per_class {
+        node.expect_attr("vectors_per_class", vectors_per_class.len() == n_classes, || {
+            format!("one value per class ({}), got {}", n_classes, vectors_per_class.len())
+        })?;
+        let n_vectors = vectors_per_class.iter().sum();
+        let vectors = get_matrix_attr(node, "support_vectors", n_vectors)?;
+        let coefficients = get_matrix_attr(node, "coefficients", n_classes - 1)?;
+        node.expect_attr("coefficients", coefficients.shape()[1] == n_vectors, || {
+            format!("{} coefficients per class, got {}", n_vectors, coefficients.shape()[1])
+        })?;
+        (vectors, coefficients, n_classes * (n_classes - 1) / 2)
+    } else {
+        // linear mode: the coefficients are the weights of each class
+        let coefficients = get_matrix_attr(node, "coefficients", n_classes)?;
+        (coefficients.clone(), coefficients, 1)
+    };
+    let rho = rctensor1(&get_vec_attr::<f32>(node, "rho", n_rho)?);
+    let probabilities = if vectors_per_class.is_some() {
+        let prob_a = get_vec_attr_opt::<f32>(node, "prob_a", n_rho)?;
+        let prob_b = get_vec_attr_opt::<f32>(node, "prob_b", n_rho)?;
+        prob_a.zip(prob_b).map(|(a, b)| (rctensor1(&a), rctensor1(&b)))
+    } else {
+        None
+    };
+    let op = tract_onnx_opl::ml::SvmClassifier {
+        kernel,
+        n_classes,
+        vectors,
+        vectors_per_class: vectors_per_class.map(|v| v.into()),
+        coefficients,
+        rho,
+        probabilities,
+    };
+    Ok((
+        expand(SvmClassifier { op, class_labels, post_transform: get_post_transform(node)? }),
+        vec![],
+    ))
+}
+
+fn svm_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let kernel = parse_kernel(node)?;
+    let n_supports = node.get_attr_opt("n_supports")?.unwrap_or(0usize);
+    let (vectors, coefficients) = if n_supports > 0 {
+        let vectors = get_matrix_attr(node, "support_vectors", n_supports)?;
+        let coefficients = rctensor1(&get_vec_attr::<f32>(node, "coefficients", n_supports)?);
+        (vectors, coefficients)
+    } else {
+        // linear mode: the coefficients are the weights of the features
+        (get_matrix_attr(node, "coefficients", 1)?, rctensor1(&[1f32]))
+    };
+    let rho = node.get_attr_opt_slice::<f32>("rho")?.and_then(|rho| rho.first().copied());
+    let op = tract_onnx_opl::ml::SvmRegressor {
+        kernel,
+        vectors,
+        coefficients,
+        rho: rho.unwrap_or(0.0),
+        one_class: node.get_attr_opt("one_class")?.unwrap_or(false),
+    };
+    Ok((expand(SvmRegressor { op, post_transform: get_post_transform(node)? }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct SvmClassifier {
+    pub op: tract_onnx_opl::ml::SvmClassifier,
+    pub class_labels: Arc<Tensor>,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(SvmClassifier);
+
+impl Expansion for SvmClassifier {
+    fn name(&self) -> Cow<str> {
+        "SVMClassifier".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 2)?;
+
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], self.op.vectors.shape()[1].to_dim())?;
+        s.equals(&outputs[0].datum_type, self.class_labels.datum_type())?;
+        s.equals(&outputs[1].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.equals(&outputs[1].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[1].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[1].shape[1], self.op.n_scores().to_dim())?;
+
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let svm = model.wire_node(format!("{}.svm", prefix), self.op.clone(), inputs)?;
+        let scores = wire_post_transform(prefix, model, tvec!(svm[1]), self.post_transform)?;
+        let labels = wire_lookup(prefix, model, svm[0], &self.class_labels)?;
+        Ok(tvec!(labels, scores[0]))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct SvmRegressor {
+    pub op: tract_onnx_opl::ml::SvmRegressor,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(SvmRegressor);
+
+impl Expansion for SvmRegressor {
+    fn name(&self) -> Cow<str> {
+        "SVMRegressor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], self.op.vectors.shape()[1].to_dim())?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], 1.to_dim())?;
+
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let scores = model.wire_node(format!("{}.svm", prefix), self.op.clone(), inputs)?;
+        wire_post_transform(prefix, model, scores, self.post_transform)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::tree::*;
+
+use super::tree_ensemble_classifier::parse_nodes_data;
+use super::{get_post_transform, get_vec_attr_opt, wire_post_transform, PostTransform};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_with_domain("ai.onnx.ml", "TreeEnsembleRegressor", .., tree_regressor);
+}
+
+fn tree_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let ensemble = parse_nodes_data(node, false)?;
+    let base_values =
+        get_vec_attr_opt::<f32>(node, "base_values", ensemble.n_classes())?.map(|t| rctensor1(&t));
+    let post_transform = get_post_transform(node)?;
+    Ok((expand(TreeEnsembleRegressor { ensemble, base_values, post_transform }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct TreeEnsembleRegressor {
+    pub ensemble: TreeEnsemble,
+    pub base_values: Option<Arc<Tensor>>,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(TreeEnsembleRegressor);
+
+impl Expansion for TreeEnsembleRegressor {
+    fn name(&self) -> Cow<str> {
+        "TreeEnsembleRegressor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], &self.ensemble.n_classes().to_dim())?;
+
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        // the classifier op only sums up (or aggregates) the leaves weights
+        // per target: it works as well for regression
+        let mut scores = model.wire_node(
+            format!("{}.regressor", prefix),
+            tract_onnx_opl::ml::tree_ensemble_classifier::TreeEnsembleClassifier {
+                ensemble: self.ensemble.clone(),
+            },
+            inputs,
+        )?;
+        if let Some(base_values) = self.base_values.as_deref() {
+            scores = model.wire_node(
+                format!("{}.base_values", prefix),
+                tract_core::ops::math::add::unary(
+                    base_values.clone().broadcast_into_rank(2)?.into_arc_tensor(),
+                ),
+                &scores,
+            )?;
+        }
+        wire_post_transform(prefix, model, scores, self.post_transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use tract_hir::internal::*;
+
+    // tree 0 splits on x <= 0.5 (leaves 2 and 4), tree 1 is a single leaf (6)
+    fn regressor(aggregate_function: &str) -> TractResult<Arc<Tensor>> {
+        let attributes = vec![
+            int("n_targets", 1),
+            string("aggregate_function", aggregate_function),
+            ints("nodes_treeids", &[0, 0, 0, 1]),
+            ints("nodes_nodeids", &[0, 1, 2, 0]),
+            ints("nodes_featureids", &[0, 0, 0, 0]),
+            strings("nodes_modes", &["BRANCH_LEQ", "LEAF", "LEAF", "LEAF"]),
+            floats("nodes_values", &[0.5, 0., 0., 0.]),
+            ints("nodes_truenodeids", &[1, 0, 0, 0]),
+            ints("nodes_falsenodeids", &[2, 0, 0, 0]),
+            ints("target_treeids", &[0, 0, 1]),
+            ints("target_nodeids", &[1, 2, 0]),
+            ints("target_ids", &[0, 0, 0]),
+            floats("target_weights", &[2., 4., 6.]),
+        ];
+        let x = tensor2(&[[0f32], [1.]]);
+        Ok(run("TreeEnsembleRegressor", attributes, &["y"], x)?.remove(0))
+    }
+
+    #[test]
+    fn regressor_sum() -> TractResult<()> {
+        assert_eq!(*regressor("SUM")?, tensor2(&[[8f32], [10.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn regressor_average() -> TractResult<()> {
+        assert_eq!(*regressor("AVERAGE")?, tensor2(&[[4f32], [5.]]));
+        Ok(())
+    }
+}
@@ -20,12 +20,15 @@ mod nn;
 mod quant;
 pub mod rec;
 mod resize;
+mod sequence;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Cast", cast::cast);
     reg.insert("Constant", konst);
     reg.insert("Identity", |_, _| Ok((Box::new(ops::identity::Identity::default()), vec![])));
-    reg.insert("Resize", resize::resize);
+    reg.insert_versioned("Resize", 10..11, resize::resize_10);
+    reg.insert_versioned("Resize", 11.., resize::resize_11);
+    reg.insert("Upsample", resize::upsample);
     array::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
@@ -33,6 +36,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     nn::register_all_ops(reg);
     quant::register_all_ops(reg);
     rec::register_all_ops(reg);
+    sequence::register_all_ops(reg);
 }
 
 fn konst(
@@ -0,0 +1,232 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_core::ops::nn::{BoxRepr, RoiAlignMode};
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("MaxRoiPool", max_roi_pool);
+    reg.insert_versioned("NonMaxSuppression", 10.., non_max_suppression);
+    reg.insert_versioned("RoiAlign", 10..16, |_, node| roi_align(node, false));
+    reg.insert_versioned("RoiAlign", 16.., |_, node| {
+        let half_pixel = match node.get_attr_opt("coordinate_transformation_mode")? {
+            None | Some("half_pixel") => true,
+            Some("output_half_pixel") => false,
+            Some(other) => bail!("Unsupported coordinate transformation mode {}", other),
+        };
+        roi_align(node, half_pixel)
+    });
+}
+
+fn non_max_suppression(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let box_repr = if node.get_attr_opt("center_point_box")?.unwrap_or(0i64) == 1 {
+        BoxRepr::CenterWidthHeight
+    } else {
+        BoxRepr::TwoPoints
+    };
+    let mut options = optional_inputs(node).skip(2);
+    let op = NonMaxSuppression {
+        box_repr,
+        optional_max_output_boxes_per_class_input: options.next().unwrap(),
+        optional_iou_threshold_input: options.next().unwrap(),
+        optional_score_threshold_input: options.next().unwrap(),
+        num_selected_indices_symbol: ctx.symbol_table.new_symbol("n"),
+    };
+    Ok((expand(op), vec![]))
+}
+
+fn roi_align(
+    node: &NodeProto,
+    half_pixel: bool,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let mode = match node.get_attr_opt("mode")?.unwrap_or("avg") {
+        "avg" => RoiAlignMode::Avg,
+        "max" => RoiAlignMode::Max,
+        other => bail!("Unsupported RoiAlign mode {}", other),
+    };
+    let op = tract_core::ops::nn::RoiAlign {
+        mode,
+        output_height: node.get_attr_opt("output_height")?.unwrap_or(1),
+        output_width: node.get_attr_opt("output_width")?.unwrap_or(1),
+        sampling_ratio: node.get_attr_opt("sampling_ratio")?.unwrap_or(0),
+        spatial_scale: node.get_attr_opt("spatial_scale")?.unwrap_or(1.0),
+        half_pixel,
+    };
+    Ok((expand(RoiAlign(op)), vec![]))
+}
+
+fn max_roi_pool(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let pooled_shape: TVec<usize> = node.get_attr_tvec("pooled_shape")?;
+    node.expect_attr("pooled_shape", pooled_shape.len() == 2, "two values")?;
+    let op = tract_core::ops::nn::MaxRoiPool {
+        pooled_height: pooled_shape[0],
+        pooled_width: pooled_shape[1],
+        spatial_scale: node.get_attr_opt("spatial_scale")?.unwrap_or(1.0),
+    };
+    Ok((expand(MaxRoiPool(op)), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct NonMaxSuppression {
+    box_repr: BoxRepr,
+    optional_max_output_boxes_per_class_input: Option<usize>,
+    optional_iou_threshold_input: Option<usize>,
+    optional_score_threshold_input: Option<usize>,
+    num_selected_indices_symbol: Symbol,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl Expansion for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        "NonMaxSuppression".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            &inputs,
+            2 + self.optional_max_output_boxes_per_class_input.is_some() as usize
+                + self.optional_iou_threshold_input.is_some() as usize
+                + self.optional_score_threshold_input.is_some() as usize,
+        )?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 3)?;
+        s.equals(&inputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&inputs[0].shape[1], &inputs[1].shape[2])?;
+        s.equals(&inputs[0].shape[2], 4.to_dim())?;
+        s.equals(&outputs[0].datum_type, i64::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], self.num_selected_indices_symbol.to_dim())?;
+        s.equals(&outputs[0].shape[1], 3.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut wires: TVec<OutletId> = inputs[0..2].into();
+        // missing inputs: no box selected, no iou suppression, no score filtering
+        for (ix, (input, default)) in [
+            (self.optional_max_output_boxes_per_class_input, rctensor0(0i64)),
+            (self.optional_iou_threshold_input, rctensor0(0f32)),
+            (self.optional_score_threshold_input, rctensor0(f32::NEG_INFINITY)),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let wire = if let Some(input) = input {
+                inputs[*input]
+            } else {
+                model.add_const(format!("{}.default-{}", prefix, ix), default.clone())?
+            };
+            wires.push(wire);
+        }
+        let op = tract_core::ops::nn::NonMaxSuppression::new(
+            self.box_repr,
+            self.num_selected_indices_symbol.clone(),
+        );
+        model.wire_node(prefix, op, &wires)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct RoiAlign(tract_core::ops::nn::RoiAlign);
+
+impl_dyn_hash!(RoiAlign);
+
+impl Expansion for RoiAlign {
+    fn name(&self) -> Cow<str> {
+        "RoiAlign".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[1].shape[1], 4.to_dim())?;
+        s.equals(&inputs[2].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[2].shape[0])?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], self.0.output_height.to_dim())?;
+        s.equals(&outputs[0].shape[3], self.0.output_width.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct MaxRoiPool(tract_core::ops::nn::MaxRoiPool);
+
+impl_dyn_hash!(MaxRoiPool);
+
+impl Expansion for MaxRoiPool {
+    fn name(&self) -> Cow<str> {
+        "MaxRoiPool".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[1].shape[1], 5.to_dim())?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], self.0.pooled_height.to_dim())?;
+        s.equals(&outputs[0].shape[3], self.0.pooled_width.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::model::{optional_inputs, optional_outputs, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+
+pub fn layer_normalization(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    if optional_outputs(node).take(3).skip(1).any(|o| o.is_some()) {
+        bail!("LayerNormalization Mean and InvStdDev outputs are not supported")
+    }
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let epsilon = node.get_attr_opt("epsilon")?.unwrap_or(1e-5);
+    let optional_bias_input = optional_inputs(node).nth(2).flatten();
+    Ok((expand(LayerNormalization { axis, epsilon, optional_bias_input }), vec![]))
+}
+
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct LayerNormalization {
+    axis: i64,
+    #[educe(Hash(method = "hash_f32"))]
+    epsilon: f32,
+    optional_bias_input: Option<usize>,
+}
+
+impl_dyn_hash!(LayerNormalization);
+
+impl Expansion for LayerNormalization {
+    fn name(&self) -> Cow<str> {
+        "LayerNormalization".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2 + self.optional_bias_input.is_some() as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].datum_type, &inputs[1].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        if let Some(bias) = self.optional_bias_input {
+            s.equals(&inputs[0].datum_type, &inputs[bias].datum_type)?;
+            s.equals(&inputs[1].shape, &inputs[bias].shape)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        name: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::binary::wire_with_rank_broadcast;
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let axis = if self.axis < 0 { self.axis + rank as i64 } else { self.axis } as usize;
+        let op =
+            tract_hir::tract_core::ops::nn::LayerNorm::new((axis..rank).collect(), self.epsilon);
+        let normed = model.wire_node(format!("{}.norm", name), op, &inputs[0..1])?;
+        let scale_name = if self.optional_bias_input.is_some() {
+            format!("{}.scaled", name)
+        } else {
+            name.to_string()
+        };
+        let mut wire = wire_with_rank_broadcast(
+            &scale_name,
+            model,
+            tract_hir::ops::math::mul::bin_typed(),
+            &[normed[0], inputs[1]],
+        )?;
+        if let Some(bias) = self.optional_bias_input {
+            wire = wire_with_rank_broadcast(
+                name,
+                model,
+                tract_hir::ops::math::add::bin_typed(),
+                &[wire[0], inputs[bias]],
+            )?;
+        }
+        Ok(wire)
+    }
+}
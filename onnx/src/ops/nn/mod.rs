@@ -8,8 +8,10 @@ use crate::pb_helpers::OptionExt;
 
 mod batch_norm;
 mod conv_transpose;
+mod detection;
 mod dropout;
 mod instance_norm;
+mod layer_norm;
 mod lrn;
 
 pub fn arg_max_min(
@@ -37,6 +39,7 @@ fn reduce(
 }
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    detection::register_all_ops(reg);
     reg.insert("ArgMax", arg_max_min);
     reg.insert("ArgMin", arg_max_min);
     reg.insert("AveragePool", average_pool);
@@ -46,12 +49,14 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("ConvTranspose", conv_transpose::conv_transpose);
     reg.insert("Dropout", dropout::dropout);
     reg.insert("Elu", elu);
+    reg.insert("Gelu", gelu);
     reg.insert("GlobalAveragePool", |_, _| Ok((expand(ops::nn::GlobalAvgPool), vec![])));
     reg.insert("GlobalLpPool", global_lp_pool);
     reg.insert("GlobalMaxPool", |_, _| Ok((expand(ops::nn::GlobalMaxPool), vec![])));
     reg.insert("Hardmax", layer_hard_max);
     reg.insert("HardSigmoid", hard_sigmoid);
     reg.insert("InstanceNormalization", instance_norm::instance_normalization);
+    reg.insert("LayerNormalization", layer_norm::layer_normalization);
     reg.insert("LeakyRelu", leaky_relu);
     reg.insert("LogSoftmax", layer_log_soft_max);
     reg.insert("LRN", lrn::lrn);
@@ -221,6 +226,18 @@ pub fn elu(
     Ok((expand(ops::activations::Elu(alpha)), vec![]))
 }
 
+pub fn gelu(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let approximate = match node.get_attr_opt("approximate")?.unwrap_or("none") {
+        "none" => false,
+        "tanh" => true,
+        other => bail!("Unsupported Gelu approximation {}", other),
+    };
+    Ok((Box::new(tract_core::ops::nn::gelu(approximate)), vec![]))
+}
+
 pub fn global_lp_pool(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -0,0 +1,486 @@
+use crate::model::{optional_inputs, optional_outputs, ParseResult, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+
+use tract_hir::ops;
+use tract_hir::ops::scan::{InputMapping, OutputMapping, Scan, StateInitializer};
+
+pub fn loop_(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let graph: &GraphProto = node.get_attr("body")?;
+    let ParseResult { model: body, unresolved_inputs, .. } = ctx.parse_graph(graph)?;
+    let mut inputs = optional_inputs(node);
+    let has_max_trip_count = inputs.next().unwrap().is_some();
+    let has_cond = inputs.next().unwrap().is_some();
+    let carried = node.input.len().saturating_sub(2);
+    if graph.input.len() != carried + 2 {
+        bail!(
+            "Loop body expects {} inputs, node provides {} loop-carried values",
+            graph.input.len(),
+            carried
+        );
+    }
+    let output_slots = optional_outputs(node).take(graph.output.len() - 1).collect();
+    Ok((
+        Box::new(Loop { body, has_max_trip_count, has_cond, carried, output_slots }),
+        unresolved_inputs,
+    ))
+}
+
+/// ONNX Loop.
+///
+/// Body inputs are the iteration number, the condition, the loop-carried
+/// values then the closures. Body outputs are the condition, the loop-carried
+/// values then the scan outputs.
+///
+/// Loops whose condition is always true (a constant true on the way in, and
+/// passed through or constant true in the body) are translated to a typed
+/// Scan, with a symbolic iteration count if the max trip count is dynamic.
+/// Loops stopped by a data-dependent condition can only run as an inference
+/// model.
+#[derive(Debug, Clone, Hash)]
+pub struct Loop {
+    pub body: InferenceModel,
+    pub has_max_trip_count: bool,
+    pub has_cond: bool,
+    pub carried: usize,
+    pub output_slots: Vec<Option<usize>>,
+}
+
+impl_dyn_hash!(Loop);
+
+impl Loop {
+    fn first_carried_input(&self) -> usize {
+        self.has_max_trip_count as usize + self.has_cond as usize
+    }
+
+    fn scan_outputs(&self) -> usize {
+        self.output_slots.len() - self.carried
+    }
+
+    /// Is there a max trip count and a constant true condition input.
+    fn runs_max_trip_count(&self, target: &TypedModel, inputs: &[OutletId]) -> TractResult<bool> {
+        if !self.has_max_trip_count {
+            return Ok(false);
+        }
+        if self.has_cond {
+            match &target.outlet_fact(inputs[1])?.konst {
+                Some(cond) => cond.cast_to_scalar::<bool>(),
+                None => Ok(false),
+            }
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Iteration numbers, from a Range over the max trip count: a constant
+    /// when the trip count is, a symbolic length otherwise.
+    fn iterations(
+        &self,
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<OutletId> {
+        let mut max_trip_count = inputs[0];
+        let rank = target.outlet_fact(max_trip_count)?.rank();
+        for axis in (0..rank).rev() {
+            max_trip_count = target.wire_node(
+                format!("{}.max_trip_count.rm_axis_{}", name, axis),
+                AxisOp::Rm(axis),
+                &[max_trip_count],
+            )?[0];
+        }
+        let start = target.add_const(format!("{}.iterations.start", name), rctensor0(0i64))?;
+        let step = target.add_const(format!("{}.iterations.step", name), rctensor0(1i64))?;
+        let wires = [start, max_trip_count, step];
+        let len = tract_hir::tract_core::ops::array::Range::len_for_facts(
+            target.outlet_fact(start)?,
+            target.outlet_fact(max_trip_count)?,
+            target.outlet_fact(step)?,
+            target.sym(&format!("{}_trip_count", name)),
+        )?;
+        let range = tract_hir::tract_core::ops::array::Range::new(len);
+        Ok(target.wire_node(format!("{}.iterations", name), range, &wires)?[0])
+    }
+
+    /// Translate to a Scan over the iteration numbers when the body condition
+    /// is always true.
+    fn to_scan(
+        &self,
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<Option<TVec<OutletId>>> {
+        if !self.runs_max_trip_count(target, inputs)? {
+            return Ok(None);
+        }
+        let mut body = self.body.clone();
+        let iter_num = body.input_outlets()?[0];
+        InferenceModelPatch::intercept(
+            &body,
+            iter_num,
+            format!("{}.iteration-adjust-dim", name),
+            expand(ops::array::RmDims::new(vec![0])),
+            InferenceFact::default(),
+        )?
+        .apply(&mut body)?;
+        body.set_outlet_fact(iter_num, InferenceFact::dt_shape(i64::datum_type(), tvec!(1)))?;
+        let mut body_outputs = body.output_outlets()?.to_vec();
+        for ix in 0..self.scan_outputs() {
+            let output = &mut body_outputs[1 + self.carried + ix];
+            let id = body.add_node(
+                format!("{}.output-{}-adjust-dim", name, ix),
+                expand(ops::array::AddDims::new(vec![0])),
+                tvec!(InferenceFact::default()),
+            )?;
+            body.add_edge(*output, InletId::new(id, 0))?;
+            *output = OutletId::new(id, 0);
+        }
+        body.set_output_outlets(&body_outputs)?;
+        let body = body.into_typed()?.declutter()?;
+
+        let cond_output = body.output_outlets()?[0];
+        let cond_passthrough = cond_output == body.input_outlets()?[1];
+        let cond_const_true = match &body.outlet_fact(cond_output)?.konst {
+            Some(k) => k.cast_to_scalar::<bool>()?,
+            None => false,
+        };
+        if !cond_passthrough && !cond_const_true {
+            return Ok(None);
+        }
+
+        let iterations = self.iterations(name, target, inputs)?;
+        let trip_count = target.outlet_fact(iterations)?.shape[0].clone();
+        let first_carried = self.first_carried_input();
+        let closures = inputs.len() - first_carried - self.carried;
+        let mut input_mapping = vec![
+            InputMapping::Scan { slot: 0, axis: 0, chunk: 1 },
+            InputMapping::State { initializer: StateInitializer::Value(rctensor0(true)) },
+        ];
+        for ix in 0..self.carried {
+            input_mapping
+                .push(InputMapping::State { initializer: StateInitializer::FromInput(1 + ix) });
+        }
+        for ix in 0..closures {
+            input_mapping.push(InputMapping::Full { slot: 1 + self.carried + ix });
+        }
+        let mut output_mapping = vec![OutputMapping {
+            state: true,
+            full_slot: None,
+            last_value_slot: None,
+            axis: 0,
+            chunk: 1,
+            full_dim_hint: None,
+        }];
+        for ix in 0..self.carried {
+            output_mapping.push(OutputMapping {
+                state: true,
+                full_slot: None,
+                last_value_slot: self.output_slots[ix],
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: None,
+            });
+        }
+        for ix in 0..self.scan_outputs() {
+            output_mapping.push(OutputMapping {
+                state: false,
+                full_slot: self.output_slots[self.carried + ix],
+                last_value_slot: None,
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: Some(trip_count.clone()),
+            });
+        }
+
+        let mut outer_inputs = tvec!(iterations);
+        outer_inputs.extend(inputs[first_carried..].iter().cloned());
+        let op = Scan::new(body, input_mapping, output_mapping, None, 0)?;
+        Ok(Some(target.wire_node(name, op, &outer_inputs)?))
+    }
+}
+
+impl Op for Loop {
+    fn name(&self) -> Cow<str> {
+        "Loop".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "max trip count: {:?}, condition: {:?}, loop-carried: {}, scan outputs: {}",
+            self.has_max_trip_count,
+            self.has_cond,
+            self.carried,
+            self.scan_outputs()
+        )])
+    }
+
+    op_onnx!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for Loop {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let max_trip_count =
+            if self.has_max_trip_count { inputs[0].cast_to_scalar::<i64>()? } else { i64::MAX };
+        let mut cond = if self.has_cond {
+            inputs[self.has_max_trip_count as usize].cast_to_scalar::<bool>()?
+        } else {
+            true
+        };
+        let first_carried = self.first_carried_input();
+        let mut state: TVec<Arc<Tensor>> =
+            inputs[first_carried..][..self.carried].iter().cloned().collect();
+        let closures = &inputs[first_carried + self.carried..];
+        let plan = SimplePlan::new(&self.body)?;
+        let mut scans: Vec<Vec<Tensor>> = vec![vec![]; self.scan_outputs()];
+        let mut i = 0;
+        while i < max_trip_count && cond {
+            let mut body_inputs: TVec<Tensor> = tvec!(tensor0(i), tensor0(cond));
+            body_inputs.extend(state.iter().chain(closures.iter()).map(|t| (**t).clone()));
+            let outputs = plan.run(body_inputs).context("Evaluating loop body")?;
+            cond = outputs[0].cast_to_scalar::<bool>()?;
+            state = outputs[1..][..self.carried].iter().cloned().collect();
+            for (ix, scan) in scans.iter_mut().enumerate() {
+                let mut t = outputs[1 + self.carried + ix].clone().into_tensor();
+                t.insert_axis(0)?;
+                scan.push(t);
+            }
+            i += 1;
+        }
+        let mut outputs = tvec!();
+        for (ix, slot) in self.output_slots.iter().enumerate() {
+            if slot.is_none() {
+                continue;
+            }
+            let value = if ix < self.carried {
+                state[ix].clone()
+            } else if scans[ix - self.carried].len() > 0 {
+                Tensor::stack_tensors(0, &scans[ix - self.carried])?.into_arc_tensor()
+            } else {
+                let fact = self.body.output_fact(1 + ix)?;
+                let dt = fact.datum_type.concretize().context("Untyped loop scan output")?;
+                let mut shape = fact
+                    .shape
+                    .as_concrete_finite()?
+                    .context("Loop did not run and scan output shape is unknown")?;
+                shape.insert(0, 0);
+                Tensor::zero_dt(dt, &shape)?.into_arc_tensor()
+            };
+            outputs.push(value);
+        }
+        Ok(outputs)
+    }
+}
+
+impl InferenceOp for Loop {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        if self.has_max_trip_count {
+            inputs[0].datum_type.unify_with(&i64::datum_type().into())?;
+        }
+        if self.has_cond {
+            inputs[self.has_max_trip_count as usize]
+                .datum_type
+                .unify_with(&bool::datum_type().into())?;
+        }
+        let scalar = |dt: DatumType| InferenceFact::dt_shape(dt, &[0usize; 0]);
+        self.body.input_fact_mut(0)?.unify_with(&scalar(i64::datum_type()))?;
+        self.body.input_fact_mut(1)?.unify_with(&scalar(bool::datum_type()))?;
+        self.body.output_fact_mut(0)?.unify_with(&scalar(bool::datum_type()))?;
+        let first_carried = self.first_carried_input();
+        loop {
+            let mut changed = false;
+            for ix in 0..self.carried {
+                let mut facts = self.body.outlets_fact_mut(&[
+                    self.body.input_outlets()?[2 + ix],
+                    self.body.output_outlets()?[1 + ix],
+                ])?;
+                facts.push(&mut inputs[first_carried + ix]);
+                if let Some(slot) = self.output_slots[ix] {
+                    facts.push(&mut outputs[slot]);
+                }
+                changed |= Factoid::unify_all(
+                    &mut *facts.iter_mut().map(|f| &mut f.datum_type).collect::<TVec<_>>(),
+                )?;
+                changed |= Factoid::unify_all(
+                    &mut *facts.iter_mut().map(|f| &mut f.shape).collect::<TVec<_>>(),
+                )?;
+            }
+            for (ix, input) in inputs[first_carried + self.carried..].iter_mut().enumerate() {
+                let inner = self.body.input_fact_mut(2 + self.carried + ix)?;
+                changed |= input.datum_type.unify_with_mut(&mut inner.datum_type)?;
+                changed |= input.shape.unify_with_mut(&mut inner.shape)?;
+            }
+            for ix in 0..self.scan_outputs() {
+                let slot = if let Some(slot) = self.output_slots[self.carried + ix] {
+                    slot
+                } else {
+                    continue;
+                };
+                let inner = self.body.output_fact_mut(1 + self.carried + ix)?;
+                changed |= outputs[slot].datum_type.unify_with_mut(&mut inner.datum_type)?;
+                if let Some(rank) = inner.shape.rank().concretize() {
+                    let mut dims: TVec<DimFact> = tvec!(GenericFactoid::Any);
+                    dims.extend((0..rank as usize).map(|axis| inner.shape.dim(axis).unwrap()));
+                    changed |= outputs[slot].shape.unify_with(&ShapeFactoid::closed(dims))?;
+                }
+            }
+            changed |= self.body.analyse(false).context("analysing Loop body")?;
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.output_slots.iter().filter(|s| s.is_some()).count())
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        if let Some(wires) = self.to_scan(&node.name, target, &inputs)? {
+            Ok(wires)
+        } else {
+            bail!(
+                "Loop {} may stop before its max trip count (condition is not always true), \
+                 it can only be evaluated as an inference model",
+                node.name
+            )
+        }
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x <- x + 1, scanning x before the update
+    fn counting_loop(data_dependent_cond: bool) -> TractResult<Loop> {
+        let mut body = InferenceModel::default();
+        let _iter = body.add_source("iter", InferenceFact::default())?;
+        let cond = body.add_source("cond", InferenceFact::default())?;
+        let x = body.add_source("x", InferenceFact::default())?;
+        let one = body.add_const("one", rctensor1(&[1f32, 1.0]))?;
+        let y = body.wire_node("add", tract_hir::ops::math::Add.into_hir(), &[x, one])?[0];
+        let cond = if data_dependent_cond {
+            // y[0] < 3
+            let zero = body.add_const("zero", rctensor0(0i64))?;
+            let first = body.wire_node("first", expand(ops::array::Gather::new(0)), &[y, zero])?[0];
+            let limit = body.add_const("limit", rctensor0(3f32))?;
+            body.wire_node("cond_out", ops::logic::Lesser.into_hir(), &[first, limit])?[0]
+        } else {
+            body.wire_node("cond_out", ops::identity::Identity::default(), &[cond])?[0]
+        };
+        body.set_output_outlets(&[cond, y, x])?;
+        Ok(Loop {
+            body,
+            has_max_trip_count: true,
+            has_cond: true,
+            carried: 1,
+            output_slots: vec![Some(0), Some(1)],
+        })
+    }
+
+    fn loop_model(
+        max_trip_count: Option<i64>,
+        data_dependent_cond: bool,
+    ) -> TractResult<InferenceModel> {
+        let mut model = InferenceModel::default();
+        let m = if let Some(m) = max_trip_count {
+            model.add_const("m", rctensor0(m))?
+        } else {
+            model.add_source("m", InferenceFact::dt_shape(i64::datum_type(), &[0usize; 0]))?
+        };
+        let cond = model.add_const("cond", rctensor0(true))?;
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), tvec!(2)))?;
+        let outputs =
+            model.wire_node("loop", counting_loop(data_dependent_cond)?, &[m, cond, x])?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn static_loop_to_scan() -> TractResult<()> {
+        let model = loop_model(Some(3), false)?;
+        let input = tvec!(tensor1(&[0f32, 1.0]));
+        let expected = model.clone().into_runnable()?.run(input.clone())?;
+        assert_eq!(expected[0], rctensor1(&[3f32, 4.0]));
+        assert_eq!(expected[1], rctensor2(&[[0f32, 1.0], [1.0, 2.0], [2.0, 3.0]]));
+        let typed = model.into_typed()?.declutter()?;
+        assert!(typed.nodes().iter().any(|n| n.op_is::<Scan>()));
+        let found = typed.into_runnable()?.run(input)?;
+        assert_eq!(found, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn dynamic_loop_to_scan() -> TractResult<()> {
+        let mut model = loop_model(None, false)?;
+        model.analyse(false)?;
+        let typed = model.clone().into_typed()?.into_optimized()?;
+        let scan_output = typed.outlet_fact(typed.output_outlets()?[1])?;
+        assert!(scan_output.shape[0].to_i64().is_err());
+        let typed = typed.into_runnable()?;
+        let model = model.into_runnable()?;
+        for m in 0..5i64 {
+            let input = tvec!(tensor0(m), tensor1(&[0f32, 1.0]));
+            let expected = model.run(input.clone())?;
+            assert_eq!(expected[1].shape(), &[m as usize, 2]);
+            assert_eq!(typed.run(input)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dynamic_loop_state_is_reusable() -> TractResult<()> {
+        let typed = loop_model(None, false)?.into_typed()?.into_optimized()?.into_runnable()?;
+        let mut state = SimpleState::new(&typed)?;
+        for &m in &[3i64, 1, 4] {
+            let found = state.run(tvec!(tensor0(m), tensor1(&[0f32, 1.0])))?;
+            assert_eq!(found[1].shape(), &[m as usize, 2]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn zero_trip_count() -> TractResult<()> {
+        let model = loop_model(Some(0), false)?;
+        let typed = model.into_typed()?.declutter()?;
+        let found = typed.into_runnable()?.run(tvec!(tensor1(&[0f32, 1.0])))?;
+        assert_eq!(found[0], rctensor1(&[0f32, 1.0]));
+        assert_eq!(found[1].shape(), &[0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn data_dependent_condition_is_not_typed() -> TractResult<()> {
+        let model = loop_model(Some(10), true)?;
+        let expected = model.clone().into_runnable()?.run(tvec!(tensor1(&[0f32, 1.0])))?;
+        assert_eq!(expected[0], rctensor1(&[3f32, 4.0]));
+        let err = model.into_typed().unwrap_err();
+        assert!(format!("{:?}", err).contains("condition is not always true"));
+        Ok(())
+    }
+}
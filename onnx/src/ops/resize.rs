@@ -1,85 +1,217 @@
 use crate::model::ParsingContext;
 use crate::pb::*;
-use std::hash::Hash;
 use tract_hir::internal::*;
 
-pub fn resize(
+pub fn resize_10(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    // Resize-10 has no roi nor sizes, and no coordinate transformation attributes
+    let mut options = crate::model::optional_inputs(node).skip(1);
+    Ok((
+        Box::new(Resize {
+            coord_transformer: CoordTransformer::Asymmetric,
+            interpolator: interpolator(node)?,
+            nearest: Nearest::Floor,
+            cubic_coeff_a: -0.75,
+            exclude_outside: false,
+            extrapolation_value: 0.0,
+            static_scales: None,
+            optional_roi_input: None,
+            optional_scales_input: options.next().unwrap(),
+            optional_sizes_input: None,
+        }),
+        vec![],
+    ))
+}
+
+pub fn resize_11(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let interpolator = interpolator(node)?;
     let coord_transformer =
         match node.get_attr_opt("coordinate_transformation_mode")?.unwrap_or("half_pixel") {
             "align_corners" => CoordTransformer::AlignCorners,
+            "asymmetric" => CoordTransformer::Asymmetric,
             "half_pixel" => CoordTransformer::HalfPixel,
-            s => todo!("coordinate_transformation_mode: {}", s),
+            "pytorch_half_pixel" => CoordTransformer::PytorchHalfPixel,
+            "tf_crop_and_resize" => CoordTransformer::TfCropAndResize,
+            "tf_half_pixel_for_nn" => CoordTransformer::TfHalfPixelForNn,
+            s => node.bail_attr(
+                "coordinate_transformation_mode",
+                &format!("unsupported value: {}", s),
+            )?,
         };
-    let interpolator = match node.get_attr("mode")? {
-        "linear" => Interpolator::Linear,
-        s => todo!("mode: {}", s),
-    };
     let nearest = match node.get_attr_opt("nearest_mode")?.unwrap_or("round_prefer_floor") {
+        "ceil" => Nearest::Ceil,
         "floor" => Nearest::Floor,
+        "round_prefer_ceil" => Nearest::RoundPreferCeil,
         "round_prefer_floor" => Nearest::RoundPreferFloor,
-        s => todo!("nearest_mode: {}", s),
+        s => node.bail_attr("nearest_mode", &format!("unsupported value: {}", s))?,
     };
-    let mut options = crate::model::optional_inputs(node).skip(2);
+    let mut options = crate::model::optional_inputs(node).skip(1);
+    let optional_roi_input = options.next().unwrap();
+    if coord_transformer == CoordTransformer::TfCropAndResize && optional_roi_input.is_none() {
+        node.bail("tf_crop_and_resize coordinate transformation mode requires a roi input")?
+    }
     Ok((
         Box::new(Resize {
-            optional_scales_input: options.next().unwrap(),
-            optional_sizes_input: options.next().unwrap(),
             coord_transformer,
             interpolator,
             nearest,
+            cubic_coeff_a: node.get_attr_opt("cubic_coeff_a")?.unwrap_or(-0.75),
+            exclude_outside: node.get_attr_opt("exclude_outside")?.unwrap_or(false),
+            extrapolation_value: node.get_attr_opt("extrapolation_value")?.unwrap_or(0.0),
+            static_scales: None,
+            optional_roi_input,
+            optional_scales_input: options.next().unwrap(),
+            optional_sizes_input: options.next().unwrap(),
+        }),
+        vec![],
+    ))
+}
+
+pub fn upsample(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    // Upsample-7 has scales as an attribute, Upsample-9 as an input
+    let static_scales = node.get_attr_opt_tvec::<f32>("scales")?;
+    let optional_scales_input = if static_scales.is_some() {
+        None
+    } else {
+        Some(node.expect_ok_or_else(crate::model::optional_inputs(node).nth(1).unwrap(), "scales")?)
+    };
+    Ok((
+        Box::new(Resize {
+            coord_transformer: CoordTransformer::Asymmetric,
+            interpolator: interpolator(node)?,
+            nearest: Nearest::Floor,
+            cubic_coeff_a: -0.75,
+            exclude_outside: false,
+            extrapolation_value: 0.0,
+            static_scales,
+            optional_roi_input: None,
+            optional_scales_input,
+            optional_sizes_input: None,
         }),
         vec![],
     ))
 }
 
-#[derive(Clone, Debug, Hash)]
+fn interpolator(node: &NodeProto) -> TractResult<Interpolator> {
+    match node.get_attr_opt("mode")?.unwrap_or("nearest") {
+        "nearest" => Ok(Interpolator::Nearest),
+        "linear" => Ok(Interpolator::Linear),
+        "cubic" if node.op_type == "Resize" => Ok(Interpolator::Cubic),
+        s => node.bail_attr("mode", &format!("unsupported value: {}", s)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
 enum CoordTransformer {
     HalfPixel,
+    PytorchHalfPixel,
     AlignCorners,
+    Asymmetric,
+    TfHalfPixelForNn,
+    TfCropAndResize,
 }
 
 impl CoordTransformer {
-    fn transform(&self, x_out: usize, scale: f32, len_in: usize, len_out: usize) -> f32 {
+    /// Position in the input of an output coordinate. `roi` is the (start,
+    /// end) normalized region of interest, only used by TfCropAndResize.
+    fn transform(
+        &self,
+        x_out: usize,
+        scale: f32,
+        len_in: usize,
+        len_out: usize,
+        roi: (f32, f32),
+    ) -> f32 {
+        let x_out = x_out as f32;
         match self {
-            CoordTransformer::HalfPixel => (x_out as f32 + 0.5) * scale - 0.5,
+            CoordTransformer::HalfPixel => (x_out + 0.5) / scale - 0.5,
+            CoordTransformer::PytorchHalfPixel => {
+                if len_out > 1 {
+                    (x_out + 0.5) / scale - 0.5
+                } else {
+                    0.0
+                }
+            }
             CoordTransformer::AlignCorners => {
-                (x_out as f32 * (len_in as f32 - 1.0)) / (len_out as f32 - 1.0)
+                if len_out > 1 {
+                    x_out * (len_in as f32 - 1.0) / (len_out as f32 - 1.0)
+                } else {
+                    0.0
+                }
+            }
+            CoordTransformer::Asymmetric => x_out / scale,
+            CoordTransformer::TfHalfPixelForNn => (x_out + 0.5) / scale,
+            CoordTransformer::TfCropAndResize => {
+                let (start, end) = roi;
+                if len_out > 1 {
+                    start * (len_in as f32 - 1.0)
+                        + x_out * (end - start) * (len_in as f32 - 1.0) / (len_out as f32 - 1.0)
+                } else {
+                    0.5 * (start + end) * (len_in as f32 - 1.0)
+                }
             }
         }
     }
 }
 
-#[derive(Clone, Debug, Hash)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
 enum Interpolator {
+    Nearest,
     Linear,
+    Cubic,
 }
 
-impl Interpolator {
-    fn interpolate(&self, y_left: f32, y_right: f32, x_ratio: f32) -> f32 {
-        match self {
-            Interpolator::Linear => y_left * (1.0 - x_ratio) + y_right * x_ratio,
-        }
-    }
-}
-
-#[derive(Clone, Debug, Hash)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
 enum Nearest {
     Floor,
+    Ceil,
     RoundPreferFloor,
+    RoundPreferCeil,
+}
+
+impl Nearest {
+    fn apply(&self, x: f32) -> f32 {
+        match self {
+            Nearest::Floor => x.floor(),
+            Nearest::Ceil => x.ceil(),
+            Nearest::RoundPreferFloor if x - x.floor() == 0.5 => x.floor(),
+            Nearest::RoundPreferCeil if x - x.floor() == 0.5 => x.ceil(),
+            _ => x.round(),
+        }
+    }
 }
 
-#[derive(Clone, new, Debug, Hash)]
+#[derive(Clone, new, Debug, Educe)]
+#[educe(Hash)]
 struct Resize {
     coord_transformer: CoordTransformer,
     interpolator: Interpolator,
     nearest: Nearest,
+    #[educe(Hash(method = "hash_f32"))]
+    cubic_coeff_a: f32,
+    exclude_outside: bool,
+    #[educe(Hash(method = "hash_f32"))]
+    extrapolation_value: f32,
+    #[educe(Hash(method = "hash_static_scales"))]
+    static_scales: Option<TVec<f32>>,
+    optional_roi_input: Option<usize>,
     optional_scales_input: Option<usize>,
     optional_sizes_input: Option<usize>,
 }
 
+fn hash_static_scales<H: std::hash::Hasher>(scales: &Option<TVec<f32>>, state: &mut H) {
+    use std::hash::Hash;
+    scales.as_ref().map(|s| s.iter().map(|f| f.to_bits()).collect::<TVec<_>>()).hash(state)
+}
+
 impl_dyn_hash!(Resize);
 
 impl Op for Resize {
@@ -92,21 +224,33 @@ impl Op for Resize {
 }
 
 impl Resize {
+    fn scales(&self, rank: usize, input_scale: Option<&Tensor>) -> TractResult<Option<TVec<f32>>> {
+        if let Some(scales) = &self.static_scales {
+            if scales.len() != rank {
+                bail!("Expected {} scales, got {:?}", rank, scales);
+            }
+            return Ok(Some(scales.clone()));
+        }
+        if let Some(scale) = input_scale {
+            if scale.len() == rank {
+                return Ok(Some(scale.cast_to::<f32>()?.as_slice::<f32>()?.into()));
+            }
+        }
+        Ok(None)
+    }
+
     fn compute_output_shape(
         &self,
         input_shape: &[usize],
         input_scale: Option<&Tensor>,
         input_sizes: Option<&Tensor>,
     ) -> TractResult<TVec<usize>> {
-        if let Some(scale) = input_scale {
-            if scale.len() == input_shape.len() {
-                let scales = scale.cast_to::<f32>()?;
-                return Ok(input_shape
-                    .iter()
-                    .zip(scales.as_slice::<f32>()?.iter())
-                    .map(|(input, scale)| ((*input as f32) * scale) as usize)
-                    .collect());
-            }
+        if let Some(scales) = self.scales(input_shape.len(), input_scale)? {
+            return Ok(input_shape
+                .iter()
+                .zip(scales.iter())
+                .map(|(input, scale)| ((*input as f32) * scale) as usize)
+                .collect());
         }
         if let Some(sizes) = input_sizes {
             if sizes.len() == input_shape.len() {
@@ -114,7 +258,48 @@ impl Resize {
                 return Ok(size.as_slice::<i64>()?.iter().map(|i| *i as usize).collect());
             }
         }
-        bail!("Neither shape not scale makes sense: input_shape: {:?}, scale: {:?}, sizes: {:?}")
+        bail!(
+            "Neither shape not scale makes sense: input_shape: {:?}, scale: {:?}, sizes: {:?}",
+            input_shape,
+            input_scale,
+            input_sizes
+        )
+    }
+
+    /// Input indices and weights contributing to an output point, for an
+    /// input position `x` along an axis of length `len`. Out of range indices
+    /// are clamped to the edge.
+    fn taps(&self, x: f32, len: usize) -> TVec<(usize, f32)> {
+        let clamp = |ix: isize| ix.max(0).min(len as isize - 1) as usize;
+        match self.interpolator {
+            Interpolator::Nearest => tvec!((clamp(self.nearest.apply(x) as isize), 1.0)),
+            Interpolator::Linear => {
+                let left = x.floor();
+                let ratio = x - left;
+                let left = left as isize;
+                tvec!((clamp(left), 1.0 - ratio), (clamp(left + 1), ratio))
+            }
+            Interpolator::Cubic => {
+                let a = self.cubic_coeff_a;
+                let left = x.floor();
+                let r = x - left;
+                let left = left as isize;
+                let coeffs = [
+                    ((a * (r + 1.0) - 5.0 * a) * (r + 1.0) + 8.0 * a) * (r + 1.0) - 4.0 * a,
+                    ((a + 2.0) * r - (a + 3.0)) * r * r + 1.0,
+                    ((a + 2.0) * (1.0 - r) - (a + 3.0)) * (1.0 - r) * (1.0 - r) + 1.0,
+                    ((a * (2.0 - r) - 5.0 * a) * (2.0 - r) + 8.0 * a) * (2.0 - r) - 4.0 * a,
+                ];
+                let mut taps: TVec<(isize, f32)> =
+                    coeffs.iter().enumerate().map(|(ix, c)| (left - 1 + ix as isize, *c)).collect();
+                if self.exclude_outside {
+                    taps.retain(|(ix, _)| *ix >= 0 && *ix < len as isize);
+                    let sum: f32 = taps.iter().map(|t| t.1).sum();
+                    taps.iter_mut().for_each(|t| t.1 /= sum);
+                }
+                taps.into_iter().map(|(ix, w)| (clamp(ix), w)).collect()
+            }
+        }
     }
 }
 
@@ -123,7 +308,7 @@ impl EvalOp for Resize {
         true
     }
 
-    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let scales = self.optional_scales_input.and_then(|ix| inputs.get(ix));
         let sizes = self.optional_sizes_input.and_then(|ix| inputs.get(ix));
         let output_shape = self.compute_output_shape(
@@ -131,35 +316,56 @@ impl EvalOp for Resize {
             scales.map(|t| &**t),
             sizes.map(|t| &**t),
         )?;
-        let mut data = inputs.remove(0).into_tensor().into_array::<f32>()?;
-        for axis in 0..data.ndim() {
-            if output_shape[axis] == data.shape()[axis] {
+        let rank = output_shape.len();
+        let scales = self.scales(rank, scales.map(|t| &**t))?;
+        let roi = if self.coord_transformer == CoordTransformer::TfCropAndResize {
+            let roi = inputs[self.optional_roi_input.unwrap()].cast_to::<f32>()?.into_owned();
+            if roi.len() != 2 * rank {
+                bail!("Expected roi to have {} values, got {:?}", 2 * rank, roi);
+            }
+            Some(roi.into_array::<f32>()?.into_raw_vec())
+        } else {
+            None
+        };
+        let dt = inputs[0].datum_type();
+        let mut data = inputs[0].cast_to::<f32>()?.into_owned().into_array::<f32>()?;
+        for axis in 0..rank {
+            let len_in = data.shape()[axis];
+            let len_out = output_shape[axis];
+            let scale = scales.as_ref().map(|s| s[axis]).unwrap_or(len_out as f32 / len_in as f32);
+            if len_in == len_out && scale == 1.0 && roi.is_none() {
                 continue;
-            } else if output_shape[axis] > data.shape()[axis] {
-                let scale = output_shape[axis] as f32 / data.shape()[axis] as f32;
-                let mut new_shape: TVec<usize> = data.shape().into();
-                new_shape[axis] = output_shape[axis];
-                data = tract_ndarray::ArrayD::from_shape_fn(&*new_shape, |co_o| -> f32 {
-                    let x_out = co_o[axis];
-                    let x_in = self.coord_transformer.transform(
-                        x_out,
-                        scale,
-                        data.shape()[axis],
-                        new_shape[axis],
-                    );
-                    let mut co_i = co_o.clone();
-                    let x_left = (x_in as usize).min(data.shape()[axis] - 1).max(0);
-                    co_i[axis] = x_left;
-                    let y_left = data[&co_i];
-                    let x_right = (x_left + 1).min(data.shape()[axis] - 1);
-                    co_i[axis] = x_right;
-                    let y_right = data[&co_i];
-                    let x_frac = x_in - x_left as f32;
-                    self.interpolator.interpolate(y_left, y_right, x_frac)
-                })
             }
+            let roi = roi.as_ref().map(|roi| (roi[axis], roi[axis + rank])).unwrap_or((0.0, 1.0));
+            let taps: Vec<Option<TVec<(usize, f32)>>> = (0..len_out)
+                .map(|x_out| {
+                    let x_in = self.coord_transformer.transform(x_out, scale, len_in, len_out, roi);
+                    if self.coord_transformer == CoordTransformer::TfCropAndResize
+                        && (x_in < 0.0 || x_in > len_in as f32 - 1.0)
+                    {
+                        None
+                    } else {
+                        Some(self.taps(x_in, len_in))
+                    }
+                })
+                .collect();
+            let mut new_shape: TVec<usize> = data.shape().into();
+            new_shape[axis] = len_out;
+            data = tract_ndarray::ArrayD::from_shape_fn(&*new_shape, |co_o| -> f32 {
+                if let Some(taps) = &taps[co_o[axis]] {
+                    let mut co_i = co_o.clone();
+                    taps.iter()
+                        .map(|(ix, w)| {
+                            co_i[axis] = *ix;
+                            data[&co_i] * w
+                        })
+                        .sum()
+                } else {
+                    self.extrapolation_value
+                }
+            })
         }
-        Ok(tvec!(data.into_arc_tensor()))
+        Ok(tvec!(data.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()))
     }
 }
 
@@ -173,15 +379,13 @@ impl InferenceRulesOp for Resize {
         check_output_arity(&outputs, 1)?;
         s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         s.equals(&inputs[0].rank, &outputs[0].rank)?;
-        if inputs.len() == 3 && self.optional_scales_input == Some(2) {
-            rules_with_scales(self, s, inputs, outputs)
-        } else if inputs.len() == 3 && self.optional_sizes_input == Some(2) {
-            rules_with_sizes(self, s, inputs, outputs)
-        } else {
-            // bogus 4 inputs case
-            s.given_2(
+        match (self.optional_scales_input, self.optional_sizes_input) {
+            (Some(_), None) => rules_with_scales(self, s, inputs, outputs),
+            (None, Some(_)) => rules_with_sizes(self, s, inputs, outputs),
+            // both are present but one of them is empty
+            (Some(scales), Some(_)) => s.given_2(
                 &inputs[0].rank,
-                &inputs[self.optional_scales_input.unwrap()].shape,
+                &inputs[scales].shape,
                 move |s, input_rank, scale_shape| {
                     if scale_shape.len() == 0 || scale_shape[0] != input_rank.to_dim() {
                         rules_with_sizes(self, s, inputs, outputs)
@@ -189,7 +393,18 @@ impl InferenceRulesOp for Resize {
                         rules_with_scales(self, s, inputs, outputs)
                     }
                 },
-            )
+            ),
+            (None, None) => s.given(&inputs[0].shape, move |s, input_shape| {
+                let input_shape = input_shape
+                    .iter()
+                    .map(|d| d.to_usize())
+                    .collect::<TractResult<TVec<usize>>>()?;
+                let output_size = self.compute_output_shape(&input_shape, None, None)?;
+                for i in 0..input_shape.len() {
+                    s.equals(&outputs[0].shape[i], output_size[i].to_dim())?;
+                }
+                Ok(())
+            }),
         }
     }
 
@@ -267,3 +482,115 @@ impl TypedOp for Resize {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(coord_transformer: CoordTransformer, interpolator: Interpolator) -> Resize {
+        let nearest = Nearest::RoundPreferFloor;
+        Resize::new(
+            coord_transformer,
+            interpolator,
+            nearest,
+            -0.75,
+            false,
+            0.0,
+            None,
+            None,
+            Some(1),
+            None,
+        )
+    }
+
+    fn run(op: Resize, input: Tensor, scales: &[f32]) -> TractResult<Tensor> {
+        let output = op.eval(tvec!(input.into_arc_tensor(), rctensor1(scales)))?;
+        Ok(output[0].clone().into_tensor())
+    }
+
+    fn ramp(h: usize, w: usize) -> Tensor {
+        let data: Vec<f32> = (1..=h * w).map(|x| x as f32).collect();
+        tensor1(&data).into_shape(&[1, 1, h, w]).unwrap()
+    }
+
+    #[test]
+    fn upsample_nearest() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Nearest);
+        let output = run(op, ramp(2, 2), &[1.0, 1.0, 2.0, 3.0])?;
+        let expected = tensor2(&[
+            [1f32, 1., 1., 2., 2., 2.],
+            [1., 1., 1., 2., 2., 2.],
+            [3., 3., 3., 4., 4., 4.],
+            [3., 3., 3., 4., 4., 4.],
+        ]);
+        assert_eq!(output, expected.into_shape(&[1, 1, 4, 6])?);
+        Ok(())
+    }
+
+    #[test]
+    fn downsample_nearest() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Nearest);
+        let output = run(op, ramp(2, 4), &[1.0, 1.0, 0.6, 0.6])?;
+        assert_eq!(output, tensor2(&[[1f32, 3.]]).into_shape(&[1, 1, 1, 2])?);
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_linear() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Linear);
+        let output = run(op, ramp(2, 2), &[1.0, 1.0, 2.0, 2.0])?;
+        let expected = tensor2(&[
+            [1f32, 1.25, 1.75, 2.],
+            [1.5, 1.75, 2.25, 2.5],
+            [2.5, 2.75, 3.25, 3.5],
+            [3., 3.25, 3.75, 4.],
+        ]);
+        output.close_enough(&expected.into_shape(&[1, 1, 4, 4])?, true)
+    }
+
+    #[test]
+    fn downsample_linear() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Linear);
+        let output = run(op, ramp(2, 4), &[1.0, 1.0, 0.6, 0.6])?;
+        let expected = tensor2(&[[2.6666665f32, 4.333333]]);
+        output.close_enough(&expected.into_shape(&[1, 1, 1, 2])?, true)
+    }
+
+    #[test]
+    fn downsample_cubic() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Cubic);
+        let output = run(op, ramp(4, 4), &[1.0, 1.0, 0.8, 0.8])?;
+        let expected = tensor2(&[
+            [1.47119141f32, 2.78125, 4.08251953],
+            [6.71142578, 8.02148438, 9.32275391],
+            [11.91650391, 13.2265625, 14.52783203],
+        ]);
+        output.close_enough(&expected.into_shape(&[1, 1, 3, 3])?, true)
+    }
+
+    #[test]
+    fn upsample_asymmetric_linear() -> TractResult<()> {
+        let op = op(CoordTransformer::Asymmetric, Interpolator::Linear);
+        let output = run(op, ramp(2, 2), &[1.0, 1.0, 1.0, 4.0])?;
+        let expected = tensor2(&[
+            [1f32, 1.25, 1.5, 1.75, 2., 2., 2., 2.],
+            [3., 3.25, 3.5, 3.75, 4., 4., 4., 4.],
+        ]);
+        output.close_enough(&expected.into_shape(&[1, 1, 2, 8])?, true)
+    }
+
+    #[test]
+    fn tf_crop_and_resize() -> TractResult<()> {
+        let mut op = op(CoordTransformer::TfCropAndResize, Interpolator::Linear);
+        op.optional_roi_input = Some(1);
+        op.optional_scales_input = None;
+        op.optional_sizes_input = Some(2);
+        let output = op.eval(tvec!(
+            ramp(4, 4).into_arc_tensor(),
+            rctensor1(&[0f32, 0., 0.4, 0.6, 1., 1., 0.6, 0.8]),
+            rctensor1(&[1i64, 1, 3, 3]),
+        ))?;
+        let expected = tensor2(&[[7.6f32, 7.9, 8.2], [8.8, 9.1, 9.4], [10., 10.3, 10.6]]);
+        output[0].close_enough(&expected.into_shape(&[1, 1, 3, 3])?, true)
+    }
+}
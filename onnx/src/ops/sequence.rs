@@ -0,0 +1,56 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::ops::array;
+use tract_hir::ops::sequence::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_versioned("ConcatFromSequence", 11.., concat_from_sequence);
+    reg.insert_versioned("SequenceAt", 11.., |_, _| Ok((expand(SequenceAt), vec![])));
+    reg.insert_versioned("SequenceConstruct", 11.., |_, _| Ok((expand(SequenceConstruct), vec![])));
+    reg.insert_versioned("SplitToSequence", 11.., split_to_sequence);
+    reg.insert_versioned("Optional", 15.., optional);
+    reg.insert_versioned("OptionalGetElement", 15.., |_, _| {
+        Ok((expand(array::RmDims::new(vec![0])), vec![]))
+    });
+    reg.insert_versioned("OptionalHasElement", 15.., |_, _| {
+        Ok((expand(OptionalHasElement), vec![]))
+    });
+}
+
+pub fn concat_from_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr("axis")?;
+    let new_axis = node.get_attr_opt("new_axis")?.unwrap_or(false);
+    Ok((expand(ConcatFromSequence::new(axis, new_axis)), vec![]))
+}
+
+pub fn split_to_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let keepdims = node.get_attr_opt("keepdims")?.unwrap_or(true);
+    let has_split_input = optional_inputs(node).nth(1).unwrap().is_some();
+    Ok((expand(SplitToSequence::new(axis, keepdims, has_split_input)), vec![]))
+}
+
+pub fn optional(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    if optional_inputs(node).next().unwrap().is_some() {
+        return Ok((expand(array::AddDims::new(vec![0])), vec![]));
+    }
+    // an empty optional is a sequence of zero element: its element shape does
+    // not matter as long as it has the right rank
+    let tp: &TypeProto = node.get_attr("type")?;
+    let element = crate::tensor::translate_type(&ctx.symbol_table, tp)?;
+    let dt = node.expect_ok_or_else(element.datum_type.concretize(), "a known element type")?;
+    node.expect(!element.shape.is_open(), "a known element rank")?;
+    let shape: TVec<usize> = tvec!(0; element.shape.dims().count() + 1);
+    let empty = Tensor::zero_dt(dt, &shape)?;
+    Ok((Box::new(tract_hir::ops::konst::Const::new(empty.into_arc_tensor())), vec![]))
+}
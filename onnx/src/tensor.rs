@@ -1,7 +1,9 @@
 use crate::pb::tensor_proto::DataType;
 use crate::pb::*;
 use prost::Message;
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::path::{Path, PathBuf};
 use tract_hir::internal::*;
 
 impl TryFrom<DataType> for DatumType {
@@ -18,6 +20,7 @@ impl TryFrom<DataType> for DatumType {
             DataType::Int32 => Ok(DatumType::I32),
             DataType::Int64 => Ok(DatumType::I64),
             DataType::Float16 => Ok(DatumType::F16),
+            DataType::Bfloat16 => Ok(DatumType::BF16),
             DataType::Float => Ok(DatumType::F32),
             DataType::Double => Ok(DatumType::F64),
             DataType::String => Ok(DatumType::String),
@@ -26,28 +29,63 @@ impl TryFrom<DataType> for DatumType {
     }
 }
 
+/// Translate a tensor type, mapping `dim_param` names to symbols of `symbol_table`.
+pub fn translate_inference_fact(
+    symbol_table: &SymbolTable,
+    t: &type_proto::Tensor,
+) -> TractResult<InferenceFact> {
+    let mut fact = InferenceFact::default();
+    fact = fact.with_datum_type(DataType::from_i32(t.elem_type).unwrap().try_into()?);
+    if let Some(shape) = &t.shape {
+        let shape: TVec<DimFact> = shape
+            .dim
+            .iter()
+            .map(|d| {
+                let mut fact = DimFact::default();
+                match &d.value {
+                    Some(tensor_shape_proto::dimension::Value::DimValue(v)) if *v > 0 => {
+                        fact = DimFact::from(v.to_dim())
+                    }
+                    Some(tensor_shape_proto::dimension::Value::DimParam(p)) if p.len() > 0 => {
+                        fact = DimFact::from(symbol_table.sym(p).to_dim())
+                    }
+                    _ => (),
+                }
+                fact
+            })
+            .collect();
+        fact = fact.with_shape(ShapeFactoid::closed(shape));
+    }
+    Ok(fact)
+}
+
+/// Translate a value type. Sequences and optionals are stacked along a leading
+/// axis, as described in `tract_hir::ops::sequence`.
+pub fn translate_type(symbol_table: &SymbolTable, t: &TypeProto) -> TractResult<InferenceFact> {
+    use tract_hir::ops::sequence::sequence_fact;
+    use type_proto::Value;
+    let element = |t: &Option<Box<TypeProto>>| {
+        t.as_deref()
+            .map(|t| translate_type(symbol_table, t))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    };
+    match &t.value {
+        None => Ok(InferenceFact::default()),
+        Some(Value::TensorType(t)) => translate_inference_fact(symbol_table, t),
+        Some(Value::SequenceType(s)) => {
+            Ok(sequence_fact(&element(&s.elem_type)?, DimFact::default()))
+        }
+        Some(Value::OptionalType(o)) => {
+            Ok(sequence_fact(&element(&o.elem_type)?, DimFact::default()))
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a type_proto::Tensor> for InferenceFact {
     type Error = TractError;
     fn try_from(t: &'a type_proto::Tensor) -> TractResult<InferenceFact> {
-        let mut fact = InferenceFact::default();
-        fact = fact.with_datum_type(DataType::from_i32(t.elem_type).unwrap().try_into()?);
-        if let Some(shape) = &t.shape {
-            let shape: TVec<DimFact> = shape
-                .dim
-                .iter()
-                .map(|d| {
-                    let mut fact = DimFact::default();
-                    if let Some(tensor_shape_proto::dimension::Value::DimValue(v)) = d.value {
-                        if v > 0 {
-                            fact = DimFact::from(v.to_dim())
-                        }
-                    }
-                    fact
-                })
-                .collect();
-            fact = fact.with_shape(ShapeFactoid::closed(shape));
-        }
-        Ok(fact)
+        translate_inference_fact(&SymbolTable::default(), t)
     }
 }
 
@@ -63,6 +101,12 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
         let dt = DataType::from_i32(t.data_type).unwrap().try_into()?;
         let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
+        if t.data_location == tensor_proto::DataLocation::External as i32 {
+            bail!(
+                "Tensor {} data is stored in an external file. Load the model with model_for_path.",
+                t.name
+            )
+        }
         if t.raw_data.len() > 0 {
             unsafe {
                 match dt {
@@ -75,6 +119,7 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                     DatumType::I32 => Tensor::from_raw::<i32>(&*shape, &*t.raw_data),
                     DatumType::I64 => Tensor::from_raw::<i64>(&*shape, &*t.raw_data),
                     DatumType::F16 => Tensor::from_raw::<f16>(&*shape, &*t.raw_data),
+                    DatumType::BF16 => Tensor::from_raw::<bf16>(&*shape, &*t.raw_data),
                     DatumType::F32 => Tensor::from_raw::<f32>(&*shape, &*t.raw_data),
                     DatumType::F64 => Tensor::from_raw::<f64>(&*shape, &*t.raw_data),
                     DatumType::Bool => Ok(Tensor::from_raw::<u8>(&*shape, &*t.raw_data)?
@@ -119,6 +164,11 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                 .into(),
                 DatumType::I32 => Array::from_shape_vec(&*shape, t.int32_data.to_vec())?.into(),
                 DatumType::I64 => Array::from_shape_vec(&*shape, t.int64_data.to_vec())?.into(),
+                DatumType::BF16 => Array::from_shape_vec(
+                    &*shape,
+                    t.int32_data.iter().map(|&x| bf16::from_bits(x as u16)).collect(),
+                )?
+                .into(),
                 DatumType::F32 => Array::from_shape_vec(&*shape, t.float_data.to_vec())?.into(),
                 DatumType::F64 => Array::from_shape_vec(&*shape, t.double_data.to_vec())?.into(),
                 DatumType::String => {
@@ -145,6 +195,109 @@ impl TryFrom<TensorProto> for Tensor {
     }
 }
 
+/// Resolves tensors stored out of the protobuf (`data_location = EXTERNAL`)
+/// against files relative to the model directory.
+///
+/// Each external file is opened only once, and only the bytes of each tensor
+/// are read from it.
+pub struct ExternalDataLoader {
+    dir: PathBuf,
+    files: HashMap<String, std::fs::File>,
+}
+
+impl ExternalDataLoader {
+    pub fn new(dir: impl AsRef<Path>) -> ExternalDataLoader {
+        ExternalDataLoader { dir: dir.as_ref().to_owned(), files: HashMap::new() }
+    }
+
+    pub fn load_model(&mut self, model: &mut ModelProto) -> TractResult<()> {
+        if let Some(graph) = &mut model.graph {
+            self.load_graph(graph)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_graph(&mut self, graph: &mut GraphProto) -> TractResult<()> {
+        for tensor in &mut graph.initializer {
+            self.load_tensor(tensor)?;
+        }
+        for node in &mut graph.node {
+            for attr in &mut node.attribute {
+                if let Some(t) = &mut attr.t {
+                    self.load_tensor(t)?;
+                }
+                for t in &mut attr.tensors {
+                    self.load_tensor(t)?;
+                }
+                if let Some(g) = &mut attr.g {
+                    self.load_graph(g)?;
+                }
+                for g in &mut attr.graphs {
+                    self.load_graph(g)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_tensor(&mut self, tensor: &mut TensorProto) -> TractResult<()> {
+        if tensor.data_location != tensor_proto::DataLocation::External as i32 {
+            return Ok(());
+        }
+        let mut location = None;
+        let mut offset = 0usize;
+        let mut length = None;
+        for entry in &tensor.external_data {
+            match &*entry.key {
+                "location" => location = Some(entry.value.clone()),
+                "offset" => offset = entry.value.parse().context("Parsing external data offset")?,
+                "length" => {
+                    length = Some(entry.value.parse().context("Parsing external data length")?)
+                }
+                _ => (),
+            }
+        }
+        let location = location
+            .with_context(|| format!("No location for tensor {} external data", tensor.name))?;
+        let relative = Path::new(&location);
+        if relative.components().any(|c| !matches!(c, std::path::Component::Normal(_))) {
+            bail!(
+                "External data of tensor {} must stay in the model directory: {:?}",
+                tensor.name,
+                location
+            )
+        }
+        if !self.files.contains_key(&location) {
+            let path = self.dir.join(relative);
+            let file = std::fs::File::open(&path)
+                .with_context(|| format!("Opening external data file {:?}", path))?;
+            self.files.insert(location.clone(), file);
+        }
+        let file = self.files.get_mut(&location).unwrap();
+        let file_len = file.metadata()?.len() as usize;
+        let length = length.unwrap_or_else(|| file_len.saturating_sub(offset));
+        if offset.checked_add(length).map(|end| end > file_len).unwrap_or(true) {
+            bail!(
+                "External data for tensor {} ({} bytes at offset {}) overflows {} ({} bytes)",
+                tensor.name,
+                length,
+                offset,
+                location,
+                file_len
+            )
+        }
+        use std::io::{Read, Seek, SeekFrom};
+        let mut data = vec![0u8; length];
+        file.seek(SeekFrom::Start(offset as u64))?;
+        file.read_exact(&mut data)
+            .with_context(|| format!("Reading external data for tensor {}", tensor.name))?;
+        tensor.raw_data = data;
+        tensor.data_location = tensor_proto::DataLocation::Default as i32;
+        tensor.external_data.clear();
+        Ok(())
+    }
+}
+
 pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProto> {
     let mut v = vec![];
     r.read_to_end(&mut v)?;
@@ -155,3 +308,167 @@ pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProt
 pub fn from_reader<R: ::std::io::Read>(r: R) -> TractResult<Tensor> {
     proto_from_reader(r)?.try_into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn external_tensor(name: &str, location: &str, offset: usize, len: usize) -> TensorProto {
+        let entry = |key: &str, value: String| StringStringEntryProto { key: key.into(), value };
+        TensorProto {
+            name: name.into(),
+            dims: vec![2],
+            data_type: DataType::Float as i32,
+            data_location: tensor_proto::DataLocation::External as i32,
+            external_data: vec![
+                entry("location", location.into()),
+                entry("offset", offset.to_string()),
+                entry("length", len.to_string()),
+            ],
+            ..TensorProto::default()
+        }
+    }
+
+    #[test]
+    fn external_data() -> TractResult<()> {
+        let dir = std::env::temp_dir().join(format!("tract-onnx-external-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let data: Vec<u8> =
+            [0f32, 1.0, 2.0, 3.0].iter().flat_map(|x| x.to_le_bytes().to_vec()).collect();
+        std::fs::write(dir.join("weights.bin"), &data)?;
+        let mut graph = GraphProto {
+            initializer: vec![
+                external_tensor("a", "weights.bin", 0, 8),
+                external_tensor("b", "weights.bin", 8, 8),
+            ],
+            ..GraphProto::default()
+        };
+        let t: TractResult<Tensor> = (&graph.initializer[0]).try_into();
+        assert!(t.is_err());
+        ExternalDataLoader::new(&dir).load_graph(&mut graph)?;
+        std::fs::remove_dir_all(&dir)?;
+        let a: Tensor = (&graph.initializer[0]).try_into()?;
+        let b: Tensor = (&graph.initializer[1]).try_into()?;
+        assert_eq!(a, tensor1(&[0f32, 1.0]));
+        assert_eq!(b, tensor1(&[2f32, 3.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn external_data_overflow() -> TractResult<()> {
+        let dir = std::env::temp_dir().join(format!("tract-onnx-overflow-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("weights.bin"), &[0u8; 8])?;
+        let mut t = external_tensor("a", "weights.bin", 4, 8);
+        let result = ExternalDataLoader::new(&dir).load_tensor(&mut t);
+        std::fs::remove_dir_all(&dir)?;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn external_data_offset_overflow() -> TractResult<()> {
+        let dir = std::env::temp_dir().join(format!("tract-onnx-wrap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("weights.bin"), &[0u8; 8])?;
+        let mut t = external_tensor("a", "weights.bin", usize::MAX, 8);
+        let result = ExternalDataLoader::new(&dir).load_tensor(&mut t);
+        std::fs::remove_dir_all(&dir)?;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn external_data_outside_model_dir() -> TractResult<()> {
+        let dir = std::env::temp_dir().join(format!("tract-onnx-escape-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("model"))?;
+        std::fs::write(dir.join("weights.bin"), &[0u8; 8])?;
+        let model_dir = dir.join("model");
+        let escaping = external_tensor("a", "../weights.bin", 0, 8);
+        let absolute = dir.join("weights.bin").to_string_lossy().to_string();
+        let absolute = external_tensor("a", &absolute, 0, 8);
+        let results = [escaping, absolute]
+            .iter_mut()
+            .map(|t| ExternalDataLoader::new(&model_dir).load_tensor(t))
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(&dir)?;
+        assert!(results.iter().all(|r| r.is_err()));
+        Ok(())
+    }
+
+    #[test]
+    fn dim_param_maps_to_symbols() -> TractResult<()> {
+        use tensor_shape_proto::dimension::Value;
+        let dim = |v| tensor_shape_proto::Dimension { value: Some(v), ..Default::default() };
+        let t = type_proto::Tensor {
+            elem_type: DataType::Float as i32,
+            shape: Some(TensorShapeProto {
+                dim: vec![
+                    dim(Value::DimParam("batch_size".into())),
+                    dim(Value::DimValue(3)),
+                    dim(Value::DimParam("".into())),
+                ],
+            }),
+        };
+        let symbols = SymbolTable::default();
+        let fact = translate_inference_fact(&symbols, &t)?;
+        let batch = symbols.get("batch_size").unwrap();
+        assert_eq!(
+            fact.shape,
+            ShapeFactoid::closed(tvec!(
+                batch.to_dim().into(),
+                3.to_dim().into(),
+                DimFact::default()
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_type() -> TractResult<()> {
+        use tensor_shape_proto::dimension::Value;
+        let dim = |v| tensor_shape_proto::Dimension { value: Some(v), ..Default::default() };
+        let tensor = type_proto::Tensor {
+            elem_type: DataType::Float as i32,
+            shape: Some(TensorShapeProto { dim: vec![dim(Value::DimValue(3))] }),
+        };
+        let tensor = TypeProto {
+            value: Some(type_proto::Value::TensorType(tensor)),
+            ..TypeProto::default()
+        };
+        let sequence = TypeProto {
+            value: Some(type_proto::Value::SequenceType(Box::new(type_proto::Sequence {
+                elem_type: Some(Box::new(tensor)),
+            }))),
+            ..TypeProto::default()
+        };
+        let fact = translate_type(&SymbolTable::default(), &sequence)?;
+        assert_eq!(fact.datum_type, f32::datum_type().into());
+        assert_eq!(fact.shape, ShapeFactoid::closed(tvec!(DimFact::default(), 3.to_dim().into())));
+        Ok(())
+    }
+
+    #[test]
+    fn bfloat16() -> TractResult<()> {
+        // 1.0 and -2.5 in bfloat16
+        let bits = [0x3f80u16, 0xc020];
+        let raw = TensorProto {
+            dims: vec![2],
+            data_type: DataType::Bfloat16 as i32,
+            raw_data: bits.iter().flat_map(|b| b.to_le_bytes().to_vec()).collect(),
+            ..TensorProto::default()
+        };
+        let packed = TensorProto {
+            dims: vec![2],
+            data_type: DataType::Bfloat16 as i32,
+            int32_data: bits.iter().map(|&b| b as i32).collect(),
+            ..TensorProto::default()
+        };
+        for proto in &[raw, packed] {
+            let t = Tensor::try_from(proto)?;
+            assert_eq!(t.datum_type(), DatumType::BF16);
+            assert_eq!(*t.cast_to::<f32>()?, tensor1(&[1f32, -2.5]));
+        }
+        Ok(())
+    }
+}
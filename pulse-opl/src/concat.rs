@@ -89,6 +89,10 @@ impl OpState for PulsedSameAxisConcatState {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 unsafe fn overwrite_part_of_pulse<T: Datum>(
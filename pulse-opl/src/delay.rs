@@ -79,6 +79,10 @@ impl OpState for DelayState {
             Ok(tvec!(output))
         }
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
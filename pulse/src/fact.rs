@@ -1,15 +1,16 @@
 use crate::internal::*;
 
 lazy_static::lazy_static! {
-    static ref S: Symbol = Symbol::new('S');
+    // the streaming axis is shared by all the pulsed models
+    static ref S: Symbol = SymbolTable::default().sym("S");
 }
 
 pub fn stream_symbol() -> Symbol {
-    *S
+    S.clone()
 }
 
 pub fn stream_dim() -> TDim {
-    (*S).into()
+    (&*S).into()
 }
 
 pub trait StreamFact {
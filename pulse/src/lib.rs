@@ -94,4 +94,52 @@ mod tests {
             TypedFact::dt_shape(DatumType::F32, &[4, 2, 3])
         );
     }
+
+    fn depth_to_space_model(shape: &[TDim]) -> TypedModel {
+        use tract_core::ops::nn::{wire_depth_to_space, DataFormat, DepthToSpaceMode};
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), shape)).unwrap();
+        let d2s =
+            wire_depth_to_space("d2s", &mut model, a, DataFormat::NCHW, 2, DepthToSpaceMode::DCR)
+                .unwrap();
+        model.set_output_outlets(&d2s).unwrap();
+        model
+    }
+
+    #[test]
+    fn test_depth_to_space_with_stream_on_batch() {
+        let model = depth_to_space_model(&[stream_dim(), 8.to_dim(), 2.to_dim(), 3.to_dim()]);
+        let pulse = PulsedModel::new(&model, 4).unwrap();
+        assert_eq!(
+            pulse.output_fact(0).unwrap().to_typed_fact().unwrap(),
+            TypedFact::dt_shape(DatumType::F32, &[4, 2, 4, 6])
+        );
+        assert_eq!(pulse.output_fact(0).unwrap().axis, 0);
+        assert_eq!(pulse.output_fact(0).unwrap().delay, 0);
+
+        let input = Tensor::from(tract_ndarray::ArrayD::from_shape_fn(&[8, 8, 2, 3][..], |ix| {
+            (ix[0] * 48 + ix[1] * 6 + ix[2] * 3 + ix[3]) as f32
+        }));
+        let concrete =
+            model.concretize_dims(&SymbolValues::default().with(&stream_symbol(), 8)).unwrap();
+        let expected = SimplePlan::new(&concrete).unwrap().run(tvec!(input.clone())).unwrap();
+
+        let plan = SimplePlan::new(pulse).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let mut chunks = vec![];
+        for i in 0..2 {
+            let chunk = input.slice(0, i * 4, (i + 1) * 4).unwrap();
+            chunks.push(state.run(tvec!(chunk)).unwrap().remove(0).into_tensor());
+        }
+        let got = Tensor::stack_tensors(0, &chunks).unwrap();
+        assert_eq!(got, *expected[0]);
+    }
+
+    #[test]
+    fn test_depth_to_space_with_stream_on_spatial_axis() {
+        let on_h = depth_to_space_model(&[1.to_dim(), 8.to_dim(), stream_dim(), 3.to_dim()]);
+        assert!(PulsedModel::new(&on_h, 4).is_err());
+        let on_w = depth_to_space_model(&[1.to_dim(), 8.to_dim(), 2.to_dim(), stream_dim()]);
+        assert!(PulsedModel::new(&on_w, 4).is_err());
+    }
 }
@@ -182,7 +182,7 @@ impl OpState for PulsedSameAxisConcatState {
             &op.pre_slice,
             pre_offset
         ))?;
-        if self.symbols_in_dim.iter().all(|s| session.resolved_symbols[*s].is_some()) {
+        if self.symbols_in_dim.iter().all(|s| session.resolved_symbols[s].is_some()) {
             let l = op.input_len.eval(&session.resolved_symbols).to_usize().unwrap();
             let post_offset = op.input_delay + l as usize;
             dispatch_datum!(overwrite_part_of_pulse(data.datum_type())(
@@ -196,6 +196,10 @@ impl OpState for PulsedSameAxisConcatState {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
 }
 
 pub fn overwrite_part_of_pulse<T: Datum>(
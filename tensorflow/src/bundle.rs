@@ -0,0 +1,219 @@
+//! Reader for TensorFlow tensor bundles, the checkpoint format found in the
+//! `variables/` directory of a SavedModel.
+//!
+//! A bundle is made of an index file, `{prefix}.index`, and data shards,
+//! `{prefix}.data-{shard:05}-of-{num_shards:05}`. The index is a (LevelDB)
+//! sorted table mapping tensor names to a BundleEntryProto locating the
+//! tensor bytes in a shard. The empty key holds the BundleHeaderProto.
+
+use crate::tfpb::tensorflow::bundle_header_proto::Endianness;
+use crate::tfpb::tensorflow::{
+    BundleEntryProto, BundleHeaderProto, DataType, TensorProto, TrackableObjectGraph,
+};
+use prost::Message;
+use std::convert::TryInto;
+use std::io::{Read, Seek, SeekFrom};
+use std::{fs, path};
+use tract_hir::internal::*;
+
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+const FOOTER_LEN: usize = 48;
+const BLOCK_TRAILER_LEN: usize = 5;
+
+/// Key of the serialized TrackableObjectGraph in TF2 checkpoints.
+pub const OBJECT_GRAPH_KEY: &str = "_CHECKPOINTABLE_OBJECT_GRAPH";
+
+#[derive(Debug)]
+pub struct TensorBundle {
+    prefix: path::PathBuf,
+    pub header: BundleHeaderProto,
+    pub entries: HashMap<String, BundleEntryProto>,
+}
+
+impl TensorBundle {
+    /// Open the bundle at `prefix` (the path of the index, without its
+    /// `.index` extension).
+    pub fn open(prefix: impl AsRef<path::Path>) -> TractResult<TensorBundle> {
+        let prefix = prefix.as_ref().to_path_buf();
+        let index_path = prefix.with_file_name(format!(
+            "{}.index",
+            prefix.file_name().context("Invalid bundle prefix")?.to_string_lossy()
+        ));
+        let index = fs::read(&index_path)
+            .with_context(|| format!("Could not read bundle index {:?}", index_path))?;
+        let mut header = None;
+        let mut entries = HashMap::new();
+        for (key, value) in read_table(&index)? {
+            if key.is_empty() {
+                header = Some(BundleHeaderProto::decode(&*value)?);
+            } else {
+                let key = String::from_utf8(key).context("Non UTF-8 tensor name in bundle")?;
+                entries.insert(key, BundleEntryProto::decode(&*value)?);
+            }
+        }
+        let header = header.context("Bundle index has no header")?;
+        if header.endianness != Endianness::Little as i32 {
+            bail!("Only little endian tensor bundles are supported")
+        }
+        Ok(TensorBundle { prefix, header, entries })
+    }
+
+    fn read_bytes(&self, entry: &BundleEntryProto) -> TractResult<Vec<u8>> {
+        let shard = self.prefix.with_file_name(format!(
+            "{}.data-{:05}-of-{:05}",
+            self.prefix.file_name().unwrap().to_string_lossy(),
+            entry.shard_id,
+            self.header.num_shards
+        ));
+        let mut file =
+            fs::File::open(&shard).with_context(|| format!("Could not open shard {:?}", shard))?;
+        file.seek(SeekFrom::Start(entry.offset as u64))?;
+        let mut bytes = vec![0u8; entry.size as usize];
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Tensor stored under `key`, as a TensorProto.
+    pub fn tensor_proto(&self, key: &str) -> TractResult<TensorProto> {
+        let entry =
+            self.entries.get(key).with_context(|| format!("No tensor {} in bundle", key))?;
+        if entry.slices.len() > 0 {
+            bail!("Partitioned tensor {} in bundle are not supported", key)
+        }
+        let bytes = self.read_bytes(entry)?;
+        let mut proto = TensorProto {
+            dtype: entry.dtype,
+            tensor_shape: entry.shape.clone(),
+            version_number: 0,
+            tensor_content: vec![],
+            half_val: vec![],
+            float_val: vec![],
+            double_val: vec![],
+            int_val: vec![],
+            string_val: vec![],
+            scomplex_val: vec![],
+            dcomplex_val: vec![],
+            resource_handle_val: vec![],
+            variant_val: vec![],
+            uint32_val: vec![],
+            uint64_val: vec![],
+            int64_val: vec![],
+            bool_val: vec![],
+        };
+        if entry.dtype == DataType::DtString as i32 {
+            // string tensors are the varint64 lengths of all elements, a
+            // checksum of the lengths, then the concatenated bytes
+            let len: usize = entry
+                .shape
+                .as_ref()
+                .map(|s| s.dim.iter().map(|d| d.size as usize).product())
+                .unwrap_or(1);
+            let mut cursor = &*bytes;
+            let lens =
+                (0..len).map(|_| read_varint(&mut cursor)).collect::<TractResult<Vec<_>>>()?;
+            let mut data = cursor.get(4..).context("Truncated string tensor in bundle")?;
+            for len in lens {
+                let len = len as usize;
+                if data.len() < len {
+                    bail!("Truncated string tensor in bundle")
+                }
+                proto.string_val.push(data[..len].to_vec());
+                data = &data[len..];
+            }
+        } else {
+            proto.tensor_content = bytes;
+        }
+        Ok(proto)
+    }
+
+    /// Tensor stored under `key`.
+    pub fn tensor(&self, key: &str) -> TractResult<Tensor> {
+        (&self.tensor_proto(key)?).try_into()
+    }
+
+    /// The object graph of TF2 checkpoints, mapping trackable objects (and
+    /// their variables) to their checkpoint keys.
+    pub fn object_graph(&self) -> TractResult<Option<TrackableObjectGraph>> {
+        if !self.entries.contains_key(OBJECT_GRAPH_KEY) {
+            return Ok(None);
+        }
+        let proto = self.tensor_proto(OBJECT_GRAPH_KEY)?;
+        let bytes = proto.string_val.get(0).context("Empty object graph in bundle")?;
+        Ok(Some(TrackableObjectGraph::decode(&**bytes)?))
+    }
+}
+
+fn read_varint(bytes: &mut &[u8]) -> TractResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().context("Truncated varint")?;
+        *bytes = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("Invalid varint")
+}
+
+fn read_block_handle(bytes: &mut &[u8]) -> TractResult<(usize, usize)> {
+    Ok((read_varint(bytes)? as usize, read_varint(bytes)? as usize))
+}
+
+fn read_block(
+    table: &[u8],
+    (offset, size): (usize, usize),
+) -> TractResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    let end = offset
+        .checked_add(size)
+        .and_then(|end| end.checked_add(BLOCK_TRAILER_LEN))
+        .context("Invalid table block handle")?;
+    let block = table.get(offset..end).context("Truncated table block")?;
+    if block[size] != 0 {
+        bail!("Compressed table blocks are not supported")
+    }
+    let block = &block[..size];
+    if block.len() < 4 {
+        bail!("Truncated table block")
+    }
+    let restarts = u32::from_le_bytes(block[block.len() - 4..].try_into()?) as usize;
+    let end = block
+        .len()
+        .checked_sub(4 + 4 * restarts)
+        .context("Invalid restart count in table block")?;
+    let mut data = &block[..end];
+    let mut key: Vec<u8> = vec![];
+    let mut entries = vec![];
+    while data.len() > 0 {
+        let shared = read_varint(&mut data)? as usize;
+        let non_shared = read_varint(&mut data)? as usize;
+        let value_len = read_varint(&mut data)? as usize;
+        if shared > key.len() || data.len() < non_shared + value_len {
+            bail!("Invalid table block entry")
+        }
+        key.truncate(shared);
+        key.extend_from_slice(&data[..non_shared]);
+        entries.push((key.clone(), data[non_shared..][..value_len].to_vec()));
+        data = &data[non_shared + value_len..];
+    }
+    Ok(entries)
+}
+
+/// All the key and values of a sorted table, in order.
+fn read_table(table: &[u8]) -> TractResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    if table.len() < FOOTER_LEN {
+        bail!("Table is too short")
+    }
+    let footer = &table[table.len() - FOOTER_LEN..];
+    if u64::from_le_bytes(footer[FOOTER_LEN - 8..].try_into()?) != TABLE_MAGIC {
+        bail!("Invalid table magic number")
+    }
+    let mut handles = footer;
+    let _metaindex = read_block_handle(&mut handles)?;
+    let index = read_block_handle(&mut handles)?;
+    let mut entries = vec![];
+    for (_, handle) in read_block(table, index)? {
+        entries.extend(read_block(table, read_block_handle(&mut &*handle)?)?);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,257 @@
+//! TensorFlow 2 function library support.
+//!
+//! Function calls (PartitionedCall, StatefulPartitionedCall, or nodes whose
+//! op is a function name) are inlined in the calling graph. Functions used as
+//! While or If bodies are turned into stand-alone graphs, their arguments
+//! becoming placeholders.
+
+use crate::model::TfOpRegister;
+use crate::tfpb::tensorflow::{DataType, FunctionDef, FunctionDefLibrary, GraphDef, NodeDef};
+use std::convert::TryFrom;
+use tract_hir::internal::*;
+
+// calls nested deeper than this are most likely recursive
+const MAX_INLINING_DEPTH: usize = 64;
+
+// Registered ops with more than one output argument, and their argument
+// names. Other registered ops outputs are found by their index in their single
+// output argument.
+const OUTPUT_ARGS: &[(&str, &[&str])] = &[
+    ("BlockLSTM", &["i", "cs", "f", "o", "ci", "co", "h"]),
+    (
+        "FusedBatchNorm",
+        &["y", "batch_mean", "batch_variance", "reserve_space_1", "reserve_space_2"],
+    ),
+    (
+        "FusedBatchNormV3",
+        &[
+            "y",
+            "batch_mean",
+            "batch_variance",
+            "reserve_space_1",
+            "reserve_space_2",
+            "reserve_space_3",
+        ],
+    ),
+    ("Merge", &["output", "value_index"]),
+    ("NonMaxSuppressionV4", &["selected_indices", "valid_outputs"]),
+    ("NonMaxSuppressionV5", &["selected_indices", "selected_scores", "valid_outputs"]),
+    ("Switch", &["output_false", "output_true"]),
+    ("TopKV2", &["values", "indices"]),
+    ("Unique", &["y", "idx"]),
+];
+
+pub fn function<'a>(
+    library: Option<&'a FunctionDefLibrary>,
+    name: &str,
+) -> Option<&'a FunctionDef> {
+    library?.function.iter().find(|f| f.signature.as_ref().map(|s| &*s.name) == Some(name))
+}
+
+/// The function called by a node, if any.
+pub fn called_function<'a>(
+    library: Option<&'a FunctionDefLibrary>,
+    node: &NodeDef,
+) -> TractResult<Option<&'a FunctionDef>> {
+    let name = if node.op == "PartitionedCall" || node.op == "StatefulPartitionedCall" {
+        node.get_attr_func_name("f")?
+    } else {
+        &node.op
+    };
+    Ok(function(library, name))
+}
+
+fn output_slot(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    op: &str,
+    arg: &str,
+    ix: usize,
+) -> TractResult<usize> {
+    let args: Vec<&str> = if let Some(func) = function(library, op) {
+        func.signature.as_ref().unwrap().output_arg.iter().map(|a| &*a.name).collect()
+    } else if let Some((_, args)) = OUTPUT_ARGS.iter().find(|(name, _)| *name == op) {
+        args.to_vec()
+    } else if ops.0.contains_key(op) || op == "PartitionedCall" || op == "StatefulPartitionedCall" {
+        // calls have a single list output, inlined as an IdentityN
+        return Ok(ix);
+    } else {
+        bail!("Output arguments of {} are unknown, can not resolve {}:{}", op, arg, ix)
+    };
+    let pos = args
+        .iter()
+        .position(|a| *a == arg)
+        .with_context(|| format!("No output argument {} for {}", arg, op))?;
+    Ok(pos + ix)
+}
+
+/// Translate a function body input ("arg", "node:output_arg:ix" or "^node")
+/// to the graph format ("node:slot" or "^node"), `arg` being called with the
+/// argument name to substitute.
+fn graph_input(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    body: &HashMap<&str, &NodeDef>,
+    prefix: &str,
+    input: &str,
+    arg: &dyn Fn(&str) -> TractResult<String>,
+) -> TractResult<String> {
+    if input.starts_with("^") {
+        if body.contains_key(&input[1..]) {
+            return Ok(format!("^{}{}", prefix, &input[1..]));
+        } else {
+            let outer = arg(&input[1..])?;
+            return Ok(format!("^{}", outer.trim_start_matches("^").split(':').next().unwrap()));
+        }
+    }
+    let splits: Vec<&str> = input.split(':').collect();
+    match splits.len() {
+        1 => arg(input),
+        3 => {
+            let node = body.get(splits[0]).with_context(|| format!("No node for {}", input))?;
+            let slot = output_slot(library, ops, &node.op, splits[1], splits[2].parse()?)?;
+            Ok(format!("{}{}:{}", prefix, splits[0], slot))
+        }
+        _ => bail!("Invalid function input {}", input),
+    }
+}
+
+fn signature_inputs(func: &FunctionDef) -> TractResult<Vec<&str>> {
+    let signature = func.signature.as_ref().context("Function without signature")?;
+    signature
+        .input_arg
+        .iter()
+        .map(|arg| {
+            if arg.number_attr != "" || arg.type_list_attr != "" {
+                bail!("Function {} has list arguments, which are not supported", signature.name)
+            }
+            Ok(&*arg.name)
+        })
+        .collect()
+}
+
+/// Function results, in signature order, in the graph format.
+fn results(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    func: &FunctionDef,
+    body: &HashMap<&str, &NodeDef>,
+    prefix: &str,
+    arg: &dyn Fn(&str) -> TractResult<String>,
+) -> TractResult<Vec<String>> {
+    let signature = func.signature.as_ref().unwrap();
+    signature
+        .output_arg
+        .iter()
+        .map(|output| {
+            let ret = func.ret.get(&output.name).with_context(|| {
+                format!("No result for {} in function {}", output.name, signature.name)
+            })?;
+            graph_input(library, ops, body, prefix, ret, arg)
+        })
+        .collect()
+}
+
+fn inline_call(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    call: &NodeDef,
+    func: &FunctionDef,
+) -> TractResult<Vec<NodeDef>> {
+    let args = signature_inputs(func)?;
+    let inputs: Vec<&String> = call.input.iter().filter(|i| !i.starts_with("^")).collect();
+    if args.len() != inputs.len() {
+        bail!("Call {} has {} inputs, function expects {}", call.name, inputs.len(), args.len())
+    }
+    let arg = |name: &str| -> TractResult<String> {
+        let ix = args
+            .iter()
+            .position(|a| *a == name)
+            .with_context(|| format!("No argument {} in function called by {}", name, call.name))?;
+        Ok(inputs[ix].clone())
+    };
+    let body: HashMap<&str, &NodeDef> = func.node_def.iter().map(|n| (&*n.name, n)).collect();
+    let prefix = format!("{}/", call.name);
+    let mut nodes = vec![];
+    for node in &func.node_def {
+        let mut node = node.clone();
+        node.name = format!("{}{}", prefix, node.name);
+        node.input = node
+            .input
+            .iter()
+            .map(|i| graph_input(library, ops, &body, &prefix, i, &arg))
+            .collect::<TractResult<_>>()?;
+        nodes.push(node);
+    }
+    // the call node becomes the identity of the function results
+    let mut results = NodeDef {
+        name: call.name.clone(),
+        op: "IdentityN".to_string(),
+        input: results(library, ops, func, &body, &prefix, &arg)?,
+        device: String::new(),
+        attr: HashMap::new(),
+    };
+    results.input.extend(call.input.iter().filter(|i| i.starts_with("^")).cloned());
+    nodes.push(results);
+    Ok(nodes)
+}
+
+/// Inline all function calls, recursively.
+pub fn inline_calls(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    mut nodes: Vec<NodeDef>,
+) -> TractResult<Vec<NodeDef>> {
+    for _ in 0..MAX_INLINING_DEPTH {
+        let mut inlined = vec![];
+        let mut changed = false;
+        for node in nodes {
+            if let Some(func) = called_function(library, &node)? {
+                inlined.extend(inline_call(library, ops, &node, func)?);
+                changed = true;
+            } else {
+                inlined.push(node);
+            }
+        }
+        nodes = inlined;
+        if !changed {
+            return Ok(nodes);
+        }
+    }
+    bail!("Function calls are nested too deeply (recursive functions are not supported)")
+}
+
+/// A graph computing the function, with a placeholder for each argument.
+///
+/// Returns the graph and its output names.
+pub fn function_graph(
+    library: Option<&FunctionDefLibrary>,
+    ops: &TfOpRegister,
+    func: &FunctionDef,
+) -> TractResult<(GraphDef, Vec<String>)> {
+    let signature = func.signature.as_ref().context("Function without signature")?;
+    signature_inputs(func)?;
+    let mut graph = GraphDef::default();
+    for arg in &signature.input_arg {
+        let mut placeholder = crate::tfpb::node().name(&arg.name).op("Placeholder");
+        // resources and variants have no tract counterpart, leave them untyped
+        let dt = DataType::from_i32(arg.r#type).context("Invalid argument type")?;
+        if DatumType::try_from(dt).is_ok() {
+            placeholder = placeholder.attr("dtype", dt);
+        }
+        graph.node.push(placeholder);
+    }
+    let body: HashMap<&str, &NodeDef> = func.node_def.iter().map(|n| (&*n.name, n)).collect();
+    let arg = |name: &str| Ok(name.to_string());
+    for node in &func.node_def {
+        let mut node = node.clone();
+        node.input = node
+            .input
+            .iter()
+            .map(|i| graph_input(library, ops, &body, "", i, &arg))
+            .collect::<TractResult<_>>()?;
+        graph.node.push(node);
+    }
+    let outputs = results(library, ops, func, &body, "", &arg)?;
+    Ok((graph, outputs))
+}
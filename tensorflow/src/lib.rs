@@ -51,6 +51,8 @@ pub extern crate tract_hir;
 #[cfg(feature = "conform")]
 pub mod conform;
 
+pub mod bundle;
+pub mod function;
 pub mod model;
 pub mod ops;
 pub mod tensor;
@@ -1,11 +1,47 @@
-use crate::tfpb::tensorflow::{GraphDef, NodeDef, SavedModel};
+use crate::bundle::TensorBundle;
+use crate::tfpb::tensorflow::attr_value::Value;
+use crate::tfpb::tensorflow::tensor_info::Encoding;
+use crate::tfpb::tensorflow::{
+    DataType, FunctionDefLibrary, GraphDef, MetaGraphDef, NodeDef, SavedModel, SignatureDef,
+    TensorInfo,
+};
 use prost::Message;
+use std::convert::TryInto;
 use std::{fs, path};
 use tract_hir::internal::*;
 
-#[derive(Default)]
-pub struct ParsingContext {
+/// Tag set of the serving meta graph in SavedModels.
+pub const SERVING_TAG: &str = "serve";
+/// Name of the default signature in SavedModels.
+pub const DEFAULT_SIGNATURE: &str = "serving_default";
+
+pub struct ParsingContext<'a> {
+    pub framework: &'a Tensorflow,
+    pub library: Option<&'a FunctionDefLibrary>,
     pub node_output_arities: HashMap<String, usize>,
+    pub symbol_table: SymbolTable,
+}
+
+impl<'a> ParsingContext<'a> {
+    /// Parse a function of the library: model inputs are the function
+    /// arguments, outputs its results.
+    pub fn parse_function(&self, name: &str) -> TractResult<InferenceModel> {
+        let func = crate::function::function(self.library, name)
+            .with_context(|| format!("Function {} not found in library", name))?;
+        let (graph, outputs) =
+            crate::function::function_graph(self.library, &self.framework.op_register, func)?;
+        let mut model =
+            self.framework.parse_graph_with_library(&graph, self.library, &self.symbol_table)?.0;
+        let outputs = outputs
+            .iter()
+            .map(|o| {
+                let (node, slot) = Tensorflow::parse_input(o)?;
+                Ok(OutletId::new(model.node_id_by_name(node)?, slot))
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
 }
 
 #[derive(Clone, Default)]
@@ -62,7 +98,7 @@ impl Tensorflow {
     // "src_output" indicating which output tensor to use from "node". If
     // "src_output" is 0 the ":0" suffix can be omitted. Regular inputs may
     // optionally be followed by control inputs that have the format "^node".
-    fn parse_input(i: &str) -> TractResult<(&str, usize)> {
+    pub(crate) fn parse_input(i: &str) -> TractResult<(&str, usize)> {
         let pair = if i.starts_with("^") {
             (&i[1..], 0)
         } else {
@@ -109,18 +145,261 @@ impl Tensorflow {
     }
 
     /// Convenience method: will read the first model in the saved model
-    /// container. Use open_avec_model for more control.
+    /// container. Use open_saved_model for more control, or
+    /// read_saved_model_dir to also restore the variables.
     pub fn read_saved_model(&self, r: &mut dyn std::io::Read) -> TractResult<GraphDef> {
         let mut saved = self.open_saved_model(r)?;
         Ok(saved.meta_graphs.remove(0).graph_def.unwrap())
     }
 
+    /// Read the saved_model.pb of a SavedModel directory.
+    pub fn open_saved_model_dir(&self, dir: impl AsRef<path::Path>) -> TractResult<SavedModel> {
+        let pb = dir.as_ref().join("saved_model.pb");
+        self.open_saved_model(
+            &mut fs::File::open(&pb).with_context(|| format!("Could not open {:?}", pb))?,
+        )
+    }
+
+    /// Read the meta graph tagged with exactly `tags` from a SavedModel
+    /// directory, its variables restored as constants from the checkpoint in
+    /// `variables/`.
+    pub fn read_saved_model_dir(
+        &self,
+        dir: impl AsRef<path::Path>,
+        tags: &[&str],
+    ) -> TractResult<MetaGraphDef> {
+        let saved = self.open_saved_model_dir(dir.as_ref())?;
+        let tag_set = |meta: &MetaGraphDef| {
+            let mut tags: Vec<String> =
+                meta.meta_info_def.as_ref().map(|info| info.tags.clone()).unwrap_or_default();
+            tags.sort();
+            tags
+        };
+        let mut wanted = tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        wanted.sort();
+        let available = saved.meta_graphs.iter().map(tag_set).collect::<Vec<_>>();
+        let mut meta = saved
+            .meta_graphs
+            .into_iter()
+            .find(|meta| tag_set(meta) == wanted)
+            .with_context(|| {
+                format!("No meta graph tagged {:?} in saved model (found {:?})", tags, available)
+            })?;
+        let variables = dir.as_ref().join("variables");
+        if variables.join("variables.index").exists() {
+            let bundle = TensorBundle::open(variables.join("variables"))?;
+            let graph = meta.graph_def.as_mut().context("Meta graph has no graph")?;
+            Self::restore_variables(graph, &bundle)?;
+        }
+        Ok(meta)
+    }
+
+    /// Load the model behind a signature (e.g. DEFAULT_SIGNATURE) of the meta
+    /// graph tagged with `tags` (e.g. SERVING_TAG) in a SavedModel directory.
+    pub fn model_for_saved_model_dir(
+        &self,
+        dir: impl AsRef<path::Path>,
+        tags: &[&str],
+        signature: &str,
+    ) -> TractResult<InferenceModel> {
+        let meta = self.read_saved_model_dir(dir, tags)?;
+        let signature = meta.signature_def.get(signature).with_context(|| {
+            format!(
+                "No signature {} in meta graph (found {:?})",
+                signature,
+                meta.signature_def.keys().collect::<Vec<_>>()
+            )
+        })?;
+        let graph = meta.graph_def.as_ref().context("Meta graph has no graph")?;
+        Ok(self.parse_graph_with_signature(graph, signature)?.0)
+    }
+
+    /// Replace the variables of `graph` found in `bundle` by constants.
+    ///
+    /// Variables are matched with their checkpoint keys by following the
+    /// assignments of the graph restore ops, then by name, shared name or
+    /// object graph full name.
+    pub fn restore_variables(graph: &mut GraphDef, bundle: &TensorBundle) -> TractResult<()> {
+        let keys = Self::variable_keys(graph, bundle)?;
+        let is_restored = |input: &str| -> TractResult<bool> {
+            Ok(keys.contains_key(Self::parse_input(input)?.0))
+        };
+        for node in &mut graph.node {
+            let assigns_restored = match node.input.first() {
+                Some(var) if node.op == "Assign" || node.op == "AssignVariableOp" => {
+                    is_restored(var)?
+                }
+                _ => false,
+            };
+            let control_inputs = node.input.iter().filter(|i| i.starts_with("^")).cloned();
+            if let Some(key) = keys.get(&node.name) {
+                let value = bundle.tensor_proto(key)?;
+                let dt = DataType::from_i32(value.dtype).context("Invalid datatype in bundle")?;
+                node.op = "Const".to_string();
+                node.input = control_inputs.collect();
+                node.attr.clear();
+                node.attr.insert("dtype".to_string(), dt.into());
+                node.attr.insert("value".to_string(), value.into());
+            } else if node.op == "Assign" && assigns_restored {
+                // assigning the restored value: just forward the variable
+                node.op = "Identity".to_string();
+                node.input = std::iter::once(node.input[0].clone()).chain(control_inputs).collect();
+                node.attr.clear();
+            } else if node.op == "AssignVariableOp" && assigns_restored {
+                node.op = "NoOp".to_string();
+                node.input = control_inputs.collect();
+                node.attr.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn variable_keys(
+        graph: &GraphDef,
+        bundle: &TensorBundle,
+    ) -> TractResult<HashMap<String, String>> {
+        let nodes: HashMap<&str, &NodeDef> = graph.node.iter().map(|n| (&*n.name, n)).collect();
+        let mut keys = HashMap::new();
+        // TF1 graphs: Assign(variable, RestoreV2:i), with RestoreV2 second
+        // input the constant list of the checkpoint keys
+        for node in &graph.node {
+            if (node.op != "Assign" && node.op != "AssignVariableOp") || node.input.len() < 2 {
+                continue;
+            }
+            let mut value = Self::parse_input(&node.input[1])?;
+            while let Some(prec) = nodes.get(value.0).filter(|n| n.op == "Identity") {
+                let input = prec.input.first().context("Identity node without input")?;
+                value = Self::parse_input(input)?;
+            }
+            let restore = match nodes.get(value.0) {
+                Some(restore) if restore.op == "RestoreV2" && restore.input.len() > 1 => restore,
+                _ => continue,
+            };
+            let names = nodes
+                .get(Self::parse_input(&restore.input[1])?.0)
+                .and_then(|n| n.attr.get("value"))
+                .and_then(|a| a.value.as_ref());
+            if let Some(Value::Tensor(names)) = names {
+                if let Some(key) = names.string_val.get(value.1) {
+                    let var = Self::parse_input(&node.input[0])?.0;
+                    keys.insert(var.to_string(), String::from_utf8(key.clone())?);
+                }
+            }
+        }
+        // TF2 checkpoints: variable full names in the object graph
+        let mut full_names = HashMap::new();
+        if let Some(object_graph) = bundle.object_graph()? {
+            for attr in object_graph.nodes.iter().flat_map(|n| n.attributes.iter()) {
+                if attr.name == "VARIABLE_VALUE" {
+                    full_names.insert(attr.full_name.clone(), attr.checkpoint_key.clone());
+                }
+            }
+        }
+        for node in &graph.node {
+            if (node.op != "VariableV2" && node.op != "VarHandleOp")
+                || keys.contains_key(&node.name)
+            {
+                continue;
+            }
+            let shared_name = node.get_attr_opt_str("shared_name")?.filter(|s| s != "");
+            for name in shared_name.iter().chain(std::iter::once(&node.name)) {
+                let key = if bundle.entries.contains_key(name) {
+                    Some(name.clone())
+                } else {
+                    full_names.get(name).cloned()
+                };
+                if let Some(key) = key {
+                    keys.insert(node.name.clone(), key);
+                    break;
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Parse the graph, with the inputs and outputs of the signature, in
+    /// signature key order.
+    pub fn parse_graph_with_signature(
+        &self,
+        graph: &GraphDef,
+        signature: &SignatureDef,
+    ) -> TractResult<TfModelAndExtensions> {
+        let mut model_and_ext = self.parse_graph(graph)?;
+        let model = &mut model_and_ext.0;
+        let outlet = |model: &InferenceModel, info: &TensorInfo| -> TractResult<OutletId> {
+            match &info.encoding {
+                Some(Encoding::Name(name)) => {
+                    let (node, slot) = Self::parse_input(name)?;
+                    Ok(OutletId::new(model.node_id_by_name(node)?, slot))
+                }
+                _ => bail!("Only dense tensors are supported in signatures, got {:?}", info),
+            }
+        };
+        let mut inputs = signature.inputs.iter().collect::<Vec<_>>();
+        inputs.sort_by_key(|pair| pair.0);
+        let mut input_outlets = tvec!();
+        for (_, info) in inputs {
+            let input = outlet(model, info)?;
+            let mut fact = InferenceFact::default();
+            if let Some(dt) = DataType::from_i32(info.dtype).filter(|dt| *dt != DataType::DtInvalid)
+            {
+                fact = fact.with_datum_type(dt.try_into()?);
+            }
+            if let Some(shape) = info.tensor_shape.as_ref().filter(|s| !s.unknown_rank) {
+                let shape: TVec<isize> = shape.try_into()?;
+                fact = fact.with_shape(shape_factoid(&shape));
+            }
+            model.set_outlet_fact(input, fact)?;
+            input_outlets.push(input);
+        }
+        let mut outputs = signature.outputs.iter().collect::<Vec<_>>();
+        outputs.sort_by_key(|pair| pair.0);
+        let output_outlets = outputs
+            .iter()
+            .map(|(_, info)| outlet(model, info))
+            .collect::<TractResult<TVec<_>>>()?;
+        model.set_input_outlets(&input_outlets)?;
+        model.set_output_outlets(&output_outlets)?;
+        Ok(model_and_ext)
+    }
+
+    /// Parse the graph, inlining the calls to functions of its library.
     pub fn parse_graph(&self, graph: &GraphDef) -> TractResult<TfModelAndExtensions> {
+        self.parse_graph_with_library(graph, graph.library.as_ref(), &SymbolTable::default())
+    }
+
+    fn parse_graph_with_library(
+        &self,
+        graph: &GraphDef,
+        library: Option<&FunctionDefLibrary>,
+        symbol_table: &SymbolTable,
+    ) -> TractResult<TfModelAndExtensions> {
         use crate::ops::control_flow as cf;
 
+        let inlined;
+        let graph = if library.map(|l| l.function.len() > 0).unwrap_or(false) {
+            inlined = GraphDef {
+                node: crate::function::inline_calls(
+                    library,
+                    &self.op_register,
+                    graph.node.clone(),
+                )?,
+                ..GraphDef::default()
+            };
+            &inlined
+        } else {
+            graph
+        };
+
         let mut model = InferenceModel::default();
+        model.symbol_table = symbol_table.clone();
         let mut inputs = tvec!();
-        let mut context = ParsingContext::default();
+        let mut context = ParsingContext {
+            framework: self,
+            library,
+            node_output_arities: HashMap::new(),
+            symbol_table: symbol_table.clone(),
+        };
         let mut control_inputs = vec![];
 
         // compute min output arity for all nodes
@@ -160,22 +439,12 @@ impl Tensorflow {
 
             let node_id = model.add_node(name.clone(), op, facts)?;
             if pbnode.op == "Placeholder" {
-                let dt = pbnode.get_attr_datum_type("dtype")?;
-                let mut fact = InferenceFact::dt(dt);
+                let mut fact = InferenceFact::default();
+                if let Some(dt) = pbnode.get_attr_opt_datum_type("dtype")? {
+                    fact = fact.with_datum_type(dt);
+                }
                 if let Some(shape) = pbnode.get_attr_opt_shape("shape")? {
-                    let shape_factoid = ShapeFactoid::closed(
-                        shape
-                            .iter()
-                            .map(|d| {
-                                if *d == -1 {
-                                    GenericFactoid::Any
-                                } else {
-                                    GenericFactoid::Only(d.to_dim())
-                                }
-                            })
-                            .collect(),
-                    );
-                    fact = fact.with_shape(shape_factoid);
+                    fact = fact.with_shape(shape_factoid(&shape));
                 }
                 inputs.push(OutletId::new(node_id, 0));
                 model.set_outlet_fact(OutletId::new(node_id, 0), fact)?;
@@ -233,9 +502,24 @@ impl Tensorflow {
     }
 }
 
+fn shape_factoid(shape: &[isize]) -> ShapeFactoid {
+    ShapeFactoid::closed(
+        shape
+            .iter()
+            .map(|d| if *d == -1 { GenericFactoid::Any } else { GenericFactoid::Only(d.to_dim()) })
+            .collect(),
+    )
+}
+
 impl Framework<GraphDef, InferenceModel> for Tensorflow {
     /// This method will try to read as frozen model, then as a saved model.
+    /// SavedModel directories are read with the serving tag and their
+    /// variables restored.
     fn proto_model_for_path(&self, r: impl AsRef<path::Path>) -> TractResult<GraphDef> {
+        if r.as_ref().is_dir() {
+            let meta = self.read_saved_model_dir(r, &[SERVING_TAG])?;
+            return meta.graph_def.context("Meta graph has no graph");
+        }
         self.read_frozen_model(&mut fs::File::open(r.as_ref())?)
             .or_else(|_| self.read_saved_model(&mut fs::File::open(r.as_ref())?))
     }
@@ -249,4 +533,19 @@ impl Framework<GraphDef, InferenceModel> for Tensorflow {
     fn model_for_proto_model(&self, graph: &GraphDef) -> TractResult<InferenceModel> {
         Ok(self.parse_graph(graph)?.0)
     }
+
+    /// SavedModel directories are loaded with the serving tag and the default
+    /// signature when there is one.
+    fn model_for_path(&self, p: impl AsRef<path::Path>) -> TractResult<InferenceModel> {
+        if p.as_ref().is_dir() {
+            let meta = self.read_saved_model_dir(p, &[SERVING_TAG])?;
+            let graph = meta.graph_def.as_ref().context("Meta graph has no graph")?;
+            if let Some(signature) = meta.signature_def.get(DEFAULT_SIGNATURE) {
+                return Ok(self.parse_graph_with_signature(graph, signature)?.0);
+            }
+            return self.model_for_proto_model(graph);
+        }
+        let proto_model = self.proto_model_for_path(p)?;
+        self.model_for_proto_model(&proto_model)
+    }
 }
@@ -9,28 +9,48 @@ mod expand_dims;
 mod fill;
 mod gather_nd;
 mod gather_v2;
+mod one_hot;
 mod pack;
 mod pad;
-mod range;
+mod reverse;
+mod split;
 mod squeeze;
+mod topk_v2;
 mod transpose;
+mod unpack;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("ConcatV2", concatv2::build);
+    reg.insert("Cumsum", cumsum);
     reg.insert("ExpandDims", expand_dims::build);
     reg.insert("Fill", fill::fill);
     reg.insert("GatherNd", gather_nd::gather_nd);
     reg.insert("GatherV2", gather_v2::gather_v2);
+    reg.insert("MirrorPad", pad::mirror_pad);
+    reg.insert("OneHot", one_hot::one_hot);
     reg.insert("Pack", pack::pack);
     reg.insert("Pad", pad::pad);
-    reg.insert("Range", range::range);
+    reg.insert("Range", |ctx, _| {
+        Ok(expand(tract_hir::ops::array::Range::new(ctx.symbol_table.new_symbol("range"))))
+    });
     reg.insert("Reshape", |_, _| Ok(expand(tract_hir::ops::array::Reshape::new())));
+    reg.insert("ReverseV2", reverse::reverse_v2);
     reg.insert("Shape", |_, _| Ok(expand(tract_hir::ops::array::Shape::new(DatumType::I32))));
     reg.insert("Slice", slice);
+    reg.insert("Split", split::split);
+    reg.insert("SplitV", split::split_v);
     reg.insert("Squeeze", squeeze::squeeze);
     reg.insert("StridedSlice", strided_slice);
     reg.insert("Tile", |_, _| Ok(expand(::tract_hir::ops::array::Tile)));
+    reg.insert("TopKV2", topk_v2::topk_v2);
     reg.insert("Transpose", transpose::transpose);
+    reg.insert("Unpack", unpack::unpack);
+}
+
+fn cumsum(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let exclusive = pb.get_attr_opt_bool("exclusive")?.unwrap_or(false);
+    let reverse = pb.get_attr_opt_bool("reverse")?.unwrap_or(false);
+    Ok(expand(tract_hir::ops::array::CumSum::new(reverse, exclusive)))
 }
 
 fn strided_slice(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
@@ -0,0 +1,77 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn one_hot(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let axis = pb.get_attr_opt_int("axis")?.unwrap_or(-1);
+    Ok(expand(OneHot::new(axis)))
+}
+
+/// OneHot, with indices, depth, on_value and off_value inputs.
+///
+/// Indices are expected in the [0, depth) range: where TensorFlow outputs
+/// all-off vectors for negative indices, the core op counts them from the end.
+#[derive(Debug, Clone, new, Hash)]
+pub struct OneHot {
+    axis: i64,
+}
+
+impl_dyn_hash!(OneHot);
+
+impl Expansion for OneHot {
+    fn name(&self) -> Cow<str> {
+        "OneHot".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&inputs[2].rank, 0)?;
+        s.equals(&inputs[3].rank, 0)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[3].datum_type, &outputs[0].datum_type)?;
+        s.equals(inputs[0].rank.bex() + 1, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, irank| {
+            let axis = if self.axis < 0 { self.axis + irank + 1 } else { self.axis } as usize;
+            for ix in 0..axis {
+                s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+            }
+            for ix in axis + 1..irank as usize + 1 {
+                s.equals(&inputs[0].shape[ix - 1], &outputs[0].shape[ix])?;
+            }
+            s.given(&inputs[1].value, move |s, depth| {
+                let depth = depth.cast_to_scalar::<i64>()?;
+                s.equals(&outputs[0].shape[axis], depth.to_dim())
+            })
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let depth = model.outlet_fact(inputs[1])?.konst.clone().context("Expect constant depth")?;
+        let on = model.outlet_fact(inputs[2])?.konst.clone().context("Expect constant on_value")?;
+        let off =
+            model.outlet_fact(inputs[3])?.konst.clone().context("Expect constant off_value")?;
+        let depth = depth.cast_to_scalar::<i64>()?;
+        if depth < 0 {
+            bail!("Expected positive depth, got {}", depth)
+        }
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let axis = if self.axis < 0 { self.axis + rank as i64 + 1 } else { self.axis } as usize;
+        let op = tract_hir::tract_core::ops::array::OneHot { axis, dim: depth as usize, off, on };
+        model.wire_node(prefix, op, &[inputs[0]])
+    }
+}
@@ -13,6 +13,15 @@ pub fn pad(_ctx: &ParsingContext, _pb: &NodeDef) -> TractResult<Box<dyn Inferenc
     Ok(Box::new(Pad))
 }
 
+pub fn mirror_pad(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let symmetric = match pb.get_attr_raw_str("mode")? {
+        b"REFLECT" => false,
+        b"SYMMETRIC" => true,
+        s => bail!("unsupported MirrorPad mode {}", String::from_utf8_lossy(s)),
+    };
+    Ok(expand(MirrorPad::new(symmetric)))
+}
+
 impl Pad {
     fn compute_t<T: Datum + Default + Copy>(
         input: &Tensor,
@@ -104,6 +113,108 @@ impl InferenceRulesOp for Pad {
     as_op!();
 }
 
+/// MirrorPad: REFLECT mode excludes the border from the mirrored values,
+/// SYMMETRIC mode includes it.
+#[derive(Debug, Clone, new, Hash)]
+pub struct MirrorPad {
+    symmetric: bool,
+}
+
+impl_dyn_hash!(MirrorPad);
+
+impl Expansion for MirrorPad {
+    fn name(&self) -> Cow<str> {
+        "MirrorPad".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[1].shape[0], inputs[0].rank.bex().to_dim())?;
+        s.equals(&inputs[1].shape[1], 2.to_dim())?;
+        s.given(&inputs[1].value, move |s, paddings| {
+            let paddings = paddings.cast_to::<i64>()?;
+            let paddings = paddings.to_array_view::<i64>()?.into_dimensionality()?;
+            for d in 0..paddings.nrows() {
+                s.equals(
+                    &outputs[0].shape[d],
+                    inputs[0].shape[d].bex() + (paddings[(d, 0)] + paddings[(d, 1)]).to_dim(),
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::ops::array::{Slice, TypedConcat};
+        use tract_hir::tract_core::ops::array::{PadMode, Reverse};
+        let paddings =
+            model.outlet_fact(inputs[1])?.konst.clone().context("Expect constant paddings")?;
+        let paddings = paddings.cast_to::<i64>()?;
+        let paddings =
+            paddings.to_array_view::<i64>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+        let pads: Vec<(usize, usize)> =
+            paddings.outer_iter().map(|p| (p[0] as usize, p[1] as usize)).collect();
+        if !self.symmetric {
+            let op = tract_hir::ops::array::Pad::new(pads, PadMode::Reflect);
+            return model.wire_node(prefix, op, &[inputs[0]]);
+        }
+        // symmetric: concatenate reversed slices of the borders, axis by axis
+        let mut wire = inputs[0];
+        for (axis, &(before, after)) in pads.iter().enumerate() {
+            if before == 0 && after == 0 {
+                continue;
+            }
+            let dim = model.outlet_fact(wire)?.shape[axis].clone();
+            let mut pieces = tvec!();
+            for (name, start, end) in
+                &[("before", 0.to_dim(), before.to_dim()), ("after", dim.clone() - after, dim)]
+            {
+                if start == end {
+                    continue;
+                }
+                let slice = model.wire_node(
+                    format!("{}.axis-{}-{}-slice", prefix, axis, name),
+                    Slice::new(axis, start.clone(), end.clone()),
+                    &[wire],
+                )?;
+                let reversed = model.wire_node(
+                    format!("{}.axis-{}-{}-reverse", prefix, axis, name),
+                    Reverse::new(tvec!(axis)),
+                    &slice,
+                )?;
+                pieces.push(reversed[0]);
+            }
+            if before > 0 {
+                pieces.insert(1, wire);
+            } else {
+                pieces.insert(0, wire);
+            }
+            wire = model.wire_node(
+                format!("{}.axis-{}", prefix, axis),
+                TypedConcat::concat_vars(axis, pieces.len()),
+                &pieces,
+            )?[0];
+        }
+        Ok(tvec!(wire))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,54 @@
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+use tract_hir::internal::*;
+
+pub fn reverse_v2(_ctx: &ParsingContext, _pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    Ok(expand(ReverseV2))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ReverseV2;
+
+impl_dyn_hash!(ReverseV2);
+
+impl Expansion for ReverseV2 {
+    fn name(&self) -> Cow<str> {
+        "ReverseV2".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 1)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank() as i64;
+        let axes = if let Some(axes) = &model.outlet_fact(inputs[1])?.konst {
+            axes.cast_to::<i64>()?
+                .as_slice::<i64>()?
+                .iter()
+                .map(|&axis| if axis < 0 { axis + rank } else { axis } as usize)
+                .collect()
+        } else {
+            bail!("ReverseV2 axis must be a constant")
+        };
+        let op = tract_hir::tract_core::ops::array::Reverse::new(axes);
+        model.wire_node(prefix, op, &inputs[0..1])
+    }
+}
@@ -0,0 +1,134 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn split(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(expand(Split::new(num_split, false)))
+}
+
+pub fn split_v(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(expand(Split::new(num_split, true)))
+}
+
+/// Split (axis, value inputs, equal parts) and SplitV (value, size_splits
+/// and axis inputs).
+#[derive(Debug, Clone, new, Hash)]
+pub struct Split {
+    num_split: usize,
+    sizes: bool,
+}
+
+impl_dyn_hash!(Split);
+
+impl Split {
+    fn value_and_axis(&self) -> (usize, usize) {
+        if self.sizes {
+            (0, 2)
+        } else {
+            (1, 0)
+        }
+    }
+
+    fn axis(axis: &Tensor, rank: usize) -> TractResult<usize> {
+        let axis = axis.cast_to_scalar::<i64>()?;
+        Ok(if axis < 0 { axis + rank as i64 } else { axis } as usize)
+    }
+
+    // SplitV sizes, resolving the -1 wildcard from the split dimension
+    fn sizes(&self, sizes: &Tensor, dim: &TDim) -> TractResult<TVec<TDim>> {
+        let sizes = sizes.cast_to::<i64>()?;
+        let sizes = sizes.as_slice::<i64>()?;
+        if sizes.len() != self.num_split {
+            bail!("Expected {} split sizes, got {:?}", self.num_split, sizes)
+        }
+        let known: i64 = sizes.iter().filter(|&&s| s >= 0).sum();
+        sizes
+            .iter()
+            .map(|&s| if s >= 0 { Ok(s.to_dim()) } else { Ok(dim.clone() - known.to_dim()) })
+            .collect()
+    }
+}
+
+impl Expansion for Split {
+    fn name(&self) -> Cow<str> {
+        if self.sizes {
+            "SplitV".into()
+        } else {
+            "Split".into()
+        }
+    }
+
+    op_tf!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num_split)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2 + self.sizes as usize)?;
+        check_output_arity(&outputs, self.num_split)?;
+        let (value, axis) = self.value_and_axis();
+        let value = &inputs[value];
+        s.equals(&inputs[axis].rank, 0)?;
+        for output in outputs {
+            s.equals(&value.datum_type, &output.datum_type)?;
+            s.equals(&value.rank, &output.rank)?;
+        }
+        s.given_2(&value.shape, &inputs[axis].value, move |s, shape, axis| {
+            let axis = Self::axis(&axis, shape.len())?;
+            for (ix, output) in outputs.iter().enumerate() {
+                for d in 0..shape.len() {
+                    if d != axis {
+                        s.equals(&output.shape[d], &shape[d])?;
+                    } else if !self.sizes {
+                        s.equals(&output.shape[d], shape[d].clone() / self.num_split)?;
+                    } else {
+                        let dim = shape[d].clone();
+                        s.given(&inputs[1].value, move |s, sizes| {
+                            let sizes = self.sizes(&sizes, &dim)?;
+                            s.equals(&outputs[ix].shape[axis], &sizes[ix])
+                        })?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let (value, axis) = self.value_and_axis();
+        let fact = model.outlet_fact(inputs[value])?.clone();
+        let axis =
+            model.outlet_fact(inputs[axis])?.konst.clone().context("Expect constant axis")?;
+        let axis = Self::axis(&axis, fact.rank())?;
+        let sizes = if self.sizes {
+            let sizes = model
+                .outlet_fact(inputs[1])?
+                .konst
+                .clone()
+                .context("Expect constant split sizes")?;
+            let sizes = self.sizes(&sizes, &fact.shape[axis])?;
+            Some(sizes.iter().map(|d| d.to_usize()).collect::<TractResult<Vec<_>>>()?)
+        } else {
+            None
+        };
+        tract_hir::ops::array::Split::new(axis as isize, self.num_split, sizes).wire(
+            prefix,
+            model,
+            &[inputs[value]],
+        )
+    }
+}
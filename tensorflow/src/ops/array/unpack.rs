@@ -0,0 +1,88 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn unpack(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num = pb.get_attr_int("num")?;
+    let axis = pb.get_attr_opt_int("axis")?.unwrap_or(0);
+    Ok(expand(Unpack::new(num, axis)))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct Unpack {
+    num: usize, // The number of outputs
+    axis: i64,
+}
+
+impl_dyn_hash!(Unpack);
+
+impl Unpack {
+    fn axis(&self, rank: usize) -> usize {
+        if self.axis < 0 {
+            (self.axis + rank as i64) as usize
+        } else {
+            self.axis as usize
+        }
+    }
+}
+
+impl Expansion for Unpack {
+    fn name(&self) -> Cow<str> {
+        "Unpack".into()
+    }
+
+    op_tf!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, self.num)?;
+        for output in outputs {
+            s.equals(&output.datum_type, &inputs[0].datum_type)?;
+            s.equals(output.rank.bex() + 1, &inputs[0].rank)?;
+        }
+        s.given(&inputs[0].rank, move |s, rank| {
+            let axis = self.axis(rank as usize);
+            s.equals(&inputs[0].shape[axis], self.num.to_dim())?;
+            for output in outputs {
+                for d in 0..axis {
+                    s.equals(&output.shape[d], &inputs[0].shape[d])?;
+                }
+                for d in axis + 1..rank as usize {
+                    s.equals(&output.shape[d - 1], &inputs[0].shape[d])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axis = self.axis(model.outlet_fact(inputs[0])?.rank());
+        let mut outputs = tvec!();
+        for ix in 0..self.num {
+            let wire = model.wire_node(
+                format!("{}.slice-{}", prefix, ix),
+                tract_hir::ops::array::Slice::new(axis, ix, ix + 1),
+                inputs,
+            )?;
+            outputs.push(
+                model.wire_node(format!("{}.rm_dims-{}", prefix, ix), AxisOp::Rm(axis), &wire)?[0],
+            );
+        }
+        Ok(outputs)
+    }
+}
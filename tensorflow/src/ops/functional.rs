@@ -0,0 +1,591 @@
+use tract_hir::internal::*;
+use tract_hir::ops::scan::{InputMapping, OutputMapping, Scan, StateInitializer};
+
+use crate::model::{ParsingContext, TfOpRegister};
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("If", if_);
+    reg.insert("StatelessIf", if_);
+    reg.insert("StatelessWhile", while_);
+    reg.insert("While", while_);
+}
+
+fn if_(ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let then_body = ctx.parse_function(node.get_attr_func_name("then_branch")?)?;
+    let else_body = ctx.parse_function(node.get_attr_func_name("else_branch")?)?;
+    Ok(Box::new(If { then_body, else_body }))
+}
+
+fn while_(ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let cond = ctx.parse_function(node.get_attr_func_name("cond")?)?;
+    let body = ctx.parse_function(node.get_attr_func_name("body")?)?;
+    Ok(Box::new(While { cond, body }))
+}
+
+// TensorFlow truth: scalars are compared to zero, other tensors must be
+// non-empty
+fn truth(t: &Tensor) -> TractResult<bool> {
+    if t.rank() == 0 {
+        t.cast_to_scalar::<bool>()
+    } else {
+        Ok(t.len() > 0)
+    }
+}
+
+/// Functional If: the first input is the condition, the others are the
+/// arguments of both branches.
+#[derive(Debug, Clone, Hash)]
+pub struct If {
+    pub then_body: InferenceModel,
+    pub else_body: InferenceModel,
+}
+
+impl_dyn_hash!(If);
+
+impl Op for If {
+    fn name(&self) -> Cow<str> {
+        "If".into()
+    }
+
+    op_tf!();
+    not_a_typed_op!();
+}
+
+type InferencePlan = Arc<InferenceSimplePlan<InferenceModel>>;
+
+impl EvalOp for If {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(IfState {
+            then_plan: Arc::new(SimplePlan::new(self.then_body.clone())?),
+            else_plan: Arc::new(SimplePlan::new(self.else_body.clone())?),
+        })))
+    }
+}
+
+/// Plans of the branches, built once per session.
+#[derive(Debug, Clone)]
+struct IfState {
+    then_plan: InferencePlan,
+    else_plan: InferencePlan,
+}
+
+impl OpState for IfState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        _op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let plan = if truth(&inputs[0])? { &self.then_plan } else { &self.else_plan };
+        let inputs = inputs[1..].iter().map(|t| t.clone().into_tensor()).collect();
+        plan.run(inputs)
+    }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
+}
+
+impl InferenceOp for If {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        if outputs.len() != self.then_body.output_outlets()?.len()
+            || outputs.len() != self.else_body.output_outlets()?.len()
+        {
+            bail!("If node and its branches must have the same number of outputs");
+        }
+        loop {
+            let mut changed = false;
+            for body in &mut [&mut self.then_body, &mut self.else_body] {
+                for (ix, input) in inputs[1..].iter_mut().enumerate() {
+                    let inner = body.input_fact_mut(ix)?;
+                    changed |= input.datum_type.unify_with_mut(&mut inner.datum_type)?;
+                    changed |= input.shape.unify_with_mut(&mut inner.shape)?;
+                }
+                changed |= body.analyse(false).context("analysing If branch")?;
+            }
+            for (ix, output) in outputs.iter_mut().enumerate() {
+                let mut then_fact = self.then_body.output_fact(ix)?.clone();
+                let mut else_fact = self.else_body.output_fact(ix)?.clone();
+                changed |= output.datum_type.unify_with_mut(&mut then_fact.datum_type)?;
+                changed |= output.datum_type.unify_with_mut(&mut else_fact.datum_type)?;
+                // branches may legitimately produce different shapes
+                if let Ok(shape) = then_fact.shape.unify(&else_fact.shape) {
+                    changed |= output.shape.unify_with(&shape)?;
+                }
+                changed |= self.then_body.output_fact(ix)? != &then_fact;
+                changed |= self.else_body.output_fact(ix)? != &else_fact;
+                self.then_body.set_output_fact(ix, then_fact)?;
+                self.else_body.set_output_fact(ix, else_fact)?;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.then_body.output_outlets()?.len())
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let cond = target.outlet_fact(inputs[0])?.clone();
+        if cond.rank() != 0 {
+            bail!("If {} condition must be a scalar to be typed, got {:?}", node.name, cond)
+        }
+        if cond.datum_type != bool::datum_type() {
+            inputs[0] = target.wire_node(
+                format!("{}.cond", node.name),
+                tract_core::ops::cast::cast(bool::datum_type()),
+                &[inputs[0]],
+            )?[0];
+        }
+        let then_body = self.then_body.clone().into_typed().context("typing then branch")?;
+        let else_body = self.else_body.clone().into_typed().context("typing else branch")?;
+        let input_mapping = (1..inputs.len()).collect::<Vec<_>>();
+        target.wire_node(
+            &*node.name,
+            tract_core::ops::logic::IfThenElse {
+                then_body,
+                then_input_mapping: input_mapping.clone(),
+                else_body,
+                else_input_mapping: input_mapping,
+            },
+            &inputs,
+        )
+    }
+
+    as_op!();
+}
+
+/// Functional While: the loop variables are the inputs and outputs of the
+/// body, and the inputs of the condition.
+///
+/// Loops whose condition only compares counters (loop variables incremented
+/// by a constant step) to bounds (constants or loop variables the body passes
+/// through) are translated to a typed Scan, with a symbolic iteration count if
+/// the counters or bounds are dynamic. Other loops can only run as an
+/// inference model.
+#[derive(Debug, Clone, Hash)]
+pub struct While {
+    pub cond: InferenceModel,
+    pub body: InferenceModel,
+}
+
+impl_dyn_hash!(While);
+
+/// Upper bound of a loop counter.
+#[derive(Debug, Clone)]
+enum Bound {
+    Const(Arc<Tensor>),
+    Var(usize),
+}
+
+/// A loop variable incremented by `step` in the body, the loop running while
+/// it is less than (or equal to, if not `strict`) its bound.
+#[derive(Debug, Clone)]
+struct Counter {
+    var: usize,
+    step: i64,
+    bound: Bound,
+    strict: bool,
+}
+
+// the outlet, seen through identities
+fn skip_identities(model: &InferenceModel, mut outlet: OutletId) -> OutletId {
+    while model.node(outlet.node).op_is::<tract_hir::ops::identity::Identity>() {
+        outlet = model.node(outlet.node).inputs[0];
+    }
+    outlet
+}
+
+fn konst(model: &InferenceModel, outlet: OutletId) -> Option<Arc<Tensor>> {
+    let outlet = skip_identities(model, outlet);
+    model.node(outlet.node).op_as::<tract_hir::ops::konst::Const>().map(|k| k.0.clone())
+}
+
+fn bin_mini_op(node: &InferenceNode) -> Option<&dyn tract_core::ops::binary::BinMiniOp> {
+    node.op_as::<Box<dyn Expansion>>()
+        .and_then(|e| e.as_any().downcast_ref::<tract_hir::ops::binary::InferenceBinOp>())
+        .map(|op| &*op.0)
+}
+
+fn input_index(model: &InferenceModel, outlet: OutletId) -> TractResult<Option<usize>> {
+    let outlet = skip_identities(model, outlet);
+    Ok(model.input_outlets()?.iter().position(|i| *i == outlet))
+}
+
+impl While {
+    /// The constant step of the loop variable `var`, if the body only adds
+    /// one to it.
+    fn step(&self, var: usize) -> TractResult<Option<i64>> {
+        let output = skip_identities(&self.body, self.body.output_outlets()?[var]);
+        let node = self.body.node(output.node);
+        let is_add =
+            bin_mini_op(node).map(|op| op.is::<tract_core::ops::math::Add>()).unwrap_or(false);
+        if !is_add {
+            return Ok(None);
+        }
+        for (var_input, step_input) in &[(0, 1), (1, 0)] {
+            if input_index(&self.body, node.inputs[*var_input])? != Some(var) {
+                continue;
+            }
+            if let Some(step) = konst(&self.body, node.inputs[*step_input]) {
+                if step.len() == 1 {
+                    let step = step.cast_to_scalar::<i64>()?;
+                    return Ok(Some(step).filter(|s| *s > 0));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The counters of the condition, if it is a conjunction of counter
+    /// comparisons.
+    fn counters(&self, outlet: OutletId, counters: &mut Vec<Counter>) -> TractResult<bool> {
+        use tract_core::ops::logic::*;
+        let outlet = skip_identities(&self.cond, outlet);
+        let node = self.cond.node(outlet.node);
+        let op = if let Some(op) = bin_mini_op(node) {
+            op
+        } else {
+            return Ok(false);
+        };
+        if op.is::<And>() {
+            return Ok(self.counters(node.inputs[0], counters)?
+                && self.counters(node.inputs[1], counters)?);
+        }
+        let (counter, bound, strict) = if op.is::<Lesser>() {
+            (node.inputs[0], node.inputs[1], true)
+        } else if op.is::<LesserEqual>() {
+            (node.inputs[0], node.inputs[1], false)
+        } else if op.is::<Greater>() {
+            (node.inputs[1], node.inputs[0], true)
+        } else if op.is::<GreaterEqual>() {
+            (node.inputs[1], node.inputs[0], false)
+        } else {
+            return Ok(false);
+        };
+        let var = if let Some(var) = input_index(&self.cond, counter)? {
+            var
+        } else {
+            return Ok(false);
+        };
+        let step = if let Some(step) = self.step(var)? {
+            step
+        } else {
+            return Ok(false);
+        };
+        let bound = if let Some(k) = konst(&self.cond, bound) {
+            Bound::Const(k)
+        } else if let Some(ix) = input_index(&self.cond, bound)? {
+            // the bound must not change from one iteration to the next
+            if input_index(&self.body, self.body.output_outlets()?[ix])? != Some(ix) {
+                return Ok(false);
+            }
+            Bound::Var(ix)
+        } else {
+            return Ok(false);
+        };
+        counters.push(Counter { var, step, bound, strict });
+        Ok(true)
+    }
+
+    /// Wire the iteration count of a counter, as a i64 scalar.
+    fn wire_trip_count(
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+        counter: &Counter,
+    ) -> TractResult<Option<OutletId>> {
+        use tract_core::ops::{cast::cast, math};
+        let mut i64_scalar = |suffix: &str, outlet: OutletId| -> TractResult<Option<OutletId>> {
+            let fact = target.outlet_fact(outlet)?;
+            if fact.rank() != 0 {
+                Ok(None)
+            } else if fact.datum_type == i64::datum_type() {
+                Ok(Some(outlet))
+            } else {
+                let name = format!("{}.{}", name, suffix);
+                Ok(Some(target.wire_node(name, cast(i64::datum_type()), &[outlet])?[0]))
+            }
+        };
+        let start = i64_scalar(&format!("counter_{}.start", counter.var), inputs[counter.var])?;
+        let bound = match &counter.bound {
+            Bound::Const(k) if k.len() == 1 => {
+                let k = tensor0(k.cast_to_scalar::<i64>()?);
+                Some(target.add_const(format!("{}.counter_{}.bound", name, counter.var), k)?)
+            }
+            Bound::Const(_) => None,
+            Bound::Var(ix) => i64_scalar(&format!("counter_{}.bound", counter.var), inputs[*ix])?,
+        };
+        let (start, bound) = match (start, bound) {
+            (Some(start), Some(bound)) => (start, bound),
+            _ => return Ok(None),
+        };
+        // max(0, (bound - start + step - 1) / step), or + step if not strict
+        let prefix = format!("{}.counter_{}", name, counter.var);
+        let offset = if counter.strict { counter.step - 1 } else { counter.step };
+        let offset = target.add_const(format!("{}.offset", prefix), rctensor0(offset))?;
+        let step = target.add_const(format!("{}.step", prefix), rctensor0(counter.step))?;
+        let zero = target.add_const(format!("{}.zero", prefix), rctensor0(0i64))?;
+        let span = target.wire_node(
+            format!("{}.span", prefix),
+            math::sub::bin_typed(),
+            &[bound, start],
+        )?;
+        let span = target.wire_node(
+            format!("{}.ceil", prefix),
+            math::add::bin_typed(),
+            &[span[0], offset],
+        )?;
+        let count = target.wire_node(
+            format!("{}.count", prefix),
+            math::div::bin_typed(),
+            &[span[0], step],
+        )?;
+        let count = target.wire_node(
+            format!("{}.trip_count", prefix),
+            math::max::bin_typed(),
+            &[count[0], zero],
+        )?;
+        Ok(Some(count[0]))
+    }
+
+    /// Iteration numbers, from a Range over the trip count: a constant when
+    /// the counters and bounds are, a symbolic length otherwise.
+    fn iterations(
+        &self,
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<Option<OutletId>> {
+        let mut counters = vec![];
+        if !self.counters(self.cond.output_outlets()?[0], &mut counters)? {
+            return Ok(None);
+        }
+        let mut trip_count = None;
+        for counter in &counters {
+            let count = if let Some(count) = Self::wire_trip_count(name, target, inputs, counter)? {
+                count
+            } else {
+                return Ok(None);
+            };
+            trip_count = Some(if let Some(trip_count) = trip_count {
+                let name = format!("{}.trip_count.min_{}", name, counter.var);
+                target.wire_node(
+                    name,
+                    tract_core::ops::math::min::bin_typed(),
+                    &[trip_count, count],
+                )?[0]
+            } else {
+                count
+            });
+        }
+        let trip_count =
+            if let Some(trip_count) = trip_count { trip_count } else { return Ok(None) };
+        let start = target.add_const(format!("{}.iterations.start", name), rctensor0(0i64))?;
+        let step = target.add_const(format!("{}.iterations.step", name), rctensor0(1i64))?;
+        let len = tract_core::ops::array::Range::len_for_facts(
+            target.outlet_fact(start)?,
+            target.outlet_fact(trip_count)?,
+            target.outlet_fact(step)?,
+            target.sym(&format!("{}_trip_count", name)),
+        )?;
+        let range = tract_core::ops::array::Range::new(len);
+        Ok(Some(
+            target.wire_node(format!("{}.iterations", name), range, &[start, trip_count, step])?[0],
+        ))
+    }
+
+    /// Translate to a Scan over the iteration numbers when the trip count is
+    /// given by counters.
+    fn to_scan(
+        &self,
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<Option<TVec<OutletId>>> {
+        let iterations = if let Some(it) = self.iterations(name, target, inputs)? {
+            it
+        } else {
+            return Ok(None);
+        };
+        // Scan needs a scanned input to iterate over: feed the iteration
+        // number to an extra, unused, body input
+        let mut body = self.body.clone();
+        let iter = body.add_source(
+            format!("{}.iteration", name),
+            InferenceFact::dt_shape(i64::datum_type(), tvec!(1)),
+        )?;
+        let mut body_inputs = tvec!(iter);
+        body_inputs.extend(self.body.input_outlets()?.iter().cloned());
+        body.set_input_outlets(&body_inputs)?;
+        let body = body.into_typed()?.declutter()?;
+
+        let mut input_mapping = vec![InputMapping::Scan { slot: 0, axis: 0, chunk: 1 }];
+        let mut output_mapping = vec![];
+        for ix in 0..inputs.len() {
+            input_mapping
+                .push(InputMapping::State { initializer: StateInitializer::FromInput(1 + ix) });
+            output_mapping.push(OutputMapping {
+                state: true,
+                full_slot: None,
+                last_value_slot: Some(ix),
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: None,
+            });
+        }
+        let mut outer_inputs = tvec!(iterations);
+        outer_inputs.extend(inputs.iter().cloned());
+        let op = Scan::new(body, input_mapping, output_mapping, None, 0)?;
+        Ok(Some(target.wire_node(name, op, &outer_inputs)?))
+    }
+}
+
+impl Op for While {
+    fn name(&self) -> Cow<str> {
+        "While".into()
+    }
+
+    op_tf!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for While {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(WhileState {
+            cond_plan: Arc::new(SimplePlan::new(self.cond.clone())?),
+            body_plan: Arc::new(SimplePlan::new(self.body.clone())?),
+        })))
+    }
+}
+
+/// Plans of the condition and body, built once per session.
+#[derive(Debug, Clone)]
+struct WhileState {
+    cond_plan: InferencePlan,
+    body_plan: InferencePlan,
+}
+
+impl OpState for WhileState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        _op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut state = inputs;
+        loop {
+            let tensors = || state.iter().map(|t| t.clone().into_tensor()).collect();
+            let cond = self.cond_plan.run(tensors()).context("Evaluating while condition")?;
+            if !truth(&cond[0])? {
+                return Ok(state);
+            }
+            state = self.body_plan.run(tensors()).context("Evaluating while body")?;
+        }
+    }
+
+    fn runs_on_session_copy(&self) -> bool {
+        true
+    }
+}
+
+impl InferenceOp for While {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        if self.body.input_outlets()?.len() != inputs.len()
+            || self.body.output_outlets()?.len() != inputs.len()
+            || self.cond.input_outlets()?.len() != inputs.len()
+        {
+            bail!("While node, body and condition must have the same loop variables");
+        }
+        loop {
+            let mut changed = false;
+            for ix in 0..inputs.len() {
+                let mut facts = self.body.outlets_fact_mut(&[
+                    self.body.input_outlets()?[ix],
+                    self.body.output_outlets()?[ix],
+                ])?;
+                facts.push(&mut inputs[ix]);
+                facts.push(&mut outputs[ix]);
+                facts.push(self.cond.input_fact_mut(ix)?);
+                changed |= Factoid::unify_all(
+                    &mut *facts.iter_mut().map(|f| &mut f.datum_type).collect::<TVec<_>>(),
+                )?;
+                changed |= Factoid::unify_all(
+                    &mut *facts.iter_mut().map(|f| &mut f.shape).collect::<TVec<_>>(),
+                )?;
+            }
+            changed |= self.cond.analyse(false).context("analysing While condition")?;
+            changed |= self.body.analyse(false).context("analysing While body")?;
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.body.output_outlets()?.len())
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        if let Some(wires) = self.to_scan(&node.name, target, &inputs)? {
+            Ok(wires)
+        } else {
+            bail!(
+                "While {} condition does not only compare counters to bounds, \
+                 it can only be evaluated as an inference model",
+                node.name
+            )
+        }
+    }
+
+    as_op!();
+}
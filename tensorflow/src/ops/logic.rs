@@ -15,9 +15,65 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("LogicalAnd", |_, _| Ok(ops::logic::And.into_hir()));
     reg.insert("LogicalOr", |_, _| Ok(ops::logic::Or.into_hir()));
     reg.insert("Merge", merge);
+    reg.insert("Select", |_, _| Ok(expand(Select)));
+    reg.insert("SelectV2", |_, _| Ok(Box::new(ops::logic::Iff)));
     reg.insert("Switch", |_, _| Ok(Box::new(Switch)));
 }
 
+/// Select (v1): the condition either has the shape of the values, or is a
+/// vector selecting along their first axis.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Select;
+
+impl_dyn_hash!(Select);
+
+impl Expansion for Select {
+    fn name(&self) -> Cow<str> {
+        "Select".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::Bool)?;
+        s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        s.equals(&inputs[2].shape, &outputs[0].shape)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            if rank > 0 {
+                s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut cond = inputs[0];
+        let rank = model.outlet_fact(inputs[1])?.rank();
+        for axis in model.outlet_fact(cond)?.rank()..rank {
+            cond = model.wire_node(
+                format!("{}.cond_add_dims-{}", prefix, axis),
+                AxisOp::Add(axis),
+                &[cond],
+            )?[0];
+        }
+        model.wire_node(prefix, ops::logic::Iff, &[cond, inputs[1], inputs[2]])
+    }
+}
+
 #[derive(Debug, Clone, new, Hash)]
 pub struct Switch;
 
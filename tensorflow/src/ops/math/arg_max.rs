@@ -0,0 +1,72 @@
+use tract_hir::internal::*;
+use tract_hir::ops::nn;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct ArgMax {
+    output_type: DatumType,
+}
+
+impl_dyn_hash!(ArgMax);
+
+pub fn arg_max(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let output_type = pb.get_attr_opt_datum_type("output_type")?.unwrap_or(DatumType::I64);
+    Ok(expand(ArgMax::new(output_type)))
+}
+
+impl Expansion for ArgMax {
+    fn name(&self) -> Cow<str> {
+        "ArgMax".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.output_type)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(inputs[0].rank.bex() - 1, &outputs[0].rank)?;
+        s.given_2(&inputs[0].rank, &inputs[1].value, move |s, rank, axis| {
+            let axis = axis.cast_to_scalar::<i64>()?;
+            let axis = if axis < 0 { axis + rank } else { axis } as usize;
+            for d in 0..axis {
+                s.equals(&inputs[0].shape[d], &outputs[0].shape[d])?;
+            }
+            for d in axis + 1..rank as usize {
+                s.equals(&inputs[0].shape[d], &outputs[0].shape[d - 1])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref axis) = model.outlet_fact(inputs[1])?.konst {
+            let axis = axis.cast_to_scalar::<i64>()?;
+            let op = nn::Reduce::new(Some(vec![axis]), false, nn::Reducer::ArgMax(false));
+            let mut wire = op.wire(prefix, model, &[inputs[0]])?;
+            if self.output_type != DatumType::I64 {
+                wire = model.wire_node(
+                    format!("{}.cast", prefix),
+                    tract_hir::tract_core::ops::cast::cast(self.output_type),
+                    &wire,
+                )?;
+            }
+            Ok(wire)
+        } else {
+            bail!("Expect axis to be a constant")
+        }
+    }
+}
@@ -14,6 +14,7 @@ macro_rules! op_tf {
 
 pub mod array;
 pub mod control_flow;
+pub mod functional;
 pub mod logic;
 pub mod math;
 pub mod nn;
@@ -25,6 +26,7 @@ pub mod vars;
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     array::register_all_ops(reg);
     control_flow::register_all_ops(reg);
+    functional::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     nn::register_all_ops(reg);
@@ -35,6 +37,9 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Cast", cast);
     reg.insert("Const", konst);
     reg.insert("Identity", |_, _| Ok(Box::new(tract_hir::ops::identity::Identity)));
+    reg.insert("IdentityN", |_, node| {
+        Ok(expand(IdentityN(node.input.iter().filter(|i| !i.starts_with("^")).count())))
+    });
     reg.insert("NoOp", |_, _| Ok(Box::new(Noop)));
     reg.insert("Placeholder", |_, _| Ok(Box::new(tract_hir::ops::source::Source::new())));
 }
@@ -102,3 +107,45 @@ impl TypedOp for Noop {
 
     as_op!();
 }
+
+/// Forwards its n inputs.
+#[derive(Clone, Debug, new, Hash)]
+pub struct IdentityN(usize);
+
+impl_dyn_hash!(IdentityN);
+
+impl Expansion for IdentityN {
+    fn name(&self) -> Cow<str> {
+        "IdentityN".into()
+    }
+
+    op_tf!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.0)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, self.0)?;
+        check_output_arity(&outputs, self.0)?;
+        for (input, output) in inputs.iter().zip(outputs.iter()) {
+            s.equals(&input.datum_type, &output.datum_type)?;
+            s.equals(&input.shape, &output.shape)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        _prefix: &str,
+        _model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        Ok(inputs.into())
+    }
+}
@@ -0,0 +1,139 @@
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+use tract_hir::internal::*;
+use tract_hir::ops::cnn::*;
+use tract_hir::ops::nn::*;
+use tract_hir::tract_core::ops::cnn::deconv::adjustments;
+use tract_hir::tract_core::ops::cnn::{DeconvUnary, KernelFormat};
+
+pub fn conv2d_backprop_input(
+    _ctx: &ParsingContext,
+    pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let data_format = super::data_format(pb)?;
+    let padding = super::padding(pb)?;
+    let strides = super::strides(pb)?.into();
+    let dilations: TVec<usize> =
+        pb.get_attr_opt_list_int("dilations")?.unwrap_or(vec![1, 1, 1, 1]).into();
+    if dilations.len() != 4 || dilations[0] != 1 && dilations[3] != 1 {
+        bail!("dilations must be of the form [1, h, v, 1], found {:?}", dilations)
+    };
+    Ok(expand(Conv2DBackpropInput::new(data_format, padding, strides, dilations)))
+}
+
+/// Transposed convolution: inputs are the output shape, the (forward
+/// convolution) HWIO kernel, and the data.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Conv2DBackpropInput {
+    data_format: DataFormat,
+    padding: PaddingSpec,
+    strides: TVec<usize>,
+    dilations: TVec<usize>,
+}
+
+impl_dyn_hash!(Conv2DBackpropInput);
+
+impl Conv2DBackpropInput {
+    fn pool_spec(&self, kernel_shape: &[usize], output_shape: &[usize]) -> TractResult<PoolSpec> {
+        let shape = self.data_format.shape(output_shape)?;
+        let kernel_hw = &kernel_shape[0..2];
+        let strides: TVec<usize> = self.strides[shape.hw_axes()].into();
+        let dilations: TVec<usize> = self.dilations[shape.hw_axes()].into();
+        // compute explicitly TensorFlow "SAME" padding, from the forward
+        // convolution geometry
+        let padding = if self.padding == PaddingSpec::Valid {
+            PaddingSpec::Valid
+        } else {
+            let (mut before, mut after) = (tvec!(), tvec!());
+            for ix in 0..2 {
+                let input = shape.hw_dims()[ix];
+                let output = (input + strides[ix] - 1) / strides[ix];
+                let total = ((output - 1) * strides[ix] + (kernel_hw[ix] - 1) * dilations[ix] + 1)
+                    .saturating_sub(input);
+                before.push(total / 2);
+                after.push(total - total / 2);
+            }
+            PaddingSpec::Explicit(before, after, false)
+        };
+        Ok(PoolSpec::new(
+            self.data_format,
+            kernel_hw.into(),
+            padding,
+            Some(dilations),
+            Some(strides),
+            Some(kernel_shape[3]),
+        ))
+    }
+}
+
+impl Expansion for Conv2DBackpropInput {
+    fn name(&self) -> Cow<str> {
+        "Conv2DBackpropInput".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 1)?;
+        s.equals(&inputs[0].shape[0], 4.to_dim())?;
+        s.equals(&inputs[1].rank, 4)?;
+        s.equals(&inputs[2].rank, 4)?;
+        s.equals(&inputs[1].datum_type, &inputs[2].datum_type)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.given(&inputs[0].value, move |s, shape| {
+            let shape: TVec<TDim> = shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into();
+            s.equals(&outputs[0].shape, shape.bex())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let output_shape = model
+            .outlet_fact(inputs[0])?
+            .konst
+            .clone()
+            .context("Expect output shape to be a constant")?;
+        let output_shape = output_shape.cast_to::<i64>()?;
+        let output_shape: TVec<usize> =
+            output_shape.as_slice::<i64>()?.iter().map(|&d| d as usize).collect();
+        let kernel = model
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("Expect kernel to be a constant")?;
+        // forward HWIO is deconvolution HWOI
+        let kernel = kernel.into_tensor().permute_axes(&[0, 1, 3, 2])?;
+        let pool_spec = self.pool_spec(kernel.shape(), &output_shape)?;
+        let input_shape = model.outlet_fact(inputs[2])?.shape.clone();
+        let input_shape = self.data_format.shape(input_shape.to_tvec())?;
+        let input_hw = input_shape
+            .hw_dims()
+            .iter()
+            .map(|d| d.to_usize())
+            .collect::<TractResult<TVec<usize>>>()
+            .context("Expect concrete input spatial dimensions for deconvolution")?;
+        let output_hw = &self.data_format.shape(&*output_shape)?.hw_dims().to_vec();
+        let adjustments = adjustments(&pool_spec, &input_hw, output_hw)?;
+        let op = DeconvUnary::new(
+            pool_spec,
+            KernelFormat::HWIO,
+            kernel.into_arc_tensor(),
+            None,
+            adjustments,
+            1,
+        );
+        model.wire_node(prefix, op, &[inputs[2]])
+    }
+}
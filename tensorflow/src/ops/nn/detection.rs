@@ -0,0 +1,254 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::change_axes::AxisOp;
+use tract_hir::tract_core::ops::nn::{BoxRepr, CropAndResizeMethod};
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn crop_and_resize(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let method = match &*pb.get_attr_opt_str("method")?.unwrap_or_else(|| "bilinear".into()) {
+        "bilinear" => CropAndResizeMethod::Bilinear,
+        "nearest" => CropAndResizeMethod::Nearest,
+        other => bail!("Unsupported CropAndResize method {}", other),
+    };
+    let extrapolation_value = pb.get_attr_opt_float("extrapolation_value")?.unwrap_or(0.0);
+    Ok(expand(CropAndResize { method, extrapolation_value }))
+}
+
+pub fn non_max_suppression(
+    ctx: &ParsingContext,
+    pb: &NodeDef,
+    version: usize,
+) -> TractResult<Box<dyn InferenceOp>> {
+    if pb.get_attr_opt_bool("pad_to_max_output_size")?.unwrap_or(false) {
+        bail!("NonMaxSuppression with pad_to_max_output_size is not supported")
+    }
+    let num_selected_indices_symbol = ctx.symbol_table.new_symbol("n");
+    Ok(expand(NonMaxSuppression { version, num_selected_indices_symbol }))
+}
+
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct CropAndResize {
+    method: CropAndResizeMethod,
+    #[educe(Hash(method = "hash_f32"))]
+    extrapolation_value: f32,
+}
+
+impl_dyn_hash!(CropAndResize);
+
+impl Expansion for CropAndResize {
+    fn name(&self) -> Cow<str> {
+        "CropAndResize".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[1].shape[1], 4.to_dim())?;
+        s.equals(&inputs[2].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[2].shape[0])?;
+        s.equals(&inputs[3].rank, 1)?;
+        s.equals(&inputs[3].shape[0], 2.to_dim())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[3], &inputs[0].shape[3])?;
+        s.given(&inputs[3].value, move |s, crop_size| {
+            let crop_size = crop_size.cast_to::<i64>()?;
+            let crop_size = crop_size.as_slice::<i64>()?;
+            s.equals(&outputs[0].shape[1], crop_size[0].to_dim())?;
+            s.equals(&outputs[0].shape[2], crop_size[1].to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let crop_size = model
+            .outlet_fact(inputs[3])?
+            .konst
+            .clone()
+            .context("CropAndResize expects a constant crop size")?;
+        let crop_size = crop_size.cast_to::<i64>()?;
+        let crop_size = crop_size.as_slice::<i64>()?;
+        let op = tract_hir::tract_core::ops::nn::CropAndResize {
+            method: self.method,
+            crop_height: crop_size[0] as usize,
+            crop_width: crop_size[1] as usize,
+            extrapolation_value: self.extrapolation_value,
+        };
+        model.wire_node(prefix, op, &inputs[0..3])
+    }
+}
+
+/// NonMaxSuppressionV3, V4 and V5, without padding nor soft NMS.
+///
+/// Boxes [N, 4] and scores [N] are wired to the core op as a single batch
+/// and class. Outputs are the selected indices, then the selected scores
+/// (V5 only) and the count of selected indices (V4 and V5).
+#[derive(Debug, Clone, Hash)]
+pub struct NonMaxSuppression {
+    version: usize,
+    num_selected_indices_symbol: Symbol,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl Expansion for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        format!("NonMaxSuppressionV{}", self.version).into()
+    }
+
+    op_tf!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(match self.version {
+            3 => 1,
+            4 => 2,
+            _ => 3,
+        })
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, if self.version == 5 { 6 } else { 5 })?;
+        check_output_arity(&outputs, self.nboutputs()?)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], 4.to_dim())?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].datum_type, i32::datum_type())?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.equals(&outputs[0].shape[0], self.num_selected_indices_symbol.to_dim())?;
+        if self.version == 5 {
+            s.equals(&outputs[1].datum_type, &inputs[1].datum_type)?;
+            s.equals(&outputs[1].shape, &outputs[0].shape)?;
+        }
+        if self.version >= 4 {
+            let valid = &outputs[outputs.len() - 1];
+            s.equals(&valid.datum_type, i32::datum_type())?;
+            s.equals(&valid.rank, 0)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops;
+        if self.version == 5 {
+            let sigma = model.outlet_fact(inputs[5])?.konst.clone();
+            if sigma.map(|s| s.cast_to_scalar::<f32>()).transpose()? != Some(0.0) {
+                bail!("NonMaxSuppressionV5 only supports a constant zero soft_nms_sigma")
+            }
+        }
+        let boxes = model.wire_node(format!("{}.boxes", prefix), AxisOp::Add(0), &[inputs[0]])?;
+        let mut scores =
+            model.wire_node(format!("{}.scores-0", prefix), AxisOp::Add(0), &[inputs[1]])?;
+        scores = model.wire_node(format!("{}.scores-1", prefix), AxisOp::Add(0), &scores)?;
+        let op = ops::nn::NonMaxSuppression::new(
+            BoxRepr::TwoPoints,
+            self.num_selected_indices_symbol.clone(),
+        );
+        let selected = model.wire_node(
+            format!("{}.nms", prefix),
+            op,
+            &[boxes[0], scores[0], inputs[2], inputs[3], inputs[4]],
+        )?;
+        let column = |model: &mut TypedModel, name: &str, col: usize| -> TractResult<OutletId> {
+            let wire = model.wire_node(
+                format!("{}.{}-slice", prefix, name),
+                ops::array::Slice::new(1, col, col + 1),
+                &selected,
+            )?;
+            Ok(model.wire_node(format!("{}.{}-rm", prefix, name), AxisOp::Rm(1), &wire)?[0])
+        };
+        let indices = column(model, "indices", 2)?;
+        let mut outputs =
+            model.wire_node(prefix, ops::cast::cast(i32::datum_type()), &[indices])?;
+        if self.version == 5 {
+            outputs.push(
+                model.wire_node(
+                    format!("{}.scores", prefix),
+                    ops::array::Gather::new(0),
+                    &[inputs[1], indices],
+                )?[0],
+            );
+        }
+        if self.version >= 4 {
+            // count the selected boxes by summing ones (the batch column is zero)
+            let batch = column(model, "batch", 0)?;
+            let batch = model.wire_node(
+                format!("{}.batch-cast", prefix),
+                ops::cast::cast(i32::datum_type()),
+                &[batch],
+            )?;
+            let ones = model.wire_node(
+                format!("{}.ones", prefix),
+                ops::math::add::unary(rctensor1(&[1i32])),
+                &batch,
+            )?;
+            let count = model.wire_node(
+                format!("{}.count", prefix),
+                ops::nn::Reduce::new(tvec!(0), ops::nn::Reducer::Sum),
+                &ones,
+            )?;
+            outputs.push(
+                model.wire_node(format!("{}.valid_outputs", prefix), AxisOp::Rm(0), &count)?[0],
+            );
+        }
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_max_suppression_v5() -> TractResult<()> {
+        let num_selected_indices_symbol = SymbolTable::default().sym("n");
+        let op = expand(NonMaxSuppression { version: 5, num_selected_indices_symbol });
+        let boxes = rctensor2(&[
+            [0.0f32, 0.0, 1.0, 1.0],
+            [0.0, 0.1, 1.0, 1.1],
+            [0.0, -0.1, 1.0, 0.9],
+            [0.0, 10.0, 1.0, 11.0],
+            [0.0, 10.1, 1.0, 11.1],
+            [0.0, 100.0, 1.0, 101.0],
+        ]);
+        let scores = rctensor1(&[0.9f32, 0.75, 0.6, 0.95, 0.5, 0.3]);
+        let result = op.eval(tvec!(
+            boxes,
+            scores,
+            rctensor0(3i32),
+            rctensor0(0.5f32),
+            rctensor0(0.0f32),
+            rctensor0(0.0f32)
+        ))?;
+        assert_eq!(*result[0], tensor1(&[3i32, 0, 5]));
+        assert_eq!(*result[1], tensor1(&[0.95f32, 0.9, 0.3]));
+        assert_eq!(*result[2], tensor0(3i32));
+        Ok(())
+    }
+}
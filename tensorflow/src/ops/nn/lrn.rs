@@ -0,0 +1,106 @@
+use tract_hir::internal::*;
+use tract_ndarray::prelude::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn lrn(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let depth_radius = pb.get_attr_opt_int("depth_radius")?.unwrap_or(5);
+    let bias = pb.get_attr_opt_float("bias")?.unwrap_or(1.0);
+    let alpha = pb.get_attr_opt_float("alpha")?.unwrap_or(1.0);
+    let beta = pb.get_attr_opt_float("beta")?.unwrap_or(0.5);
+    Ok(Box::new(Lrn { depth_radius, bias, alpha, beta }))
+}
+
+/// Local response normalization, across the channels of NHWC images:
+/// `x / (bias + alpha * sum(x^2 over the neighbouring channels)) ^ beta`
+#[derive(Debug, Clone, Default, Educe)]
+#[educe(Hash)]
+pub struct Lrn {
+    pub depth_radius: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub bias: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub alpha: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub beta: f32,
+}
+
+impl_dyn_hash!(Lrn);
+
+impl Lrn {
+    fn eval_t<
+        T: Datum + tract_num_traits::Float + tract_num_traits::FromPrimitive + ::std::iter::Sum,
+    >(
+        &self,
+        input: Arc<Tensor>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = input.to_array_view::<T>()?;
+        let c_axis = input.ndim() - 1;
+        let channels = input.shape()[c_axis];
+        let bias = T::from(self.bias).unwrap();
+        let alpha = T::from(self.alpha).unwrap();
+        let beta = T::from(self.beta).unwrap();
+        let output = Array::from_shape_fn(input.shape(), |mut coords| {
+            let c = coords[c_axis];
+            let x = input[&coords];
+            let c_min = c.saturating_sub(self.depth_radius);
+            let c_max = (c + self.depth_radius).min(channels - 1);
+            let square_sum: T = (c_min..=c_max)
+                .map(|c| {
+                    coords[c_axis] = c;
+                    input[&coords].powi(2)
+                })
+                .sum();
+            x / (bias + alpha * square_sum).powf(beta)
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl Op for Lrn {
+    fn name(&self) -> Cow<str> {
+        "LRN".into()
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Lrn {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        dispatch_floatlike!(Self::eval_t(input.datum_type())(self, input))
+    }
+}
+
+impl InferenceRulesOp for Lrn {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Lrn {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].without_value()))
+    }
+}
@@ -1,29 +1,46 @@
 use tract_hir::internal::*;
 use tract_hir::ops::cnn::PaddingSpec;
-use tract_hir::ops::nn::{DataFormat, LayerSoftmax};
+use tract_hir::ops::nn::{DataFormat, DepthToSpace, DepthToSpaceMode, LayerSoftmax, SpaceToDepth};
 
-use crate::model::TfOpRegister;
+use crate::model::{ParsingContext, TfOpRegister};
 use crate::tfpb::tensorflow::NodeDef;
 
 pub mod conv2d;
+pub mod conv2d_backprop_input;
+pub mod detection;
 pub mod dw_conv2d;
 pub mod fused_batch_norm;
+pub mod lrn;
 pub mod pools;
+pub mod resize;
 pub mod s2b;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("AvgPool", pools::avgpool);
     reg.insert("Conv2D", conv2d::conv2d);
+    reg.insert("Conv2DBackpropInput", conv2d_backprop_input::conv2d_backprop_input);
+    reg.insert("CropAndResize", detection::crop_and_resize);
+    reg.insert("DepthToSpace", depth_to_space);
     reg.insert("DepthwiseConv2dNative", dw_conv2d::depthwise_conv2d);
+    reg.insert("Elu", |_, _| Ok(expand(tract_hir::ops::activations::Elu(1.0))));
     reg.insert("FusedBatchNorm", fused_batch_norm::fused_batch_norm);
+    reg.insert("LeakyRelu", leaky_relu);
+    reg.insert("LRN", lrn::lrn);
     reg.insert("MaxPool", pools::maxpool);
+    reg.insert("NonMaxSuppressionV3", |ctx, pb| detection::non_max_suppression(ctx, pb, 3));
+    reg.insert("NonMaxSuppressionV4", |ctx, pb| detection::non_max_suppression(ctx, pb, 4));
+    reg.insert("NonMaxSuppressionV5", |ctx, pb| detection::non_max_suppression(ctx, pb, 5));
     reg.insert("Relu", |_, _| Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), None))));
     reg.insert("Relu6", |_, _| {
         Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), Some(6.0))))
     });
+    reg.insert("ResizeBilinear", resize::resize_bilinear);
+    reg.insert("ResizeNearestNeighbor", resize::resize_nearest_neighbor);
     reg.insert("Sigmoid", |_, _| Ok(Box::new(tract_hir::ops::nn::sigmoid())));
     reg.insert("Softmax", |_, _| Ok(expand(LayerSoftmax::new(1))));
+    reg.insert("Softplus", |_, _| Ok(expand(tract_hir::ops::activations::Softplus)));
     reg.insert("SpaceToBatchND", s2b::space_to_batch_nd);
+    reg.insert("SpaceToDepth", space_to_depth);
     reg.insert("BatchToSpaceND", s2b::batch_to_space_nd);
 }
 
@@ -44,6 +61,28 @@ pub fn data_format(pb: &NodeDef) -> TractResult<DataFormat> {
     Ok(df)
 }
 
+fn block_data_format(pb: &NodeDef) -> TractResult<DataFormat> {
+    if pb.get_attr_opt_raw_str("data_format")? == Some(b"NCHW_VECT_C") {
+        bail!("NCHW_VECT_C data format is not supported")
+    }
+    data_format(pb)
+}
+
+pub fn depth_to_space(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let block_size = pb.get_attr_int("block_size")?;
+    Ok(expand(DepthToSpace::new(block_size, DepthToSpaceMode::DCR, block_data_format(pb)?)))
+}
+
+pub fn space_to_depth(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let block_size = pb.get_attr_int("block_size")?;
+    Ok(expand(SpaceToDepth::new(block_size, block_data_format(pb)?)))
+}
+
+pub fn leaky_relu(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let alpha = pb.get_attr_opt_float("alpha")?.unwrap_or(0.2);
+    Ok(expand(tract_hir::ops::activations::LeakyRelu(alpha)))
+}
+
 pub fn padding(pb: &NodeDef) -> TractResult<PaddingSpec> {
     let padding = pb.get_attr_raw_str("padding")?;
     match padding {
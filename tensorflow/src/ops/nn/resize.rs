@@ -0,0 +1,201 @@
+use tract_hir::internal::*;
+use tract_ndarray::prelude::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    Bilinear,
+    Nearest,
+}
+
+pub fn resize_bilinear(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    resize(pb, Interpolation::Bilinear)
+}
+
+pub fn resize_nearest_neighbor(
+    _ctx: &ParsingContext,
+    pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    resize(pb, Interpolation::Nearest)
+}
+
+fn resize(pb: &NodeDef, interpolation: Interpolation) -> TractResult<Box<dyn InferenceOp>> {
+    let align_corners = pb.get_attr_opt_bool("align_corners")?.unwrap_or(false);
+    let half_pixel_centers = pb.get_attr_opt_bool("half_pixel_centers")?.unwrap_or(false);
+    if align_corners && half_pixel_centers {
+        bail!("align_corners and half_pixel_centers can not be both set in {}", pb.name)
+    }
+    Ok(Box::new(Resize::new(interpolation, align_corners, half_pixel_centers)))
+}
+
+/// Resize of NHWC images, the target height and width being the second
+/// input. Bilinear interpolation always outputs f32.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Resize {
+    interpolation: Interpolation,
+    align_corners: bool,
+    half_pixel_centers: bool,
+}
+
+impl_dyn_hash!(Resize);
+
+impl Resize {
+    fn output_dt(&self, input_dt: DatumType) -> DatumType {
+        match self.interpolation {
+            Interpolation::Bilinear => DatumType::F32,
+            Interpolation::Nearest => input_dt,
+        }
+    }
+
+    fn scale(&self, input: usize, output: usize) -> f32 {
+        if self.align_corners && output > 1 {
+            (input - 1) as f32 / (output - 1) as f32
+        } else {
+            input as f32 / output as f32
+        }
+    }
+
+    // two source indices and the weight of the second one
+    fn bilinear_coords(&self, input: usize, output: usize) -> Vec<(usize, usize, f32)> {
+        let scale = self.scale(input, output);
+        (0..output)
+            .map(|o| {
+                let x = if self.half_pixel_centers {
+                    (o as f32 + 0.5) * scale - 0.5
+                } else {
+                    o as f32 * scale
+                };
+                let lower = x.floor().max(0.0) as usize;
+                let upper = (x.ceil() as usize).min(input - 1);
+                (lower, upper, x - x.floor())
+            })
+            .collect()
+    }
+
+    fn nearest_coords(&self, input: usize, output: usize) -> Vec<usize> {
+        let scale = self.scale(input, output);
+        (0..output)
+            .map(|o| {
+                let x = if self.half_pixel_centers {
+                    (o as f32 + 0.5) * scale
+                } else {
+                    o as f32 * scale
+                };
+                let x = if self.align_corners { x.round() } else { x.floor() };
+                (x.max(0.0) as usize).min(input - 1)
+            })
+            .collect()
+    }
+
+    fn eval_bilinear(&self, input: &Tensor, size: (usize, usize)) -> TractResult<Tensor> {
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix4>()?;
+        let ys = self.bilinear_coords(input.shape()[1], size.0);
+        let xs = self.bilinear_coords(input.shape()[2], size.1);
+        let shape = (input.shape()[0], size.0, size.1, input.shape()[3]);
+        let output = Array4::from_shape_fn(shape, |(n, y, x, c)| {
+            let (y0, y1, wy) = ys[y];
+            let (x0, x1, wx) = xs[x];
+            let top = input[(n, y0, x0, c)] * (1.0 - wx) + input[(n, y0, x1, c)] * wx;
+            let bottom = input[(n, y1, x0, c)] * (1.0 - wx) + input[(n, y1, x1, c)] * wx;
+            top * (1.0 - wy) + bottom * wy
+        });
+        Ok(output.into_tensor())
+    }
+
+    fn eval_nearest<T: Datum>(&self, input: &Tensor, size: (usize, usize)) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?.into_dimensionality::<Ix4>()?;
+        let ys = self.nearest_coords(input.shape()[1], size.0);
+        let xs = self.nearest_coords(input.shape()[2], size.1);
+        let shape = (input.shape()[0], size.0, size.1, input.shape()[3]);
+        let output =
+            Array4::from_shape_fn(shape, |(n, y, x, c)| input[(n, ys[y], xs[x], c)].clone());
+        Ok(output.into_tensor())
+    }
+}
+
+fn size(size: &Tensor) -> TractResult<(usize, usize)> {
+    let size = size.cast_to::<i64>()?;
+    let size = size.as_slice::<i64>()?;
+    if size.len() != 2 || size.iter().any(|&d| d <= 0) {
+        bail!("Resize expects a positive height and width, got {:?}", size)
+    }
+    Ok((size[0] as usize, size[1] as usize))
+}
+
+impl Op for Resize {
+    fn name(&self) -> Cow<str> {
+        "Resize".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "{:?} align_corners: {} half_pixel_centers: {}",
+            self.interpolation, self.align_corners, self.half_pixel_centers
+        )])
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Resize {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, size_input) = args_2!(inputs);
+        let size = size(&size_input)?;
+        let output = match self.interpolation {
+            Interpolation::Bilinear => self.eval_bilinear(&input, size)?,
+            Interpolation::Nearest => {
+                dispatch_datum!(Self::eval_nearest(input.datum_type())(self, &input, size))?
+            }
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Resize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], 2.to_dim())?;
+        s.given(&inputs[0].datum_type, move |s, dt| {
+            s.equals(&outputs[0].datum_type, self.output_dt(dt))
+        })?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[3], &outputs[0].shape[3])?;
+        s.given(&inputs[1].value, move |s, value| {
+            let (h, w) = size(&value)?;
+            s.equals(&outputs[0].shape[1], h.to_dim())?;
+            s.equals(&outputs[0].shape[2], w.to_dim())
+        })
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Resize {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (h, w) = size(inputs[1].konst.as_ref().context("Expect size to be a constant")?)?;
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[1] = h.to_dim();
+        shape[2] = w.to_dim();
+        Ok(tvec!(TypedFact::dt_shape(self.output_dt(inputs[0].datum_type), shape)))
+    }
+}
@@ -5,6 +5,8 @@ use crate::tfpb::tensorflow::NodeDef;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Assign", |_, _| Ok(Box::new(Assign::default())));
+    // resource variables are restored as constants, reading them is a no-op
+    reg.insert("ReadVariableOp", |_, _| Ok(Box::new(tract_hir::ops::identity::Identity)));
     reg.insert("VariableV2", variable_v2);
 }
 
@@ -20,6 +20,7 @@ impl TryFrom<DataType> for DatumType {
             DataType::DtInt32 => Ok(DatumType::I32),
             DataType::DtInt64 => Ok(DatumType::I64),
             DataType::DtHalf => Ok(DatumType::F16),
+            DataType::DtBfloat16 => Ok(DatumType::BF16),
             DataType::DtFloat => Ok(DatumType::F32),
             DataType::DtDouble => Ok(DatumType::F64),
             DataType::DtString => Ok(DatumType::Blob),
@@ -59,6 +60,7 @@ impl TryFrom<DatumType> for DataType {
             DatumType::I32 => Ok(DataType::DtInt32),
             DatumType::I64 => Ok(DataType::DtInt64),
             DatumType::F16 => Ok(DataType::DtHalf),
+            DatumType::BF16 => Ok(DataType::DtBfloat16),
             DatumType::F32 => Ok(DataType::DtFloat),
             DatumType::F64 => Ok(DataType::DtDouble),
             DatumType::Blob => Ok(DataType::DtString),
@@ -87,13 +89,23 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
         let rank = dims.len();
         let content = &t.tensor_content;
         let dtype = DataType::from_i32(t.dtype).unwrap();
-        let mat: Tensor = if content.len() != 0 {
+        let mat: Tensor = if content.len() != 0 && dtype == DataType::DtBool {
+            // not every byte is a valid bool
+            let values = content.iter().map(|&b| b != 0).collect::<Vec<bool>>();
+            tract_ndarray::ArrayD::from_shape_vec(&*dims, values)?.into()
+        } else if content.len() != 0 {
             unsafe {
                 match dtype {
+                    DataType::DtUint8 => Self::from_raw::<u8>(&dims, content)?,
+                    DataType::DtUint16 => Self::from_raw::<u16>(&dims, content)?,
+                    DataType::DtInt8 => Self::from_raw::<i8>(&dims, content)?,
+                    DataType::DtInt16 => Self::from_raw::<i16>(&dims, content)?,
+                    DataType::DtHalf => Self::from_raw::<f16>(&dims, content)?,
                     DataType::DtFloat => Self::from_raw::<f32>(&dims, content)?,
                     DataType::DtDouble => Self::from_raw::<f64>(&dims, content)?,
                     DataType::DtInt32 => Self::from_raw::<i32>(&dims, content)?,
                     DataType::DtInt64 => Self::from_raw::<i64>(&dims, content)?,
+                    DataType::DtBfloat16 => Self::from_raw::<bf16>(&dims, content)?,
                     _ => unimplemented!("missing type (for get_tensor_content) {:?}", dtype),
                 }
             }
@@ -103,6 +115,14 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                 DataType::DtInt64 => tensor_from_repeated_field(&*dims, t.int64_val.to_vec())?,
                 DataType::DtFloat => tensor_from_repeated_field(&*dims, t.float_val.to_vec())?,
                 DataType::DtDouble => tensor_from_repeated_field(&*dims, t.double_val.to_vec())?,
+                DataType::DtBfloat16 => {
+                    let values = t
+                        .half_val
+                        .iter()
+                        .map(|&bits| bf16::from_bits(bits as u16))
+                        .collect::<Vec<bf16>>();
+                    tensor_from_repeated_field(&*dims, values)?
+                }
                 DataType::DtString => {
                     let strings =
                         t.string_val.iter().map(|s| Blob(s.to_owned())).collect::<Vec<Blob>>();
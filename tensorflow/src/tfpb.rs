@@ -136,6 +136,21 @@ impl NodeDef {
         Ok(None)
     }
 
+    pub fn get_attr_func_name(&self, name: &str) -> TractResult<&str> {
+        Ok(self.get_attr_opt_func_name(name)?.with_context(|| {
+            format!("Node {} ({}) expected function attribute '{}'", self.name, self.op, name)
+        })?)
+    }
+
+    pub fn get_attr_opt_func_name(&self, name: &str) -> TractResult<Option<&str>> {
+        if let Some(a) = self.attr.get(name) {
+            if let Value::Func(func) = a.value.as_ref().unwrap() {
+                return Ok(Some(&func.name));
+            }
+        };
+        Ok(None)
+    }
+
     pub fn get_attr_shape(&self, name: &str) -> TractResult<TVec<isize>> {
         Ok(self.get_attr_opt_shape(name)?.with_context(|| {
             format!("Node {} ({}) expected shape attribute '{}'", self.name, self.op, name)
@@ -0,0 +1,203 @@
+use std::convert::TryInto;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::attr_value::Value;
+use tract_tensorflow::tfpb::tensorflow::op_def::ArgDef;
+use tract_tensorflow::tfpb::tensorflow::*;
+
+fn arg(name: &str, dt: DataType) -> ArgDef {
+    ArgDef { name: name.to_string(), r#type: dt as i32, ..ArgDef::default() }
+}
+
+fn func_attr(name: &str) -> AttrValue {
+    AttrValue {
+        value: Some(Value::Func(NameAttrList { name: name.to_string(), attr: Default::default() })),
+    }
+}
+
+fn function(
+    name: &str,
+    inputs: &[(&str, DataType)],
+    outputs: &[(&str, DataType, &str)],
+    nodes: Vec<NodeDef>,
+) -> FunctionDef {
+    FunctionDef {
+        signature: Some(OpDef {
+            name: name.to_string(),
+            input_arg: inputs.iter().map(|(n, dt)| arg(n, *dt)).collect(),
+            output_arg: outputs.iter().map(|(n, dt, _)| arg(n, *dt)).collect(),
+            ..OpDef::default()
+        }),
+        node_def: nodes,
+        ret: outputs.iter().map(|(n, _, ret)| (n.to_string(), ret.to_string())).collect(),
+        ..FunctionDef::default()
+    }
+}
+
+fn const_i32(name: &str, value: i32) -> NodeDef {
+    let value: TensorProto = (&tensor0(value)).try_into().unwrap();
+    tfpb::node().name(name).op("Const").attr("dtype", DataType::DtInt32).attr("value", value)
+}
+
+// add_one(x) = x + 1, and a loop calling it while i < 3
+fn library() -> FunctionDefLibrary {
+    let one: TensorProto = (&tensor0(1f32)).try_into().unwrap();
+    let add_one = function(
+        "add_one",
+        &[("x", DataType::DtFloat)],
+        &[("y", DataType::DtFloat, "add:z:0")],
+        vec![
+            tfpb::node()
+                .name("one")
+                .op("Const")
+                .attr("dtype", DataType::DtFloat)
+                .attr("value", one),
+            tfpb::node().name("add").op("AddV2").input("x").input("one:output:0"),
+        ],
+    );
+    let identity =
+        function("identity", &[("x", DataType::DtFloat)], &[("y", DataType::DtFloat, "x")], vec![]);
+    let cond = function(
+        "cond",
+        &[("i", DataType::DtInt32), ("x", DataType::DtFloat)],
+        &[("ok", DataType::DtBool, "less:z:0")],
+        vec![
+            const_i32("limit", 3),
+            tfpb::node().name("less").op("Less").input("i").input("limit:output:0"),
+        ],
+    );
+    let body = function(
+        "body",
+        &[("i", DataType::DtInt32), ("x", DataType::DtFloat)],
+        &[("i", DataType::DtInt32, "inc:z:0"), ("x", DataType::DtFloat, "call:output:0")],
+        vec![
+            const_i32("one", 1),
+            tfpb::node().name("inc").op("AddV2").input("i").input("one:output:0"),
+            tfpb::node()
+                .name("call")
+                .op("PartitionedCall")
+                .input("x")
+                .attr("f", func_attr("add_one")),
+        ],
+    );
+    FunctionDefLibrary { function: vec![add_one, identity, cond, body], gradient: vec![] }
+}
+
+fn graph(node: NodeDef) -> GraphDef {
+    let mut graph = tfpb::graph()
+        .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DataType::DtFloat))
+        .node(node);
+    graph.library = Some(library());
+    graph
+}
+
+fn run(graph: &GraphDef, output: &str) -> TractResult<(Arc<Tensor>, Arc<Tensor>)> {
+    let model = tensorflow()
+        .model_for_proto_model(graph)?
+        .with_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), tvec!(2)))?
+        .with_output_names(&[output])?;
+    let input = tensor1(&[1f32, 2.0]);
+    let inferred = model.clone().into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+    let optimized = model.into_optimized()?.into_runnable()?.run(tvec!(input))?.remove(0);
+    Ok((inferred, optimized))
+}
+
+#[test]
+fn partitioned_call_is_inlined() -> TractResult<()> {
+    let graph = graph(
+        tfpb::node()
+            .name("y")
+            .op("StatefulPartitionedCall")
+            .input("x")
+            .attr("f", func_attr("add_one")),
+    );
+    let model = tensorflow().model_for_proto_model(&graph)?;
+    assert!(model.node_id_by_name("y/add").is_ok());
+    let (inferred, optimized) = run(&graph, "y")?;
+    assert_eq!(inferred, rctensor1(&[2f32, 3.0]));
+    assert_eq!(optimized, rctensor1(&[2f32, 3.0]));
+    Ok(())
+}
+
+#[test]
+fn while_with_static_trip_count() -> TractResult<()> {
+    let mut graph = graph(
+        tfpb::node()
+            .name("loop")
+            .op("StatelessWhile")
+            .input("i")
+            .input("x")
+            .attr("cond", func_attr("cond"))
+            .attr("body", func_attr("body")),
+    );
+    graph.node.insert(0, const_i32("i", 0));
+    let (inferred, optimized) = run(&graph, "loop:1")?;
+    assert_eq!(inferred, rctensor1(&[4f32, 5.0]));
+    assert_eq!(optimized, rctensor1(&[4f32, 5.0]));
+    Ok(())
+}
+
+#[test]
+fn while_with_dynamic_trip_count() -> TractResult<()> {
+    let mut graph = graph(
+        tfpb::node()
+            .name("loop")
+            .op("StatelessWhile")
+            .input("i")
+            .input("x")
+            .attr("cond", func_attr("cond"))
+            .attr("body", func_attr("body")),
+    );
+    graph.node.insert(0, tfpb::node().name("i").op("Placeholder").attr("dtype", DataType::DtInt32));
+    let model = tensorflow()
+        .model_for_proto_model(&graph)?
+        .with_input_names(&["i", "x"])?
+        .with_input_fact(0, InferenceFact::dt_shape(i32::datum_type(), &[0usize; 0]))?
+        .with_input_fact(1, InferenceFact::dt_shape(f32::datum_type(), tvec!(2)))?
+        .with_output_names(&["loop:1"])?;
+    let inferred = model.clone().into_runnable()?;
+    let optimized = model.into_optimized()?;
+    assert!(optimized.nodes().iter().any(|n| n.op().name() == "Scan"));
+    let optimized = optimized.into_runnable()?;
+    for &(i, expected) in &[(0, [4f32, 5.0]), (-2, [6f32, 7.0]), (5, [1f32, 2.0])] {
+        let input = tvec!(tensor0(i), tensor1(&[1f32, 2.0]));
+        assert_eq!(inferred.run(input.clone())?[0], rctensor1(&expected));
+        assert_eq!(optimized.run(input)?[0], rctensor1(&expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn unknown_op_output_argument_is_an_error() {
+    let mut graph = graph(
+        tfpb::node().name("y").op("StatefulPartitionedCall").input("x").attr("f", func_attr("f")),
+    );
+    let f = function(
+        "f",
+        &[("x", DataType::DtFloat)],
+        &[("y", DataType::DtFloat, "mystery:second:0")],
+        vec![tfpb::node().name("mystery").op("Mystery").input("x")],
+    );
+    graph.library.as_mut().unwrap().function.push(f);
+    assert!(tensorflow().model_for_proto_model(&graph).is_err());
+}
+
+#[test]
+fn if_with_scalar_condition() -> TractResult<()> {
+    for &(cond, expected) in &[(1, [2f32, 3.0]), (0, [1f32, 2.0])] {
+        let mut graph = graph(
+            tfpb::node()
+                .name("if")
+                .op("StatelessIf")
+                .input("cond")
+                .input("x")
+                .attr("then_branch", func_attr("add_one"))
+                .attr("else_branch", func_attr("identity")),
+        );
+        graph.node.insert(0, const_i32("cond", cond));
+        let (inferred, optimized) = run(&graph, "if")?;
+        assert_eq!(inferred, rctensor1(&expected));
+        assert_eq!(optimized, rctensor1(&expected));
+    }
+    Ok(())
+}
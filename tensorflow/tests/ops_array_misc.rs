@@ -0,0 +1,109 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::{DtBool, DtFloat, DtInt32};
+
+fn input(shape: &[usize]) -> Tensor {
+    let len = shape.iter().product::<usize>();
+    tract_ndarray::Array::from_shape_vec(shape, (0..len).map(|i| i as f32).collect())
+        .unwrap()
+        .into()
+}
+
+fn one_hot(indices: Tensor, depth: i32, axis: i64) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(placeholder_i32("indices"))
+        .node(const_i32("depth", &tensor0(depth)))
+        .node(const_f32("on", &tensor0(5f32)))
+        .node(const_f32("off", &tensor0(-1f32)))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("OneHot")
+                .input("indices")
+                .input("depth")
+                .input("on")
+                .input("off")
+                .attr("T", DtFloat)
+                .attr("TI", DtInt32)
+                .attr("axis", axis),
+        );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("indices", indices)], "op")
+}
+
+#[test]
+fn one_hot_0() -> Result<(), TestCaseError> {
+    one_hot(tensor1(&[0i32, 2, 1]), 3, -1)
+}
+
+#[test]
+fn one_hot_axis_0() -> Result<(), TestCaseError> {
+    one_hot(tensor2(&[[0i32, 3], [1, 2]]), 4, 0)
+}
+
+fn mirror_pad(shape: &[usize], pads: &[[i32; 2]], mode: &str) -> Result<(), TestCaseError> {
+    let graph =
+        tfpb::graph().node(placeholder_f32("input")).node(const_i32("pads", &tensor2(pads))).node(
+            tfpb::node()
+                .name("op")
+                .op("MirrorPad")
+                .input("input")
+                .input("pads")
+                .attr("T", DtFloat)
+                .attr("Tpaddings", DtInt32)
+                .attr("mode", mode),
+        );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input(shape))], "op")
+}
+
+#[test]
+fn mirror_pad_reflect() -> Result<(), TestCaseError> {
+    mirror_pad(&[3, 4], &[[1, 2], [2, 3]], "REFLECT")
+}
+
+#[test]
+fn mirror_pad_symmetric() -> Result<(), TestCaseError> {
+    mirror_pad(&[3, 4], &[[1, 3], [0, 2]], "SYMMETRIC")
+}
+
+fn select(op: &str, cond: Tensor, shape: &[usize]) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(placeholder("cond", DtBool, None))
+        .node(const_f32("t", &input(shape)))
+        .node(const_f32("e", &tensor0(-1f32).broadcast_scalar_to_shape(shape).unwrap()))
+        .node(
+            tfpb::node().name("op").op(op).input("cond").input("t").input("e").attr("T", DtFloat),
+        );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("cond", cond)], "op")
+}
+
+#[test]
+fn select_same_shape() -> Result<(), TestCaseError> {
+    select("Select", tensor2(&[[true, false], [false, true]]), &[2, 2])
+}
+
+#[test]
+fn select_rank_1_condition() -> Result<(), TestCaseError> {
+    select("Select", tensor1(&[false, true, true]), &[3, 2])
+}
+
+#[test]
+fn select_v2_broadcast() -> Result<(), TestCaseError> {
+    select("SelectV2", tensor1(&[false, true]), &[3, 2])
+}
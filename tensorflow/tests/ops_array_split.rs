@@ -0,0 +1,121 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::{DtFloat, DtInt32};
+
+fn input(shape: &[usize]) -> Tensor {
+    let len = shape.iter().product::<usize>();
+    tract_ndarray::Array::from_shape_vec(shape, (0..len).map(|i| i as f32).collect())
+        .unwrap()
+        .into()
+}
+
+// compare checks the first output only, so other outputs go through Identity
+fn identity(output: usize) -> tfpb::tensorflow::NodeDef {
+    tfpb::node().name("output").op("Identity").input(format!("op:{}", output)).attr("T", DtFloat)
+}
+
+fn split(shape: &[usize], axis: i32, num_split: usize, output: usize) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(const_i32("axis", &tensor0(axis)))
+        .node(placeholder_f32("input"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("Split")
+                .input("axis")
+                .input("input")
+                .attr("T", DtFloat)
+                .attr("num_split", num_split as i64),
+        )
+        .node(identity(output));
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input(shape))], "output")
+}
+
+fn split_v(shape: &[usize], sizes: &[i32], axis: i32, output: usize) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(const_i32("sizes", &tensor1(sizes)))
+        .node(const_i32("axis", &tensor0(axis)))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("SplitV")
+                .input("input")
+                .input("sizes")
+                .input("axis")
+                .attr("T", DtFloat)
+                .attr("Tlen", DtInt32)
+                .attr("num_split", sizes.len() as i64),
+        )
+        .node(identity(output));
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input(shape))], "output")
+}
+
+fn unpack(shape: &[usize], axis: i64, output: usize) -> Result<(), TestCaseError> {
+    let num = shape[if axis < 0 { axis + shape.len() as i64 } else { axis } as usize];
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("Unpack")
+                .input("input")
+                .attr("T", DtFloat)
+                .attr("num", num as i64)
+                .attr("axis", axis),
+        )
+        .node(identity(output));
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input(shape))], "output")
+}
+
+#[test]
+fn split_0() -> Result<(), TestCaseError> {
+    split(&[6], 0, 3, 0)
+}
+
+#[test]
+fn split_last_output() -> Result<(), TestCaseError> {
+    split(&[2, 6, 3], 1, 2, 1)
+}
+
+#[test]
+fn split_negative_axis() -> Result<(), TestCaseError> {
+    split(&[2, 3, 4], -1, 4, 2)
+}
+
+#[test]
+fn split_v_0() -> Result<(), TestCaseError> {
+    split_v(&[2, 7], &[2, 5], 1, 1)
+}
+
+#[test]
+fn split_v_wildcard() -> Result<(), TestCaseError> {
+    split_v(&[6, 2], &[1, -1, 2], 0, 1)
+}
+
+#[test]
+fn unpack_0() -> Result<(), TestCaseError> {
+    unpack(&[3, 2], 0, 2)
+}
+
+#[test]
+fn unpack_negative_axis() -> Result<(), TestCaseError> {
+    unpack(&[2, 3, 4], -1, 1)
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::{DtFloat, DtInt32};
+
+fn tensor(shape: Vec<usize>, values: Vec<i32>) -> Tensor {
+    tract_ndarray::Array::from(values.into_iter().map(|i| i as f32).collect::<Vec<_>>())
+        .into_shape(shape)
+        .unwrap()
+        .into()
+}
+
+fn strat() -> BoxedStrategy<Tensor> {
+    vec(1usize..5, 0..4)
+        .prop_flat_map(|shape| {
+            let len = shape.iter().product::<usize>();
+            (Just(shape), vec(-9i32..9, len..len + 1))
+        })
+        .prop_map(|(shape, values)| tensor(shape, values))
+        .boxed()
+}
+
+fn unary(op: &str, input: &Tensor) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(tfpb::node().name("op").op(op).input("input").attr("T", DtFloat));
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input.clone())], "op")
+}
+
+proptest! {
+    #[test]
+    fn square(ref input in strat()) {
+        unary("Square", input)?
+    }
+
+    #[test]
+    fn sqrt(ref input in strat()) {
+        let input = input.to_array_view::<f32>().unwrap().mapv(f32::abs).into_tensor();
+        unary("Sqrt", &input)?
+    }
+
+    #[test]
+    fn exp(ref input in strat()) {
+        unary("Exp", input)?
+    }
+
+    #[test]
+    fn squared_difference(ref a in strat(), ref b in strat()) {
+        let graph = tfpb::graph()
+            .node(placeholder_f32("a"))
+            .node(const_f32("b", &tensor0(3f32)))
+            .node(tfpb::node().name("op").op("SquaredDifference").input("a").input("b").attr("T", DtFloat));
+        let graph = graph.write_to_bytes().unwrap();
+        compare(&graph, vec![("a", a.clone())], "op")?;
+        let graph = tfpb::graph()
+            .node(placeholder_f32("b"))
+            .node(const_f32("a", &tensor0(3f32)))
+            .node(tfpb::node().name("op").op("SquaredDifference").input("a").input("b").attr("T", DtFloat));
+        let graph = graph.write_to_bytes().unwrap();
+        compare(&graph, vec![("b", b.clone())], "op")?
+    }
+
+    #[test]
+    fn arg_max((ref input, axis) in strat().prop_filter("rank", |t| t.rank() > 0)
+            .prop_flat_map(|t| { let r = t.rank() as i32; (Just(t), -r..r) })) {
+        let graph = tfpb::graph()
+            .node(placeholder_f32("input"))
+            .node(const_i32("axis", &tensor0(axis)))
+            .node(tfpb::node().name("op").op("ArgMax").input("input").input("axis")
+                .attr("T", DtFloat).attr("Tidx", DtInt32).attr("output_type", DtInt32));
+        let graph = graph.write_to_bytes().unwrap();
+        compare(&graph, vec![("input", input.clone())], "op")?
+    }
+
+    #[test]
+    fn cumsum((ref input, axis) in strat().prop_filter("rank", |t| t.rank() > 0)
+            .prop_flat_map(|t| { let r = t.rank() as i32; (Just(t), 0..r) }),
+            exclusive in any::<bool>(), reverse in any::<bool>()) {
+        let graph = tfpb::graph()
+            .node(placeholder_f32("input"))
+            .node(const_i32("axis", &tensor0(axis)))
+            .node(tfpb::node().name("op").op("Cumsum").input("input").input("axis")
+                .attr("T", DtFloat).attr("Tidx", DtInt32)
+                .attr("exclusive", exclusive).attr("reverse", reverse));
+        let graph = graph.write_to_bytes().unwrap();
+        compare(&graph, vec![("input", input.clone())], "op")?
+    }
+}
+
+fn batch_mat_mul(
+    op: &str,
+    a: Tensor,
+    b: Tensor,
+    adj_x: bool,
+    adj_y: bool,
+) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph().node(placeholder_f32("a")).node(const_f32("b", &b)).node(
+        tfpb::node()
+            .name("op")
+            .op(op)
+            .input("a")
+            .input("b")
+            .attr("T", DtFloat)
+            .attr("adj_x", adj_x)
+            .attr("adj_y", adj_y),
+    );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("a", a)], "op")
+}
+
+fn range(shape: &[usize]) -> Tensor {
+    let len = shape.iter().product::<usize>();
+    tensor(shape.to_vec(), (0..len as i32).map(|i| i % 7 - 3).collect())
+}
+
+#[test]
+fn batch_mat_mul_0() -> Result<(), TestCaseError> {
+    batch_mat_mul("BatchMatMul", range(&[2, 3, 4]), range(&[2, 4, 5]), false, false)
+}
+
+#[test]
+fn batch_mat_mul_adj() -> Result<(), TestCaseError> {
+    batch_mat_mul("BatchMatMul", range(&[2, 4, 3]), range(&[2, 5, 4]), true, true)
+}
+
+#[test]
+fn batch_mat_mul_v2_broadcast() -> Result<(), TestCaseError> {
+    batch_mat_mul("BatchMatMulV2", range(&[2, 1, 3, 4]), range(&[3, 4, 5]), false, false)
+}
+
+fn einsum(equation: &str, a: Tensor, b: Tensor) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph().node(placeholder_f32("a")).node(const_f32("b", &b)).node(
+        tfpb::node()
+            .name("op")
+            .op("Einsum")
+            .input("a")
+            .input("b")
+            .attr("T", DtFloat)
+            .attr("N", 2i64)
+            .attr("equation", equation),
+    );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("a", a)], "op")
+}
+
+#[test]
+fn einsum_matmul() -> Result<(), TestCaseError> {
+    einsum("ij,jk->ik", range(&[3, 4]), range(&[4, 5]))
+}
+
+#[test]
+fn einsum_batch_transposed() -> Result<(), TestCaseError> {
+    einsum("bij,bkj->bik", range(&[2, 3, 4]), range(&[2, 5, 4]))
+}
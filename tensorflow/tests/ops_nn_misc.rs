@@ -0,0 +1,194 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::DtFloat;
+
+fn nhwc() -> BoxedStrategy<Tensor> {
+    (1usize..3, 1usize..5, 1usize..5, 1usize..8)
+        .prop_flat_map(|(n, h, w, c)| {
+            let len = n * h * w * c;
+            (Just((n, h, w, c)), vec(-9i32..9, len..len + 1))
+        })
+        .prop_map(|(shape, values)| {
+            tract_ndarray::Array::from(values.into_iter().map(|i| i as f32).collect::<Vec<_>>())
+                .into_shape(shape)
+                .unwrap()
+                .into()
+        })
+        .boxed()
+}
+
+fn unary(op: tfpb::tensorflow::NodeDef, input: &Tensor) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph().node(placeholder_f32("input")).node(op.input("input"));
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input.clone())], "op")
+}
+
+fn resize(
+    op: &str,
+    input: &Tensor,
+    size: (i32, i32),
+    align_corners: bool,
+    half_pixel_centers: bool,
+) -> Result<(), TestCaseError> {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(const_i32("size", &tensor1(&[size.0, size.1])))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op(op)
+                .input("input")
+                .input("size")
+                .attr("T", DtFloat)
+                .attr("align_corners", align_corners)
+                .attr("half_pixel_centers", half_pixel_centers),
+        );
+    let graph = graph.write_to_bytes().unwrap();
+    compare(&graph, vec![("input", input.clone())], "op")
+}
+
+fn resize_modes() -> impl Strategy<Value = (bool, bool)> {
+    prop_oneof![Just((false, false)), Just((true, false)), Just((false, true))]
+}
+
+proptest! {
+    #[test]
+    fn leaky_relu(ref input in nhwc(), alpha in 0.01f32..1.0) {
+        let op = tfpb::node().name("op").op("LeakyRelu").attr("T", DtFloat).attr("alpha", alpha);
+        unary(op, input)?
+    }
+
+    #[test]
+    fn elu(ref input in nhwc()) {
+        unary(tfpb::node().name("op").op("Elu").attr("T", DtFloat), input)?
+    }
+
+    #[test]
+    fn softplus(ref input in nhwc()) {
+        unary(tfpb::node().name("op").op("Softplus").attr("T", DtFloat), input)?
+    }
+
+    #[test]
+    fn lrn(ref input in nhwc(), depth_radius in 0i64..4, bias in 0.5f32..2.0,
+           alpha in 0.01f32..1.0, beta in 0.25f32..1.0) {
+        let op = tfpb::node()
+            .name("op")
+            .op("LRN")
+            .attr("T", DtFloat)
+            .attr("depth_radius", depth_radius)
+            .attr("bias", bias)
+            .attr("alpha", alpha)
+            .attr("beta", beta);
+        unary(op, input)?
+    }
+
+    #[test]
+    fn resize_bilinear(ref input in nhwc(), h in 1i32..8, w in 1i32..8,
+                       (align_corners, half_pixel_centers) in resize_modes()) {
+        resize("ResizeBilinear", input, (h, w), align_corners, half_pixel_centers)?
+    }
+
+    #[test]
+    fn resize_nearest_neighbor(ref input in nhwc(), h in 1i32..8, w in 1i32..8,
+                               (align_corners, half_pixel_centers) in resize_modes()) {
+        resize("ResizeNearestNeighbor", input, (h, w), align_corners, half_pixel_centers)?
+    }
+}
+
+fn conv2d_backprop_input_pb(
+    output_shape: &[i32],
+    kernel: &Tensor,
+    strides: (usize, usize),
+    valid: bool,
+) -> Vec<u8> {
+    let op = tfpb::node()
+        .name("op")
+        .op("Conv2DBackpropInput")
+        .input("output_shape")
+        .input("kernel")
+        .input("data")
+        .attr("T", DtFloat)
+        .attr("strides", vec![1, strides.0 as i64, strides.1 as i64, 1])
+        .attr("padding", if valid { "VALID" } else { "SAME" });
+    let graph = tfpb::graph()
+        .node(const_i32("output_shape", &tensor1(output_shape)))
+        .node(const_f32("kernel", kernel))
+        .node(placeholder_f32("data"))
+        .node(op);
+    graph.write_to_bytes().unwrap()
+}
+
+fn deconv_case() -> BoxedStrategy<(Tensor, Tensor, Vec<i32>, (usize, usize), bool)> {
+    (1usize..3, 1usize..4, 1usize..4, 1usize..3, 1usize..3, 1usize..3, 1usize..3, any::<bool>())
+        .prop_flat_map(|(ic, oc, h, kh, kw, sh, sw, valid)| {
+            let w = h + 1;
+            let i_size = h * w * ic;
+            let k_size = kh * kw * oc * ic;
+            (
+                Just((h, w, ic, oc, kh, kw, (sh, sw), valid)),
+                vec(-9i32..9, i_size..i_size + 1),
+                vec(-9i32..9, k_size..k_size + 1),
+            )
+        })
+        .prop_map(|((h, w, ic, oc, kh, kw, strides, valid), data, ker)| {
+            let out = |i: usize, k: usize, s: usize| {
+                if valid {
+                    (i - 1) * s + k
+                } else {
+                    i * s
+                }
+            };
+            let output_shape =
+                vec![1, out(h, kh, strides.0) as i32, out(w, kw, strides.1) as i32, oc as i32];
+            let data =
+                tract_ndarray::Array::from(data.into_iter().map(|i| i as f32).collect::<Vec<_>>())
+                    .into_shape((1, h, w, ic))
+                    .unwrap()
+                    .into();
+            let ker =
+                tract_ndarray::Array::from(ker.into_iter().map(|i| i as f32).collect::<Vec<_>>())
+                    .into_shape((kh, kw, oc, ic))
+                    .unwrap()
+                    .into();
+            (data, ker, output_shape, strides, valid)
+        })
+        .boxed()
+}
+
+proptest! {
+    #[test]
+    fn conv2d_backprop_input((ref data, ref ker, ref output_shape, strides, valid) in deconv_case()) {
+        let graph = conv2d_backprop_input_pb(output_shape, ker, strides, valid);
+        compare(&graph, vec![("data", data.clone())], "op")?
+    }
+}
+
+#[test]
+fn conv2d_backprop_input_same_stride_2() -> Result<(), TestCaseError> {
+    let data: Tensor = tensor4(&[[[[1.0f32], [2.0]], [[3.0], [4.0]]]]);
+    let ker: Tensor = tensor4(&[[[[1.0f32]], [[1.0]], [[1.0]]], [[[1.0]], [[1.0]], [[1.0]]]]);
+    let graph = conv2d_backprop_input_pb(&[1, 4, 4, 1], &ker, (2, 2), false);
+    compare(&graph, vec![("data", data)], "op")
+}
+
+#[test]
+fn resize_to_same_size() -> Result<(), TestCaseError> {
+    let input: Tensor = tensor4(&[[[[1.0f32], [2.0]], [[3.0], [4.0]]]]);
+    resize("ResizeBilinear", &input, (2, 2), false, false)?;
+    resize("ResizeNearestNeighbor", &input, (2, 2), false, false)
+}
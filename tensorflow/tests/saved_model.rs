@@ -0,0 +1,218 @@
+use prost::Message;
+use std::convert::TryFrom;
+use std::{fs, path};
+use tract_tensorflow::model::{DEFAULT_SIGNATURE, SERVING_TAG};
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::meta_graph_def::MetaInfoDef;
+use tract_tensorflow::tfpb::tensorflow::tensor_info::Encoding;
+use tract_tensorflow::tfpb::tensorflow::tensor_shape_proto::Dim;
+use tract_tensorflow::tfpb::tensorflow::*;
+
+fn varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+// one uncompressed table block, with no key prefix sharing
+fn write_block(table: &mut Vec<u8>, entries: &[(&[u8], Vec<u8>)]) -> Vec<u8> {
+    let offset = table.len();
+    for (key, value) in entries {
+        varint(table, 0);
+        varint(table, key.len() as u64);
+        varint(table, value.len() as u64);
+        table.extend_from_slice(key);
+        table.extend_from_slice(value);
+    }
+    table.extend_from_slice(&0u32.to_le_bytes());
+    table.extend_from_slice(&1u32.to_le_bytes());
+    let size = table.len() - offset;
+    table.extend_from_slice(&[0u8; 5]);
+    let mut handle = vec![];
+    varint(&mut handle, offset as u64);
+    varint(&mut handle, size as u64);
+    handle
+}
+
+fn write_bundle(prefix: &path::Path, key: &str, tensor: &[f32]) {
+    let data = tensor.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect::<Vec<u8>>();
+    fs::write(prefix.with_extension("data-00000-of-00001"), &data).unwrap();
+    let header = BundleHeaderProto { num_shards: 1, ..BundleHeaderProto::default() };
+    let entry = BundleEntryProto {
+        dtype: DataType::DtFloat as i32,
+        shape: Some(TensorShapeProto {
+            dim: vec![Dim { size: tensor.len() as i64, name: String::new() }],
+            unknown_rank: false,
+        }),
+        size: data.len() as i64,
+        ..BundleEntryProto::default()
+    };
+    let mut table = vec![];
+    let data_handle = write_block(
+        &mut table,
+        &[(b"", header.encode_to_vec()), (key.as_bytes(), entry.encode_to_vec())],
+    );
+    let metaindex_handle = write_block(&mut table, &[]);
+    let index_handle = write_block(&mut table, &[(key.as_bytes(), data_handle)]);
+    let mut footer = [metaindex_handle, index_handle].concat();
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb4775248b80fb57u64.to_le_bytes());
+    table.extend(footer);
+    fs::write(prefix.with_extension("index"), &table).unwrap();
+}
+
+fn tensor_info(name: &str) -> TensorInfo {
+    TensorInfo {
+        encoding: Some(Encoding::Name(name.to_string())),
+        dtype: DataType::DtFloat as i32,
+        tensor_shape: None,
+    }
+}
+
+fn string_const(name: &str, value: &str) -> NodeDef {
+    let value = TensorProto {
+        dtype: DataType::DtString as i32,
+        tensor_shape: Some(TensorShapeProto {
+            dim: vec![Dim { size: 1, name: String::new() }],
+            unknown_rank: false,
+        }),
+        string_val: vec![value.as_bytes().to_vec()],
+        ..TensorProto::default()
+    };
+    tfpb::node().name(name).op("Const").attr("dtype", DataType::DtString).attr("value", value)
+}
+
+// y = x + w, with w restored by a TF1 saver from the checkpoint key "weights"
+fn write_saved_model(dir: &path::Path) {
+    let graph = tfpb::graph()
+        .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DataType::DtFloat))
+        .node(
+            tfpb::node()
+                .name("w")
+                .op("VariableV2")
+                .attr("dtype", DataType::DtFloat)
+                .attr("shape", TensorShapeProto { dim: vec![], unknown_rank: true })
+                .attr("container", "")
+                .attr("shared_name", ""),
+        )
+        .node(tfpb::node().name("y").op("Add").input("x").input("w"))
+        .node(string_const("save/Const", "model"))
+        .node(string_const("save/RestoreV2/tensor_names", "weights"))
+        .node(string_const("save/RestoreV2/shape_and_slices", ""))
+        .node(
+            tfpb::node()
+                .name("save/RestoreV2")
+                .op("RestoreV2")
+                .input("save/Const")
+                .input("save/RestoreV2/tensor_names")
+                .input("save/RestoreV2/shape_and_slices"),
+        )
+        .node(tfpb::node().name("save/Assign").op("Assign").input("w").input("save/RestoreV2"))
+        .node(tfpb::node().name("save/restore_all").op("NoOp").input("^save/Assign"));
+    let signature = SignatureDef {
+        inputs: vec![("x".to_string(), tensor_info("x:0"))].into_iter().collect(),
+        outputs: vec![("y".to_string(), tensor_info("y:0"))].into_iter().collect(),
+        method_name: "tensorflow/serving/predict".to_string(),
+    };
+    let meta = MetaGraphDef {
+        meta_info_def: Some(MetaInfoDef {
+            tags: vec![SERVING_TAG.to_string()],
+            ..MetaInfoDef::default()
+        }),
+        graph_def: Some(graph),
+        signature_def: vec![(DEFAULT_SIGNATURE.to_string(), signature)].into_iter().collect(),
+        ..MetaGraphDef::default()
+    };
+    let saved = SavedModel { saved_model_schema_version: 1, meta_graphs: vec![meta] };
+    fs::create_dir_all(dir.join("variables")).unwrap();
+    fs::write(dir.join("saved_model.pb"), saved.encode_to_vec()).unwrap();
+    write_bundle(&dir.join("variables").join("variables"), "weights", &[10.0, 20.0]);
+}
+
+fn saved_model_dir(name: &str) -> path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("tract-{}-{}", name, std::process::id()));
+    write_saved_model(&dir);
+    dir
+}
+
+fn run(model: InferenceModel) -> TractResult<Arc<Tensor>> {
+    let model = model
+        .with_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), tvec!(2)))?
+        .into_optimized()?;
+    let mut outputs = SimplePlan::new(&model)?.run(tvec!(tensor1(&[1f32, 2.0])))?;
+    Ok(outputs.remove(0))
+}
+
+#[test]
+fn saved_model_dir_with_signature() -> TractResult<()> {
+    let dir = saved_model_dir("signature");
+    let tf = tensorflow();
+    let model = tf.model_for_saved_model_dir(&dir, &[SERVING_TAG], DEFAULT_SIGNATURE)?;
+    assert_eq!(model.input_outlets()?, &[OutletId::new(model.node_id_by_name("x")?, 0)]);
+    assert_eq!(model.output_outlets()?, &[OutletId::new(model.node_id_by_name("y")?, 0)]);
+    assert_eq!(run(model)?, rctensor1(&[11f32, 22.0]));
+    assert!(tf.model_for_saved_model_dir(&dir, &["train"], DEFAULT_SIGNATURE).is_err());
+    assert!(tf.model_for_saved_model_dir(&dir, &[SERVING_TAG], "predict").is_err());
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn saved_model_dir_as_path() -> TractResult<()> {
+    let dir = saved_model_dir("path");
+    let model = tensorflow().model_for_path(&dir)?;
+    assert_eq!(run(model)?, rctensor1(&[11f32, 22.0]));
+    let graph = tensorflow().proto_model_for_path(&dir)?;
+    let w = graph.node.iter().find(|n| n.name == "w").unwrap();
+    assert_eq!(w.get_attr_tensor("value")?, tensor1(&[10f32, 20.0]));
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn bundle_with_overflowing_block_handle() -> TractResult<()> {
+    let prefix = std::env::temp_dir().join(format!("tract-overflow-{}", std::process::id()));
+    let mut footer = vec![];
+    varint(&mut footer, 0);
+    varint(&mut footer, 0);
+    varint(&mut footer, u64::MAX - 2);
+    varint(&mut footer, 1);
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb4775248b80fb57u64.to_le_bytes());
+    fs::write(prefix.with_extension("index"), &footer)?;
+    assert!(tract_tensorflow::bundle::TensorBundle::open(&prefix).is_err());
+    fs::remove_file(prefix.with_extension("index"))?;
+    Ok(())
+}
+
+#[test]
+fn restore_variables_with_input_less_assign() -> TractResult<()> {
+    let dir = saved_model_dir("assign");
+    let bundle = tract_tensorflow::bundle::TensorBundle::open(dir.join("variables/variables"))?;
+    let mut graph = tfpb::graph()
+        .node(tfpb::node().name("assign").op("Assign"))
+        .node(tfpb::node().name("assign_var").op("AssignVariableOp"));
+    tract_tensorflow::Tensorflow::restore_variables(&mut graph, &bundle)?;
+    assert_eq!(graph.node[0].op, "Assign");
+    assert_eq!(graph.node[1].op, "AssignVariableOp");
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn bool_tensor_content() -> TractResult<()> {
+    let proto = TensorProto {
+        dtype: DataType::DtBool as i32,
+        tensor_shape: Some(TensorShapeProto {
+            dim: vec![Dim { size: 3, name: String::new() }],
+            unknown_rank: false,
+        }),
+        tensor_content: vec![0, 1, 2],
+        ..TensorProto::default()
+    };
+    assert_eq!(Tensor::try_from(&proto)?, tensor1(&[false, true, true]));
+    Ok(())
+}
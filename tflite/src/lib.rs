@@ -0,0 +1,25 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate derive_new;
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+
+pub mod model;
+pub mod ops;
+pub mod schema;
+pub mod tensors;
+
+pub use model::Tflite;
+
+pub use tract_core;
+pub mod prelude {
+    pub use crate::tflite;
+    pub use tract_core::prelude::*;
+}
+
+pub fn tflite() -> Tflite {
+    let mut ops = crate::model::TfliteOpRegister::default();
+    ops::register_all_ops(&mut ops);
+    Tflite { op_register: ops }
+}
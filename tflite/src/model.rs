@@ -0,0 +1,184 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use tract_core::internal::*;
+
+use crate::schema;
+use crate::tensors;
+
+/// A TensorFlow Lite model, as a flatbuffer verified at load time.
+#[derive(Clone)]
+pub struct TfliteProtoModel(Vec<u8>);
+
+impl TfliteProtoModel {
+    pub fn new(buf: Vec<u8>) -> TractResult<TfliteProtoModel> {
+        flatbuffers::root::<schema::Model>(&buf)
+            .map_err(|e| format_err!("Invalid TFLite flatbuffer: {}", e))?;
+        Ok(TfliteProtoModel(buf))
+    }
+
+    pub fn root(&self) -> schema::Model<'_> {
+        // safety: verified in new()
+        unsafe { flatbuffers::root_unchecked::<schema::Model>(&self.0) }
+    }
+}
+
+impl std::fmt::Debug for TfliteProtoModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "TfliteProtoModel ({} bytes)", self.0.len())
+    }
+}
+
+/// State available to operator builders while wiring one TFLite operator.
+pub struct DeserOp<'op> {
+    pub model: &'op mut TypedModel,
+    pub prefix: String,
+    pub subgraph: schema::SubGraph<'op>,
+    pub flat: schema::Operator<'op>,
+    pub inputs: TVec<OutletId>,
+    pub output_facts: TVec<TypedFact>,
+}
+
+impl<'op> DeserOp<'op> {
+    /// Flatbuffer tensor descriptor of an input.
+    pub fn input_tensor(&self, slot: usize) -> TractResult<schema::Tensor<'op>> {
+        let ix = self
+            .flat
+            .inputs()
+            .and_then(|inputs| inputs.iter().filter(|i| *i >= 0).nth(slot))
+            .with_context(|| format!("{} has no input #{}", self.prefix, slot))?;
+        tensors::get(&self.subgraph.tensors().context("Subgraph has no tensors")?, ix)
+    }
+
+    /// Value of an input which must be constant.
+    pub fn input_const(&self, slot: usize) -> TractResult<Arc<Tensor>> {
+        self.model
+            .outlet_fact(self.inputs[slot])?
+            .konst
+            .clone()
+            .with_context(|| format!("{} expects input #{} to be a constant", self.prefix, slot))
+    }
+
+    pub fn options<O: schema::BuiltinOptions<'op>>(&self) -> TractResult<O> {
+        self.flat.builtin_options::<O>().with_context(|| {
+            format!(
+                "{} has unexpected builtin options (type {})",
+                self.prefix,
+                self.flat.builtin_options_type()
+            )
+        })
+    }
+}
+
+pub type OpBuilder = fn(&mut DeserOp) -> TractResult<TVec<OutletId>>;
+
+#[derive(Clone, Default)]
+pub struct TfliteOpRegister(pub HashMap<i32, OpBuilder>);
+
+impl TfliteOpRegister {
+    pub fn insert(&mut self, code: i32, builder: OpBuilder) {
+        self.0.insert(code, builder);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Tflite {
+    pub op_register: TfliteOpRegister,
+}
+
+impl Tflite {
+    fn wire_subgraph(
+        &self,
+        root: &schema::Model,
+        subgraph: schema::SubGraph,
+    ) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let tensors = subgraph.tensors().context("Subgraph has no tensors")?;
+        let buffers = root.buffers().context("Model has no buffers")?;
+        let codes = root.operator_codes().context("Model has no operator codes")?;
+        let mut mapping = HashMap::<i32, OutletId>::new();
+        for input in subgraph.inputs().into_iter().flatten() {
+            let tensor = tensors::get(&tensors, input)?;
+            let fact = tensors::fact(&tensor)?;
+            let outlet = model.add_source(tensors::name(&tensor, input), fact)?;
+            mapping.insert(input, outlet);
+        }
+        for (ix, flat) in subgraph.operators().into_iter().flatten().enumerate() {
+            let code_ix = flat.opcode_index() as usize;
+            if code_ix >= codes.len() {
+                bail!("Operator #{} has invalid opcode index {}", ix, code_ix)
+            }
+            let code = codes.get(code_ix);
+            let outputs: TVec<i32> = flat.outputs().into_iter().flatten().collect();
+            let prefix = match outputs.first() {
+                Some(o) => tensors::name(&tensors::get(&tensors, *o)?, *o),
+                None => format!("op_{}", ix),
+            };
+            let mut inputs = tvec!();
+            for input in flat.inputs().into_iter().flatten().filter(|i| *i >= 0) {
+                let outlet = match mapping.entry(input) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let tensor = tensors::get(&tensors, input)?;
+                        let value = tensors::value(&tensor, &buffers)?.with_context(|| {
+                            format!(
+                                "Input {} of {} is neither computed nor constant",
+                                input, prefix
+                            )
+                        })?;
+                        *entry.insert(model.add_const(tensors::name(&tensor, input), value)?)
+                    }
+                };
+                inputs.push(outlet);
+            }
+            let output_facts = outputs
+                .iter()
+                .map(|o| tensors::fact(&tensors::get(&tensors, *o)?))
+                .collect::<TractResult<TVec<_>>>()?;
+            let builder = self.op_register.0.get(&code.code()).with_context(|| {
+                format!(
+                    "Unsupported TFLite operator {} ({}) for {}",
+                    code.code(),
+                    code.custom_code().unwrap_or("builtin"),
+                    prefix
+                )
+            })?;
+            let mut op =
+                DeserOp { model: &mut model, prefix, subgraph, flat, inputs, output_facts };
+            let wires = (builder)(&mut op)
+                .with_context(|| format!("Translating TFLite operator {}", op.prefix))?;
+            if wires.len() != outputs.len() {
+                bail!("{} produced {} outputs, expected {}", op.prefix, wires.len(), outputs.len())
+            }
+            for (o, wire) in outputs.iter().zip(wires.iter()) {
+                mapping.insert(*o, *wire);
+            }
+        }
+        let outputs = subgraph
+            .outputs()
+            .into_iter()
+            .flatten()
+            .map(|o| mapping.get(&o).copied().with_context(|| format!("Output {} not computed", o)))
+            .collect::<TractResult<TVec<_>>>()?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
+}
+
+impl Framework<TfliteProtoModel, TypedModel> for Tflite {
+    fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<TfliteProtoModel> {
+        let mut v = vec![];
+        r.read_to_end(&mut v)?;
+        TfliteProtoModel::new(v)
+    }
+
+    fn model_for_proto_model(&self, proto: &TfliteProtoModel) -> TractResult<TypedModel> {
+        let root = proto.root();
+        let main = root
+            .subgraphs()
+            .filter(|s| !s.is_empty())
+            .context("TFLite model has no subgraph")?
+            .get(0);
+        self.wire_subgraph(&root, main)
+    }
+}
@@ -0,0 +1,103 @@
+use tract_core::internal::*;
+use tract_core::ops;
+use tract_core::ops::array::{Pad, PadMode, TypedConcat};
+use tract_core::ops::nn::{Reduce, Reducer};
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{builtin, ConcatenationOptions, ReducerOptions};
+
+use super::{broadcast_scalar, wire_as_float, wire_fused_activation, wire_reshape_to_output};
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin::CONCATENATION, concatenation);
+    reg.insert(builtin::MEAN, mean);
+    reg.insert(builtin::PAD, pad);
+    reg.insert(builtin::RESHAPE, reshape);
+    reg.insert(builtin::SQUEEZE, reshape);
+    reg.insert(builtin::TRANSPOSE, transpose);
+}
+
+fn axes(op: &DeserOp, slot: usize) -> TractResult<TVec<usize>> {
+    let rank = op.model.outlet_fact(op.inputs[0])?.rank() as i64;
+    let axes = op.input_const(slot)?.cast_to::<i64>()?.into_owned();
+    Ok(axes.as_slice::<i64>()?.iter().map(|&a| if a < 0 { a + rank } else { a } as usize).collect())
+}
+
+fn concatenation(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: ConcatenationOptions = op.options()?;
+    let rank = op.output_facts[0].rank() as i32;
+    let axis = if options.axis() < 0 { options.axis() + rank } else { options.axis() } as usize;
+    // inputs may be quantized with different parameters than the output
+    let dt = op.output_facts[0].datum_type;
+    let mut inputs = tvec!();
+    for (ix, input) in op.inputs.iter().enumerate() {
+        if op.model.outlet_fact(*input)?.datum_type != dt {
+            inputs.push(
+                op.model.wire_node(
+                    format!("{}.cast_{}", op.prefix, ix),
+                    ops::cast::cast(dt),
+                    &[*input],
+                )?[0],
+            );
+        } else {
+            inputs.push(*input);
+        }
+    }
+    let wires =
+        op.model.wire_node(&op.prefix, TypedConcat::concat_vars(axis, inputs.len()), &inputs)?;
+    wire_fused_activation(op, &wires, options.fused_activation_function())
+}
+
+fn mean(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let axes = axes(op, 1)?;
+    let keep_dims =
+        op.flat.builtin_options::<ReducerOptions>().map(|o| o.keep_dims()).unwrap_or(false);
+    let input_shape = op.model.outlet_fact(op.inputs[0])?.shape.to_tvec();
+    let count = axes.iter().map(|a| input_shape[*a].clone()).product::<TDim>().to_usize()?;
+    let wires = wire_as_float(op, |model, prefix, inputs| {
+        let sum = model.wire_node(
+            format!("{}.sum", prefix),
+            Reduce::new(axes.clone(), Reducer::Sum),
+            &inputs[0..1],
+        )?[0];
+        let recip = broadcast_scalar(model, sum, 1.0 / count as f32)?;
+        let mut wire = model.wire_node(prefix, ops::math::mul::unary(recip), &[sum])?[0];
+        if !keep_dims {
+            let mut axes = axes.clone();
+            axes.sort();
+            for axis in axes.iter().rev() {
+                wire = model.wire_node(
+                    format!("{}.rm_axis_{}", prefix, axis),
+                    AxisOp::Rm(*axis),
+                    &[wire],
+                )?[0];
+            }
+        }
+        Ok(tvec!(wire))
+    })?;
+    Ok(wires)
+}
+
+fn pad(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let paddings = op.input_const(1)?.cast_to::<i64>()?.into_owned();
+    let pads =
+        paddings.as_slice::<i64>()?.chunks(2).map(|p| (p[0] as usize, p[1] as usize)).collect();
+    // for quantized inputs, this is the zero point
+    let dt = op.model.outlet_fact(op.inputs[0])?.datum_type;
+    let zero = tensor0(0f32).cast_to_dt(dt)?.into_owned().into_arc_tensor();
+    op.model.wire_node(&op.prefix, Pad::new(pads, PadMode::Constant(zero)), &op.inputs[0..1])
+}
+
+fn reshape(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let input = op.inputs[0];
+    Ok(tvec!(wire_reshape_to_output(op, input, 0)?))
+}
+
+fn transpose(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let perm = axes(op, 1)?;
+    let mut wire = op.inputs[0];
+    for (ix, axis_op) in perm_to_ops(&perm).into_iter().enumerate() {
+        wire = op.model.wire_node(format!("{}.{}", op.prefix, ix), axis_op, &[wire])?[0];
+    }
+    Ok(tvec!(wire))
+}
@@ -0,0 +1,139 @@
+use tract_core::internal::*;
+use tract_core::ops::cnn::{ConvUnary, KernelFormat, MaxPool, PaddingSpec, PoolSpec, SumPool};
+use tract_core::ops::matmul::MatMulQParams;
+use tract_core::ops::nn::DataFormat;
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{builtin, padding};
+use crate::schema::{Conv2DOptions, DepthwiseConv2DOptions, Pool2DOptions};
+use crate::tensors;
+
+use super::{wire_activation, wire_as_float, wire_fused_activation};
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin::AVERAGE_POOL_2D, average_pool_2d);
+    reg.insert(builtin::CONV_2D, conv_2d);
+    reg.insert(builtin::DEPTHWISE_CONV_2D, depthwise_conv_2d);
+    reg.insert(builtin::MAX_POOL_2D, max_pool_2d);
+}
+
+fn padding_spec(padding: i8) -> TractResult<PaddingSpec> {
+    match padding {
+        padding::SAME => Ok(PaddingSpec::SameUpper),
+        padding::VALID => Ok(PaddingSpec::Valid),
+        p => bail!("Unsupported padding {}", p),
+    }
+}
+
+/// Quantization parameters for convolution-like operators, from the input,
+/// kernel (at input slot 1) and output types.
+pub(crate) fn conv_qparams(op: &DeserOp) -> TractResult<Option<(DatumType, MatMulQParams)>> {
+    let input_dt = op.model.outlet_fact(op.inputs[0])?.datum_type;
+    let kernel_dt = op.model.outlet_fact(op.inputs[1])?.datum_type;
+    let output_dt = op.output_facts[0].datum_type;
+    if !input_dt.is_quantized() && !output_dt.is_quantized() {
+        return Ok(None);
+    }
+    let (a0, a_scale) = if let Some((_, scales)) = tensors::per_axis_scales(&op.input_tensor(1)?)? {
+        (0, scales)
+    } else {
+        let (zp, scale) = kernel_dt.zp_scale();
+        (zp, tensor0(scale))
+    };
+    let (b0, b_scale) = input_dt.zp_scale();
+    let (c0, c_scale) = output_dt.zp_scale();
+    Ok(Some((
+        output_dt,
+        MatMulQParams {
+            a0: AttrOrInput::Attr(rctensor0(a0)),
+            a_scale: AttrOrInput::Attr(a_scale.into_arc_tensor()),
+            b0: AttrOrInput::Attr(rctensor0(b0)),
+            b_scale: AttrOrInput::Attr(rctensor0(b_scale)),
+            c0: AttrOrInput::Attr(rctensor0(c0)),
+            c_scale: AttrOrInput::Attr(rctensor0(c_scale)),
+        },
+    )))
+}
+
+fn wire_conv(
+    op: &mut DeserOp,
+    pool_spec: PoolSpec,
+    kernel: Tensor,
+    group: usize,
+    activation: i8,
+) -> TractResult<TVec<OutletId>> {
+    let bias = if op.inputs.len() > 2 { Some(op.input_const(2)?) } else { None };
+    let q_params = conv_qparams(op)?;
+    let conv = ConvUnary::new(
+        pool_spec,
+        KernelFormat::HWIO,
+        kernel.into_arc_tensor(),
+        group,
+        bias,
+        q_params,
+    );
+    let wires = op.model.wire_node(&op.prefix, conv, &op.inputs[0..1])?;
+    wire_fused_activation(op, &wires, activation)
+}
+
+fn conv_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Conv2DOptions = op.options()?;
+    // OHWI to HWIO
+    let kernel = op.input_const(1)?.into_tensor().permute_axes(&[1, 2, 3, 0])?;
+    let pool_spec = PoolSpec::new(
+        DataFormat::NHWC,
+        kernel.shape()[0..2].into(),
+        padding_spec(options.padding())?,
+        Some(tvec!(options.dilation_h_factor() as usize, options.dilation_w_factor() as usize)),
+        Some(tvec!(options.stride_h() as usize, options.stride_w() as usize)),
+        Some(kernel.shape()[3]),
+    );
+    wire_conv(op, pool_spec, kernel, 1, options.fused_activation_function())
+}
+
+fn depthwise_conv_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: DepthwiseConv2DOptions = op.options()?;
+    let input_channels = op.model.outlet_fact(op.inputs[0])?.shape[3].to_usize()?;
+    // 1HW(C*M) to HWCM, which is HWIO with one group per input channel
+    let kernel = op.input_const(1)?.into_tensor();
+    let (h, w, cm) = (kernel.shape()[1], kernel.shape()[2], kernel.shape()[3]);
+    let kernel = kernel.into_shape(&[h, w, input_channels, cm / input_channels])?;
+    let pool_spec = PoolSpec::new(
+        DataFormat::NHWC,
+        tvec!(h, w),
+        padding_spec(options.padding())?,
+        Some(tvec!(options.dilation_h_factor() as usize, options.dilation_w_factor() as usize)),
+        Some(tvec!(options.stride_h() as usize, options.stride_w() as usize)),
+        Some(cm),
+    );
+    wire_conv(op, pool_spec, kernel, input_channels, options.fused_activation_function())
+}
+
+fn pool_spec(options: &Pool2DOptions) -> TractResult<PoolSpec> {
+    Ok(PoolSpec::new(
+        DataFormat::NHWC,
+        tvec!(options.filter_height() as usize, options.filter_width() as usize),
+        padding_spec(options.padding())?,
+        None,
+        Some(tvec!(options.stride_h() as usize, options.stride_w() as usize)),
+        None,
+    ))
+}
+
+fn average_pool_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Pool2DOptions = op.options()?;
+    let pool = SumPool::new(pool_spec(&options)?, false, true);
+    wire_as_float(op, |model, prefix, inputs| {
+        let wire = model.wire_node(prefix, pool, inputs)?[0];
+        Ok(tvec!(wire_activation(model, prefix, wire, options.fused_activation_function())?))
+    })
+}
+
+fn max_pool_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Pool2DOptions = op.options()?;
+    let pool = MaxPool::new(pool_spec(&options)?, None);
+    wire_as_float(op, |model, prefix, inputs| {
+        let wire = model.wire_node(prefix, pool, inputs)?[0];
+        Ok(tvec!(wire_activation(model, prefix, wire, options.fused_activation_function())?))
+    })
+}
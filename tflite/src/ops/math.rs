@@ -0,0 +1,46 @@
+use tract_core::internal::*;
+use tract_core::ops;
+use tract_core::ops::binary::{wire_with_rank_broadcast, TypedBinOp};
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{activation, builtin, AddOptions, DivOptions, MulOptions, SubOptions};
+
+use super::{wire_activation, wire_as_float};
+
+// options are optional for these operators
+macro_rules! fused_activation {
+    ($op: expr, $options: ty) => {
+        $op.flat
+            .builtin_options::<$options>()
+            .map(|o| o.fused_activation_function())
+            .unwrap_or(activation::NONE)
+    };
+}
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin::ADD, |op| {
+        let activation = fused_activation!(op, AddOptions);
+        binary(op, ops::math::add::bin_typed(), activation)
+    });
+    reg.insert(builtin::SUB, |op| {
+        let activation = fused_activation!(op, SubOptions);
+        binary(op, ops::math::sub::bin_typed(), activation)
+    });
+    reg.insert(builtin::MUL, |op| {
+        let activation = fused_activation!(op, MulOptions);
+        binary(op, ops::math::mul::bin_typed(), activation)
+    });
+    reg.insert(builtin::DIV, |op| {
+        let activation = fused_activation!(op, DivOptions);
+        binary(op, ops::math::div::bin_typed(), activation)
+    });
+    reg.insert(builtin::MAXIMUM, |op| binary(op, ops::math::max::bin_typed(), activation::NONE));
+    reg.insert(builtin::MINIMUM, |op| binary(op, ops::math::min::bin_typed(), activation::NONE));
+}
+
+fn binary(op: &mut DeserOp, bin: TypedBinOp, activation: i8) -> TractResult<TVec<OutletId>> {
+    wire_as_float(op, |model, prefix, inputs| {
+        let wire = wire_with_rank_broadcast(prefix, model, bin, inputs)?[0];
+        Ok(tvec!(wire_activation(model, prefix, wire, activation)?))
+    })
+}
@@ -0,0 +1,164 @@
+use tract_core::internal::*;
+use tract_core::ops;
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::activation;
+
+mod array;
+mod cnn;
+mod math;
+mod nn;
+mod quant;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    array::register_all_ops(reg);
+    cnn::register_all_ops(reg);
+    math::register_all_ops(reg);
+    nn::register_all_ops(reg);
+    quant::register_all_ops(reg);
+}
+
+/// A scalar of the input datum type, broadcast to its rank, to be used as
+/// the constant side of a unary op.
+pub(crate) fn broadcast_scalar(
+    model: &TypedModel,
+    wire: OutletId,
+    value: f32,
+) -> TractResult<Arc<Tensor>> {
+    let fact = model.outlet_fact(wire)?;
+    Ok(tensor0(value)
+        .cast_to_dt(fact.datum_type)?
+        .into_owned()
+        .broadcast_into_rank(fact.rank())?
+        .into_arc_tensor())
+}
+
+pub(crate) fn wire_clip(
+    model: &mut TypedModel,
+    prefix: &str,
+    wire: OutletId,
+    min: Option<f32>,
+    max: Option<f32>,
+) -> TractResult<OutletId> {
+    let mut wire = wire;
+    if let Some(min) = min {
+        let min = broadcast_scalar(model, wire, min)?;
+        wire =
+            model.wire_node(format!("{}.clip_low", prefix), ops::math::max::unary(min), &[wire])?
+                [0];
+    }
+    if let Some(max) = max {
+        let max = broadcast_scalar(model, wire, max)?;
+        wire = model.wire_node(
+            format!("{}.clip_high", prefix),
+            ops::math::min::unary(max),
+            &[wire],
+        )?[0];
+    }
+    Ok(wire)
+}
+
+/// Wire a fused activation function on a float wire.
+pub(crate) fn wire_activation(
+    model: &mut TypedModel,
+    prefix: &str,
+    wire: OutletId,
+    function: i8,
+) -> TractResult<OutletId> {
+    match function {
+        activation::NONE => Ok(wire),
+        activation::RELU => wire_clip(model, prefix, wire, Some(0.0), None),
+        activation::RELU_N1_TO_1 => wire_clip(model, prefix, wire, Some(-1.0), Some(1.0)),
+        activation::RELU6 => wire_clip(model, prefix, wire, Some(0.0), Some(6.0)),
+        activation::TANH => {
+            Ok(model.wire_node(format!("{}.tanh", prefix), ops::math::tanh(), &[wire])?[0])
+        }
+        f => bail!("Unsupported fused activation function {}", f),
+    }
+}
+
+/// Wire the fused activation function of an operator after its (single)
+/// output. Quantized outputs go through float and back.
+pub(crate) fn wire_fused_activation(
+    op: &mut DeserOp,
+    wires: &[OutletId],
+    function: i8,
+) -> TractResult<TVec<OutletId>> {
+    if function == activation::NONE {
+        return Ok(wires.into());
+    }
+    let dt = op.model.outlet_fact(wires[0])?.datum_type;
+    let prefix = format!("{}.activation", op.prefix);
+    let mut wire = wires[0];
+    if dt.is_quantized() {
+        wire = op.model.wire_node(
+            format!("{}.dequant", prefix),
+            ops::cast::cast(f32::datum_type()),
+            &[wire],
+        )?[0];
+    }
+    wire = wire_activation(op.model, &prefix, wire, function)?;
+    if dt.is_quantized() {
+        wire = op.model.wire_node(format!("{}.quant", prefix), ops::cast::cast(dt), &[wire])?[0];
+    }
+    Ok(tvec!(wire))
+}
+
+/// Wire an operator with no quantized implementation: quantized inputs are
+/// dequantized, the body is wired in float, and outputs are quantized back
+/// to the types the model declares.
+pub(crate) fn wire_as_float(
+    op: &mut DeserOp,
+    body: impl FnOnce(&mut TypedModel, &str, &[OutletId]) -> TractResult<TVec<OutletId>>,
+) -> TractResult<TVec<OutletId>> {
+    let mut inputs = tvec!();
+    for (ix, input) in op.inputs.iter().enumerate() {
+        if op.model.outlet_fact(*input)?.datum_type.is_quantized() {
+            inputs.push(
+                op.model.wire_node(
+                    format!("{}.dequant_{}", op.prefix, ix),
+                    ops::cast::cast(f32::datum_type()),
+                    &[*input],
+                )?[0],
+            );
+        } else {
+            inputs.push(*input);
+        }
+    }
+    let outputs = body(op.model, &op.prefix, &inputs)?;
+    let output_facts = op.output_facts.clone();
+    outputs
+        .iter()
+        .zip(output_facts.iter())
+        .enumerate()
+        .map(|(ix, (wire, fact))| {
+            if fact.datum_type.is_quantized() {
+                Ok(op.model.wire_node(
+                    format!("{}.quant_{}", op.prefix, ix),
+                    ops::cast::cast(fact.datum_type),
+                    &[*wire],
+                )?[0])
+            } else {
+                Ok(*wire)
+            }
+        })
+        .collect()
+}
+
+/// Reshape a wire to the shape the model declares for the operator output.
+pub(crate) fn wire_reshape_to_output(
+    op: &mut DeserOp,
+    wire: OutletId,
+    slot: usize,
+) -> TractResult<OutletId> {
+    let from = op.model.outlet_fact(wire)?.shape.to_tvec();
+    let to = op.output_facts[slot].shape.to_tvec();
+    if from == to {
+        return Ok(wire);
+    }
+    Ok(op.model.wire_node(
+        format!("{}.reshape", op.prefix),
+        AxisOp::Reshape(0, from, to),
+        &[wire],
+    )?[0])
+}
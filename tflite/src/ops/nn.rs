@@ -0,0 +1,131 @@
+use tract_core::internal::*;
+use tract_core::ops;
+use tract_core::ops::binary::wire_with_rank_broadcast;
+use tract_core::ops::matmul::mir_quant_unary::QMatMulUnary;
+use tract_core::ops::matmul::MatMulUnary;
+use tract_core::ops::nn::{Reduce, Reducer};
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{activation, builtin, FullyConnectedOptions, SoftmaxOptions};
+
+use super::cnn::conv_qparams;
+use super::{broadcast_scalar, wire_reshape_to_output};
+use super::{wire_activation, wire_as_float, wire_clip, wire_fused_activation};
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin::FULLY_CONNECTED, fully_connected);
+    reg.insert(builtin::HARD_SWISH, |op| wire_as_float(op, hard_swish));
+    reg.insert(builtin::LOGISTIC, |op| {
+        wire_as_float(op, |model, prefix, inputs| {
+            model.wire_node(prefix, ops::nn::sigmoid(), inputs)
+        })
+    });
+    reg.insert(builtin::RELU, |op| activation(op, activation::RELU));
+    reg.insert(builtin::RELU6, |op| activation(op, activation::RELU6));
+    reg.insert(builtin::SOFTMAX, softmax);
+    reg.insert(builtin::TANH, |op| activation(op, activation::TANH));
+}
+
+fn activation(op: &mut DeserOp, function: i8) -> TractResult<TVec<OutletId>> {
+    wire_as_float(op, |model, prefix, inputs| {
+        Ok(tvec!(wire_activation(model, prefix, inputs[0], function)?))
+    })
+}
+
+fn fully_connected(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: FullyConnectedOptions = op.options()?;
+    if options.weights_format() != 0 {
+        bail!("Only default weights format is supported in fully connected operator")
+    }
+    let weights = op.input_const(1)?;
+    let (o, k) = (weights.shape()[0], weights.shape()[1]);
+    let mut wire = op.inputs[0];
+    let input_shape = op.model.outlet_fact(wire)?.shape.to_tvec();
+    if input_shape.len() != 2 || input_shape[1] != k.to_dim() {
+        let n = input_shape.iter().product::<TDim>().to_usize()? / k;
+        wire = op.model.wire_node(
+            format!("{}.flatten", op.prefix),
+            AxisOp::Reshape(0, input_shape, tvec!(n.to_dim(), k.to_dim())),
+            &[wire],
+        )?[0];
+    }
+    // input is [n, k], weights are [o, k], output is [n, o]
+    let bias = if op.inputs.len() > 2 { Some(op.input_const(2)?) } else { None };
+    wire = if let Some((output_type, params)) = conv_qparams(op)? {
+        let bias = bias.map(|b| b.into_tensor().into_shape(&[1, o])).transpose()?;
+        let op_ = QMatMulUnary::new(
+            weights,
+            bias.map(|b| b.into_arc_tensor()),
+            false,
+            true,
+            true,
+            output_type,
+            params,
+        );
+        op.model.wire_node(&op.prefix, op_, &[wire])?[0]
+    } else {
+        let mut wire = op.model.wire_node(
+            &op.prefix,
+            MatMulUnary::new(weights, false, true, true),
+            &[wire],
+        )?[0];
+        if let Some(bias) = bias {
+            let bias = op.model.add_const(format!("{}.bias", op.prefix), bias)?;
+            wire = wire_with_rank_broadcast(
+                &format!("{}.add_bias", op.prefix),
+                op.model,
+                ops::math::add::bin_typed(),
+                &[wire, bias],
+            )?[0];
+        }
+        wire
+    };
+    let wires = wire_fused_activation(op, &[wire], options.fused_activation_function())?;
+    Ok(tvec!(wire_reshape_to_output(op, wires[0], 0)?))
+}
+
+fn hard_swish(
+    model: &mut TypedModel,
+    prefix: &str,
+    inputs: &[OutletId],
+) -> TractResult<TVec<OutletId>> {
+    // x * relu6(x + 3) / 6
+    let x = inputs[0];
+    let three = broadcast_scalar(model, x, 3.0)?;
+    let sixth = broadcast_scalar(model, x, 1.0 / 6.0)?;
+    let wire =
+        model.wire_node(format!("{}.plus_3", prefix), ops::math::add::unary(three), &[x])?[0];
+    let wire = wire_clip(model, prefix, wire, Some(0.0), Some(6.0))?;
+    let wire =
+        model.wire_node(format!("{}.div_6", prefix), ops::math::mul::unary(sixth), &[wire])?[0];
+    model.wire_node(prefix, ops::math::mul::bin_typed(), &[x, wire])
+}
+
+fn softmax(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let beta = op.options::<SoftmaxOptions>()?.beta();
+    wire_as_float(op, |model, prefix, inputs| {
+        let axis = model.outlet_fact(inputs[0])?.rank() - 1;
+        let mut x = inputs[0];
+        if beta != 1.0 {
+            let beta = broadcast_scalar(model, x, beta)?;
+            x = model.wire_node(format!("{}.beta", prefix), ops::math::mul::unary(beta), &[x])?[0];
+        }
+        let max = model.wire_node(
+            format!("{}.max", prefix),
+            Reduce::new(tvec!(axis), Reducer::Max),
+            &[x],
+        )?[0];
+        let x = model.wire_node(
+            format!("{}.sub_max", prefix),
+            ops::math::sub::bin_typed(),
+            &[x, max],
+        )?[0];
+        let exp = model.wire_node(format!("{}.exp", prefix), ops::math::exp(), &[x])?[0];
+        let sum = model.wire_node(
+            format!("{}.sum", prefix),
+            Reduce::new(tvec!(axis), Reducer::Sum),
+            &[exp],
+        )?[0];
+        model.wire_node(prefix, ops::math::div::bin_typed(), &[exp, sum])
+    })
+}
@@ -0,0 +1,17 @@
+use tract_core::internal::*;
+use tract_core::ops;
+
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::builtin;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin::DEQUANTIZE, cast_to_output_type);
+    reg.insert(builtin::QUANTIZE, cast_to_output_type);
+}
+
+/// Quantize, dequantize and requantize are all casts between the input and
+/// output types.
+fn cast_to_output_type(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let dt = op.output_facts[0].datum_type;
+    op.model.wire_node(&op.prefix, ops::cast::cast(dt), &op.inputs[0..1])
+}
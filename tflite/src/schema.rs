@@ -0,0 +1,394 @@
+//! Read-only accessors over the subset of the TensorFlow Lite flatbuffer
+//! schema (tensorflow/lite/schema/schema.fbs) tract understands.
+//!
+//! Field slots follow the schema declaration order, so they must be kept in
+//! sync with upstream when new fields are used.
+use flatbuffers::{
+    Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector, Verifiable, Verifier,
+};
+
+const fn slot(ix: VOffsetT) -> VOffsetT {
+    4 + 2 * ix
+}
+
+macro_rules! table {
+    ($name: ident $(= $options_type: literal)? {
+        scalars { $($s: ident: $s_slot: expr => $s_ty: ty = $s_default: expr),* $(,)? }
+        offsets { $($o: ident: $o_slot: expr => $o_ty: ty),* $(,)? }
+    }) => {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $name<'a> {
+            table: Table<'a>,
+        }
+
+        impl<'a> Follow<'a> for $name<'a> {
+            type Inner = Self;
+            unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+                $name { table: Table::new(buf, loc) }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl<'a> $name<'a> {
+            $(
+                pub fn $s(&self) -> $s_ty {
+                    // safety: the buffer has been verified on load
+                    unsafe { self.table.get::<$s_ty>(slot($s_slot), Some($s_default)).unwrap() }
+                }
+            )*
+            $(
+                pub fn $o(&self) -> Option<$o_ty> {
+                    // safety: the buffer has been verified on load
+                    unsafe { self.table.get::<ForwardsUOffset<$o_ty>>(slot($o_slot), None) }
+                }
+            )*
+        }
+
+        impl<'a> Verifiable for $name<'a> {
+            fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+                v.visit_table(pos)?
+                    $(.visit_field::<$s_ty>(stringify!($s), slot($s_slot), false)?)*
+                    $(.visit_field::<ForwardsUOffset<$o_ty>>(stringify!($o), slot($o_slot), false)?)*
+                    .finish();
+                Ok(())
+            }
+        }
+
+        $(
+            impl<'a> BuiltinOptions<'a> for $name<'a> {
+                const OPTIONS_TYPE: u8 = $options_type;
+            }
+        )?
+    };
+}
+
+/// A table that can be found in `Operator.builtin_options`.
+pub trait BuiltinOptions<'a>: Follow<'a, Inner = Self> + Verifiable + 'a {
+    const OPTIONS_TYPE: u8;
+}
+
+table!(Model {
+    scalars { version: 0 => u32 = 0 }
+    offsets {
+        operator_codes: 1 => Vector<'a, ForwardsUOffset<OperatorCode<'a>>>,
+        subgraphs: 2 => Vector<'a, ForwardsUOffset<SubGraph<'a>>>,
+        description: 3 => &'a str,
+        buffers: 4 => Vector<'a, ForwardsUOffset<Buffer<'a>>>,
+    }
+});
+
+table!(OperatorCode {
+    scalars {
+        deprecated_builtin_code: 0 => i8 = 0,
+        version: 2 => i32 = 1,
+        builtin_code: 3 => i32 = 0,
+    }
+    offsets { custom_code: 1 => &'a str }
+});
+
+impl<'a> OperatorCode<'a> {
+    /// Builtin operator code, accounting for the pre-2.3 single byte field.
+    pub fn code(&self) -> i32 {
+        self.builtin_code().max(self.deprecated_builtin_code() as i32)
+    }
+}
+
+table!(SubGraph {
+    scalars {}
+    offsets {
+        tensors: 0 => Vector<'a, ForwardsUOffset<Tensor<'a>>>,
+        inputs: 1 => Vector<'a, i32>,
+        outputs: 2 => Vector<'a, i32>,
+        operators: 3 => Vector<'a, ForwardsUOffset<Operator<'a>>>,
+        name: 4 => &'a str,
+    }
+});
+
+table!(Tensor {
+    scalars {
+        type_: 1 => i8 = 0,
+        buffer: 2 => u32 = 0,
+        is_variable: 5 => bool = false,
+    }
+    offsets {
+        shape: 0 => Vector<'a, i32>,
+        name: 3 => &'a str,
+        quantization: 4 => QuantizationParameters<'a>,
+        shape_signature: 7 => Vector<'a, i32>,
+    }
+});
+
+table!(QuantizationParameters {
+    scalars { quantized_dimension: 6 => i32 = 0 }
+    offsets {
+        min: 0 => Vector<'a, f32>,
+        max: 1 => Vector<'a, f32>,
+        scale: 2 => Vector<'a, f32>,
+        zero_point: 3 => Vector<'a, i64>,
+    }
+});
+
+table!(Buffer {
+    scalars { offset: 1 => u64 = 0, size: 2 => u64 = 0 }
+    offsets { data: 0 => Vector<'a, u8> }
+});
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Operator<'a> {
+    table: Table<'a>,
+}
+
+impl<'a> Follow<'a> for Operator<'a> {
+    type Inner = Self;
+    unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+        Operator { table: Table::new(buf, loc) }
+    }
+}
+
+impl<'a> Operator<'a> {
+    pub fn opcode_index(&self) -> u32 {
+        // safety: the buffer has been verified on load
+        unsafe { self.table.get::<u32>(slot(0), Some(0)).unwrap() }
+    }
+
+    pub fn inputs(&self) -> Option<Vector<'a, i32>> {
+        // safety: the buffer has been verified on load
+        unsafe { self.table.get::<ForwardsUOffset<Vector<'a, i32>>>(slot(1), None) }
+    }
+
+    pub fn outputs(&self) -> Option<Vector<'a, i32>> {
+        // safety: the buffer has been verified on load
+        unsafe { self.table.get::<ForwardsUOffset<Vector<'a, i32>>>(slot(2), None) }
+    }
+
+    pub fn builtin_options_type(&self) -> u8 {
+        // safety: the buffer has been verified on load
+        unsafe { self.table.get::<u8>(slot(3), Some(0)).unwrap() }
+    }
+
+    /// Builtin options, if present and of the expected type.
+    pub fn builtin_options<O: BuiltinOptions<'a>>(&self) -> Option<O> {
+        if self.builtin_options_type() != O::OPTIONS_TYPE {
+            return None;
+        }
+        // safety: the buffer has been verified on load, and the union
+        // discriminant matches O
+        unsafe { self.table.get::<ForwardsUOffset<O>>(slot(4), None) }
+    }
+}
+
+impl<'a> Verifiable for Operator<'a> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("opcode_index", slot(0), false)?
+            .visit_field::<ForwardsUOffset<Vector<i32>>>("inputs", slot(1), false)?
+            .visit_field::<ForwardsUOffset<Vector<i32>>>("outputs", slot(2), false)?
+            .visit_union::<u8, _>(
+                "builtin_options_type",
+                slot(3),
+                "builtin_options",
+                slot(4),
+                false,
+                verify_builtin_options,
+            )?
+            .finish();
+        Ok(())
+    }
+}
+
+fn verify_builtin_options(
+    options_type: u8,
+    v: &mut Verifier,
+    pos: usize,
+) -> Result<(), InvalidFlatbuffer> {
+    macro_rules! verify {
+        ($($t: ident),*) => {
+            $(if options_type == $t::OPTIONS_TYPE {
+                return v.verify_union_variant::<ForwardsUOffset<$t>>(stringify!($t), pos);
+            })*
+        }
+    }
+    verify!(
+        Conv2DOptions,
+        DepthwiseConv2DOptions,
+        Pool2DOptions,
+        FullyConnectedOptions,
+        SoftmaxOptions,
+        ConcatenationOptions,
+        AddOptions,
+        ReshapeOptions,
+        MulOptions,
+        PadOptions,
+        TransposeOptions,
+        ReducerOptions,
+        SubOptions,
+        DivOptions,
+        SqueezeOptions
+    );
+    // options tract does not read only need to be a valid table
+    v.verify_union_variant::<ForwardsUOffset<UnknownOptions>>("UnknownOptions", pos)
+}
+
+table!(UnknownOptions {
+    scalars {}
+    offsets {}
+});
+
+table!(Conv2DOptions = 1 {
+    scalars {
+        padding: 0 => i8 = 0,
+        stride_w: 1 => i32 = 0,
+        stride_h: 2 => i32 = 0,
+        fused_activation_function: 3 => i8 = 0,
+        dilation_w_factor: 4 => i32 = 1,
+        dilation_h_factor: 5 => i32 = 1,
+    }
+    offsets {}
+});
+
+table!(DepthwiseConv2DOptions = 2 {
+    scalars {
+        padding: 0 => i8 = 0,
+        stride_w: 1 => i32 = 0,
+        stride_h: 2 => i32 = 0,
+        depth_multiplier: 3 => i32 = 0,
+        fused_activation_function: 4 => i8 = 0,
+        dilation_w_factor: 5 => i32 = 1,
+        dilation_h_factor: 6 => i32 = 1,
+    }
+    offsets {}
+});
+
+table!(Pool2DOptions = 5 {
+    scalars {
+        padding: 0 => i8 = 0,
+        stride_w: 1 => i32 = 0,
+        stride_h: 2 => i32 = 0,
+        filter_width: 3 => i32 = 0,
+        filter_height: 4 => i32 = 0,
+        fused_activation_function: 5 => i8 = 0,
+    }
+    offsets {}
+});
+
+table!(FullyConnectedOptions = 8 {
+    scalars {
+        fused_activation_function: 0 => i8 = 0,
+        weights_format: 1 => i8 = 0,
+        keep_num_dims: 2 => bool = false,
+    }
+    offsets {}
+});
+
+table!(SoftmaxOptions = 9 {
+    scalars { beta: 0 => f32 = 0.0 }
+    offsets {}
+});
+
+table!(ConcatenationOptions = 10 {
+    scalars { axis: 0 => i32 = 0, fused_activation_function: 1 => i8 = 0 }
+    offsets {}
+});
+
+table!(AddOptions = 11 {
+    scalars { fused_activation_function: 0 => i8 = 0 }
+    offsets {}
+});
+
+table!(ReshapeOptions = 17 {
+    scalars {}
+    offsets { new_shape: 0 => Vector<'a, i32> }
+});
+
+table!(MulOptions = 21 {
+    scalars { fused_activation_function: 0 => i8 = 0 }
+    offsets {}
+});
+
+table!(PadOptions = 22 {
+    scalars {}
+    offsets {}
+});
+
+table!(TransposeOptions = 26 {
+    scalars {}
+    offsets {}
+});
+
+table!(ReducerOptions = 27 {
+    scalars { keep_dims: 0 => bool = false }
+    offsets {}
+});
+
+table!(SubOptions = 28 {
+    scalars { fused_activation_function: 0 => i8 = 0 }
+    offsets {}
+});
+
+table!(DivOptions = 29 {
+    scalars { fused_activation_function: 0 => i8 = 0 }
+    offsets {}
+});
+
+table!(SqueezeOptions = 30 {
+    scalars {}
+    offsets { squeeze_dims: 0 => Vector<'a, i32> }
+});
+
+/// TensorType enum values.
+pub mod tensor_type {
+    pub const FLOAT32: i8 = 0;
+    pub const FLOAT16: i8 = 1;
+    pub const INT32: i8 = 2;
+    pub const UINT8: i8 = 3;
+    pub const INT64: i8 = 4;
+    pub const STRING: i8 = 5;
+    pub const BOOL: i8 = 6;
+    pub const INT16: i8 = 7;
+    pub const INT8: i8 = 9;
+    pub const FLOAT64: i8 = 10;
+}
+
+/// Padding enum values.
+pub mod padding {
+    pub const SAME: i8 = 0;
+    pub const VALID: i8 = 1;
+}
+
+/// ActivationFunctionType enum values.
+pub mod activation {
+    pub const NONE: i8 = 0;
+    pub const RELU: i8 = 1;
+    pub const RELU_N1_TO_1: i8 = 2;
+    pub const RELU6: i8 = 3;
+    pub const TANH: i8 = 4;
+}
+
+/// BuiltinOperator enum values.
+pub mod builtin {
+    pub const ADD: i32 = 0;
+    pub const AVERAGE_POOL_2D: i32 = 1;
+    pub const CONCATENATION: i32 = 2;
+    pub const CONV_2D: i32 = 3;
+    pub const DEPTHWISE_CONV_2D: i32 = 4;
+    pub const DEQUANTIZE: i32 = 6;
+    pub const FULLY_CONNECTED: i32 = 9;
+    pub const LOGISTIC: i32 = 14;
+    pub const MAX_POOL_2D: i32 = 17;
+    pub const MUL: i32 = 18;
+    pub const RELU: i32 = 19;
+    pub const RELU6: i32 = 21;
+    pub const RESHAPE: i32 = 22;
+    pub const SOFTMAX: i32 = 25;
+    pub const TANH: i32 = 28;
+    pub const PAD: i32 = 34;
+    pub const TRANSPOSE: i32 = 39;
+    pub const MEAN: i32 = 40;
+    pub const SUB: i32 = 41;
+    pub const DIV: i32 = 42;
+    pub const SQUEEZE: i32 = 43;
+    pub const MAXIMUM: i32 = 55;
+    pub const MINIMUM: i32 = 57;
+    pub const QUANTIZE: i32 = 114;
+    pub const HARD_SWISH: i32 = 117;
+}
@@ -0,0 +1,112 @@
+use flatbuffers::{ForwardsUOffset, Vector};
+use tract_core::internal::*;
+
+use crate::schema;
+use crate::schema::tensor_type;
+
+/// Tensor `ix` of a subgraph, checked against the tensor table length.
+pub fn get<'a>(
+    tensors: &Vector<'a, ForwardsUOffset<schema::Tensor<'a>>>,
+    ix: i32,
+) -> TractResult<schema::Tensor<'a>> {
+    if ix < 0 || ix as usize >= tensors.len() {
+        bail!("Invalid tensor index {} (subgraph has {} tensors)", ix, tensors.len())
+    }
+    Ok(tensors.get(ix as usize))
+}
+
+pub fn name(tensor: &schema::Tensor, ix: i32) -> String {
+    tensor.name().map(|s| s.to_string()).unwrap_or_else(|| format!("tensor_{}", ix))
+}
+
+/// Quantization parameters of a tensor quantized per-tensor.
+///
+/// Per-axis quantized tensors (weights only) return None here, see
+/// `per_axis_scales`.
+pub fn qparams(tensor: &schema::Tensor) -> Option<QParams> {
+    let q = tensor.quantization()?;
+    let scale = q.scale().filter(|s| s.len() == 1)?.get(0);
+    let zero_point = q.zero_point().filter(|z| z.len() == 1).map(|z| z.get(0)).unwrap_or(0);
+    Some(QParams::ZpScale { zero_point: zero_point as i32, scale })
+}
+
+/// Per output channel scales of a per-axis quantized tensor.
+pub fn per_axis_scales(tensor: &schema::Tensor) -> TractResult<Option<(usize, Tensor)>> {
+    if let Some(q) = tensor.quantization() {
+        if let Some(scale) = q.scale().filter(|s| s.len() > 1) {
+            if q.zero_point().map(|zp| zp.iter().any(|z| z != 0)).unwrap_or(false) {
+                bail!("Per-axis quantization with non-zero zero points is not supported")
+            }
+            let scale: Vec<f32> = scale.iter().collect();
+            return Ok(Some((q.quantized_dimension() as usize, tensor1(&scale))));
+        }
+    }
+    Ok(None)
+}
+
+pub fn datum_type(tensor: &schema::Tensor) -> TractResult<DatumType> {
+    let dt = match tensor.type_() {
+        tensor_type::FLOAT32 => DatumType::F32,
+        tensor_type::FLOAT16 => DatumType::F16,
+        tensor_type::FLOAT64 => DatumType::F64,
+        tensor_type::INT32 => DatumType::I32,
+        tensor_type::INT64 => DatumType::I64,
+        tensor_type::INT16 => DatumType::I16,
+        tensor_type::BOOL => DatumType::Bool,
+        tensor_type::UINT8 => qparams(tensor).map(DatumType::QU8).unwrap_or(DatumType::U8),
+        tensor_type::INT8 => qparams(tensor).map(DatumType::QI8).unwrap_or(DatumType::I8),
+        t => bail!("Unsupported TFLite tensor type {}", t),
+    };
+    Ok(dt)
+}
+
+pub fn shape(tensor: &schema::Tensor) -> TractResult<TVec<usize>> {
+    tensor
+        .shape()
+        .into_iter()
+        .flatten()
+        .map(|d| {
+            if d < 0 {
+                bail!("Tensor {:?} has a negative dimension ({})", tensor.name(), d)
+            }
+            Ok(d as usize)
+        })
+        .collect()
+}
+
+pub fn fact(tensor: &schema::Tensor) -> TractResult<TypedFact> {
+    Ok(TypedFact::dt_shape(datum_type(tensor)?, &*shape(tensor)?))
+}
+
+/// Value of a constant tensor, None if the tensor is not backed by a buffer.
+pub fn value(
+    tensor: &schema::Tensor,
+    buffers: &Vector<ForwardsUOffset<schema::Buffer>>,
+) -> TractResult<Option<Tensor>> {
+    let buffer_ix = tensor.buffer() as usize;
+    if buffer_ix >= buffers.len() {
+        bail!("Tensor {:?} refers to missing buffer {}", tensor.name(), buffer_ix)
+    }
+    let buffer = buffers.get(buffer_ix);
+    if buffer.offset() > 1 {
+        bail!("TFLite buffers stored out of the flatbuffer are not supported");
+    }
+    let data = if let Some(data) = buffer.data().filter(|d| !d.is_empty()) {
+        data.bytes()
+    } else {
+        return Ok(None);
+    };
+    let dt = datum_type(tensor)?;
+    let shape = shape(tensor)?;
+    let expected = shape.iter().product::<usize>() * dt.size_of();
+    if data.len() != expected {
+        bail!(
+            "Buffer for tensor {:?} has {} bytes, expected {}",
+            tensor.name(),
+            data.len(),
+            expected
+        );
+    }
+    // tflite buffers are little endian, like all platforms tract supports
+    unsafe { Ok(Some(Tensor::from_raw_dt(dt, &shape, data)?)) }
+}
@@ -0,0 +1,38 @@
+mod utils;
+
+use tract_tflite::prelude::*;
+use tract_tflite::schema::builtin;
+use utils::*;
+
+fn load(model: &ModelDef) -> TractResult<TypedModel> {
+    tract_tflite::tflite().model_for_read(&mut &*model.to_bytes())
+}
+
+#[test]
+fn reject_tensor_index_out_of_range() -> TractResult<()> {
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[2, 3]));
+    let o = model.tensor(TensorDef::f32("output", &[2, 3]));
+    model.ops.push(OpDef {
+        code: builtin::SOFTMAX,
+        inputs: vec![42],
+        outputs: vec![o],
+        ..OpDef::default()
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let err = load(&model).unwrap_err();
+    assert!(format!("{:?}", err).contains("Invalid tensor index 42"));
+    Ok(())
+}
+
+#[test]
+fn reject_negative_dimension() -> TractResult<()> {
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[-1, 3]));
+    model.inputs = vec![i];
+    model.outputs = vec![i];
+    let err = load(&model).unwrap_err();
+    assert!(format!("{:?}", err).contains("negative dimension"));
+    Ok(())
+}
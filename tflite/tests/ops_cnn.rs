@@ -0,0 +1,197 @@
+mod utils;
+
+use tract_tflite::prelude::*;
+use tract_tflite::schema::{activation, builtin, padding, tensor_type};
+use utils::*;
+
+/// Reference NHWC convolution, VALID padding, stride 1, kernel in HWIO with
+/// `group` groups.
+fn conv_ref(input: &Tensor, kernel: &Tensor, bias: &[f32], group: usize) -> TractResult<Tensor> {
+    let input = input.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix4>()?;
+    let kernel = kernel.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix4>()?;
+    let (n, h, w, _) = input.dim();
+    let (kh, kw, ci, co) = kernel.dim();
+    let ci_per_group = ci / group;
+    let co_total = co * group;
+    let (oh, ow) = (h - kh + 1, w - kw + 1);
+    let output = tract_ndarray::Array4::from_shape_fn((n, oh, ow, co_total), |(n, y, x, c)| {
+        let g = c / co;
+        let mut sum = bias[c];
+        for ky in 0..kh {
+            for kx in 0..kw {
+                for i in 0..ci_per_group {
+                    let ic = g * ci_per_group + i;
+                    sum += input[(n, y + ky, x + kx, ic)] * kernel[(ky, kx, ic, c % co)];
+                }
+            }
+        }
+        sum
+    });
+    Ok(output.into_tensor())
+}
+
+fn ramp(shape: &[usize], mul: f32, offset: f32) -> Tensor {
+    let len = shape.iter().product();
+    let data: Vec<f32> = (0..len).map(|i| ((i * 7) % 11) as f32 * mul + offset).collect();
+    tract_ndarray::ArrayD::from_shape_vec(shape, data).unwrap().into_tensor()
+}
+
+fn conv_options(padding: i8, activation: i8) -> Vec<Field> {
+    vec![Field::I8(0, padding), Field::I32(1, 1), Field::I32(2, 1), Field::I8(3, activation)]
+}
+
+#[test]
+fn conv_2d_valid_with_bias() -> TractResult<()> {
+    let input = ramp(&[1, 4, 3, 2], 0.5, -1.0);
+    // OHWI, as in TFLite
+    let kernel = ramp(&[3, 2, 2, 2], 0.25, -0.5);
+    let bias = [0.5f32, -1.0, 2.0];
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[1, 4, 3, 2]));
+    let k = model.tensor(TensorDef::f32_const("kernel", &[3, 2, 2, 2], kernel.as_slice()?));
+    let b = model.tensor(TensorDef::f32_const("bias", &[3], &bias));
+    let o = model.tensor(TensorDef::f32("output", &[1, 3, 2, 3]));
+    model.ops.push(OpDef {
+        code: builtin::CONV_2D,
+        inputs: vec![i, k, b],
+        outputs: vec![o],
+        options_type: 1,
+        options: conv_options(padding::VALID, activation::NONE),
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let result = model.run(tvec!(input.clone()))?;
+    let hwio = kernel.permute_axes(&[1, 2, 3, 0])?;
+    result[0].close_enough(&conv_ref(&input, &hwio, &bias, 1)?, true)
+}
+
+#[test]
+fn conv_2d_fused_relu() -> TractResult<()> {
+    let input = ramp(&[1, 3, 3, 1], 1.0, -5.0);
+    let kernel = ramp(&[2, 2, 2, 1], 0.5, -1.0);
+    let bias = [0.0f32, 0.0];
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[1, 3, 3, 1]));
+    let k = model.tensor(TensorDef::f32_const("kernel", &[2, 2, 2, 1], kernel.as_slice()?));
+    let b = model.tensor(TensorDef::f32_const("bias", &[2], &bias));
+    let o = model.tensor(TensorDef::f32("output", &[1, 2, 2, 2]));
+    model.ops.push(OpDef {
+        code: builtin::CONV_2D,
+        inputs: vec![i, k, b],
+        outputs: vec![o],
+        options_type: 1,
+        options: conv_options(padding::VALID, activation::RELU),
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let result = model.run(tvec!(input.clone()))?;
+    let hwio = kernel.permute_axes(&[1, 2, 3, 0])?;
+    let mut expected = conv_ref(&input, &hwio, &bias, 1)?;
+    expected.as_slice_mut::<f32>()?.iter_mut().for_each(|x| *x = x.max(0.0));
+    result[0].close_enough(&expected, true)
+}
+
+#[test]
+fn depthwise_conv_2d_multiplier() -> TractResult<()> {
+    let input = ramp(&[1, 3, 3, 2], 0.5, -1.0);
+    // 1HW(C*M), with C = 2 and M = 2
+    let kernel = ramp(&[1, 2, 2, 4], 0.25, -0.5);
+    let bias = [0.0f32, 1.0, 2.0, 3.0];
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[1, 3, 3, 2]));
+    let k = model.tensor(TensorDef::f32_const("kernel", &[1, 2, 2, 4], kernel.as_slice()?));
+    let b = model.tensor(TensorDef::f32_const("bias", &[4], &bias));
+    let o = model.tensor(TensorDef::f32("output", &[1, 2, 2, 4]));
+    model.ops.push(OpDef {
+        code: builtin::DEPTHWISE_CONV_2D,
+        inputs: vec![i, k, b],
+        outputs: vec![o],
+        options_type: 2,
+        options: vec![
+            Field::I8(0, padding::VALID),
+            Field::I32(1, 1),
+            Field::I32(2, 1),
+            Field::I32(3, 2),
+        ],
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let result = model.run(tvec!(input.clone()))?;
+    let hwio = kernel.into_shape(&[2, 2, 2, 2])?;
+    result[0].close_enough(&conv_ref(&input, &hwio, &bias, 2)?, true)
+}
+
+#[test]
+fn conv_2d_int8_per_axis() -> TractResult<()> {
+    let (input_scale, input_zp) = (0.05f32, 3);
+    let weight_scales = [0.02f32, 0.01, 0.04];
+    let (output_scale, output_zp) = (0.1f32, -5);
+    let input_i8: Vec<i8> = (0..24).map(|i| ((i * 37) % 61 - 30) as i8).collect();
+    let kernel_i8: Vec<i8> = (0..24).map(|i| ((i * 13) % 41 - 20) as i8).collect();
+    let bias_i32 = [100i32, -200, 50];
+
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::quant(
+        "input",
+        tensor_type::INT8,
+        &[1, 4, 3, 2],
+        &[input_scale],
+        &[input_zp],
+    ));
+    let mut kernel =
+        TensorDef::quant("kernel", tensor_type::INT8, &[3, 2, 2, 2], &weight_scales, &[0, 0, 0])
+            .with_data(kernel_i8.iter().map(|x| *x as u8).collect());
+    kernel.quantized_dimension = 0;
+    let k = model.tensor(kernel);
+    let bias_scales: Vec<f32> = weight_scales.iter().map(|s| s * input_scale).collect();
+    let b = model.tensor(
+        TensorDef::quant("bias", tensor_type::INT32, &[3], &bias_scales, &[0, 0, 0])
+            .with_data(bias_i32.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect()),
+    );
+    let o = model.tensor(TensorDef::quant(
+        "output",
+        tensor_type::INT8,
+        &[1, 3, 2, 3],
+        &[output_scale],
+        &[output_zp],
+    ));
+    model.ops.push(OpDef {
+        code: builtin::CONV_2D,
+        inputs: vec![i, k, b],
+        outputs: vec![o],
+        options_type: 1,
+        options: conv_options(padding::VALID, activation::NONE),
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+
+    let input_dt =
+        DatumType::QI8(QParams::ZpScale { zero_point: input_zp as i32, scale: input_scale });
+    let mut input =
+        tract_ndarray::Array4::from_shape_vec((1, 4, 3, 2), input_i8.clone())?.into_tensor();
+    unsafe { input.set_datum_type(input_dt) };
+    let result = model.run(tvec!(input))?;
+    let result = result[0].cast_to::<i8>()?;
+
+    let input_f32: Vec<f32> =
+        input_i8.iter().map(|x| (*x as i32 - input_zp as i32) as f32 * input_scale).collect();
+    let input_f32 = tract_ndarray::ArrayD::from_shape_vec(&[1, 4, 3, 2][..], input_f32)?;
+    let kernel_f32: Vec<f32> =
+        kernel_i8.iter().enumerate().map(|(ix, x)| *x as f32 * weight_scales[ix / 8]).collect();
+    let kernel_f32 = tract_ndarray::ArrayD::from_shape_vec(&[3, 2, 2, 2][..], kernel_f32)?
+        .into_tensor()
+        .permute_axes(&[1, 2, 3, 0])?;
+    let bias_f32: Vec<f32> =
+        bias_i32.iter().zip(bias_scales.iter()).map(|(b, s)| *b as f32 * s).collect();
+    let expected = conv_ref(&input_f32.into_tensor(), &kernel_f32, &bias_f32, 1)?;
+    for (found, expected) in result.as_slice::<i8>()?.iter().zip(expected.as_slice::<f32>()?.iter())
+    {
+        let expected = (expected / output_scale)
+            .round()
+            .max(-128.0 - output_zp as f32)
+            .min(127.0 - output_zp as f32)
+            + output_zp as f32;
+        assert!((*found as f32 - expected).abs() <= 1.0, "found {} expected {}", found, expected);
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+mod utils;
+
+use tract_tflite::prelude::*;
+use tract_tflite::schema::{activation, builtin, tensor_type};
+use utils::*;
+
+#[test]
+fn fully_connected_with_bias() -> TractResult<()> {
+    let weights = [1.0f32, 2.0, 3.0, -1.0, 0.5, 0.0];
+    let bias = [0.5f32, -2.0];
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[2, 3]));
+    let w = model.tensor(TensorDef::f32_const("weights", &[2, 3], &weights));
+    let b = model.tensor(TensorDef::f32_const("bias", &[2], &bias));
+    let o = model.tensor(TensorDef::f32("output", &[2, 2]));
+    model.ops.push(OpDef {
+        code: builtin::FULLY_CONNECTED,
+        inputs: vec![i, w, b],
+        outputs: vec![o],
+        options_type: 8,
+        options: vec![Field::I8(0, activation::RELU)],
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let result = model.run(tvec!(tensor2(&[[1.0f32, 1.0, 1.0], [0.0, -1.0, 2.0]])))?;
+    result[0].close_enough(&tensor2(&[[6.5f32, 0.0], [4.5, 0.0]]), true)
+}
+
+#[test]
+fn fully_connected_uint8() -> TractResult<()> {
+    let (input_scale, input_zp) = (0.5f32, 128i64);
+    let (weight_scale, weight_zp) = (0.25f32, 120i64);
+    let (output_scale, output_zp) = (1.0f32, 100i64);
+    let weights: Vec<u8> = vec![124, 116, 128, 120];
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::quant(
+        "input",
+        tensor_type::UINT8,
+        &[1, 2],
+        &[input_scale],
+        &[input_zp],
+    ));
+    let w = model.tensor(
+        TensorDef::quant("weights", tensor_type::UINT8, &[2, 2], &[weight_scale], &[weight_zp])
+            .with_data(weights),
+    );
+    let o = model.tensor(TensorDef::quant(
+        "output",
+        tensor_type::UINT8,
+        &[1, 2],
+        &[output_scale],
+        &[output_zp],
+    ));
+    model.ops.push(OpDef {
+        code: builtin::FULLY_CONNECTED,
+        inputs: vec![i, w, -1],
+        outputs: vec![o],
+        options_type: 8,
+        options: vec![],
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    // input is [4.0, -2.0], weights are [[1, -1], [2, 0]]
+    let mut input = tensor2(&[[136u8, 124]]);
+    unsafe {
+        input.set_datum_type(DatumType::QU8(QParams::ZpScale {
+            zero_point: input_zp as i32,
+            scale: input_scale,
+        }))
+    };
+    let result = model.run(tvec!(input))?;
+    // [6.0, 8.0] quantized
+    assert_eq!(unsafe { result[0].as_slice_unchecked::<u8>() }, &[106, 108]);
+    Ok(())
+}
+
+#[test]
+fn softmax_beta() -> TractResult<()> {
+    let mut model = ModelDef::default();
+    let i = model.tensor(TensorDef::f32("input", &[1, 3]));
+    let o = model.tensor(TensorDef::f32("output", &[1, 3]));
+    model.ops.push(OpDef {
+        code: builtin::SOFTMAX,
+        inputs: vec![i],
+        outputs: vec![o],
+        options_type: 9,
+        options: vec![Field::F32(0, 2.0)],
+    });
+    model.inputs = vec![i];
+    model.outputs = vec![o];
+    let result = model.run(tvec!(tensor2(&[[0.0f32, 0.5, 1.0]])))?;
+    let e: Vec<f32> = [0.0f32, 1.0, 2.0].iter().map(|x| x.exp()).collect();
+    let sum: f32 = e.iter().sum();
+    result[0].close_enough(&tensor2(&[[e[0] / sum, e[1] / sum, e[2] / sum]]), true)
+}
@@ -0,0 +1,207 @@
+#![allow(dead_code)]
+use flatbuffers::{FlatBufferBuilder, TableFinishedWIPOffset, WIPOffset};
+use tract_tflite::prelude::*;
+use tract_tflite::schema::tensor_type;
+
+/// Minimal TFLite model writer, one subgraph, for tests.
+#[derive(Default)]
+pub struct ModelDef {
+    pub tensors: Vec<TensorDef>,
+    pub ops: Vec<OpDef>,
+    pub inputs: Vec<i32>,
+    pub outputs: Vec<i32>,
+}
+
+#[derive(Default, Clone)]
+pub struct TensorDef {
+    pub name: String,
+    pub shape: Vec<i32>,
+    pub type_: i8,
+    pub data: Option<Vec<u8>>,
+    pub scale: Vec<f32>,
+    pub zero_point: Vec<i64>,
+    pub quantized_dimension: i32,
+}
+
+impl TensorDef {
+    pub fn f32(name: &str, shape: &[i32]) -> TensorDef {
+        TensorDef {
+            name: name.into(),
+            shape: shape.into(),
+            type_: tensor_type::FLOAT32,
+            ..TensorDef::default()
+        }
+    }
+
+    pub fn f32_const(name: &str, shape: &[i32], data: &[f32]) -> TensorDef {
+        let data = data.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect();
+        TensorDef { data: Some(data), ..TensorDef::f32(name, shape) }
+    }
+
+    pub fn quant(name: &str, type_: i8, shape: &[i32], scale: &[f32], zp: &[i64]) -> TensorDef {
+        TensorDef {
+            name: name.into(),
+            shape: shape.into(),
+            type_,
+            scale: scale.into(),
+            zero_point: zp.into(),
+            ..TensorDef::default()
+        }
+    }
+
+    pub fn with_data(self, data: Vec<u8>) -> TensorDef {
+        TensorDef { data: Some(data), ..self }
+    }
+}
+
+/// One scalar field of an options table: slot and value.
+#[derive(Clone, Copy)]
+pub enum Field {
+    I8(u16, i8),
+    I32(u16, i32),
+    F32(u16, f32),
+    Bool(u16, bool),
+}
+
+#[derive(Default, Clone)]
+pub struct OpDef {
+    pub code: i32,
+    pub inputs: Vec<i32>,
+    pub outputs: Vec<i32>,
+    pub options_type: u8,
+    pub options: Vec<Field>,
+}
+
+fn slot(ix: u16) -> u16 {
+    4 + 2 * ix
+}
+
+impl ModelDef {
+    pub fn tensor(&mut self, t: TensorDef) -> i32 {
+        self.tensors.push(t);
+        self.tensors.len() as i32 - 1
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        // buffer 0 is the empty buffer, by convention
+        let mut buffers = vec![{
+            let t = fbb.start_table();
+            fbb.end_table(t)
+        }];
+        let mut tensors = vec![];
+        for t in &self.tensors {
+            let buffer = if let Some(data) = &t.data {
+                let data = fbb.create_vector(data);
+                let b = fbb.start_table();
+                fbb.push_slot_always(slot(0), data);
+                buffers.push(fbb.end_table(b));
+                buffers.len() as u32 - 1
+            } else {
+                0
+            };
+            let quantization = if !t.scale.is_empty() {
+                let scale = fbb.create_vector(&t.scale);
+                let zp = fbb.create_vector(&t.zero_point);
+                let q = fbb.start_table();
+                fbb.push_slot_always(slot(2), scale);
+                fbb.push_slot_always(slot(3), zp);
+                fbb.push_slot(slot(6), t.quantized_dimension, 0);
+                Some(fbb.end_table(q))
+            } else {
+                None
+            };
+            let shape = fbb.create_vector(&t.shape);
+            let name = fbb.create_string(&t.name);
+            let table = fbb.start_table();
+            fbb.push_slot_always(slot(0), shape);
+            fbb.push_slot(slot(1), t.type_, 0);
+            fbb.push_slot(slot(2), buffer, 0);
+            fbb.push_slot_always(slot(3), name);
+            if let Some(q) = quantization {
+                fbb.push_slot_always(slot(4), q);
+            }
+            tensors.push(fbb.end_table(table));
+        }
+        let mut codes: Vec<i32> = self.ops.iter().map(|op| op.code).collect();
+        codes.sort();
+        codes.dedup();
+        let mut ops = vec![];
+        for op in &self.ops {
+            let options = if op.options_type != 0 {
+                let o = fbb.start_table();
+                for field in &op.options {
+                    match *field {
+                        Field::I8(s, v) => fbb.push_slot_always(slot(s), v),
+                        Field::I32(s, v) => fbb.push_slot_always(slot(s), v),
+                        Field::F32(s, v) => fbb.push_slot_always(slot(s), v),
+                        Field::Bool(s, v) => fbb.push_slot_always(slot(s), v),
+                    }
+                }
+                Some(fbb.end_table(o))
+            } else {
+                None
+            };
+            let inputs = fbb.create_vector(&op.inputs);
+            let outputs = fbb.create_vector(&op.outputs);
+            let table = fbb.start_table();
+            let index = codes.iter().position(|c| *c == op.code).unwrap() as u32;
+            fbb.push_slot(slot(0), index, 0);
+            fbb.push_slot_always(slot(1), inputs);
+            fbb.push_slot_always(slot(2), outputs);
+            if let Some(options) = options {
+                fbb.push_slot(slot(3), op.options_type, 0);
+                fbb.push_slot_always(slot(4), options);
+            }
+            ops.push(fbb.end_table(table));
+        }
+        let codes: Vec<WIPOffset<TableFinishedWIPOffset>> = codes
+            .iter()
+            .map(|c| {
+                let t = fbb.start_table();
+                fbb.push_slot(slot(0), (*c).min(127) as i8, 0);
+                fbb.push_slot(slot(3), *c, 0);
+                fbb.end_table(t)
+            })
+            .collect();
+        let tensors = fbb.create_vector(&tensors);
+        let inputs = fbb.create_vector(&self.inputs);
+        let outputs = fbb.create_vector(&self.outputs);
+        let ops = fbb.create_vector(&ops);
+        let subgraph = fbb.start_table();
+        fbb.push_slot_always(slot(0), tensors);
+        fbb.push_slot_always(slot(1), inputs);
+        fbb.push_slot_always(slot(2), outputs);
+        fbb.push_slot_always(slot(3), ops);
+        let subgraph = fbb.end_table(subgraph);
+        let subgraphs = fbb.create_vector(&[subgraph]);
+        let codes = fbb.create_vector(&codes);
+        let buffers = fbb.create_vector(&buffers);
+        let model = fbb.start_table();
+        fbb.push_slot(slot(0), 3u32, 0);
+        fbb.push_slot_always(slot(1), codes);
+        fbb.push_slot_always(slot(2), subgraphs);
+        fbb.push_slot_always(slot(4), buffers);
+        let model = fbb.end_table(model);
+        fbb.finish(model, Some("TFL3"));
+        fbb.finished_data().to_vec()
+    }
+
+    pub fn run(&self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let model = tract_tflite::tflite().model_for_read(&mut &*self.to_bytes())?;
+        let decluttered = model.declutter()?;
+        let optimized = model.clone().into_optimized()?;
+        let reference = model.into_runnable()?.run(inputs.clone())?;
+        for other in [
+            decluttered.into_runnable()?.run(inputs.clone())?,
+            optimized.into_runnable()?.run(inputs)?,
+        ]
+        .iter()
+        {
+            for (a, b) in reference.iter().zip(other.iter()) {
+                a.close_enough(b, true)?;
+            }
+        }
+        Ok(reference)
+    }
+}